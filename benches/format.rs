@@ -0,0 +1,91 @@
+/// file: benches/format.rs
+/// description: Cost of formatting trades and books, with rows going through the buffered file output
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use rs_hyperliquid::{
+    formatter::{BboFormatter, BookLadderFormatter, OutputFormat, QuietLevel, TradeFormatter},
+    output::TradeOutput,
+    time_display::TimeDisplay,
+    types::{Bbo, Trade, WebSocketMessage},
+};
+use std::hint::black_box;
+use std::path::Path;
+
+const TRADES_BURST: &str = include_str!("payloads/trades_burst.json");
+const L2_BOOK: &str = include_str!("payloads/l2book.json");
+
+// Rows are formatted and buffered as for a file, without disk I/O setting the pace
+#[cfg(unix)]
+const DISCARD: &str = "/dev/null";
+#[cfg(windows)]
+const DISCARD: &str = "NUL";
+
+fn trades() -> Vec<Trade> {
+    match WebSocketMessage::parse(TRADES_BURST).unwrap() {
+        WebSocketMessage::TradeData(message) => message
+            .data
+            .into_iter()
+            .map(|trade| trade.into_owned())
+            .collect(),
+        _ => unreachable!("the payload is a trades message"),
+    }
+}
+
+fn formatter(format: OutputFormat) -> TradeFormatter {
+    TradeFormatter::new(
+        format,
+        false,
+        false,
+        QuietLevel::DataOnly,
+        false,
+        false,
+        None,
+    )
+    .with_output(TradeOutput::create(Path::new(DISCARD)).unwrap())
+}
+
+fn format_trades(c: &mut Criterion) {
+    let trades = trades();
+    let mut group = c.benchmark_group("format_trades");
+    group.throughput(Throughput::Elements(trades.len() as u64));
+    for (name, format) in [
+        ("table", OutputFormat::Table),
+        ("csv", OutputFormat::Csv),
+        ("json", OutputFormat::Json),
+        ("minimal", OutputFormat::Minimal),
+        ("events", OutputFormat::Events),
+    ] {
+        let mut formatter = formatter(format);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for trade in &trades {
+                    formatter.print_trade(black_box(trade));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn format_book(c: &mut Criterion) {
+    let book = match WebSocketMessage::parse(L2_BOOK).unwrap() {
+        WebSocketMessage::BookData(message) => message.data,
+        _ => unreachable!("the payload is an l2Book message"),
+    };
+    let ladder = BookLadderFormatter::new(false, 20, 0);
+    c.bench_function("format_book/ladder", |b| {
+        b.iter(|| ladder.render(black_box(&book)))
+    });
+
+    let bbo = Bbo {
+        coin: book.coin.clone(),
+        time: book.time,
+        bbo: (book.best_bid().cloned(), book.best_ask().cloned()),
+    };
+    let line = BboFormatter::new(false, TimeDisplay::default());
+    c.bench_function("format_book/bbo_line", |b| {
+        b.iter(|| line.render_line(black_box(&bbo)))
+    });
+}
+
+criterion_group!(benches, format_trades, format_book);
+criterion_main!(benches);
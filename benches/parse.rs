@@ -0,0 +1,46 @@
+/// file: benches/parse.rs
+/// description: Parse cost of representative frames, from raw text to the values sent as events
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use rs_hyperliquid::types::WebSocketMessage;
+use std::hint::black_box;
+
+const TRADES_BURST: &str = include_str!("payloads/trades_burst.json");
+const L2_BOOK: &str = include_str!("payloads/l2book.json");
+const ALL_MIDS: &str = include_str!("payloads/all_mids.json");
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, payload) in [
+        ("trades_burst", TRADES_BURST),
+        ("l2book", L2_BOOK),
+        ("all_mids", ALL_MIDS),
+    ] {
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| WebSocketMessage::parse(black_box(payload)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Parsing plus the copy each trade gets when it leaves the client as an event
+fn trades_to_events(c: &mut Criterion) {
+    let mut group = c.benchmark_group("events");
+    group.throughput(Throughput::Bytes(TRADES_BURST.len() as u64));
+    group.bench_function("trades_burst", |b| {
+        b.iter(
+            || match WebSocketMessage::parse(black_box(TRADES_BURST)).unwrap() {
+                WebSocketMessage::TradeData(message) => message
+                    .data
+                    .into_iter()
+                    .map(|trade| trade.into_owned())
+                    .collect::<Vec<_>>(),
+                _ => unreachable!("the payload is a trades message"),
+            },
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, trades_to_events);
+criterion_main!(benches);
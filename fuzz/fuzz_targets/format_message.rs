@@ -0,0 +1,71 @@
+#![no_main]
+
+/// file: fuzz/fuzz_targets/format_message.rs
+/// description: Whatever parses is rendered by every formatter, so hostile values cannot panic output
+use libfuzzer_sys::fuzz_target;
+use rs_hyperliquid::{
+    events::ClientEvent,
+    formatter::{BboFormatter, BookLadderFormatter, OutputFormat, QuietLevel, TradeFormatter},
+    output::TradeOutput,
+    time_display::TimeDisplay,
+    types::WebSocketMessage,
+};
+use std::path::Path;
+use std::sync::Arc;
+
+const FORMATS: [OutputFormat; 6] = [
+    OutputFormat::Table,
+    OutputFormat::Csv,
+    OutputFormat::Json,
+    OutputFormat::JsonPretty,
+    OutputFormat::Minimal,
+    OutputFormat::Events,
+];
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let Ok(message) = WebSocketMessage::parse(&text) else {
+        return;
+    };
+    match message {
+        WebSocketMessage::TradeData(message) => format_trades(message.data),
+        WebSocketMessage::DirectTrades(trades) => format_trades(trades),
+        WebSocketMessage::BookData(message) => {
+            let ladder = BookLadderFormatter::new(false, 20, 0);
+            let _ = ladder.render(&message.data);
+            let _ = message.data.spread();
+            let _ = message.data.datetime_local();
+            let _ = ClientEvent::BookReceived(Arc::new(message.data)).to_json();
+        }
+        WebSocketMessage::BboData(message) => {
+            let _ = BboFormatter::new(false, TimeDisplay::default()).render_line(&message.data);
+            let _ = ClientEvent::BboReceived(Arc::new(message.data)).to_json();
+        }
+        WebSocketMessage::CandleData(message) => {
+            for candle in message.data {
+                let _ = candle.open_time_local();
+                let _ = candle.close_time_local();
+            }
+        }
+        _ => {}
+    }
+});
+
+fn format_trades(trades: Vec<rs_hyperliquid::types::TradeRef<'_>>) {
+    let trades: Vec<_> = trades.into_iter().map(|trade| trade.into_owned()).collect();
+    for format in FORMATS {
+        let mut formatter = TradeFormatter::new(
+            format,
+            false,
+            true,
+            QuietLevel::DataOnly,
+            false,
+            false,
+            None,
+        )
+        .with_output(TradeOutput::create(Path::new("/dev/null")).unwrap());
+        for trade in &trades {
+            formatter.print_trade(trade);
+        }
+    }
+}
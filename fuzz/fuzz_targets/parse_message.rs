@@ -0,0 +1,30 @@
+#![no_main]
+
+/// file: fuzz/fuzz_targets/parse_message.rs
+/// description: Arbitrary frames through parsing, the strict schema check, and event conversion
+use libfuzzer_sys::fuzz_target;
+use rs_hyperliquid::{events::trade_json, schema::SchemaChecker, types::WebSocketMessage};
+
+fuzz_target!(|data: &[u8]| {
+    // The client decodes text frames the same way before parsing
+    let text = String::from_utf8_lossy(data);
+    let Ok(message) = WebSocketMessage::parse(&text) else {
+        return;
+    };
+    let _ = message.channel();
+    let _ = message.route_key();
+    let _ = message.is_control();
+    SchemaChecker::default().check(&text, &message);
+
+    let trades = match message {
+        WebSocketMessage::TradeData(message) => message.data,
+        WebSocketMessage::DirectTrades(trades) => trades,
+        _ => return,
+    };
+    for trade in trades {
+        let trade = trade.into_owned();
+        let _ = trade.datetime_local();
+        let _ = trade.buyer_seller();
+        let _ = trade_json(&trade);
+    }
+});
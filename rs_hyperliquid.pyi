@@ -0,0 +1,65 @@
+# Type stubs for the `python` feature module, shipped with the package by maturin
+from typing import AsyncIterator, Optional, Sequence, Union
+
+__version__: str
+
+class Trade:
+    coin: str
+    side: str
+    px: float
+    sz: float
+    time: int
+    tid: int
+    hash: str
+    users: list[str]
+    @property
+    def is_buy(self) -> bool: ...
+    @property
+    def value(self) -> float: ...
+
+class Level:
+    px: float
+    sz: float
+    n: int
+
+class Book:
+    coin: str
+    time: int
+    bids: list[Level]
+    asks: list[Level]
+
+class Bbo:
+    coin: str
+    time: int
+    bid: Optional[Level]
+    ask: Optional[Level]
+
+class Candle:
+    coin: str
+    interval: str
+    open_time: int
+    close_time: int
+    open: float
+    high: float
+    low: float
+    close: float
+    volume: float
+    trades: int
+    closed: bool
+
+Event = Union[Trade, Book, Bbo, Candle]
+
+class Client:
+    def __init__(
+        self,
+        coin: str = "BTC",
+        channels: Optional[Sequence[str]] = None,
+        intervals: Optional[Sequence[str]] = None,
+        user: Optional[str] = None,
+        url: Optional[str] = None,
+        candles: str = "all",
+        args: Optional[Sequence[str]] = None,
+    ) -> None: ...
+    def __aiter__(self) -> AsyncIterator[Event]: ...
+    async def __anext__(self) -> Event: ...
+    def close(self) -> None: ...
@@ -0,0 +1,367 @@
+/// file: src/budget.rs
+/// description: Shared memory budget for queues and buffers, with eviction once it is used up
+use crate::{
+    events::ClientEvent,
+    symbol::Symbol,
+    types::{
+        AllMids, Bbo, Book, Candle, ExplorerBlock, ExplorerTx, Fill, Level, Liquidation,
+        NonUserCancel, OrderUpdate, Trade, UserFunding,
+    },
+};
+use metrics::{Counter, Gauge, counter, gauge};
+use std::collections::VecDeque;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tracing::{info, warn};
+
+/// Buffers covered by the budget, the `buffer` label on its metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Buffer {
+    /// Events queued from the client to the UI
+    Events,
+    /// Raw frames queued for the `--record` writer
+    Recorder,
+    /// Interactive scrollback
+    History,
+    /// Recent trades kept for the dashboard and `/status`
+    Recent,
+    /// Last trade id per coin, for duplicate detection
+    Dedup,
+}
+
+impl Buffer {
+    const ALL: [Buffer; 5] = [
+        Buffer::Events,
+        Buffer::Recorder,
+        Buffer::History,
+        Buffer::Recent,
+        Buffer::Dedup,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Buffer::Events => "events",
+            Buffer::Recorder => "recorder",
+            Buffer::History => "history",
+            Buffer::Recent => "recent",
+            Buffer::Dedup => "dedup",
+        }
+    }
+
+    /// Rings give up their oldest entries to make room, so they never hold back a queue
+    fn evictable(self) -> bool {
+        matches!(self, Buffer::History | Buffer::Recent)
+    }
+}
+
+struct Usage {
+    bytes: AtomicUsize,
+    gauge: Gauge,
+    evictions: Counter,
+}
+
+/// Estimated bytes held per buffer against one limit
+///
+/// Queues refuse new items once the budget is used up, counting each as dropped, while rings
+/// drop their oldest entries until it fits again. Ring memory does not count against queues, so
+/// a full scrollback is trimmed rather than starving the live stream. Sizes are estimates of the
+/// heap and inline size of each item, not allocator figures.
+pub struct MemoryBudget {
+    limit: usize,
+    usage: [Usage; Buffer::ALL.len()],
+    exceeded: AtomicBool,
+}
+
+impl std::fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("limit", &self.limit)
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+impl MemoryBudget {
+    pub fn new(limit_bytes: usize) -> Self {
+        gauge!("hyperliquid_memory_budget_limit_bytes").set(limit_bytes as f64);
+        Self {
+            limit: limit_bytes,
+            usage: Buffer::ALL.map(|buffer| Usage {
+                bytes: AtomicUsize::new(0),
+                gauge: gauge!("hyperliquid_memory_budget_used_bytes", "buffer" => buffer.as_str()),
+                evictions: counter!(
+                    "hyperliquid_memory_budget_evictions_total",
+                    "buffer" => buffer.as_str()
+                ),
+            }),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    fn usage(&self, buffer: Buffer) -> &Usage {
+        &self.usage[buffer as usize]
+    }
+
+    /// Estimated bytes across every buffer
+    pub fn used(&self) -> usize {
+        Buffer::ALL
+            .iter()
+            .map(|buffer| self.usage(*buffer).bytes.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    fn used_by_queues(&self) -> usize {
+        Buffer::ALL
+            .iter()
+            .filter(|buffer| !buffer.evictable())
+            .map(|buffer| self.usage(*buffer).bytes.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    pub fn over_limit(&self) -> bool {
+        self.used() > self.limit
+    }
+
+    /// Reserve room for a queued item; `false` means it must be dropped
+    pub fn try_charge(&self, buffer: Buffer, bytes: usize) -> bool {
+        if self.used_by_queues() + bytes > self.limit {
+            self.evicted(buffer, 1);
+            return false;
+        }
+        self.charge(buffer, bytes);
+        true
+    }
+
+    /// Count bytes a ring has already taken on; the ring evicts while `over_limit` holds
+    pub fn charge(&self, buffer: Buffer, bytes: usize) {
+        let usage = self.usage(buffer);
+        let total = usage.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        usage.gauge.set(total as f64);
+    }
+
+    /// Return bytes once an item leaves its buffer
+    pub fn release(&self, buffer: Buffer, bytes: usize) {
+        let usage = self.usage(buffer);
+        // Saturating, since a replay feeds the event queue without charging it
+        let before = usage
+            .bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            })
+            .unwrap_or_default();
+        usage.gauge.set(before.saturating_sub(bytes) as f64);
+        self.check_recovered();
+    }
+
+    /// Replace the figure for a buffer that is measured rather than tracked item by item
+    pub fn set(&self, buffer: Buffer, bytes: usize) {
+        let usage = self.usage(buffer);
+        usage.bytes.store(bytes, Ordering::Relaxed);
+        usage.gauge.set(bytes as f64);
+    }
+
+    /// Count items a buffer dropped or refused because of the budget
+    pub fn evicted(&self, buffer: Buffer, items: u64) {
+        self.usage(buffer).evictions.increment(items);
+        if !self.exceeded.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Memory budget of {:.1} MB reached: oldest history is evicted and new events are dropped until usage falls",
+                self.limit as f64 / 1_048_576.0
+            );
+            gauge!("hyperliquid_memory_budget_exceeded").set(1.0);
+        }
+    }
+
+    // Some headroom, so usage hovering at the limit logs once rather than on every item
+    fn check_recovered(&self) {
+        if self.exceeded.load(Ordering::Relaxed)
+            && self.used() <= self.limit / 10 * 9
+            && self.exceeded.swap(false, Ordering::Relaxed)
+        {
+            info!(
+                "Memory usage back under budget ({:.1} of {:.1} MB)",
+                self.used() as f64 / 1_048_576.0,
+                self.limit as f64 / 1_048_576.0
+            );
+            gauge!("hyperliquid_memory_budget_exceeded").set(0.0);
+        }
+    }
+}
+
+/// Estimated size of a trade and the strings it owns
+///
+/// Coins are interned, so the name is shared with every other trade on the coin and not counted.
+pub fn trade_bytes(trade: &Trade) -> usize {
+    size_of::<Trade>()
+        + trade.side.len()
+        + trade.hash.len()
+        + trade
+            .users
+            .iter()
+            .map(|user| size_of::<String>() + user.len())
+            .sum::<usize>()
+}
+
+fn book_bytes(book: &Book) -> usize {
+    size_of::<Book>() + (book.levels.0.len() + book.levels.1.len()) * size_of::<Level>()
+}
+
+/// Estimated size of a queued event, including what its `Arc` points to
+pub fn event_bytes(event: &ClientEvent) -> usize {
+    size_of::<ClientEvent>()
+        + match event {
+            ClientEvent::TradeReceived(trade) => trade_bytes(trade),
+            ClientEvent::BookReceived(book) => book_bytes(book),
+            ClientEvent::BboReceived(_) => size_of::<Bbo>(),
+            ClientEvent::AllMidsReceived(all_mids) => {
+                size_of::<AllMids>()
+                    + all_mids
+                        .mids
+                        .values()
+                        .map(|mid| size_of::<(Symbol, String)>() + mid.len())
+                        .sum::<usize>()
+            }
+            ClientEvent::CandleReceived { candle, .. } => size_of::<Candle>() + candle.i.len(),
+            ClientEvent::FillReceived { fill, .. } => {
+                size_of::<Fill>()
+                    + fill.side.len()
+                    + fill.dir.len()
+                    + fill.hash.len()
+                    + fill.fee_token.len()
+                    + fill.builder_fee.as_ref().map_or(0, String::len)
+            }
+            ClientEvent::FundingReceived(_) => size_of::<UserFunding>(),
+            ClientEvent::LiquidationReceived(liquidation) => {
+                size_of::<Liquidation>()
+                    + liquidation.liquidator.len()
+                    + liquidation.liquidated_user.len()
+            }
+            ClientEvent::NonUserCancelReceived(_) => size_of::<NonUserCancel>(),
+            ClientEvent::OrderUpdated(update) => {
+                size_of::<OrderUpdate>()
+                    + update.status.len()
+                    + update.order.side.len()
+                    + update.order.cloid.as_ref().map_or(0, String::len)
+            }
+            ClientEvent::BlockReceived(block) => {
+                size_of::<ExplorerBlock>() + block.hash.len() + block.proposer.len()
+            }
+            // The action is a JSON tree; its text length stands in for its heap size
+            ClientEvent::TxReceived(tx) => {
+                size_of::<ExplorerTx>()
+                    + tx.hash.len()
+                    + tx.user.len()
+                    + tx.action.to_string().len()
+            }
+            ClientEvent::MessageReceived { raw_message } => raw_message.len(),
+            _ => 0,
+        }
+}
+
+/// Push onto a ring of at most `capacity` items, evicting the oldest past it and, with a budget,
+/// while the budget is over; the new item itself is always kept
+pub fn push_ring<T>(
+    ring: &mut VecDeque<T>,
+    capacity: usize,
+    item: T,
+    budget: Option<(&MemoryBudget, Buffer)>,
+    bytes: impl Fn(&T) -> usize,
+) {
+    if let Some((budget, buffer)) = budget {
+        budget.charge(buffer, bytes(&item));
+    }
+    ring.push_back(item);
+    let mut evicted = 0;
+    while ring.len() > 1
+        && (ring.len() > capacity || budget.is_some_and(|(budget, _)| budget.over_limit()))
+    {
+        let Some(oldest) = ring.pop_front() else {
+            break;
+        };
+        if let Some((budget, buffer)) = budget {
+            budget.release(buffer, bytes(&oldest));
+            // Trimming to capacity is normal turnover; only trimming for the budget is eviction
+            if ring.len() >= capacity {
+                continue;
+            }
+            evicted += 1;
+        }
+    }
+    if let Some((budget, buffer)) = budget.filter(|_| evicted > 0) {
+        budget.evicted(buffer, evicted);
+    }
+}
+
+/// Estimated size of a queued raw frame
+pub fn line_bytes(line: &str) -> usize {
+    size_of::<String>() + line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exceeded(budget: &MemoryBudget) -> bool {
+        budget.exceeded.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn queues_are_refused_past_the_limit_but_rings_do_not_count() {
+        let budget = MemoryBudget::new(1000);
+        budget.charge(Buffer::History, 5000);
+        assert!(budget.try_charge(Buffer::Events, 600));
+        assert!(!budget.try_charge(Buffer::Recorder, 401));
+        assert!(exceeded(&budget));
+        assert!(budget.try_charge(Buffer::Recorder, 400));
+        assert_eq!(budget.used_by_queues(), 1000);
+    }
+
+    #[test]
+    fn releasing_uncharged_bytes_saturates_at_zero() {
+        let budget = MemoryBudget::new(1000);
+        budget.charge(Buffer::Events, 10);
+        budget.release(Buffer::Events, 500);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn recovery_waits_for_usage_to_fall_under_ninety_percent() {
+        let budget = MemoryBudget::new(1000);
+        assert!(budget.try_charge(Buffer::Events, 1000));
+        assert!(!budget.try_charge(Buffer::Events, 1));
+        budget.release(Buffer::Events, 50);
+        assert!(exceeded(&budget), "950 of 1000 is still over the headroom");
+        budget.release(Buffer::Events, 50);
+        assert!(!exceeded(&budget));
+    }
+
+    #[test]
+    fn trimming_a_ring_to_capacity_is_not_eviction() {
+        let budget = MemoryBudget::new(1000);
+        let mut ring = VecDeque::new();
+        for item in 0..10 {
+            push_ring(&mut ring, 3, item, Some((&budget, Buffer::History)), |_| 10);
+        }
+        assert_eq!(ring, [7, 8, 9]);
+        assert_eq!(budget.used(), 30);
+        assert!(!exceeded(&budget));
+    }
+
+    #[test]
+    fn a_ring_over_budget_evicts_its_oldest_and_keeps_the_newest() {
+        let budget = MemoryBudget::new(100);
+        // Item 5 alone is bigger than the whole budget
+        let bytes = |item: &i32| if *item == 5 { 400 } else { 40 };
+        let mut ring = VecDeque::new();
+        for item in 0..5 {
+            push_ring(&mut ring, 10, item, Some((&budget, Buffer::Recent)), bytes);
+        }
+        assert_eq!(ring, [3, 4]);
+        assert_eq!(budget.used(), 80);
+        assert!(exceeded(&budget));
+
+        push_ring(&mut ring, 10, 5, Some((&budget, Buffer::Recent)), bytes);
+        assert_eq!(ring, [5]);
+        assert_eq!(budget.used(), 400);
+    }
+}
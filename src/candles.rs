@@ -0,0 +1,466 @@
+/// file: src/candles.rs
+/// description: Candle bar tracking that marks each bar final once the next one opens, and checks
+/// a coin's intervals against each other
+use crate::{symbol::Symbol, time::exchange_time, types::Candle};
+use metrics::counter;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+/// Closed bars of a coin's shortest interval kept for the check, a week of 1m bars
+const MAX_SHORT_BARS: usize = 10_080;
+/// Closed longer bars kept waiting for the shorter bars inside them to close
+const MAX_PENDING_BARS: usize = 64;
+
+/// Which candle updates `--candles` passes on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandleMode {
+    /// Every update to the open bar, and each bar once more as final when it closes
+    #[default]
+    All,
+    /// One final record per bar, when it closes
+    ClosedOnly,
+}
+
+impl CandleMode {
+    const ALL: [CandleMode; 2] = [CandleMode::All, CandleMode::ClosedOnly];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CandleMode::All => "all",
+            CandleMode::ClosedOnly => "closed-only",
+        }
+    }
+}
+
+impl FromStr for CandleMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(value))
+            .ok_or_else(|| {
+                format!(
+                    "unknown candle mode '{}', expected all or closed-only",
+                    value
+                )
+            })
+    }
+}
+
+/// A candle as emitted: the bar's latest state, and whether it is final
+#[derive(Debug, Clone)]
+pub struct CandleUpdate {
+    pub candle: Candle,
+    pub closed: bool,
+}
+
+/// The open bar per coin and interval, closed when a bar with a later open time arrives
+///
+/// The exchange sends no separate close message: the last update before the next bar opens is
+/// the final state, so a bar is only known to be closed once its successor shows up. A quiet
+/// market can therefore hold the last bar open past its close time, and the bar open when the
+/// stream ends is never emitted as closed.
+///
+/// With several intervals for one coin, each closed bar also goes through that coin's
+/// `IntervalCheck`.
+#[derive(Debug, Default)]
+pub struct CandleTracker {
+    mode: CandleMode,
+    open: HashMap<(Symbol, String), Candle>,
+    checks: HashMap<Symbol, IntervalCheck>,
+}
+
+impl CandleTracker {
+    pub fn new(mode: CandleMode) -> Self {
+        Self {
+            mode,
+            open: HashMap::new(),
+            checks: HashMap::new(),
+        }
+    }
+
+    /// Updates to emit for a received candle, oldest first
+    ///
+    /// A snapshot lists past bars oldest first, so each one closes the one before it. Updates to
+    /// a bar that has already closed, as resent after a reconnect, are dropped.
+    pub fn observe(&mut self, candle: Candle) -> Vec<CandleUpdate> {
+        let mut updates = Vec::new();
+        let key = (candle.s.clone(), candle.i.clone());
+        match self.open.get(&key) {
+            Some(open) if candle.t < open.t => {
+                debug!(
+                    "Dropping update to closed {} {} bar opening at {}",
+                    candle.s, candle.i, candle.t
+                );
+                return updates;
+            }
+            Some(open) if candle.t > open.t => {
+                if let Some(closed) = self.open.insert(key, candle.clone()) {
+                    if let Some(check) = self.checks.get_mut(&closed.s) {
+                        check.closed(&closed);
+                    }
+                    updates.push(CandleUpdate {
+                        candle: closed,
+                        closed: true,
+                    });
+                }
+            }
+            Some(_) => {
+                self.open.insert(key, candle.clone());
+            }
+            None => {
+                self.checks
+                    .entry(candle.s.clone())
+                    .or_default()
+                    .add_interval(&candle.i);
+                self.open.insert(key, candle.clone());
+            }
+        }
+        if self.mode == CandleMode::All {
+            updates.push(CandleUpdate {
+                candle,
+                closed: false,
+            });
+        }
+        updates
+    }
+}
+
+/// Rebuilds a coin's longer bars from the closed bars of its shortest interval
+///
+/// A longer bar should open at the first short bar's open, close at the last one's close, span
+/// their highest high and lowest low, and carry their summed volume and trade count. Each
+/// disagreement is logged and counted. Bars whose span is not fully covered, such as the first
+/// one after subscribing or one across a gap, are skipped, as is `1M`, whose months differ in
+/// length.
+#[derive(Debug, Default)]
+struct IntervalCheck {
+    /// Fixed-length intervals seen for the coin, with their length in milliseconds
+    intervals: Vec<(String, i64)>,
+    /// Closed bars of the shortest interval, oldest first
+    short: VecDeque<Candle>,
+    /// Closed longer bars whose last short bar has not closed yet, oldest first
+    pending: VecDeque<Candle>,
+}
+
+impl IntervalCheck {
+    fn add_interval(&mut self, interval: &str) {
+        let Some(length) = interval_ms(interval) else {
+            return;
+        };
+        let shortest = self.shortest();
+        self.intervals.push((interval.to_string(), length));
+        // Bars kept for the previous shortest interval no longer line up
+        if self.shortest() != shortest {
+            self.short.clear();
+            self.pending.clear();
+        }
+    }
+
+    fn shortest(&self) -> Option<(String, i64)> {
+        self.intervals
+            .iter()
+            .min_by_key(|(_, length)| *length)
+            .cloned()
+    }
+
+    fn closed(&mut self, bar: &Candle) {
+        if self.intervals.len() < 2 {
+            return;
+        }
+        let Some((shortest, shortest_ms)) = self.shortest() else {
+            return;
+        };
+        if bar.i == shortest {
+            self.short.push_back(bar.clone());
+            let longest = self
+                .intervals
+                .iter()
+                .map(|(_, length)| *length)
+                .max()
+                .unwrap_or(shortest_ms);
+            while self
+                .short
+                .front()
+                .is_some_and(|oldest| oldest.t < bar.t - longest)
+                || self.short.len() > MAX_SHORT_BARS
+            {
+                self.short.pop_front();
+            }
+        } else if interval_ms(&bar.i).is_some_and(|length| length % shortest_ms == 0) {
+            self.pending.push_back(bar.clone());
+            if self.pending.len() > MAX_PENDING_BARS {
+                self.pending.pop_front();
+            }
+        } else {
+            return;
+        }
+
+        let Some(latest) = self.short.back().map(|short| short.close_time) else {
+            return;
+        };
+        while let Some(position) = self
+            .pending
+            .iter()
+            .position(|long| long.close_time <= latest)
+        {
+            if let Some(long) = self.pending.remove(position) {
+                self.compare(&long, shortest_ms);
+            }
+        }
+    }
+
+    /// The fields `bar` disagrees on, or `None` when its short bars were not all seen
+    fn compare(&self, bar: &Candle, shortest_ms: i64) -> Option<Vec<String>> {
+        let parts: Vec<&Candle> = self
+            .short
+            .iter()
+            .filter(|short| short.t >= bar.t && short.close_time <= bar.close_time)
+            .collect();
+        let expected = interval_ms(&bar.i).map_or(0, |length| length / shortest_ms) as usize;
+        let (Some(first), Some(last)) = (parts.first(), parts.last()) else {
+            return None;
+        };
+        let opened = exchange_time(bar.t).map_or_else(|| bar.t.to_string(), |at| at.to_rfc3339());
+        if parts.len() != expected || first.t != bar.t {
+            debug!(
+                "Not checking the {} {} bar opening at {}: {} of its {} {} bars were seen",
+                bar.s,
+                bar.i,
+                opened,
+                parts.len(),
+                expected,
+                first.i
+            );
+            return None;
+        }
+
+        let high = parts.iter().map(|part| part.h).fold(f64::MIN, f64::max);
+        let low = parts.iter().map(|part| part.l).fold(f64::MAX, f64::min);
+        let volume: f64 = parts.iter().map(|part| part.v).sum();
+        let trades: i64 = parts.iter().map(|part| i64::from(part.n)).sum();
+        let mismatches: Vec<String> = [
+            ("open", bar.o, first.o),
+            ("high", bar.h, high),
+            ("low", bar.l, low),
+            ("close", bar.c, last.c),
+            ("volume", bar.v, volume),
+        ]
+        .into_iter()
+        .filter(|(_, reported, rebuilt)| !agrees(*reported, *rebuilt))
+        .map(|(field, reported, rebuilt)| format!("{} {} vs {}", field, reported, rebuilt))
+        .chain((i64::from(bar.n) != trades).then(|| format!("trades {} vs {}", bar.n, trades)))
+        .collect();
+        if mismatches.is_empty() {
+            debug!(
+                "The {} {} bar opening at {} matches its {} {} bars",
+                bar.s,
+                bar.i,
+                opened,
+                parts.len(),
+                first.i
+            );
+            return Some(mismatches);
+        }
+
+        counter!("hyperliquid_candle_mismatches_total", "interval" => bar.i.clone()).increment(1);
+        warn!(
+            "Candle mismatch: the {} {} bar opening at {} disagrees with its {} {} bars ({})",
+            bar.s,
+            bar.i,
+            opened,
+            parts.len(),
+            first.i,
+            mismatches.join(", ")
+        );
+        Some(mismatches)
+    }
+}
+
+/// Length of a bar in milliseconds; `None` for `1M` and anything unknown
+fn interval_ms(interval: &str) -> Option<i64> {
+    let unit = interval.chars().last()?;
+    let count: i64 = interval[..interval.len() - unit.len_utf8()].parse().ok()?;
+    let unit_ms = match unit {
+        'm' => 60_000,
+        'h' => 3_600_000,
+        'd' => 86_400_000,
+        'w' => 604_800_000,
+        _ => return None,
+    };
+    Some(count * unit_ms)
+}
+
+/// Equal up to the rounding of summing decimal volumes as floats
+fn agrees(reported: f64, rebuilt: f64) -> bool {
+    (reported - rebuilt).abs() <= reported.abs().max(1.0) * 1e-9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN: i64 = 1_760_000_400_000;
+
+    /// A bar of `interval` opening `index` bars after `OPEN`
+    fn bar(interval: &str, index: i64, ohlc: [f64; 4], v: f64, n: i32) -> Candle {
+        let length = interval_ms(interval).unwrap();
+        let t = OPEN + index * length;
+        Candle {
+            t,
+            close_time: t + length - 1,
+            s: Symbol::from("BTC"),
+            i: interval.to_string(),
+            o: ohlc[0],
+            h: ohlc[1],
+            l: ohlc[2],
+            c: ohlc[3],
+            v,
+            n,
+        }
+    }
+
+    fn flat(interval: &str, index: i64) -> Candle {
+        bar(interval, index, [100.0; 4], 1.0, 1)
+    }
+
+    fn summary(updates: &[CandleUpdate]) -> Vec<(&str, i64, bool)> {
+        updates
+            .iter()
+            .map(|update| {
+                (
+                    update.candle.i.as_str(),
+                    (update.candle.t - OPEN) / interval_ms(&update.candle.i).unwrap(),
+                    update.closed,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn interval_lengths() {
+        assert_eq!(interval_ms("1m"), Some(60_000));
+        assert_eq!(interval_ms("15m"), Some(900_000));
+        assert_eq!(interval_ms("4h"), Some(14_400_000));
+        assert_eq!(interval_ms("1d"), Some(86_400_000));
+        assert_eq!(interval_ms("1w"), Some(604_800_000));
+        for interval in ["1M", "m", "", "1x", "-"] {
+            assert_eq!(interval_ms(interval), None, "{}", interval);
+        }
+    }
+
+    #[test]
+    fn a_bar_closes_when_the_next_one_opens() {
+        let mut tracker = CandleTracker::new(CandleMode::All);
+        assert_eq!(summary(&tracker.observe(flat("1m", 0))), [("1m", 0, false)]);
+        let mut latest = flat("1m", 0);
+        latest.c = 101.0;
+        assert_eq!(summary(&tracker.observe(latest)), [("1m", 0, false)]);
+
+        let updates = tracker.observe(flat("1m", 1));
+        assert_eq!(summary(&updates), [("1m", 0, true), ("1m", 1, false)]);
+        // The closed bar carries its last state
+        assert_eq!(updates[0].candle.c, 101.0);
+
+        // A resent update to a closed bar is dropped
+        assert!(tracker.observe(flat("1m", 0)).is_empty());
+    }
+
+    #[test]
+    fn closed_only_emits_each_bar_once() {
+        let mut tracker = CandleTracker::new(CandleMode::ClosedOnly);
+        assert!(tracker.observe(flat("1m", 0)).is_empty());
+        assert!(tracker.observe(flat("1m", 0)).is_empty());
+        assert_eq!(summary(&tracker.observe(flat("1m", 1))), [("1m", 0, true)]);
+    }
+
+    #[test]
+    fn intervals_are_tracked_separately() {
+        let mut tracker = CandleTracker::new(CandleMode::ClosedOnly);
+        tracker.observe(flat("1m", 0));
+        tracker.observe(flat("5m", 0));
+        for index in 1..5 {
+            assert_eq!(
+                summary(&tracker.observe(flat("1m", index))),
+                [("1m", index - 1, true)]
+            );
+        }
+        assert_eq!(summary(&tracker.observe(flat("5m", 1))), [("5m", 0, true)]);
+    }
+
+    fn check_with(short: &[Candle]) -> IntervalCheck {
+        let mut check = IntervalCheck::default();
+        check.add_interval("1m");
+        check.add_interval("5m");
+        check.short.extend(short.iter().cloned());
+        check
+    }
+
+    #[test]
+    fn a_longer_bar_is_rebuilt_from_the_short_bars_inside_it() {
+        let short = [
+            bar("1m", 0, [100.0, 103.0, 99.0, 102.0], 1.5, 3),
+            bar("1m", 1, [102.0, 104.0, 101.0, 101.0], 0.25, 1),
+            bar("1m", 2, [101.0, 101.0, 97.0, 98.0], 2.0, 4),
+            bar("1m", 3, [98.0, 100.0, 98.0, 99.5], 0.1, 1),
+            bar("1m", 4, [99.5, 100.0, 99.0, 100.0], 0.15, 2),
+            // The next 5m bar's first minute is not part of it
+            bar("1m", 5, [100.0, 120.0, 90.0, 110.0], 9.0, 9),
+        ];
+        let check = check_with(&short);
+        let long = bar("5m", 0, [100.0, 104.0, 97.0, 100.0], 4.0, 11);
+        assert_eq!(check.compare(&long, 60_000), Some(vec![]));
+
+        let mut wrong = long.clone();
+        wrong.h = 105.0;
+        wrong.n = 12;
+        assert_eq!(
+            check.compare(&wrong, 60_000),
+            Some(vec![
+                "high 105 vs 104".to_string(),
+                "trades 12 vs 11".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn partly_covered_bars_are_not_checked() {
+        // The first minute was missed, as when subscribing mid-bar
+        let short: Vec<Candle> = (1..5).map(|index| flat("1m", index)).collect();
+        let long = bar("5m", 0, [100.0; 4], 5.0, 5);
+        assert_eq!(check_with(&short).compare(&long, 60_000), None);
+        // Nor is one across a gap
+        let short: Vec<Candle> = [0, 1, 3, 4].map(|index| flat("1m", index)).into();
+        assert_eq!(check_with(&short).compare(&long, 60_000), None);
+    }
+
+    #[test]
+    fn a_closed_longer_bar_waits_for_its_last_short_bar() {
+        let mut check = IntervalCheck::default();
+        check.add_interval("1m");
+        check.add_interval("5m");
+        for index in 0..4 {
+            check.closed(&flat("1m", index));
+        }
+        check.closed(&bar("5m", 0, [100.0; 4], 5.0, 5));
+        assert_eq!(check.pending.len(), 1);
+        check.closed(&flat("1m", 4));
+        assert!(check.pending.is_empty());
+    }
+
+    #[test]
+    fn a_new_shortest_interval_drops_the_kept_bars() {
+        let mut check = IntervalCheck::default();
+        check.add_interval("5m");
+        check.add_interval("15m");
+        check.closed(&flat("5m", 0));
+        assert_eq!(check.short.len(), 1);
+        check.add_interval("1h");
+        assert_eq!(check.short.len(), 1);
+        check.add_interval("1m");
+        assert!(check.short.is_empty());
+    }
+}
@@ -1,22 +1,361 @@
 /// file: src/cli.rs
 /// description: Command-line interface definitions and argument parsing using clap
 /// reference: https://docs.rs/clap/latest/clap/
-use clap::Parser;
+use crate::candles::CandleMode;
+use crate::compat::ApiCompat;
+use crate::filter::{SideFilter, TradeFilter};
+use crate::log_file::LogRotation;
+use crate::monitoring::MetricsBackend;
+use crate::parse_pool::DEFAULT_PARSE_WORKERS;
+use crate::recent::DEFAULT_RECENT_TRADES;
+use crate::replay::{ReplaySpeed, SeekPoint};
+use crate::schedule::ScheduleTime;
+use crate::tracing_setup::{LogTarget, SampleRate};
+use clap::{ArgAction, ArgGroup, Parser, Subcommand};
+use clap_complete::Shell;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
     name = "rs-hyperliquid",
     about = "websocket client for hyperliquid trading data with tui-ready output",
-    version
+    version,
+    args_conflicts_with_subcommands = true
 )]
 pub struct Args {
-    /// The cryptocurrency symbol to subscribe to (e.g., SOL, BTC, ETH)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Flags for the default `stream` command, so `rs-hyperliquid --coin BTC` keeps working
+    #[command(flatten)]
+    pub stream: StreamArgs,
+}
+
+impl Args {
+    /// The selected subcommand, with bare flags treated as `stream`
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Stream(self.stream))
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Stream live market data to the terminal (default)
+    Stream(StreamArgs),
+    /// Stream while recording every raw message to a JSONL file
+    Record(RecordArgs),
+    /// Play back a recording through the normal output at its original pace
+    Replay(ReplayArgs),
+    /// Print every trade in a recording in the selected format, as fast as possible
+    Export(FileArgs),
+    /// Fetch a one-off order book snapshot over REST
+    Snapshot(SnapshotArgs),
+    /// Show an account's open orders over REST
+    Orders(OrdersArgs),
+    /// List the exchange's perpetual or spot markets over REST
+    Info(InfoArgs),
+    /// Print a shell completion script, with `--coin` values from the exchange's listed markets
+    Completions(CompletionsArgs),
+    /// Print a Grafana dashboard or Prometheus alert rules for the exported metrics
+    Metrics(MetricsArgs),
+    /// Interactively create a config file
+    Init(InitArgs),
+    /// Install, remove, or run the collector as a Windows service
+    #[cfg(all(windows, feature = "windows-service"))]
+    Service(ServiceArgs),
+}
+
+#[cfg(all(windows, feature = "windows-service"))]
+#[derive(clap::Args, Debug, Clone)]
+pub struct ServiceArgs {
+    #[command(subcommand)]
+    pub action: ServiceAction,
+}
+
+#[cfg(all(windows, feature = "windows-service"))]
+#[derive(Subcommand, Debug, Clone)]
+pub enum ServiceAction {
+    /// Register an auto-start service that streams with the options after `--`
+    Install {
+        /// Service name, also used by `uninstall`
+        #[arg(long, default_value = crate::service::DEFAULT_SERVICE_NAME)]
+        name: String,
+
+        /// Stream options for the service, e.g. -- --coin BTC --silent --output C:\data\btc.log
+        #[arg(last = true, value_name = "OPTIONS")]
+        options: Vec<std::ffi::OsString>,
+    },
+    /// Stop and remove the service
+    Uninstall {
+        #[arg(long, default_value = crate::service::DEFAULT_SERVICE_NAME)]
+        name: String,
+    },
+    /// Entry point used by the service manager; fails when started from a console
+    Run {
+        #[arg(long, default_value = crate::service::DEFAULT_SERVICE_NAME)]
+        name: String,
+
+        #[command(flatten)]
+        stream: StreamArgs,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct InitArgs {
+    /// Where to write the config [default: ~/.config/rs-hyperliquid/config.toml]
+    pub path: Option<PathBuf>,
+
+    /// Replace an existing file without asking
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CompletionsArgs {
+    /// Shell to generate the script for: bash, zsh, fish, elvish, or powershell
+    pub shell: Shell,
+
+    /// WebSocket endpoint whose host serves the coin list
+    #[arg(
+        short,
+        long,
+        env = "HYPERLIQUID_URL",
+        default_value = "wss://api.hyperliquid.xyz/ws"
+    )]
+    pub url: String,
+
+    /// Use the cached coin list instead of fetching a fresh one
+    #[arg(long)]
+    pub offline: bool,
+}
+
+/// Where one-off REST queries go and how their answer is printed
+#[derive(clap::Args, Debug, Clone)]
+pub struct RestArgs {
+    /// WebSocket endpoint whose host serves the info requests
+    #[arg(
+        short,
+        long,
+        env = "HYPERLIQUID_URL",
+        default_value = "wss://api.hyperliquid.xyz/ws"
+    )]
+    pub url: String,
+
+    /// Give up on the request after this many seconds
+    #[arg(long, default_value = "10", value_name = "SECONDS")]
+    pub timeout: u64,
+
+    /// Print the exchange's JSON response instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct SnapshotArgs {
+    /// Coin whose order book to fetch
     #[arg(short, long, default_value = "BTC")]
     pub coin: String,
 
-    /// WebSocket endpoint URL
-    #[arg(short, long, default_value = "wss://api.hyperliquid.xyz/ws")]
-    pub url: String,
+    /// Price levels to show on each side
+    #[arg(long, default_value = "10")]
+    pub depth: usize,
+
+    #[command(flatten)]
+    pub rest: RestArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct OrdersArgs {
+    /// Wallet address (0x...) whose open orders to list
+    #[arg(long, env = "HYPERLIQUID_USER", value_name = "ADDRESS")]
+    pub user: String,
+
+    #[command(flatten)]
+    pub rest: RestArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct InfoArgs {
+    /// List spot pairs instead of perpetuals
+    #[arg(long)]
+    pub spot: bool,
+
+    /// Include delisted perpetuals
+    #[arg(long)]
+    pub delisted: bool,
+
+    #[command(flatten)]
+    pub rest: RestArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct MetricsArgs {
+    #[command(subcommand)]
+    pub action: MetricsAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum MetricsAction {
+    /// Grafana dashboard JSON, ready to import
+    Dashboard {
+        /// Prometheus datasource UID to bake in, for provisioning; asked for on import otherwise
+        #[arg(long, value_name = "UID")]
+        datasource_uid: Option<String>,
+    },
+    /// Prometheus alerting rules in YAML
+    Alerts,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct RecordArgs {
+    /// JSONL file to record into
+    pub path: PathBuf,
+
+    #[command(flatten)]
+    pub stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ReplayArgs {
+    /// Recording written by `record` or `--record`
+    pub path: PathBuf,
+
+    /// Playback rate: a factor such as 2 or 0.5, or max for no pauses
+    #[arg(long, default_value = "1")]
+    pub speed: ReplaySpeed,
+
+    /// Start over when the recording ends, until quit, --max-trades, or --duration
+    #[arg(long = "loop")]
+    pub looped: bool,
+
+    /// Skip to +OFFSET from the start (e.g. +5m), an RFC3339 time, or unix milliseconds
+    #[arg(long, value_name = "TIMESTAMP")]
+    pub seek: Option<SeekPoint>,
+
+    #[command(flatten)]
+    pub stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct FileArgs {
+    /// Recording written by `record` or `--record`
+    pub path: PathBuf,
+
+    #[command(flatten)]
+    pub stream: StreamArgs,
+}
+
+/// Connection and presentation flags shared by the streaming and playback commands
+#[derive(clap::Args, Debug, Clone)]
+// The flags that each pick what to stream instead of trades are exclusive, so combining two is an
+// error rather than one silently winning; --heatmap reads the --book-view stream and may join it
+#[command(group(
+    ArgGroup::new("mode")
+        .multiple(false)
+        .args(["book_view", "ticker", "bbo", "all_mids", "user_fills", "user_events", "candles"])
+))]
+pub struct StreamArgs {
+    /// TOML config file; defaults to ./hyperliquid.toml, then ~/.config/rs-hyperliquid/config.toml
+    #[arg(long, env = "HYPERLIQUID_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// The cryptocurrency symbol to subscribe to (e.g., SOL, BTC, ETH)
+    #[arg(short, long, env = "HYPERLIQUID_COIN", default_value = "BTC")]
+    pub coin: String,
+
+    /// Comma-separated channels: trades, bbo, l2Book, candle for the coin; mids for every coin;
+    /// fills, orders, funding, events for --user; blocks, txs from the explorer
+    #[arg(long, env = "HYPERLIQUID_SUBSCRIBE")]
+    pub subscribe: Option<String>,
+
+    /// Candle bar length for the coin (1m through 1M), adding the candle channel; repeat or
+    /// comma-separate for several at once
+    #[arg(long = "interval", value_name = "INTERVAL", value_delimiter = ',')]
+    pub intervals: Vec<String>,
+
+    /// Wallet address (0x...) for user-scoped channels; alone it streams fills, orders, and funding
+    #[arg(long, env = "HYPERLIQUID_USER")]
+    pub user: Option<String>,
+
+    /// Stream only the --user account's fills, in a table with closed PnL and fees
+    #[arg(long)]
+    pub user_fills: bool,
+
+    /// Stream the --user account's fills, funding payments, liquidations, and orders canceled by
+    /// the exchange from the userEvents channel
+    #[arg(long)]
+    pub user_events: bool,
+
+    /// Treat --coin as a spot pair (PURR/USDC, or HYPE for HYPE/USDC) and look up its exchange id
+    #[arg(long)]
+    pub spot: bool,
+
+    /// Skip checking the coin against the exchange's listed markets before connecting
+    #[arg(long)]
+    pub no_validate: bool,
+
+    /// Output layout: auto, split (pinned BBO above scrolling trades), or scroll
+    #[arg(long, default_value = "auto")]
+    pub layout: String,
+
+    /// Pin a status bar with connection state, uptime, reconnects, and message rate
+    #[arg(long)]
+    pub status_bar: bool,
+
+    /// Replace Unicode box drawing, arrows, and block characters with plain ASCII
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Draw full-screen views on the main screen instead of the alternate screen
+    #[arg(long)]
+    pub no_alt_screen: bool,
+
+    /// In interactive mode, scroll the trade history with the mouse wheel, sort a history page by
+    /// clicking its column labels, and click a coin in the --all-mids grid to switch to it
+    #[arg(long)]
+    pub mouse: bool,
+
+    /// Color theme: dark, light, high-contrast, or a path to a TOML theme file
+    #[arg(long, default_value = "dark")]
+    pub theme: String,
+
+    /// Record every raw message as JSONL to this file
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Start a new timestamped recording file every N seconds (0 = never)
+    #[arg(long, default_value = "0")]
+    pub record_rotate_secs: u64,
+
+    /// Serve a browser dashboard with live trades and status on this port
+    #[arg(long)]
+    pub serve_http: Option<u16>,
+
+    /// Serve /healthz and /status on this port for load balancers and monitoring
+    #[arg(long, env = "HYPERLIQUID_HEALTH_PORT")]
+    pub health_port: Option<u16>,
+
+    /// Cap estimated memory for queues and buffers, evicting and dropping past it (0 = no cap)
+    #[arg(long, value_name = "MB", default_value_t = 0)]
+    pub memory_budget_mb: usize,
+
+    /// Trades kept for dashboard viewers and /status that attach mid-session
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_RECENT_TRADES)]
+    pub recent_trades: usize,
+
+    /// Threads that parse allMids and l2Book frames off the read loop (0 = parse inline)
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_PARSE_WORKERS)]
+    pub parse_workers: usize,
+
+    /// WebSocket endpoint URL [default: the --network endpoint, wss://api.hyperliquid.xyz/ws]
+    #[arg(short, long, env = "HYPERLIQUID_URL")]
+    pub url: Option<String>,
+
+    /// Network profile, mainnet or testnet; picks the default URL and flags a mismatched --url
+    #[arg(long, env = "HYPERLIQUID_NETWORK")]
+    pub network: Option<String>,
 
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info")]
@@ -26,34 +365,203 @@ pub struct Args {
     #[arg(long)]
     pub json_logs: bool,
 
-    /// Enable metrics server
+    /// Write logs to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Where logs go: stdout, file, journald, or syslog (default: file with --log-file, else stdout)
+    #[arg(long, env = "HYPERLIQUID_LOG_TARGET", value_name = "TARGET")]
+    pub log_target: Option<LogTarget>,
+
+    /// Start a new log file daily, hourly, or never (UTC)
+    #[arg(long, default_value = "daily", value_name = "WHEN")]
+    pub log_rotate: LogRotation,
+
+    /// Also start a new log file before it grows past this many megabytes (0 = no limit)
+    #[arg(long, default_value = "0", value_name = "MB")]
+    pub log_max_mb: u64,
+
+    /// Rotated log files to keep; older ones are deleted (0 = keep all)
+    #[arg(long, default_value = "7", value_name = "N")]
+    pub log_keep: usize,
+
+    /// Delete rotated log files older than this many days (0 = no age limit)
+    #[arg(long, default_value = "0", value_name = "DAYS")]
+    pub log_max_age: u64,
+
+    /// Gzip rotated log files
     #[arg(long)]
+    pub log_compress: bool,
+
+    /// Log a throughput summary every N seconds (0 = off)
+    #[arg(
+        long,
+        env = "HYPERLIQUID_LOG_STATS",
+        default_value = "0",
+        value_name = "SECS"
+    )]
+    pub log_stats: u64,
+
+    /// Enable metrics server
+    #[arg(long, env = "HYPERLIQUID_METRICS")]
     pub metrics: bool,
 
     /// Metrics server port
-    #[arg(long, default_value = "9090")]
+    #[arg(long, env = "HYPERLIQUID_METRICS_PORT", default_value = "9090")]
     pub metrics_port: u16,
 
+    /// Metrics server bind address, e.g. 127.0.0.1 to keep it off the network
+    #[arg(
+        long,
+        env = "HYPERLIQUID_METRICS_BIND",
+        default_value = "0.0.0.0",
+        value_name = "ADDR"
+    )]
+    pub metrics_bind: IpAddr,
+
+    /// Require HTTP basic auth for /metrics
+    #[arg(
+        long,
+        env = "HYPERLIQUID_METRICS_BASIC_AUTH",
+        hide_env_values = true,
+        value_name = "USER:PASSWORD"
+    )]
+    pub metrics_basic_auth: Option<String>,
+
+    /// Require this bearer token for /metrics
+    #[arg(
+        long,
+        env = "HYPERLIQUID_METRICS_TOKEN",
+        hide_env_values = true,
+        value_name = "TOKEN"
+    )]
+    pub metrics_token: Option<String>,
+
+    /// Metrics backend for --metrics: prometheus (scrape), statsd (UDP push), or pushgateway
+    #[arg(
+        long,
+        env = "HYPERLIQUID_METRICS_BACKEND",
+        default_value = "prometheus",
+        value_name = "BACKEND"
+    )]
+    pub metrics_backend: MetricsBackend,
+
+    /// Pushgateway for --metrics-backend pushgateway; user:password@ in the URL sends basic auth
+    #[arg(
+        long,
+        env = "HYPERLIQUID_PUSHGATEWAY_URL",
+        hide_env_values = true,
+        default_value = "http://127.0.0.1:9091",
+        value_name = "URL"
+    )]
+    pub pushgateway_url: String,
+
+    /// Job name the pushed metrics are grouped under
+    #[arg(
+        long,
+        env = "HYPERLIQUID_PUSHGATEWAY_JOB",
+        default_value = "rs-hyperliquid",
+        value_name = "JOB"
+    )]
+    pub pushgateway_job: String,
+
+    /// Also export tokio runtime metrics (workers, tasks, queue depth, busy ratio)
+    #[arg(long, env = "HYPERLIQUID_RUNTIME_METRICS")]
+    pub runtime_metrics: bool,
+
+    /// StatsD/DogStatsD agent address for --metrics-backend statsd
+    #[arg(
+        long,
+        env = "HYPERLIQUID_STATSD_ADDR",
+        default_value = "127.0.0.1:8125",
+        value_name = "HOST:PORT"
+    )]
+    pub statsd_addr: String,
+
+    /// Export metrics and spans over OTLP, configured by the standard OTEL_* variables
+    #[cfg(feature = "otlp")]
+    #[arg(long, env = "HYPERLIQUID_OTLP")]
+    pub otlp: bool,
+
+    /// Share of messages traced with channel, coin, and parse, handle, and format timings (0-1)
+    #[arg(
+        long,
+        env = "HYPERLIQUID_TRACE_SAMPLE_RATE",
+        default_value = "0",
+        value_name = "RATE"
+    )]
+    pub trace_sample_rate: SampleRate,
+
+    /// Report panics and fatal errors to this Sentry DSN
+    #[cfg(feature = "sentry")]
+    #[arg(long, env = "SENTRY_DSN", hide_env_values = true, value_name = "DSN")]
+    pub sentry_dsn: Option<String>,
+
     /// Connection timeout in seconds
-    #[arg(long, default_value = "30")]
+    #[arg(long, env = "HYPERLIQUID_TIMEOUT", default_value = "30")]
     pub timeout: u64,
 
     /// Reconnection delay in seconds
-    #[arg(long, default_value = "5")]
+    #[arg(long, env = "HYPERLIQUID_RECONNECT_DELAY", default_value = "5")]
     pub reconnect_delay: u64,
 
     /// Maximum number of reconnection attempts (0 for unlimited)
-    #[arg(long, default_value = "0")]
+    #[arg(long, env = "HYPERLIQUID_MAX_RECONNECTS", default_value = "0")]
     pub max_reconnects: u32,
 
+    /// Extra handshake header as 'Name: value' (repeatable), e.g. for an authenticating gateway
+    #[arg(long = "header", value_name = "NAME: VALUE")]
+    pub headers: Vec<String>,
+
+    /// User-Agent sent with the handshake and info requests, `rs-hyperliquid/<version>` by default
+    #[arg(long, env = "HYPERLIQUID_USER_AGENT")]
+    pub user_agent: Option<String>,
+
+    /// Warn when the local clock is more than this many milliseconds off exchange time (0 = off)
+    #[arg(long, value_name = "MS", default_value = "1000")]
+    pub max_clock_skew: u64,
+
+    /// Drop data messages with fields the client does not know, logging each new field once
+    #[arg(long, env = "HYPERLIQUID_STRICT_SCHEMA")]
+    pub strict_schema: bool,
+
+    /// Message format variations to normalize: auto (all known), off, or a list of envelope,
+    /// field-names, levels, numbers
+    #[arg(
+        long,
+        value_name = "MODE",
+        default_value = "auto",
+        env = "HYPERLIQUID_API_COMPAT"
+    )]
+    pub api_compat: ApiCompat,
+
+    /// Count a data gap when a subscribed stream sends nothing for this many seconds while
+    /// connected (0 = off)
+    #[arg(long, value_name = "SECS", default_value = "0")]
+    pub max_silence: u64,
+
+    /// Stream only candles for the --interval bar lengths, unless --subscribe picks channels.
+    /// MODE is all (each update, then every bar again once it closes, the default) or
+    /// closed-only (one final record per bar)
+    #[arg(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "all")]
+    pub candles: Option<CandleMode>,
+
     /// Enable detailed trade logging with buyer/seller info
     #[arg(long)]
     pub verbose_trades: bool,
 
-    /// Output format: table, csv, json, minimal
+    /// Output format: table, csv, json, json-pretty, minimal, events
     #[arg(long, default_value = "table")]
     pub format: String,
 
+    /// Write the formatted trade stream to this file; status lines stay on the terminal
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Name JSON trade fields so every key works as a plain jq path (`.seq` instead of `.["#"]`)
+    #[arg(long)]
+    pub jq_friendly: bool,
+
     /// Disable colored output (useful for piping to files)
     #[arg(long)]
     pub no_color: bool,
@@ -62,9 +570,13 @@ pub struct Args {
     #[arg(long)]
     pub csv_export: bool,
 
-    /// Quiet mode - minimal output for TUI integration
+    /// Quiet mode: -q hides banners and status lines, -qq also hides headers and the summary
+    #[arg(short = 'q', long, action = ArgAction::Count)]
+    pub quiet: u8,
+
+    /// Print errors only; a file given to --output still receives the trade stream
     #[arg(long)]
-    pub quiet: bool,
+    pub silent: bool,
 
     /// Show only price updates (for price monitoring)
     #[arg(long)]
@@ -73,4 +585,203 @@ pub struct Args {
     /// Maximum number of trades to display (0 for unlimited)
     #[arg(long, default_value = "0")]
     pub max_trades: u64,
+
+    /// Drop trades smaller than this size before they reach the output
+    #[arg(long, value_name = "SIZE")]
+    pub min_size: Option<f64>,
+
+    /// Drop trades with a notional value (price x size) below this amount
+    #[arg(long, value_name = "USD")]
+    pub min_notional: Option<f64>,
+
+    /// Only pass trades from this aggressor side: buy, sell, or all
+    #[arg(long, default_value = "all")]
+    pub side: SideFilter,
+
+    /// Stop after this much wall-clock time, e.g. 90s, 15m, 1h30m (plain numbers are seconds)
+    #[arg(long, value_parser = parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Start collecting at this time (RFC3339 or local HH:MM); connects 30 seconds early
+    #[arg(long, value_name = "TIME")]
+    pub start_at: Option<ScheduleTime>,
+
+    /// Stop cleanly at this time (RFC3339 or local HH:MM, the next occurrence after --start-at)
+    #[arg(long, value_name = "TIME")]
+    pub stop_at: Option<ScheduleTime>,
+
+    /// Timezone for displayed timestamps: UTC, local, or an IANA name (e.g. Europe/London)
+    #[arg(long, default_value = "local")]
+    pub tz: String,
+
+    /// strftime-style format for displayed timestamps (defaults depend on the output format)
+    #[arg(long)]
+    pub time_format: Option<String>,
+
+    /// Digit grouping in table and terminal views: plain, en, de, fr, ch, a locale, or auto
+    #[arg(long, value_name = "LOCALE", default_value = "plain")]
+    pub number_locale: String,
+
+    /// Decimal places for prices, for every coin (N) or one (COIN=N); repeat or comma-separate
+    #[arg(long, value_name = "[COIN=]N", value_delimiter = ',')]
+    pub price_decimals: Vec<String>,
+
+    /// Decimal places for sizes, for every coin (N) or one (COIN=N); repeat or comma-separate
+    #[arg(long, value_name = "[COIN=]N", value_delimiter = ',')]
+    pub size_decimals: Vec<String>,
+
+    /// Output template for minimal and price-only modes, e.g. '{time} {coin} {side} {px} {sz}'
+    #[arg(long)]
+    pub template: Option<String>,
+
+    /// Stream l2Book updates instead of trades: a ladder redrawn in place, or records in the
+    /// json and csv formats
+    #[arg(long, visible_alias = "book")]
+    pub book_view: bool,
+
+    /// Number of price levels per side shown in the book ladder
+    #[arg(long, default_value = "10")]
+    pub book_depth: usize,
+
+    /// Aggregate l2Book levels to this many significant figures (2-5) to cut bandwidth
+    #[arg(long, value_name = "N")]
+    pub book_sigfigs: Option<u32>,
+
+    /// Aggregation step mantissa (1, 2, or 5) at --book-sigfigs 5
+    #[arg(long, value_name = "N", requires = "book_sigfigs")]
+    pub book_mantissa: Option<u32>,
+
+    /// Render a scrolling depth heatmap (price by time) from l2Book updates
+    #[arg(
+        long,
+        conflicts_with_all = ["ticker", "bbo", "all_mids", "user_fills", "user_events", "candles"]
+    )]
+    pub heatmap: bool,
+
+    /// Number of snapshots (columns) kept in the depth heatmap
+    #[arg(long, default_value = "60")]
+    pub heatmap_width: usize,
+
+    /// Number of price rows in the depth heatmap
+    #[arg(long, default_value = "24")]
+    pub heatmap_rows: usize,
+
+    /// Rewrite one line in place with the latest price, change, and spread
+    #[arg(long)]
+    pub ticker: bool,
+
+    /// Stream best bid and offer updates instead of trades, with the spread in every format
+    #[arg(long)]
+    pub bbo: bool,
+
+    /// Stream the mid price of every coin instead of trades, refreshed in place on a terminal
+    #[arg(long)]
+    pub all_mids: bool,
+
+    /// Refresh a single line with a sparkline of recent trade prices
+    #[arg(long)]
+    pub sparkline: bool,
+
+    /// Enable keyboard controls (pause, columns, min-size filter, coin switch, quit)
+    #[arg(long)]
+    pub interactive: bool,
+
+    /// Number of trades kept in the scrollback buffer in interactive mode
+    #[arg(long, default_value = "10000")]
+    pub history_size: usize,
+
+    /// Number of recent prices kept in sparklines
+    #[arg(long, default_value = "40")]
+    pub sparkline_len: usize,
+
+    /// Print a rolling VWAP / buy-sell / rate footer every N table rows (0 disables)
+    #[arg(long, value_name = "N", default_value = "0")]
+    pub stats_every: usize,
+
+    /// Highlight table rows whose notional value is at least this amount
+    #[arg(long, value_name = "USD")]
+    pub highlight_notional: Option<f64>,
+
+    /// Marker printed before highlighted rows (with --highlight-notional)
+    #[arg(long, value_name = "TEXT")]
+    pub highlight_marker: Option<String>,
+}
+
+impl StreamArgs {
+    /// Filter from `--min-size`, `--min-notional`, and `--side`, applied before events are emitted
+    pub fn trade_filter(&self) -> TradeFilter {
+        TradeFilter {
+            min_size: self.min_size,
+            min_notional: self.min_notional,
+            side: self.side,
+        }
+    }
+}
+
+/// Parse `90`, `90s`, `15m`, `2h`, or combinations such as `1h30m`; zero is rejected
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        if secs == 0 {
+            return Err(format!("duration '{}' must be more than zero", value));
+        }
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => {
+                return Err(format!(
+                    "unknown unit '{}' in '{}', use s, m, h, or d",
+                    ch, value
+                ));
+            }
+        };
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| format!("missing number before '{}' in '{}'", ch, value))?;
+        total = total.saturating_add(amount.saturating_mul(unit));
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err(format!(
+            "invalid duration '{}', expected e.g. 90s, 15m, or 1h30m",
+            value
+        ));
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        for (value, secs) in [("90", 90), ("90s", 90), ("1h30m", 5400), ("2d", 172_800)] {
+            assert_eq!(
+                parse_duration(value),
+                Ok(Duration::from_secs(secs)),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_zero_unknown_units_and_trailing_numbers() {
+        for value in ["0", "0s", "0h0m", "5x", "10m5", "m", ""] {
+            assert!(parse_duration(value).is_err(), "{} parsed", value);
+        }
+    }
 }
@@ -2,94 +2,240 @@
 /// description: WebSocket client implementation for Hyperliquid exchange data streaming
 /// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/websocket
 use crate::{
+    budget::{Buffer, MemoryBudget, event_bytes, line_bytes},
+    candles::{CandleMode, CandleTracker},
     client_state::SharedClientState,
-    config::Config,
+    clock::{ClockSkew, ClockSkewMonitor},
+    compat::{ApiCompat, Decoded},
+    config::{Channel, Config, SubscriptionSink},
     error::HyperliquidError,
-    events::{ClientEvent, EventSender},
+    events::{ClientCommand, ClientEvent, CommandReceiver, EventSender},
+    filter::TradeFilter,
+    gaps::GapDetector,
+    monitoring::{
+        ChannelDepth, DisconnectReason, EventConsumer, GapKind, MessageHistograms, record_data_gap,
+        record_dropped_events, record_reconnect, record_trade_delay,
+    },
+    parse_pool::{ParsePool, Parsed, Submission},
+    recorder::{RecordSender, RecordingProgress, record_line},
+    schema::SchemaChecker,
+    symbol::Symbol,
+    systemd::SystemdNotifier,
+    time::{Delay, exchange_time},
+    tracing_setup::{SampleRate, SpanSampler},
+    transport::{connect, header_lines},
     types::{
-        AllMids, Bbo, Book, Candle, Notification, SubscriptionRequest, Trade, UserEvent,
+        AllMids, Bbo, Book, Candle, Notification, SubscriptionRequest, Trade, TradeRef, UserEvent,
         WebSocketMessage,
     },
 };
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use fastwebsockets::{Frame, OpCode, WebSocket};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
-use tokio::net::TcpStream;
-use tokio::time::{sleep, timeout};
-use tracing::{debug, error, info, trace, warn};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::time::{Instant, sleep, timeout};
+use tracing::{Instrument, Span, debug, error, field, info, info_span, trace, warn};
 
-enum MaybeTlsStream {
-    Tls(Box<tokio_rustls::client::TlsStream<TcpStream>>),
-    Plain(TcpStream),
+pub struct HyperliquidWebSocketClient {
+    pub config: Arc<Config>,
+    event_sender: EventSender,
+    command_receiver: Option<CommandReceiver>,
+    active_coin: String,
+    pending_messages: u64,
+    last_state_flush: Instant,
+    recorder: Option<(RecordSender, Arc<RecordingProgress>)>,
+    trade_filter: TradeFilter,
+    has_connected: bool,
+    collect_from: Option<DateTime<Utc>>,
+    coin_label: Option<(String, Symbol)>,
+    clock_skew: Option<ClockSkewMonitor>,
+    gaps: GapDetector,
+    candles: CandleTracker,
+    span_sampler: SpanSampler,
+    /// Set by `--strict-schema`
+    schema: Option<SchemaChecker>,
+    compat: ApiCompat,
+    message_histograms: MessageHistograms,
+    event_depth: ChannelDepth,
+    record_depth: ChannelDepth,
+    /// Flushed to `ClientState::last_seen` with the message count
+    last_seen: HashMap<String, HashMap<String, Instant>>,
+    /// Taken once the first subscription is confirmed and READY=1 is sent
+    systemd: Option<SystemdNotifier>,
+    last_ping: Instant,
+    /// Send time of the ping still waiting for its pong
+    ping_sent: Option<Instant>,
+    /// The server pinged and is owed a pong
+    pong_due: bool,
+    /// Set by `--memory-budget-mb`; covers the event and recorder queues and the dedup map
+    budget: Option<Arc<MemoryBudget>>,
+    /// Set by `--parse-workers`; decodes large frames while the loop keeps reading
+    parse_pool: Option<ParsePool>,
+    pub state: SharedClientState,
 }
 
-impl tokio::io::AsyncRead for MaybeTlsStream {
-    fn poll_read(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-        buf: &mut tokio::io::ReadBuf<'_>,
-    ) -> std::task::Poll<std::io::Result<()>> {
-        match &mut *self {
-            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_read(cx, buf),
-            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_read(cx, buf),
-        }
+// Message activity is batched into the shared state to keep the lock off the hot path
+const STATE_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+// Well inside the 60 seconds after which the server drops a client that has sent nothing
+const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+const PING_MESSAGE: &str = r#"{"method":"ping"}"#;
+const PONG_MESSAGE: &str = r#"{"method":"pong"}"#;
+
+/// The next frame back from the pool; never ready without one
+async fn next_parsed(pool: &mut Option<ParsePool>) -> Option<Parsed> {
+    match pool {
+        Some(pool) => pool.next().await,
+        None => std::future::pending().await,
     }
 }
 
-impl tokio::io::AsyncWrite for MaybeTlsStream {
-    fn poll_write(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-        buf: &[u8],
-    ) -> std::task::Poll<std::io::Result<usize>> {
-        match &mut *self {
-            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_write(cx, buf),
-            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_write(cx, buf),
-        }
-    }
-
-    fn poll_flush(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<std::io::Result<()>> {
-        match &mut *self {
-            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_flush(cx),
-            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_flush(cx),
-        }
-    }
-
-    fn poll_shutdown(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<std::io::Result<()>> {
-        match &mut *self {
-            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_shutdown(cx),
-            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_shutdown(cx),
-        }
-    }
-}
-
-pub struct HyperliquidWebSocketClient {
-    pub config: Arc<Config>,
-    event_sender: EventSender,
-    pub state: SharedClientState,
+/// Messages sent but not yet received on a bounded channel
+fn queued<T>(sender: &tokio::sync::mpsc::Sender<T>) -> usize {
+    sender.max_capacity() - sender.capacity()
 }
 
 impl HyperliquidWebSocketClient {
     pub fn new(config: Arc<Config>, event_sender: EventSender, state: SharedClientState) -> Self {
+        let active_coin = config.subscription.coin.clone();
         Self {
             config,
             event_sender,
+            command_receiver: None,
+            active_coin,
+            pending_messages: 0,
+            last_state_flush: Instant::now(),
+            recorder: None,
+            trade_filter: TradeFilter::default(),
+            has_connected: false,
+            collect_from: None,
+            coin_label: None,
+            clock_skew: None,
+            gaps: GapDetector::default(),
+            candles: CandleTracker::default(),
+            span_sampler: SpanSampler::default(),
+            schema: None,
+            compat: ApiCompat::default(),
+            message_histograms: MessageHistograms::default(),
+            event_depth: ChannelDepth::new(EventConsumer::Ui),
+            record_depth: ChannelDepth::new(EventConsumer::Recorder),
+            last_seen: HashMap::new(),
+            systemd: None,
+            last_ping: Instant::now(),
+            ping_sent: None,
+            pong_due: false,
+            budget: None,
+            parse_pool: None,
             state,
         }
     }
 
+    /// Accept runtime commands (e.g. coin switches) from the UI
+    pub fn with_command_receiver(mut self, command_receiver: CommandReceiver) -> Self {
+        self.command_receiver = Some(command_receiver);
+        self
+    }
+
+    /// Persist every raw text frame through the recorder
+    pub fn with_recorder(mut self, sender: RecordSender, progress: Arc<RecordingProgress>) -> Self {
+        self.recorder = Some((sender, progress));
+        self
+    }
+
+    /// Drop queued events and raw frames instead of growing past `budget`
+    pub fn with_memory_budget(mut self, budget: Arc<MemoryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Parse `allMids` and `l2Book` frames on `pool` instead of in the read loop
+    pub fn with_parse_pool(mut self, pool: ParsePool) -> Self {
+        self.parse_pool = Some(pool);
+        self
+    }
+
+    /// Drop trades outside `filter` before they are sent to the UI
+    pub fn with_trade_filter(mut self, trade_filter: TradeFilter) -> Self {
+        self.trade_filter = trade_filter;
+        self
+    }
+
+    /// Keep the connection warm but drop data frames, recorded or not, until `start`
+    pub fn with_collect_from(mut self, start: Option<DateTime<Utc>>) -> Self {
+        self.collect_from = start;
+        self
+    }
+
+    /// Show `label` wherever the exchange sends `coin`, e.g. `HYPE/USDC` for spot pair `@107`
+    pub fn with_coin_label(mut self, coin: String, label: String) -> Self {
+        self.coin_label = Some((coin, Symbol::from(label)));
+        self
+    }
+
+    /// Count a data gap when a stream sends nothing for longer than `threshold` while connected
+    pub fn with_max_silence(mut self, threshold: std::time::Duration) -> Self {
+        self.gaps = GapDetector::new(Some(threshold));
+        self
+    }
+
+    /// Give this share of received frames a `message` span with channel, coin, and timings
+    pub fn with_trace_sampling(mut self, rate: SampleRate) -> Self {
+        self.span_sampler = SpanSampler::new(rate);
+        self
+    }
+
+    /// Which candle updates to emit: every one, or only each bar's final state
+    pub fn with_candle_mode(mut self, mode: CandleMode) -> Self {
+        self.candles = CandleTracker::new(mode);
+        self
+    }
+
+    /// Which message format variations to normalize instead of failing on
+    pub fn with_api_compat(mut self, compat: ApiCompat) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// Drop data messages carrying fields the types do not know, logging each new field once
+    pub fn with_strict_schema(mut self) -> Self {
+        self.schema = Some(SchemaChecker::default());
+        self
+    }
+
+    /// Warn when the local clock drifts more than `threshold` from exchange timestamps
+    pub fn with_clock_skew_check(mut self, threshold: std::time::Duration) -> Self {
+        self.clock_skew = Some(ClockSkewMonitor::new(threshold));
+        self
+    }
+
+    /// Tell systemd the service is ready after the first subscription confirmation
+    pub fn with_systemd(mut self, notifier: SystemdNotifier) -> Self {
+        self.systemd = Some(notifier);
+        self
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let _ = self.send_event(ClientEvent::Starting).await;
 
         loop {
-            match self.connect_and_run().await {
+            // One span per connection attempt, covering the whole session on success. The attempt
+            // is read first so the span's field values are not held across the lock, which keeps
+            // the future `Send`
+            let attempt = self
+                .state
+                .lock()
+                .await
+                .total_reconnects
+                .load(Ordering::Relaxed)
+                + 1;
+            let span = info_span!(
+                "connection",
+                url = %self.config.websocket.url,
+                attempt = attempt,
+            );
+            match self.connect_and_run().instrument(span).await {
                 Ok(_) => {
                     info!("Connection loop exited unexpectedly");
                     break;
@@ -111,6 +257,10 @@ impl HyperliquidWebSocketClient {
             let mut state = self.state.lock().await;
             state.reset_connection();
         }
+        if let Some(monitor) = self.clock_skew.as_mut() {
+            monitor.reset_connection();
+        }
+        self.gaps.reset_connection();
 
         let _ = self
             .send_event(ClientEvent::Connecting {
@@ -123,45 +273,7 @@ impl HyperliquidWebSocketClient {
         let host = url
             .host_str()
             .ok_or_else(|| HyperliquidError::WebSocketError("Invalid host".to_string()))?;
-        let port = url.port_or_known_default().unwrap_or(443);
-
-        // Establish TCP connection
-        let stream = timeout(
-            self.config.websocket.timeout,
-            TcpStream::connect(format!("{}:{}", host, port)),
-        )
-        .await
-        .map_err(|_| HyperliquidError::Timeout)?
-        .map_err(|e| {
-            error!("Failed to connect to TCP stream: {}", e);
-            HyperliquidError::IoError(e)
-        })?;
-
-        // Perform TLS handshake for wss://
-        let mut stream = if url.scheme() == "wss" {
-            let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(
-                rustls::ClientConfig::builder_with_provider(
-                    rustls::crypto::ring::default_provider().into(),
-                )
-                .with_safe_default_protocol_versions()
-                .map_err(|e| HyperliquidError::WebSocketError(format!("TLS config error: {}", e)))?
-                .with_root_certificates(rustls::RootCertStore {
-                    roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
-                })
-                .with_no_client_auth(),
-            ));
-            let domain =
-                rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| {
-                    HyperliquidError::WebSocketError(format!("Invalid DNS name: {}", e))
-                })?;
-            let tls_stream = connector
-                .connect(domain, stream)
-                .await
-                .map_err(|e| HyperliquidError::WebSocketError(format!("TLS error: {}", e)))?;
-            MaybeTlsStream::Tls(Box::new(tls_stream))
-        } else {
-            MaybeTlsStream::Plain(stream)
-        };
+        let mut stream = connect(&url, self.config.websocket.timeout).await?;
 
         // Perform WebSocket handshake
         use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -174,10 +286,13 @@ impl HyperliquidWebSocketClient {
              Connection: Upgrade\r\n\
              Sec-WebSocket-Key: {}\r\n\
              Sec-WebSocket-Version: 13\r\n\
-             \r\n",
+             User-Agent: {}\r\n\
+             {}\r\n",
             url.path(),
             host,
-            key
+            key,
+            self.config.websocket.user_agent,
+            header_lines(&self.config.websocket.headers)
         );
 
         stream
@@ -203,6 +318,7 @@ impl HyperliquidWebSocketClient {
         }
 
         // Create WebSocket after successful handshake
+        self.has_connected = true;
         let mut ws = WebSocket::after_handshake(stream, fastwebsockets::Role::Client);
         ws.set_writev(true);
         ws.set_auto_close(true);
@@ -216,26 +332,110 @@ impl HyperliquidWebSocketClient {
         let _ = self
             .send_event(ClientEvent::Connected {
                 connection_id: {
-                    let state = self.state.lock().await;
-                    state.connection_id.clone()
+                    let mut state = self.state.lock().await;
+                    if let Some(downtime) = state.mark_connected() {
+                        record_data_gap(GapKind::Disconnect, downtime);
+                    }
+                    crate::monitoring::CONNECTED_GAUGE.set(1.0);
+                    state.connection.id.clone()
                 },
             })
             .await;
 
         // Send subscription message
         self.send_subscription(&mut ws).await?;
+        self.last_ping = Instant::now();
+        self.ping_sent = None;
+        self.pong_due = false;
 
         // Handle incoming messages
         self.handle_message_stream(&mut ws).await
     }
 
-    async fn send_subscription<S>(&self, ws: &mut WebSocket<S>) -> Result<()>
+    fn subscription_requests(&self, coin: &str) -> Vec<SubscriptionRequest> {
+        let subscription = &self.config.subscription;
+        let user = subscription.user.as_deref();
+        // Channels keyed by neither a coin nor a route: allMids and the account's own
+        let coinless = subscription
+            .channels
+            .iter()
+            .filter_map(|channel| match (channel, user) {
+                (Channel::AllMids, _) => Some(SubscriptionRequest::new_all_mids_subscription()),
+                (Channel::UserFills, Some(user)) => {
+                    Some(SubscriptionRequest::new_user_fills_subscription(user))
+                }
+                (Channel::OrderUpdates, Some(user)) => {
+                    Some(SubscriptionRequest::new_order_updates_subscription(user))
+                }
+                (Channel::UserFundings, Some(user)) => {
+                    Some(SubscriptionRequest::new_user_fundings_subscription(user))
+                }
+                (Channel::UserEvents, Some(user)) => {
+                    Some(SubscriptionRequest::new_user_events_subscription(user))
+                }
+                _ => None,
+            });
+
+        if !self.config.routes.is_empty() {
+            return self
+                .config
+                .routes
+                .iter()
+                .flat_map(|route| {
+                    route.channels.iter().flat_map(|channel| {
+                        self.market_requests(*channel, &route.coin, &route.intervals)
+                    })
+                })
+                .chain(coinless)
+                .collect();
+        }
+
+        subscription
+            .channels
+            .iter()
+            .filter(|channel| !channel.is_user() && **channel != Channel::AllMids)
+            .flat_map(|channel| self.market_requests(*channel, coin, &subscription.intervals))
+            .chain(coinless)
+            .collect()
+    }
+
+    /// Requests for one channel: a single one, or one per interval for `candle`
+    fn market_requests(
+        &self,
+        channel: Channel,
+        coin: &str,
+        intervals: &[String],
+    ) -> Vec<SubscriptionRequest> {
+        match channel {
+            Channel::L2Book => vec![
+                SubscriptionRequest::new_l2_book_subscription(coin).with_book_aggregation(
+                    self.config.subscription.book_sig_figs,
+                    self.config.subscription.book_mantissa,
+                ),
+            ],
+            Channel::Bbo => vec![SubscriptionRequest::new_bbo_subscription(coin)],
+            Channel::Candle => intervals
+                .iter()
+                .map(|interval| SubscriptionRequest::new_candle_subscription(coin, interval))
+                .collect(),
+            channel if channel.is_explorer() => {
+                vec![SubscriptionRequest::new_explorer_subscription(
+                    channel.as_str(),
+                )]
+            }
+            _ => vec![SubscriptionRequest::new_trades_subscription(coin)],
+        }
+    }
+
+    async fn write_request<S>(
+        &self,
+        ws: &mut WebSocket<S>,
+        request: &SubscriptionRequest,
+    ) -> Result<String>
     where
         S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
     {
-        let subscription =
-            SubscriptionRequest::new_trades_subscription(&self.config.subscription.coin);
-        let message = serde_json::to_string(&subscription).map_err(|e| {
+        let message = serde_json::to_string(request).map_err(|e| {
             error!("Failed to serialize subscription message: {}", e);
             HyperliquidError::SerdeError(e)
         })?;
@@ -247,13 +447,24 @@ impl HyperliquidWebSocketClient {
             HyperliquidError::WebSocketError(format!("{}", e))
         })?;
 
-        let _ = self
-            .send_event(ClientEvent::SubscriptionSent {
-                message: message.clone(),
-            })
-            .await;
+        Ok(message)
+    }
+
+    async fn send_subscription<S>(&self, ws: &mut WebSocket<S>) -> Result<()>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        for subscription in self.subscription_requests(&self.active_coin) {
+            let message = self.write_request(ws, &subscription).await?;
+
+            let _ = self
+                .send_event(ClientEvent::SubscriptionSent {
+                    message: message.clone(),
+                })
+                .await;
 
-        info!("Sent subscription: {}", message);
+            info!("Sent subscription: {}", message);
+        }
         Ok(())
     }
 
@@ -264,19 +475,53 @@ impl HyperliquidWebSocketClient {
         info!("Starting message handling loop");
 
         loop {
-            let frame = timeout(self.config.websocket.timeout, ws.read_frame())
-                .await
-                .map_err(|_| HyperliquidError::Timeout)?
-                .map_err(|e| {
-                    error!("WebSocket read error: {}", e);
-                    HyperliquidError::WebSocketError(format!("{}", e))
-                })?;
+            // Frames parsed on the pool are finished while the read is pending; the read itself
+            // is never dropped mid-frame, since fastwebsockets would lose what it had buffered
+            let frame = {
+                let read = timeout(self.config.websocket.timeout, ws.read_frame());
+                tokio::pin!(read);
+                loop {
+                    tokio::select! {
+                        biased;
+                        Some(parsed) = next_parsed(&mut self.parse_pool) => {
+                            let span = parsed.span.clone();
+                            if let Err(e) = self.finish_parsed(parsed).instrument(span).await {
+                                error!("Error handling frame: {}. Continuing...", e);
+                            }
+                        }
+                        frame = &mut read => break frame,
+                    }
+                }
+            };
+            let frame = frame.map_err(|_| HyperliquidError::Timeout)?.map_err(|e| {
+                error!("WebSocket read error: {}", e);
+                HyperliquidError::WebSocketError(format!("{}", e))
+            })?;
 
             match frame.opcode {
                 OpCode::Text | OpCode::Binary => {
-                    if let Err(e) = self.handle_frame(frame).await {
+                    let span = if self.span_sampler.sample() {
+                        info_span!(
+                            "message",
+                            bytes = frame.payload.len(),
+                            channel = field::Empty,
+                            coin = field::Empty,
+                            parse_us = field::Empty,
+                            handle_us = field::Empty,
+                        )
+                    } else {
+                        Span::none()
+                    };
+                    if let Err(e) = self
+                        .handle_frame(frame, &span)
+                        .instrument(span.clone())
+                        .await
+                    {
                         error!("Error handling frame: {}. Continuing...", e);
                     }
+                    self.record_activity().await;
+                    self.drain_commands(ws).await?;
+                    self.send_heartbeats(ws).await?;
                 }
                 OpCode::Close => {
                     info!("Received close frame");
@@ -296,15 +541,152 @@ impl HyperliquidWebSocketClient {
         }
     }
 
+    /// Count a received message, flushing to the shared state and metrics at most every
+    /// `STATE_FLUSH_INTERVAL`
+    async fn record_activity(&mut self) {
+        self.pending_messages += 1;
+        if self.last_state_flush.elapsed() >= STATE_FLUSH_INTERVAL {
+            let count = std::mem::take(&mut self.pending_messages);
+            {
+                let mut state = self.state.lock().await;
+                state.record_messages(count);
+                state.last_seen.clone_from(&self.last_seen);
+                if let Some(budget) = &self.budget {
+                    budget.set(Buffer::Dedup, state.dedup_bytes());
+                }
+                if let Some(since) = state.connection.connected_since {
+                    crate::monitoring::CONNECTION_AGE.set(since.elapsed().as_secs_f64());
+                }
+            }
+            crate::monitoring::MESSAGES_RECEIVED_COUNTER.increment(count);
+            // Depth is sampled here; the high-water marks are raised on every send
+            self.event_depth.sample(queued(&self.event_sender));
+            if let Some((sender, _)) = &self.recorder {
+                self.record_depth.sample(queued(sender));
+            }
+            self.last_state_flush = Instant::now();
+        }
+    }
+
+    /// Note the arrival of a data message under its channel and coin
+    fn mark_seen(&mut self, message: &WebSocketMessage<'_>) {
+        let channel = message.channel();
+        let coin = message.route_key().map_or("", |(_, coin)| coin);
+        if !self.last_seen.contains_key(channel) {
+            self.last_seen.insert(channel.to_string(), HashMap::new());
+        }
+        if let Some(coins) = self.last_seen.get_mut(channel) {
+            let previous = match coins.get_mut(coin) {
+                Some(at) => Some(std::mem::replace(at, Instant::now())),
+                None => {
+                    coins.insert(coin.to_string(), Instant::now());
+                    None
+                }
+            };
+            if let Some(silence) = self.gaps.observe_message(previous) {
+                let stream = if coin.is_empty() {
+                    channel.to_string()
+                } else {
+                    format!("{} {}", coin, channel)
+                };
+                warn!(
+                    "Data gap: no {} data for {:.1}s while connected",
+                    stream,
+                    silence.as_secs_f64()
+                );
+                record_data_gap(GapKind::Silence, silence);
+            }
+        }
+    }
+
+    /// Answer a server ping, then send our own once `PING_INTERVAL` has passed since the last
+    ///
+    /// Like commands, these go out between frames, so a feed silent for longer than the interval
+    /// is left to the read timeout. A ping whose pong never came is superseded.
+    async fn send_heartbeats<S>(&mut self, ws: &mut WebSocket<S>) -> Result<()>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        if std::mem::take(&mut self.pong_due) {
+            Self::write_heartbeat(ws, PONG_MESSAGE).await?;
+        }
+        if self.last_ping.elapsed() >= PING_INTERVAL {
+            Self::write_heartbeat(ws, PING_MESSAGE).await?;
+            self.last_ping = Instant::now();
+            self.ping_sent = Some(self.last_ping);
+        }
+        Ok(())
+    }
+
+    async fn write_heartbeat<S>(ws: &mut WebSocket<S>, message: &str) -> Result<()>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        let frame = Frame::text(fastwebsockets::Payload::Borrowed(message.as_bytes()));
+        ws.write_frame(frame).await.map_err(|e| {
+            error!("Failed to send {}: {}", message, e);
+            HyperliquidError::WebSocketError(format!("{}", e))
+        })?;
+        Ok(())
+    }
+
+    /// Apply pending runtime commands between frames
+    ///
+    /// fastwebsockets reads are not cancel safe, so commands are picked up after
+    /// each frame instead of racing the read in a `select!`.
+    async fn drain_commands<S>(&mut self, ws: &mut WebSocket<S>) -> Result<()>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        while let Some(command) = self
+            .command_receiver
+            .as_mut()
+            .and_then(|receiver| receiver.try_recv().ok())
+        {
+            match command {
+                ClientCommand::SwitchCoin(coin) => {
+                    if coin == self.active_coin {
+                        continue;
+                    }
+                    if !self.config.routes.is_empty() {
+                        warn!(
+                            "Coin switching is disabled while [[subscriptions]] blocks are active"
+                        );
+                        continue;
+                    }
+                    info!(
+                        "Switching active coin from {} to {}",
+                        self.active_coin, coin
+                    );
+                    let previous = std::mem::replace(&mut self.active_coin, coin);
+                    for request in self.subscription_requests(&previous) {
+                        self.write_request(ws, &request.into_unsubscribe()).await?;
+                    }
+                    self.send_subscription(ws).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_connection_error(&mut self, error: anyhow::Error) -> Result<()> {
+        let reason = error.to_string();
         let _ = self
-            .send_event(ClientEvent::ConnectionFailed(error.to_string()))
+            .send_event(ClientEvent::ConnectionFailed(reason.clone()))
             .await;
 
-        {
+        let session = {
             let mut state = self.state.lock().await;
+            let session = state
+                .connection
+                .connected_since
+                .map(|since| since.elapsed());
             state.increment_reconnect();
-        }
+            session
+        };
+        let disconnect_reason = DisconnectReason::classify(&error, session.is_some());
+        record_reconnect(disconnect_reason, session);
+        crate::monitoring::ACTIVE_SUBSCRIPTIONS.set(0.0);
 
         let reconnect_count = {
             let state = self.state.lock().await;
@@ -318,6 +700,10 @@ impl HyperliquidWebSocketClient {
                 "Maximum reconnection attempts ({}) reached",
                 self.config.websocket.max_reconnects
             );
+            // Never reaching the endpoint is a different failure than losing a working connection
+            if !self.has_connected {
+                return Err(HyperliquidError::ConnectFailed(reason).into());
+            }
             return Err(HyperliquidError::MaxReconnectsExceeded.into());
         }
 
@@ -335,9 +721,10 @@ impl HyperliquidWebSocketClient {
         let delay = exponential_delay + jitter;
 
         warn!(
-            "Reconnecting in {:.2} seconds (attempt {}, exponential backoff)",
+            "Reconnecting in {:.2} seconds (attempt {}, {}, exponential backoff)",
             delay.as_secs_f64(),
-            reconnect_count
+            reconnect_count,
+            disconnect_reason.as_str()
         );
 
         let _ = self
@@ -352,8 +739,30 @@ impl HyperliquidWebSocketClient {
     }
 
     async fn send_event(&self, event: ClientEvent) -> Result<()> {
-        // CRITICAL: Differentiate between critical (trades) and non-critical events
-        let is_critical = matches!(event, ClientEvent::TradeReceived(_));
+        // CRITICAL: Differentiate between critical (trades, account events) and non-critical events
+        let is_critical = matches!(
+            event,
+            ClientEvent::TradeReceived(_)
+                | ClientEvent::FillReceived { .. }
+                | ClientEvent::FundingReceived(_)
+                | ClientEvent::LiquidationReceived(_)
+                | ClientEvent::NonUserCancelReceived(_)
+                | ClientEvent::OrderUpdated(_)
+        );
+
+        // Over budget even trades are dropped, so a burst degrades output instead of the process
+        let bytes = event_bytes(&event);
+        if let Some(budget) = &self.budget
+            && !budget.try_charge(Buffer::Events, bytes)
+        {
+            record_dropped_events(EventConsumer::Ui, 1);
+            return Ok(());
+        }
+        let unsent = || {
+            if let Some(budget) = &self.budget {
+                budget.release(Buffer::Events, bytes);
+            }
+        };
 
         if is_critical {
             // NEVER drop trade data - block if needed (with short timeout)
@@ -363,67 +772,65 @@ impl HyperliquidWebSocketClient {
             )
             .await
             {
-                Ok(Ok(())) => Ok(()),
+                Ok(Ok(())) => {
+                    self.event_depth.observe(queued(&self.event_sender));
+                    Ok(())
+                }
                 Ok(Err(e)) => {
+                    unsent();
                     error!("Critical: Event channel closed: {}", e);
                     Err(HyperliquidError::EventSendError("Channel closed".to_string()).into())
                 }
                 Err(_) => {
                     // Even trade events can't wait forever - 10ms timeout exceeded
+                    unsent();
                     error!("CRITICAL: Trade event dropped due to channel timeout!");
-                    crate::monitoring::EVENTS_DROPPED.increment(1);
+                    record_dropped_events(EventConsumer::Ui, 1);
                     Ok(()) // Continue processing to avoid cascade failure
                 }
             }
         } else {
             // Non-critical events: use try_send (zero-wait)
             match self.event_sender.try_send(event) {
-                Ok(()) => Ok(()),
+                Ok(()) => {
+                    self.event_depth.observe(queued(&self.event_sender));
+                    Ok(())
+                }
                 Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                    unsent();
                     // Drop non-critical events silently if channel full
-                    crate::monitoring::EVENTS_DROPPED.increment(1);
+                    record_dropped_events(EventConsumer::Ui, 1);
                     Ok(())
                 }
                 Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                    unsent();
                     Err(HyperliquidError::EventSendError("Channel closed".to_string()).into())
                 }
             }
         }
     }
 
-    async fn handle_frame(&mut self, frame: Frame<'_>) -> Result<()> {
+    /// Parse and dispatch one frame, filling in `span` when this frame was sampled
+    async fn handle_frame(&mut self, frame: Frame<'_>, span: &Span) -> Result<()> {
         match frame.opcode {
             OpCode::Text => {
                 // Use Cow to avoid allocation when UTF-8 is valid (common case)
                 let text = String::from_utf8_lossy(&frame.payload);
-
-                if tracing::level_enabled!(tracing::Level::TRACE) {
-                    trace!("Received text message: {}", text);
-                }
-
-                // Only send message received event if in verbose mode
-                // to avoid unnecessary allocations
-                if tracing::level_enabled!(tracing::Level::DEBUG) {
-                    let _ = self
-                        .send_event(ClientEvent::MessageReceived {
-                            raw_message: text.to_string(),
-                        })
-                        .await;
+                let received = Utc::now();
+                if let Some(pool) = &mut self.parse_pool {
+                    match pool.submit(&text, received, span).await {
+                        Submission::Queued => return Ok(()),
+                        Submission::AfterQueued => self.finish_queued().await,
+                        Submission::Inline => {}
+                    }
                 }
 
-                // HFT CRITICAL: Skip message counting in hot path to eliminate lock
-                // Metrics are updated via TRADE_COUNTER instead
-
                 // Parse directly from the Cow reference to avoid allocation
-                match serde_json::from_str::<WebSocketMessage>(text.as_ref()) {
-                    Ok(ws_message) => {
-                        self.handle_websocket_message(ws_message).await?;
-                    }
-                    Err(e) => {
-                        warn!("Failed to parse message: {}. Raw: {}", e, text);
-                        return Err(HyperliquidError::InvalidMessage(e.to_string()).into());
-                    }
-                }
+                let parse_started = Instant::now();
+                let decoded = self.compat.parse(text.as_ref());
+                let parse_time = parse_started.elapsed();
+                self.handle_parsed(&text, decoded, parse_time, received, span)
+                    .await?;
             }
             OpCode::Binary => {
                 debug!("Received binary message of {} bytes", frame.payload.len());
@@ -436,30 +843,197 @@ impl HyperliquidWebSocketClient {
         Ok(())
     }
 
-    async fn handle_websocket_message(&mut self, message: WebSocketMessage) -> Result<()> {
+    /// Finish every frame still on the pool, so one parsed inline next cannot overtake them
+    async fn finish_queued(&mut self) {
+        while let Some(pool) = &mut self.parse_pool
+            && pool.in_flight() > 0
+        {
+            let Some(parsed) = pool.next().await else {
+                break;
+            };
+            let span = parsed.span.clone();
+            if let Err(e) = self.finish_parsed(parsed).instrument(span).await {
+                error!("Error handling frame: {}. Continuing...", e);
+            }
+        }
+    }
+
+    /// Finish a frame that was parsed on the pool
+    async fn finish_parsed(&mut self, parsed: Parsed) -> Result<()> {
+        let Parsed {
+            text,
+            message,
+            normalized,
+            parse_time,
+            received,
+            span,
+        } = parsed;
+        let decoded = Decoded {
+            message,
+            normalized,
+        };
+        self.handle_parsed(&text, decoded, parse_time, received, &span)
+            .await
+    }
+
+    /// Everything after parsing: metrics, bookkeeping, recording, and dispatch
+    async fn handle_parsed(
+        &mut self,
+        text: &str,
+        decoded: Decoded<'_>,
+        parse_time: std::time::Duration,
+        received: DateTime<Utc>,
+        span: &Span,
+    ) -> Result<()> {
+        let Decoded {
+            message: parsed,
+            normalized,
+        } = decoded;
+        let collecting = self.collect_from.is_none_or(|start| received >= start);
+        let channel = parsed.as_ref().map_or("invalid", WebSocketMessage::channel);
+        self.message_histograms
+            .record(channel, text.len(), parse_time);
+        if !span.is_none() {
+            span.record("channel", channel);
+            span.record("parse_us", parse_time.as_micros() as u64);
+            if let Some((_, coin)) = parsed.as_ref().ok().and_then(WebSocketMessage::route_key) {
+                span.record("coin", coin);
+            }
+        }
+        if let Ok(message) = &parsed
+            && !message.is_control()
+        {
+            self.mark_seen(message);
+        }
+        // Control messages are only reported, so the session keeps its bookkeeping. A normalized
+        // frame is checked in its canonical form, so renamed fields do not count as unknown
+        let schema_ok = match (self.schema.as_mut(), &parsed) {
+            (Some(schema), Ok(message)) => {
+                schema.check(normalized.as_deref().unwrap_or(text), message) || message.is_control()
+            }
+            _ => true,
+        };
+        // With [[subscriptions]] blocks, each block decides where its messages go
+        let route = parsed
+            .as_ref()
+            .ok()
+            .and_then(WebSocketMessage::route_key)
+            .and_then(|(channel, coin)| self.config.route(channel, coin))
+            .map(|route| (route.sink, route.filter));
+        let sink = route.map_or(SubscriptionSink::Both, |(sink, _)| sink);
+
+        if collecting
+            && sink.records()
+            && let Some((sender, progress)) = &self.recorder
+        {
+            let line = record_line(text, received);
+            let bytes = line_bytes(&line);
+            if self
+                .budget
+                .as_ref()
+                .is_some_and(|budget| !budget.try_charge(Buffer::Recorder, bytes))
+            {
+                progress.record_dropped();
+            } else {
+                match sender.try_send(line) {
+                    Ok(()) => self.record_depth.observe(queued(sender)),
+                    Err(e) => {
+                        if let Some(budget) = &self.budget {
+                            budget.release(Buffer::Recorder, bytes);
+                        }
+                        if matches!(e, TrySendError::Full(_)) {
+                            progress.record_dropped();
+                        }
+                    }
+                }
+            }
+        }
+
+        if tracing::level_enabled!(tracing::Level::TRACE) {
+            trace!("Received text message: {}", text);
+        }
+
+        // Only send message received event if in verbose mode
+        // to avoid unnecessary allocations
+        if tracing::level_enabled!(tracing::Level::DEBUG) {
+            let _ = self
+                .send_event(ClientEvent::MessageReceived {
+                    raw_message: text.to_string(),
+                })
+                .await;
+        }
+
+        // Messages are counted in batches by record_activity, keeping the lock off the
+        // hot path
+
+        match parsed {
+            Ok(_) if !schema_ok => {}
+            Ok(ws_message) if ws_message.is_control() || (collecting && sink.displays()) => {
+                let route_filter = route.map(|(_, filter)| filter);
+                let handle_started = Instant::now();
+                self.handle_websocket_message(ws_message, route_filter)
+                    .await?;
+                span.record("handle_us", handle_started.elapsed().as_micros() as u64);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to parse message: {}. Raw: {}", e, text);
+                return Err(HyperliquidError::InvalidMessage(e.to_string()).into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_websocket_message(
+        &mut self,
+        message: WebSocketMessage<'_>,
+        route_filter: Option<TradeFilter>,
+    ) -> Result<()> {
         match message {
             WebSocketMessage::SubscriptionResponse(response) => {
                 info!("Subscription response received");
+                // User-scoped channels are keyed by address and carry no coin
+                let subscription = response.data.subscription;
+                {
+                    let mut state = self.state.lock().await;
+                    state.confirm_subscription(&response.data.method, subscription.clone());
+                    crate::monitoring::ACTIVE_SUBSCRIPTIONS
+                        .set(state.connection.subscriptions.len() as f64);
+                }
+                let coin = match subscription.user {
+                    Some(user) if subscription.coin.is_empty() => user,
+                    _ => self.label_of(&subscription.coin).to_string(),
+                };
+                if let Some(notifier) = self.systemd.take() {
+                    notifier.ready(&format!(
+                        "Streaming {} {}",
+                        coin, subscription.subscription_type
+                    ));
+                }
                 let _ = self
                     .send_event(ClientEvent::SubscriptionConfirmed {
-                        sub_type: response.data.subscription.subscription_type.clone(),
-                        coin: response.data.subscription.coin.clone(),
+                        sub_type: subscription.subscription_type,
+                        coin,
+                        interval: subscription.interval,
                     })
                     .await;
             }
 
             WebSocketMessage::TradeData(trade_data) => {
                 debug!("Processing {} trades", trade_data.data.len());
-                self.handle_trade_data(trade_data).await?;
+                self.handle_trade_data(trade_data.data, route_filter)
+                    .await?;
             }
 
-            WebSocketMessage::BookData(book_data) => {
+            WebSocketMessage::BookData(mut book_data) => {
                 debug!("Processing order book data for {}", book_data.data.coin);
+                book_data.data.coin = self.label(book_data.data.coin);
                 self.handle_book_data(book_data.data).await?;
             }
 
-            WebSocketMessage::BboData(bbo_data) => {
+            WebSocketMessage::BboData(mut bbo_data) => {
                 debug!("Processing BBO data for {}", bbo_data.data.coin);
+                bbo_data.data.coin = self.label(bbo_data.data.coin);
                 self.handle_bbo_data(bbo_data.data).await?;
             }
 
@@ -481,6 +1055,30 @@ impl HyperliquidWebSocketClient {
                 self.handle_user_event(user_event.data).await?;
             }
 
+            WebSocketMessage::OrderUpdates(order_updates) => {
+                debug!("Processing {} order updates", order_updates.data.len());
+                for update in order_updates.data {
+                    trace!(
+                        "Order {} on {}: {}",
+                        update.order.oid, update.order.coin, update.status
+                    );
+                    self.send_event(ClientEvent::OrderUpdated(Arc::new(update)))
+                        .await?;
+                }
+            }
+
+            WebSocketMessage::UserFundings(user_fundings) => {
+                debug!(
+                    "Processing {} user fundings",
+                    user_fundings.data.fundings.len()
+                );
+                for funding in user_fundings.data.fundings {
+                    trace!("Funding on {}: {}", funding.coin, funding.usdc);
+                    self.send_event(ClientEvent::FundingReceived(Arc::new(funding)))
+                        .await?;
+                }
+            }
+
             WebSocketMessage::Notification(notification) => {
                 info!(
                     "Processing notification: {}",
@@ -488,68 +1086,89 @@ impl HyperliquidWebSocketClient {
                 );
                 self.handle_notification(notification.data).await?;
             }
+            WebSocketMessage::Error(message) => {
+                error!("Server error: {}", message.data);
+                self.send_event(ClientEvent::ServerError(message.data))
+                    .await?;
+            }
 
+            // Bare arrays carry no channel, so no [[subscriptions]] block routes them
             WebSocketMessage::DirectTrades(trades) => {
                 debug!("Processing {} direct trades", trades.len());
+                self.handle_trade_data(trades, None).await?;
+            }
 
-                // Batch validation and lock acquisition
-                let valid_trades: Vec<_> = {
-                    let mut state = self.state.lock().await;
-                    trades
-                        .into_iter()
-                        .filter(|trade| {
-                            if !state.validate_trade_sequence(&trade.coin, trade.tid) {
-                                crate::monitoring::DUPLICATE_TRADES.increment(1);
-                                return false;
-                            }
-                            state.record_trade();
-                            true
-                        })
-                        .collect()
-                };
-
-                for trade in valid_trades {
+            WebSocketMessage::DirectBlocks(blocks) => {
+                debug!("Processing {} explorer blocks", blocks.len());
+                for block in blocks {
                     let _ = self
-                        .send_event(ClientEvent::TradeReceived(Arc::new(trade)))
+                        .send_event(ClientEvent::BlockReceived(Arc::new(block)))
                         .await;
                 }
             }
 
+            WebSocketMessage::DirectTxs(txs) => {
+                debug!("Processing {} explorer transactions", txs.len());
+                for tx in txs {
+                    let _ = self.send_event(ClientEvent::TxReceived(Arc::new(tx))).await;
+                }
+            }
+
             WebSocketMessage::DirectCandles(candles) => {
                 debug!("Processing {} direct candles", candles.len());
                 self.handle_candle_data(candles).await?;
             }
-            WebSocketMessage::Ping(ping) => {
-                debug!("Received ping message: {:?}", ping);
-            }
+            // Every frame already refreshes the liveness timestamp in record_activity
+            WebSocketMessage::Ping(ping) => match ping.channel.as_str() {
+                "pong" => {
+                    if let Some(sent) = self.ping_sent.take() {
+                        let rtt = sent.elapsed();
+                        debug!("Pong after {:.1}ms", rtt.as_secs_f64() * 1000.0);
+                        crate::monitoring::PING_RTT.record(rtt.as_secs_f64());
+                        self.state.lock().await.connection.ping_rtt = Some(rtt);
+                    }
+                }
+                "ping" => {
+                    debug!("Server ping, replying with pong");
+                    self.pong_due = true;
+                }
+                channel => debug!("Ignoring {} message without a payload type", channel),
+            },
         }
         Ok(())
     }
 
+    /// Check a batch of trades for repeats and impossible timestamps, then pass the ones the
+    /// filters keep on to the UI
     async fn handle_trade_data(
         &mut self,
-        trade_data: crate::types::TradeDataMessage,
+        trades: Vec<TradeRef<'_>>,
+        route_filter: Option<TradeFilter>,
     ) -> Result<()> {
+        let skew = self.sample_trade_clock(&trades);
+        self.report_clock_skew(skew).await;
+
         // Batch lock acquisition - single lock for all trades
         let valid_trades: Vec<_> = {
             let mut state = self.state.lock().await;
 
-            trade_data
-                .data
+            // Trades stay borrowed from the frame until they pass, so rejected ones cost no copies
+            trades
                 .into_iter()
                 .filter(|trade| {
+                    let coin = self.label_of(&trade.coin);
                     // Validate trade sequence
-                    if !state.validate_trade_sequence(&trade.coin, trade.tid) {
+                    if !state.validate_trade_sequence(coin, trade.tid) {
                         warn!(
                             "Skipping duplicate or out-of-order trade: {} tid={} ",
-                            trade.coin, trade.tid
+                            coin, trade.tid
                         );
                         crate::monitoring::DUPLICATE_TRADES.increment(1);
                         return false;
                     }
 
                     // Validate timestamp
-                    if chrono::DateTime::from_timestamp_millis(trade.time).is_none() {
+                    if exchange_time(trade.time).is_none() {
                         warn!("Invalid timestamp for trade {}: {}", trade.tid, trade.time);
                         state.record_invalid_timestamp();
                         crate::monitoring::INVALID_TIMESTAMPS.increment(1);
@@ -557,8 +1176,11 @@ impl HyperliquidWebSocketClient {
                     }
 
                     state.record_trade();
-                    true
+                    // Filtered trades still count as received, they just never reach the UI
+                    self.trade_filter.matches_ref(trade)
+                        && route_filter.is_none_or(|filter| filter.matches_ref(trade))
                 })
+                .map(|trade| self.owned_trade(trade))
                 .collect()
         }; // Lock released here
 
@@ -578,6 +1200,68 @@ impl HyperliquidWebSocketClient {
         Ok(())
     }
 
+    /// Feed the newest trade of a batch to the clock skew check and the trade delay histogram
+    fn sample_trade_clock(&mut self, trades: &[TradeRef<'_>]) -> Option<ClockSkew> {
+        let monitor = self.clock_skew.as_mut()?;
+        // A corrupt timestamp would become the newest and throw the estimate off by years
+        let latest = trades
+            .iter()
+            .filter(|trade| exchange_time(trade.time).is_some())
+            .max_by_key(|trade| trade.time)?;
+        let local_ms = Utc::now().timestamp_millis();
+        // The snapshot batch replays older trades, so only live batches say how late delivery is
+        if monitor.is_live(&latest.coin) {
+            record_trade_delay(&Delay::new(latest.time, local_ms, monitor.estimate_ms()));
+        }
+        monitor.observe_trades(&latest.coin, latest.time, local_ms)
+    }
+
+    async fn report_clock_skew(&self, skew: Option<ClockSkew>) {
+        let Some(skew) = skew else {
+            return;
+        };
+        if skew.exceeded() {
+            warn!(
+                "Local clock is {}ms off exchange time (limit {}ms)",
+                skew.skew_ms, skew.threshold_ms
+            );
+        } else {
+            info!(
+                "Local clock back within {}ms of exchange time ({}ms)",
+                skew.threshold_ms, skew.skew_ms
+            );
+        }
+        let _ = self
+            .send_event(ClientEvent::ClockSkew {
+                skew_ms: skew.skew_ms,
+                threshold_ms: skew.threshold_ms,
+            })
+            .await;
+    }
+
+    /// The display name for a coin as sent by the exchange
+    fn label(&self, coin: Symbol) -> Symbol {
+        match &self.coin_label {
+            Some((wire, label)) if coin == *wire => label.clone(),
+            _ => coin,
+        }
+    }
+
+    /// `label` for a coin still borrowed from the frame
+    fn label_of<'a>(&'a self, coin: &'a str) -> &'a str {
+        match &self.coin_label {
+            Some((wire, label)) if wire == coin => label.as_str(),
+            _ => coin,
+        }
+    }
+
+    /// The event form of a trade that passed validation and filtering
+    fn owned_trade(&self, trade: TradeRef<'_>) -> Trade {
+        let mut trade = trade.into_owned();
+        trade.coin = self.label(trade.coin);
+        trade
+    }
+
     async fn handle_book_data(&mut self, book: Book) -> Result<()> {
         trace!(
             "Order book update for {} with {} bids and {} asks",
@@ -585,51 +1269,102 @@ impl HyperliquidWebSocketClient {
             book.levels.0.len(),
             book.levels.1.len()
         );
+        let _ = self
+            .send_event(ClientEvent::BookReceived(Arc::new(book)))
+            .await;
         Ok(())
     }
 
     async fn handle_bbo_data(&mut self, bbo: Bbo) -> Result<()> {
         trace!("BBO update for {}", bbo.coin);
+        let _ = self
+            .send_event(ClientEvent::BboReceived(Arc::new(bbo)))
+            .await;
         Ok(())
     }
 
     async fn handle_all_mids_data(&mut self, all_mids: AllMids) -> Result<()> {
         trace!("All mids update for {} symbols", all_mids.mids.len());
+        let _ = self
+            .send_event(ClientEvent::AllMidsReceived(Arc::new(all_mids)))
+            .await;
         Ok(())
     }
 
     async fn handle_candle_data(&mut self, candles: Vec<Candle>) -> Result<()> {
+        let local_ms = Utc::now().timestamp_millis();
         for candle in candles {
+            let skew = self
+                .clock_skew
+                .as_mut()
+                .filter(|_| exchange_time(candle.t).is_some())
+                .and_then(|monitor| monitor.observe_candle(candle.t, local_ms));
+            self.report_clock_skew(skew).await;
+            if let Some(missing) = self.gaps.observe_candle(&candle) {
+                warn!(
+                    "Data gap: {} {} candles missing for {:.0}s before the bar opening at {}",
+                    candle.s,
+                    candle.i,
+                    missing.as_secs_f64(),
+                    exchange_time(candle.t)
+                        .map_or_else(|| candle.t.to_string(), |at| at.to_rfc3339())
+                );
+                record_data_gap(GapKind::Candle, missing);
+            }
             trace!(
                 "Candle data for {} - O: {}, H: {}, L: {}, C: {}",
                 candle.s, candle.o, candle.h, candle.l, candle.c
             );
+            for update in self.candles.observe(candle) {
+                let _ = self
+                    .send_event(ClientEvent::CandleReceived {
+                        candle: Arc::new(update.candle),
+                        closed: update.closed,
+                    })
+                    .await;
+            }
         }
         Ok(())
     }
 
     async fn handle_user_event(&mut self, user_event: UserEvent) -> Result<()> {
         match user_event {
-            UserEvent::Fills { fills } => {
-                info!("Received {} user fills", fills.len());
+            UserEvent::Fills {
+                fills, is_snapshot, ..
+            } => {
+                debug!("Received {} user fills", fills.len());
+                let snapshot = is_snapshot.unwrap_or(false);
                 for fill in fills {
-                    debug!(
+                    trace!(
                         "Fill: {} {} @ {} for {}",
                         fill.side, fill.sz, fill.px, fill.coin
                     );
+                    self.send_event(ClientEvent::FillReceived {
+                        fill: Arc::new(fill),
+                        snapshot,
+                    })
+                    .await?;
                 }
             }
             UserEvent::Funding { funding } => {
-                info!(
+                debug!(
                     "Received funding update for {}: {}",
                     funding.coin, funding.usdc
                 );
+                self.send_event(ClientEvent::FundingReceived(Arc::new(funding)))
+                    .await?;
             }
             UserEvent::Liquidation { liquidation } => {
                 warn!("Liquidation event: ID {}", liquidation.lid);
+                self.send_event(ClientEvent::LiquidationReceived(Arc::new(liquidation)))
+                    .await?;
             }
             UserEvent::NonUserCancel { non_user_cancel } => {
-                info!("Non-user cancellation events: {}", non_user_cancel.len());
+                debug!("Non-user cancellation events: {}", non_user_cancel.len());
+                for cancel in non_user_cancel {
+                    self.send_event(ClientEvent::NonUserCancelReceived(Arc::new(cancel)))
+                        .await?;
+                }
             }
         }
         Ok(())
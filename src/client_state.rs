@@ -1,42 +1,93 @@
 /// file: src/client_state.rs
 /// description: Separate state management from client logic
+use crate::{
+    events::{ClientEvent, ConnectionStats, EventSender},
+    recorder::RecordingProgress,
+    types::Subscription,
+};
 use std::collections::HashMap;
 use std::sync::{
     Arc,
     atomic::{AtomicU32, AtomicU64, Ordering},
 };
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::time::Instant;
+use tracing::info;
+
+/// State that belongs to one WebSocket connection and starts over on every attempt
+#[derive(Debug)]
+pub struct ConnectionState {
+    pub id: String,
+    /// When the handshake completed; `None` until then and after the connection ends
+    pub connected_since: Option<Instant>,
+    /// Channels the server has confirmed on this connection
+    pub subscriptions: Vec<Subscription>,
+    /// Latest ping to pong round trip on this connection
+    pub ping_rtt: Option<Duration>,
+}
+
+impl Default for ConnectionState {
+    fn default() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            connected_since: None,
+            subscriptions: Vec::new(),
+            ping_rtt: None,
+        }
+    }
+}
 
+/// Session-wide state; `connection` is replaced on each attempt while the counters accumulate
 #[derive(Debug)]
 pub struct ClientState {
-    pub connection_id: String,
+    pub session_id: String,
+    pub session_started: Instant,
+    pub connection: ConnectionState,
+    /// Consecutive failed attempts, the count `--max-reconnects` limits
     pub reconnect_count: AtomicU32,
+    pub total_reconnects: AtomicU32,
+    /// Handshakes completed over the session
+    pub connections: u32,
+    /// Time spent connected on connections that have since ended
+    pub connected_time: Duration,
     pub last_message_time: Option<Instant>,
     pub trade_count: AtomicU64,
-    pub is_connected: bool,
     pub total_messages_received: AtomicU64,
 
     // Trading data integrity tracking
     pub last_trade_ids: HashMap<String, i64>, // coin -> last trade ID
     pub duplicate_trades: AtomicU64,
     pub invalid_timestamps: AtomicU64,
+    /// When the current disconnected stretch began, through any failed attempts since
     pub last_disconnection_time: Option<Instant>,
+    /// Disconnected periods that were followed by a successful reconnect
+    pub gap_count: u32,
+    pub total_downtime: Duration,
+    /// Latest data message by channel, then coin; the coin is empty for channels without one
+    pub last_seen: HashMap<String, HashMap<String, Instant>>,
 }
 
 impl Default for ClientState {
     fn default() -> Self {
         Self {
-            connection_id: uuid::Uuid::new_v4().to_string(),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            session_started: Instant::now(),
+            connection: ConnectionState::default(),
             reconnect_count: AtomicU32::new(0),
+            total_reconnects: AtomicU32::new(0),
+            connections: 0,
+            connected_time: Duration::ZERO,
             last_message_time: None,
             trade_count: AtomicU64::new(0),
-            is_connected: false,
             total_messages_received: AtomicU64::new(0),
             last_trade_ids: HashMap::new(),
             duplicate_trades: AtomicU64::new(0),
             invalid_timestamps: AtomicU64::new(0),
             last_disconnection_time: None,
+            gap_count: 0,
+            total_downtime: Duration::ZERO,
+            last_seen: HashMap::new(),
         }
     }
 }
@@ -46,17 +97,29 @@ impl ClientState {
         Self::default()
     }
 
+    /// Start a new connection attempt; session counters are left alone
     pub fn reset_connection(&mut self) {
-        self.connection_id = uuid::Uuid::new_v4().to_string();
+        self.disconnect();
+        self.connection = ConnectionState::default();
         self.last_message_time = Some(Instant::now());
-        self.is_connected = true;
-        self.reconnect_count.store(0, Ordering::Relaxed);
     }
 
     pub fn increment_reconnect(&mut self) {
         self.reconnect_count.fetch_add(1, Ordering::AcqRel);
-        self.is_connected = false;
-        self.last_disconnection_time = Some(Instant::now());
+        self.total_reconnects.fetch_add(1, Ordering::Relaxed);
+        self.disconnect();
+        // A flap of several failed attempts is one gap, measured from the first
+        self.last_disconnection_time
+            .get_or_insert_with(Instant::now);
+    }
+
+    /// Track a `subscriptionResponse`, which echoes the method and channel it answers
+    pub fn confirm_subscription(&mut self, method: &str, subscription: Subscription) {
+        let subscriptions = &mut self.connection.subscriptions;
+        subscriptions.retain(|active| *active != subscription);
+        if method == "subscribe" {
+            subscriptions.push(subscription);
+        }
     }
 
     /// Validates trade sequence and returns true if trade should be processed
@@ -73,26 +136,198 @@ impl ClientState {
 
         // NOTE: We cannot detect sequence gaps with non-sequential IDs
         // Each trade has a unique random-looking ID
-        self.last_trade_ids.insert(coin.to_string(), trade_id);
+        // The key is only copied the first time a coin trades
+        match self.last_trade_ids.get_mut(coin) {
+            Some(last) => *last = trade_id,
+            None => {
+                self.last_trade_ids.insert(coin.to_string(), trade_id);
+            }
+        }
         true
     }
 
+    /// Estimated bytes held by the per-coin duplicate check
+    pub fn dedup_bytes(&self) -> usize {
+        self.last_trade_ids
+            .keys()
+            .map(|coin| std::mem::size_of::<(String, i64)>() + coin.len())
+            .sum()
+    }
+
     pub fn record_invalid_timestamp(&self) {
         self.invalid_timestamps.fetch_add(1, Ordering::Relaxed);
     }
 
     pub fn record_message(&mut self) {
+        self.record_messages(1);
+    }
+
+    /// Record a batch of messages counted outside the lock
+    pub fn record_messages(&mut self, count: u64) {
         self.last_message_time = Some(Instant::now());
-        self.total_messages_received.fetch_add(1, Ordering::Relaxed);
+        self.total_messages_received
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Mark the handshake as complete so uptime counts from now
+    /// Returns how long the connection was down, when this ends a disconnected stretch
+    pub fn mark_connected(&mut self) -> Option<Duration> {
+        // Only consecutive failed attempts count toward --max-reconnects
+        self.reconnect_count.store(0, Ordering::Relaxed);
+        self.connection.connected_since = Some(Instant::now());
+        self.connections += 1;
+        // Any stretch spent disconnected is a gap in the received data
+        let downtime = self.last_disconnection_time.take()?.elapsed();
+        self.gap_count += 1;
+        self.total_downtime += downtime;
+        Some(downtime)
     }
 
     pub fn record_trade(&self) {
         self.trade_count.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// End the current connection, adding its uptime to the session total
     pub fn disconnect(&mut self) {
-        self.is_connected = false;
+        if let Some(since) = self.connection.connected_since.take() {
+            self.connected_time += since.elapsed();
+        }
+        self.connection.subscriptions.clear();
+        self.connection.ping_rtt = None;
+    }
+
+    /// Whether the current connection has completed its handshake
+    pub fn is_connected(&self) -> bool {
+        self.connection.connected_since.is_some()
+    }
+
+    /// Time spent connected over the whole session, including the current connection
+    pub fn session_uptime(&self) -> Duration {
+        self.connected_time
+            + self
+                .connection
+                .connected_since
+                .map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    /// Time since `subscription` last delivered data, `None` if it never has
+    pub fn subscription_age(&self, subscription: &Subscription) -> Option<Duration> {
+        self.last_seen
+            .get(&subscription.subscription_type)?
+            .get(&subscription.coin)
+            .map(|at| at.elapsed())
+    }
+
+    /// Snapshot for the status bar; `msgs_per_sec` is filled in by the reporter
+    pub fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            connected: self.is_connected(),
+            uptime: self.connection.connected_since.map(|since| since.elapsed()),
+            reconnects: self.total_reconnects.load(Ordering::Relaxed),
+            total_messages: self.total_messages_received.load(Ordering::Relaxed),
+            msgs_per_sec: 0.0,
+            last_message_age: self.last_message_time.map(|at| at.elapsed()),
+            ping_rtt: self.connection.ping_rtt,
+            recording: None,
+        }
     }
 }
 
+/// Periodically sample the shared state and publish `ClientEvent::Stats`
+pub fn spawn_stats_reporter(
+    state: SharedClientState,
+    recording: Option<Arc<RecordingProgress>>,
+    sender: EventSender,
+    period: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        let mut previous: Option<(Instant, u64)> = None;
+        loop {
+            ticker.tick().await;
+            let mut stats = state.lock().await.snapshot();
+            stats.recording = recording.as_ref().map(|progress| progress.snapshot());
+            let now = Instant::now();
+            if let Some((at, total)) = previous {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                if elapsed > 0.0 {
+                    stats.msgs_per_sec =
+                        stats.total_messages.saturating_sub(total) as f64 / elapsed;
+                }
+            }
+            previous = Some((now, stats.total_messages));
+
+            // Stats are advisory; skip a tick rather than wait on a full channel
+            if let Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) =
+                sender.try_send(ClientEvent::Stats(stats))
+            {
+                break;
+            }
+        }
+    })
+}
+
+/// Log a throughput line every `period`, for deployments watched through their logs alone
+pub fn spawn_stats_logger(
+    state: SharedClientState,
+    period: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        // The first tick is immediate; the line after it covers a whole period
+        ticker.tick().await;
+        let totals = |state: &ClientState| {
+            (
+                state.total_messages_received.load(Ordering::Relaxed),
+                state.trade_count.load(Ordering::Relaxed),
+            )
+        };
+        let mut previous = (Instant::now(), totals(&*state.lock().await));
+        loop {
+            ticker.tick().await;
+            let state = state.lock().await;
+            let now = Instant::now();
+            let (messages, trades) = totals(&state);
+            let elapsed = now
+                .duration_since(previous.0)
+                .as_secs_f64()
+                .max(f64::EPSILON);
+            let rate = |total: u64, before: u64| {
+                (total.saturating_sub(before) as f64 / elapsed * 10.0).round() / 10.0
+            };
+            let msgs_per_sec = rate(messages, previous.1.0);
+            let trades_per_sec = rate(trades, previous.1.1);
+            previous = (now, (messages, trades));
+
+            // A subscription that has never delivered is the one worth noticing
+            let ages: Vec<String> = state
+                .connection
+                .subscriptions
+                .iter()
+                .map(|subscription| {
+                    let name = match subscription.coin.as_str() {
+                        "" => subscription.subscription_type.clone(),
+                        coin => format!("{}:{}", subscription.subscription_type, coin),
+                    };
+                    match state.subscription_age(subscription) {
+                        Some(age) => format!("{}={:.1}s", name, age.as_secs_f64()),
+                        None => format!("{}=never", name),
+                    }
+                })
+                .collect();
+            info!(
+                msgs_per_sec,
+                trades_per_sec,
+                reconnects = state.total_reconnects.load(Ordering::Relaxed),
+                connected = state.is_connected(),
+                last_message_age_secs = state
+                    .last_message_time
+                    .map(|at| (at.elapsed().as_secs_f64() * 10.0).round() / 10.0),
+                subscriptions = %ages.join(" "),
+                "Throughput"
+            );
+        }
+    })
+}
+
 pub type SharedClientState = Arc<Mutex<ClientState>>;
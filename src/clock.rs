@@ -0,0 +1,120 @@
+/// file: src/clock.rs
+/// description: Local clock skew estimated from exchange timestamps on live trades and candles
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::time::Instant;
+
+// The startup estimate waits this long for a few samples so one slow message cannot trip it
+const STARTUP_WINDOW: Duration = Duration::from_secs(5);
+// After startup the skew is re-checked once per window
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Skew estimate from one window, reported when it crosses the threshold in either direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    /// Local time minus exchange time; positive means the local clock runs ahead
+    pub skew_ms: i64,
+    pub threshold_ms: i64,
+}
+
+impl ClockSkew {
+    pub fn exceeded(&self) -> bool {
+        self.skew_ms.abs() > self.threshold_ms
+    }
+}
+
+/// Compares local receive time against exchange timestamps
+///
+/// Each sample is local time minus an exchange timestamp, which is the skew plus delivery
+/// latency. The smallest sample in a window carries the least latency, so it is the estimate: a
+/// fast clock is overstated by the best-case latency and a slow clock is never hidden.
+#[derive(Debug)]
+pub struct ClockSkewMonitor {
+    threshold_ms: i64,
+    window_min: Option<i64>,
+    window_start: Option<Instant>,
+    /// Skew from the last completed window
+    estimate_ms: Option<i64>,
+    checked: bool,
+    exceeded: bool,
+    /// Coins whose first trades batch since connecting has been seen
+    seen_coins: HashSet<String>,
+}
+
+impl ClockSkewMonitor {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold_ms: threshold.as_millis() as i64,
+            window_min: None,
+            window_start: None,
+            estimate_ms: None,
+            checked: false,
+            exceeded: false,
+            seen_coins: HashSet::new(),
+        }
+    }
+
+    /// Called on every (re)connect, since each subscription starts with a snapshot again
+    pub fn reset_connection(&mut self) {
+        self.seen_coins.clear();
+    }
+
+    /// Skew from the last completed window, `None` until the first check
+    pub fn estimate_ms(&self) -> Option<i64> {
+        self.estimate_ms
+    }
+
+    /// Whether trades for `coin` are live, i.e. its snapshot batch has already been seen
+    pub fn is_live(&self, coin: &str) -> bool {
+        self.seen_coins.contains(coin)
+    }
+
+    /// Sample the newest trade of a batch; the first batch per coin replays older trades
+    pub fn observe_trades(
+        &mut self,
+        coin: &str,
+        latest_ms: i64,
+        local_ms: i64,
+    ) -> Option<ClockSkew> {
+        if self.seen_coins.insert(coin.to_string()) {
+            return None;
+        }
+        self.observe(local_ms - latest_ms)
+    }
+
+    /// A candle update cannot arrive before the candle opens, which only bounds a slow clock
+    pub fn observe_candle(&mut self, open_ms: i64, local_ms: i64) -> Option<ClockSkew> {
+        let delta = local_ms - open_ms;
+        if delta >= 0 {
+            return None;
+        }
+        self.observe(delta)
+    }
+
+    fn observe(&mut self, delta_ms: i64) -> Option<ClockSkew> {
+        let now = Instant::now();
+        self.window_min = Some(self.window_min.map_or(delta_ms, |min| min.min(delta_ms)));
+        let started = *self.window_start.get_or_insert(now);
+        let window = if self.checked {
+            CHECK_INTERVAL
+        } else {
+            STARTUP_WINDOW
+        };
+        if now.duration_since(started) < window {
+            return None;
+        }
+
+        let skew = ClockSkew {
+            skew_ms: self.window_min.take()?,
+            threshold_ms: self.threshold_ms,
+        };
+        self.window_start = Some(now);
+        self.checked = true;
+        self.estimate_ms = Some(skew.skew_ms);
+        crate::monitoring::CLOCK_SKEW_MS.set(skew.skew_ms as f64);
+
+        // Only changes are reported, so a steady offset warns once
+        let was_exceeded = std::mem::replace(&mut self.exceeded, skew.exceeded());
+        (skew.exceeded() != was_exceeded).then_some(skew)
+    }
+}
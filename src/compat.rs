@@ -0,0 +1,493 @@
+/// file: src/compat.rs
+/// description: Normalizes known variations of the exchange message format into the canonical types
+use crate::{schema::snippet, types::WebSocketMessage};
+use metrics::counter;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::{debug, warn};
+
+/// Keys some feeds use in place of `channel`
+const CHANNEL_ALIASES: [&str; 3] = ["type", "topic", "stream"];
+/// Long field names and their canonical short forms
+const FIELD_ALIASES: [(&str, &str); 7] = [
+    ("price", "px"),
+    ("size", "sz"),
+    ("symbol", "coin"),
+    ("asset", "coin"),
+    ("timestamp", "time"),
+    ("tradeId", "tid"),
+    ("trade_id", "tid"),
+];
+/// Fields the types read as decimal strings
+const DECIMAL_FIELDS: [&str; 7] = ["px", "sz", "o", "c", "h", "l", "v"];
+
+/// Variations already logged at warn level, one bit each
+static REPORTED: AtomicU8 = AtomicU8::new(0);
+
+/// A known departure from the message format the types are written against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variation {
+    /// `type`, `topic`, or `stream` in place of `channel`, or the payload wrapped once more in
+    /// a `data` or channel-named object
+    Envelope,
+    /// `price`, `size`, `symbol`, `asset`, `timestamp`, or `tradeId` in place of the short names
+    FieldNames,
+    /// Levels as `[px, sz, n]` arrays, and book sides or BBOs as `bids`/`asks` objects
+    Levels,
+    /// Prices, sizes, and candle values as JSON numbers instead of decimal strings
+    Numbers,
+}
+
+impl Variation {
+    /// In the order they are undone: unwrapping and renaming first, so later steps find the
+    /// canonical names
+    pub const ALL: [Variation; 4] = [
+        Variation::Envelope,
+        Variation::FieldNames,
+        Variation::Levels,
+        Variation::Numbers,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variation::Envelope => "envelope",
+            Variation::FieldNames => "field-names",
+            Variation::Levels => "levels",
+            Variation::Numbers => "numbers",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Rewrite `frame` into the canonical shape; `true` when this variation was present
+    fn normalize(self, frame: &mut Value) -> bool {
+        match self {
+            Variation::Envelope => normalize_envelope(frame),
+            Variation::FieldNames => visit_objects(frame, &mut rename_fields),
+            Variation::Levels => visit_objects(frame, &mut reshape_levels),
+            Variation::Numbers => visit_objects(frame, &mut quote_decimals),
+        }
+    }
+}
+
+/// Which variations `--api-compat` lets the parser normalize; all of them by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiCompat {
+    enabled: u8,
+}
+
+impl Default for ApiCompat {
+    fn default() -> Self {
+        Self {
+            enabled: Variation::ALL
+                .iter()
+                .fold(0, |enabled, variation| enabled | variation.bit()),
+        }
+    }
+}
+
+impl FromStr for ApiCompat {
+    type Err = String;
+
+    /// `auto` for every known variation, `off` for none, or a comma-separated list of them
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => return Ok(Self::default()),
+            "off" | "none" => return Ok(Self { enabled: 0 }),
+            _ => {}
+        }
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Self { enabled: 0 }, |compat, name| {
+                let variation = Variation::ALL
+                    .into_iter()
+                    .find(|variation| variation.as_str().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown API variation '{}', expected auto, off, or a list of envelope, field-names, levels, numbers",
+                            name
+                        )
+                    })?;
+                Ok(Self {
+                    enabled: compat.enabled | variation.bit(),
+                })
+            })
+    }
+}
+
+/// A frame parsed through `ApiCompat::parse`
+pub struct Decoded<'a> {
+    pub message: serde_json::Result<WebSocketMessage<'a>>,
+    /// The frame rewritten in the canonical format, when a variation was normalized
+    pub normalized: Option<String>,
+}
+
+impl ApiCompat {
+    pub fn allows(self, variation: Variation) -> bool {
+        self.enabled & variation.bit() != 0
+    }
+
+    /// Parse a frame, normalizing it when the canonical format does not fit
+    ///
+    /// A frame in the current format parses exactly as `WebSocketMessage::parse` does, at no extra
+    /// cost. Only a frame that fails is read again as plain JSON, rewritten for each variation it
+    /// shows, and parsed once more. When that fails too, the original error is kept, since it
+    /// names what the canonical types expected.
+    pub fn parse(self, text: &str) -> Decoded<'_> {
+        let error = match WebSocketMessage::parse(text) {
+            Ok(message) => {
+                return Decoded {
+                    message: Ok(message),
+                    normalized: None,
+                };
+            }
+            Err(error) => error,
+        };
+        let failed = |error| Decoded {
+            message: Err(error),
+            normalized: None,
+        };
+        if self.enabled == 0 {
+            return failed(error);
+        }
+        let Ok(mut frame) = serde_json::from_str::<Value>(text) else {
+            return failed(error);
+        };
+        let found: Vec<Variation> = Variation::ALL
+            .into_iter()
+            .filter(|variation| self.allows(*variation) && variation.normalize(&mut frame))
+            .collect();
+        if found.is_empty() {
+            return failed(error);
+        }
+
+        let normalized = frame.to_string();
+        match WebSocketMessage::parse(&normalized).map(WebSocketMessage::into_owned) {
+            Ok(message) => {
+                report(&found, message.channel(), text);
+                Decoded {
+                    message: Ok(message),
+                    normalized: Some(normalized),
+                }
+            }
+            Err(_) => failed(error),
+        }
+    }
+}
+
+/// Count a normalized frame, and log each variation the first time it turns up
+fn report(found: &[Variation], channel: &str, text: &str) {
+    for variation in found {
+        counter!(
+            "hyperliquid_api_compat_normalized_total",
+            "variation" => variation.as_str(),
+            "channel" => channel.to_string()
+        )
+        .increment(1);
+        if REPORTED.fetch_or(variation.bit(), Ordering::Relaxed) & variation.bit() == 0 {
+            warn!(
+                "Detected the {} variation of the API format in a {} message and normalized it; the exchange may have changed its schema: {}",
+                variation.as_str(),
+                channel,
+                snippet(text)
+            );
+        } else {
+            debug!(
+                "Normalized the {} variation in a {} message",
+                variation.as_str(),
+                channel
+            );
+        }
+    }
+}
+
+fn normalize_envelope(frame: &mut Value) -> bool {
+    let Value::Object(frame) = frame else {
+        return false;
+    };
+    let mut changed = false;
+    if !frame.contains_key("channel")
+        && let Some(alias) = CHANNEL_ALIASES
+            .into_iter()
+            .find(|alias| frame.get(*alias).is_some_and(Value::is_string))
+        && let Some(channel) = frame.remove(alias)
+    {
+        frame.insert("channel".to_string(), channel);
+        changed = true;
+    }
+
+    // `{"data": {"data": [...]}}` or `{"data": {"trades": [...]}}`; a string inside, as in a
+    // notification, is the payload itself
+    let Some(channel) = frame
+        .get("channel")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+    else {
+        return changed;
+    };
+    if let Some(Value::Object(data)) = frame.get_mut("data")
+        && data.len() == 1
+        && let Some(key) = ["data", channel.as_str()].into_iter().find(|key| {
+            data.get(*key)
+                .is_some_and(|inner| inner.is_object() || inner.is_array())
+        })
+        && let Some(inner) = data.remove(key)
+    {
+        frame.insert("data".to_string(), inner);
+        changed = true;
+    }
+    changed
+}
+
+fn rename_fields(object: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+    for (alias, canonical) in FIELD_ALIASES {
+        if !object.contains_key(canonical)
+            && let Some(value) = object.remove(alias)
+        {
+            object.insert(canonical.to_string(), value);
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn reshape_levels(object: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+    for (field, sides) in [("levels", ["bids", "asks"]), ("bbo", ["bid", "ask"])] {
+        let Some(value) = object.get_mut(field) else {
+            continue;
+        };
+        if let Value::Object(named) = value
+            && sides.iter().all(|side| named.contains_key(*side))
+        {
+            let pair = sides.map(|side| named.remove(side).unwrap_or(Value::Null));
+            *value = Value::Array(pair.into());
+            changed = true;
+        }
+        let Value::Array(pair) = value else {
+            continue;
+        };
+        for side in pair.iter_mut() {
+            match side {
+                // A book side: a list of levels
+                Value::Array(levels) if field == "levels" => {
+                    for level in levels.iter_mut() {
+                        changed |= level_object(level);
+                    }
+                }
+                // A BBO side: one level or null
+                level => changed |= level_object(level),
+            }
+        }
+    }
+    changed
+}
+
+/// `[px, sz]` or `[px, sz, n]` as a level object
+fn level_object(level: &mut Value) -> bool {
+    let Value::Array(fields) = level else {
+        return false;
+    };
+    if !(2..=3).contains(&fields.len()) {
+        return false;
+    }
+    let mut fields = std::mem::take(fields).into_iter();
+    let mut object = Map::new();
+    object.insert("px".to_string(), fields.next().unwrap_or_default());
+    object.insert("sz".to_string(), fields.next().unwrap_or_default());
+    object.insert("n".to_string(), fields.next().unwrap_or(Value::from(0)));
+    *level = Value::Object(object);
+    true
+}
+
+fn quote_decimals(object: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+    for field in DECIMAL_FIELDS {
+        if let Some(value) = object.get_mut(field)
+            && let Value::Number(number) = value
+        {
+            *value = Value::String(number.to_string());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Apply `visit` to every object in `value`, parents before their children
+fn visit_objects(
+    value: &mut Value,
+    visit: &mut impl FnMut(&mut Map<String, Value>) -> bool,
+) -> bool {
+    match value {
+        Value::Object(object) => {
+            let mut changed = visit(object);
+            for child in object.values_mut() {
+                changed |= visit_objects(child, visit);
+            }
+            changed
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |changed, item| visit_objects(item, visit) | changed),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn normalized(variation: Variation, mut frame: Value) -> (bool, Value) {
+        let changed = variation.normalize(&mut frame);
+        (changed, frame)
+    }
+
+    #[test]
+    fn renames_the_channel_key_and_unwraps_nested_data() {
+        assert_eq!(
+            normalized(
+                Variation::Envelope,
+                json!({"type": "trades", "data": {"trades": [{"px": "1"}]}})
+            ),
+            (true, json!({"channel": "trades", "data": [{"px": "1"}]}))
+        );
+        assert_eq!(
+            normalized(
+                Variation::Envelope,
+                json!({"topic": "l2Book", "data": {"data": {"coin": "BTC"}}})
+            ),
+            (true, json!({"channel": "l2Book", "data": {"coin": "BTC"}}))
+        );
+        // A notification's string payload and a canonical frame stay as they are
+        let notification = json!({"channel": "notification", "data": {"notification": "hi"}});
+        assert_eq!(
+            normalized(Variation::Envelope, notification.clone()),
+            (false, notification)
+        );
+    }
+
+    #[test]
+    fn renames_long_field_names_without_clobbering_short_ones() {
+        assert_eq!(
+            normalized(
+                Variation::FieldNames,
+                json!({"data": [{"symbol": "BTC", "price": "1", "size": "2", "timestamp": 3, "tradeId": 4}]})
+            ),
+            (
+                true,
+                json!({"data": [{"coin": "BTC", "px": "1", "sz": "2", "time": 3, "tid": 4}]})
+            )
+        );
+        let both = json!({"coin": "BTC", "asset": "ETH"});
+        assert_eq!(
+            normalized(Variation::FieldNames, both.clone()),
+            (false, both)
+        );
+    }
+
+    #[test]
+    fn reshapes_array_levels_and_named_sides() {
+        assert_eq!(
+            normalized(
+                Variation::Levels,
+                json!({"data": {"levels": {"bids": [["99", "1", 2]], "asks": [["101", "3"]]}}})
+            ),
+            (
+                true,
+                json!({"data": {"levels": [
+                    [{"px": "99", "sz": "1", "n": 2}],
+                    [{"px": "101", "sz": "3", "n": 0}],
+                ]}})
+            )
+        );
+        assert_eq!(
+            normalized(
+                Variation::Levels,
+                json!({"bbo": {"bid": ["99", "1", 1], "ask": null}})
+            ),
+            (
+                true,
+                json!({"bbo": [{"px": "99", "sz": "1", "n": 1}, null]})
+            )
+        );
+        assert_eq!(
+            normalized(
+                Variation::Levels,
+                json!({"bbo": [["99", "1"], ["101", "2", 3]]})
+            ),
+            (
+                true,
+                json!({"bbo": [{"px": "99", "sz": "1", "n": 0}, {"px": "101", "sz": "2", "n": 3}]})
+            )
+        );
+        let canonical = json!({"levels": [[{"px": "99", "sz": "1", "n": 1}], []]});
+        assert_eq!(
+            normalized(Variation::Levels, canonical.clone()),
+            (false, canonical)
+        );
+    }
+
+    #[test]
+    fn quotes_numeric_decimals_but_not_other_numbers() {
+        assert_eq!(
+            normalized(
+                Variation::Numbers,
+                json!({"data": {"px": 112450.5, "sz": 2, "time": 1760000000000_i64, "o": "1"}})
+            ),
+            (
+                true,
+                json!({"data": {"px": "112450.5", "sz": "2", "time": 1760000000000_i64, "o": "1"}})
+            )
+        );
+    }
+
+    #[test]
+    fn parses_a_frame_showing_every_variation() {
+        let text = r#"{"type":"trades","data":{"trades":[{"symbol":"BTC","side":"B","price":112450.0,"size":0.5,"timestamp":1760000000000,"hash":"0x00","tradeId":7,"users":["0xa","0xb"]}]}}"#;
+        let decoded = ApiCompat::default().parse(text);
+        let Ok(WebSocketMessage::TradeData(message)) = decoded.message else {
+            panic!("not parsed as trades");
+        };
+        assert_eq!(message.data[0].px, 112450.0);
+        assert_eq!(message.data[0].tid, 7);
+        assert!(decoded.normalized.unwrap().contains(r#""px":"112450.0""#));
+
+        // Only the enabled variations are undone, and a partial fix keeps the original error
+        let envelope_only: ApiCompat = "envelope".parse().unwrap();
+        assert!(envelope_only.parse(text).message.is_err());
+        assert!(
+            "off"
+                .parse::<ApiCompat>()
+                .unwrap()
+                .parse(text)
+                .message
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn canonical_frames_are_not_rewritten() {
+        let text = r#"{"channel":"pong"}"#;
+        let decoded = ApiCompat::default().parse(text);
+        assert!(decoded.message.is_ok());
+        assert!(decoded.normalized.is_none());
+    }
+
+    #[test]
+    fn parses_the_flag() {
+        let compat: ApiCompat = " Levels, numbers ,".parse().unwrap();
+        assert!(compat.allows(Variation::Levels));
+        assert!(compat.allows(Variation::Numbers));
+        assert!(!compat.allows(Variation::Envelope));
+        assert_eq!("AUTO".parse::<ApiCompat>().unwrap(), ApiCompat::default());
+        assert!("none".parse::<ApiCompat>().unwrap() != ApiCompat::default());
+        assert!("levels,fields".parse::<ApiCompat>().is_err());
+    }
+}
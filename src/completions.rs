@@ -0,0 +1,107 @@
+/// file: src/completions.rs
+/// description: Shell completion scripts with coin names from the exchange universe
+/// reference: https://docs.rs/clap_complete/latest/clap_complete/
+use crate::{cli::CompletionsArgs, error::HyperliquidError, rest::InfoClient};
+use anyhow::Result;
+use clap::builder::PossibleValuesParser;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// The listing is a single small request; fail fast and fall back to the cache
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Write the completion script for `args.shell` to stdout
+///
+/// Coin names are fetched from `meta` and cached, so later runs with `--offline` or without
+/// network still complete real symbols. Without either, `--coin` completes nothing.
+pub async fn print_completions(args: CompletionsArgs, mut command: clap::Command) -> Result<()> {
+    let coins = coin_names(&args).await;
+    if !coins.is_empty() {
+        command = with_coin_values(command, &coins);
+    }
+    let name = command.get_name().to_string();
+    // Rendered up front because the generator panics on write errors such as a closed pipe
+    let mut script = Vec::new();
+    clap_complete::generate(args.shell, &mut command, name, &mut script);
+    std::io::stdout().write_all(&script)?;
+    Ok(())
+}
+
+/// Fresh coin names when reachable, the cached list otherwise
+async fn coin_names(args: &CompletionsArgs) -> Vec<String> {
+    if !args.offline {
+        match fetch_coin_names(&args.url).await {
+            Ok(coins) => {
+                if let Err(e) = write_cache(&coins) {
+                    eprintln!("warning: could not cache the coin list: {}", e);
+                }
+                return coins;
+            }
+            Err(e) => eprintln!(
+                "warning: could not fetch the coin list, using the cache: {}",
+                e
+            ),
+        }
+    }
+    read_cache().unwrap_or_else(|| {
+        eprintln!("warning: no cached coin list, --coin will not complete symbols");
+        Vec::new()
+    })
+}
+
+async fn fetch_coin_names(url: &str) -> Result<Vec<String>> {
+    let client = InfoClient::from_ws_url(&url::Url::parse(url)?, FETCH_TIMEOUT)?;
+    let meta = client.meta().await?;
+    Ok(meta
+        .universe
+        .into_iter()
+        .filter(|asset| !asset.is_delisted)
+        .map(|asset| asset.name)
+        .collect())
+}
+
+/// Offer `coins` for every `--coin` flag, including the ones on subcommands
+fn with_coin_values(command: clap::Command, coins: &[String]) -> clap::Command {
+    let has_coin = command.get_arguments().any(|arg| arg.get_id() == "coin");
+    let mut command = if has_coin {
+        command.mut_arg("coin", |arg| {
+            arg.value_parser(PossibleValuesParser::new(coins.to_vec()))
+        })
+    } else {
+        command
+    };
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |sub| with_coin_values(sub, coins));
+    }
+    command
+}
+
+/// `$XDG_CACHE_HOME/rs-hyperliquid/coins.json`, falling back to `~/.cache`
+fn cache_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("rs-hyperliquid").join("coins.json"))
+}
+
+fn read_cache() -> Option<Vec<String>> {
+    let contents = std::fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_cache(coins: &[String]) -> Result<()> {
+    let path = cache_path().ok_or_else(|| {
+        HyperliquidError::ConfigError("no cache directory (HOME is not set)".to_string())
+    })?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, serde_json::to_string(coins)?)?;
+    Ok(())
+}
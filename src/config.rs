@@ -1,16 +1,48 @@
 /// file: src/config.rs
 /// description: Configuration management and CLI argument parsing for WebSocket client settings
 /// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/websocket
-use crate::cli::Args;
+use crate::{
+    cli::StreamArgs,
+    error::HyperliquidError,
+    filter::{SideFilter, TradeFilter},
+    log_file::LogRotation,
+    monitoring::{MetricsAuth, MetricsBackend},
+    tracing_setup::LogTarget,
+};
 use anyhow::Result;
+use clap::{ArgMatches, parser::ValueSource};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Duration;
 use url::Url;
 
+// Subscribed when --user is given without --subscribe
+const DEFAULT_USER_CHANNELS: [Channel; 3] = [
+    Channel::UserFills,
+    Channel::OrderUpdates,
+    Channel::UserFundings,
+];
+
+// Bar lengths accepted by the `candle` channel
+const CANDLE_INTERVALS: [&str; 14] = [
+    "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "8h", "12h", "1d", "3d", "1w", "1M",
+];
+
+// Searched in order when --config is not given
+const LOCAL_CONFIG_FILE: &str = "hyperliquid.toml";
+const USER_CONFIG_FILE: &str = "rs-hyperliquid/config.toml";
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub websocket: WebSocketConfig,
     pub subscription: SubscriptionConfig,
     pub metrics: MetricsConfig,
+    /// `[[subscriptions]]` blocks; empty means the single `subscription` above
+    pub routes: Vec<SubscriptionRoute>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,23 +51,279 @@ pub struct WebSocketConfig {
     pub timeout: Duration,
     pub reconnect_delay: Duration,
     pub max_reconnects: u32,
+    /// Set by `--network`; without it the URL is taken at face value
+    pub network: Option<Network>,
+    pub user_agent: String,
+    /// Extra handshake headers, also sent with info requests
+    pub headers: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SubscriptionConfig {
     pub coin: String,
-    pub subscription_type: String,
+    /// WebSocket channels subscribed for the coin (e.g. trades, l2Book, bbo)
+    /// and, with `user`, for the account (e.g. userFills, orderUpdates)
+    pub channels: Vec<Channel>,
+    /// Bar lengths for the `candle` channel, one subscription each
+    pub intervals: Vec<String>,
+    /// Lowercased wallet address for user-scoped channels
+    pub user: Option<String>,
+    /// `l2Book` aggregation: significant figures and, at 5 figures, the step mantissa
+    pub book_sig_figs: Option<u32>,
+    pub book_mantissa: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
     pub enabled: bool,
+    pub backend: MetricsBackend,
+    /// Prometheus scrape address
+    pub bind: IpAddr,
     pub port: u16,
+    /// Credentials accepted by the scrape endpoint; empty leaves it open
+    pub auth: Vec<MetricsAuth>,
+    /// StatsD agent, resolved when metrics start
+    pub statsd_addr: String,
+    pub pushgateway_url: Url,
+    pub pushgateway_job: String,
+    /// Sample the tokio runtime into the same backends
+    pub runtime: bool,
+}
+
+/// Hyperliquid deployment a URL is expected to point at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Network::Mainnet => "wss://api.hyperliquid.xyz/ws",
+            Network::Testnet => "wss://api.hyperliquid-testnet.xyz/ws",
+        }
+    }
+
+    /// The explorer's endpoint, which serves blocks and transactions instead of market data
+    pub fn explorer_url(self) -> &'static str {
+        match self {
+            Network::Mainnet => "wss://rpc.hyperliquid.xyz/ws",
+            Network::Testnet => "wss://rpc.hyperliquid-testnet.xyz/ws",
+        }
+    }
+
+    /// The network a public Hyperliquid API host belongs to; `None` for proxies and local servers
+    pub fn of_host(host: &str) -> Option<Self> {
+        match host.to_ascii_lowercase().as_str() {
+            "api.hyperliquid.xyz" | "rpc.hyperliquid.xyz" => Some(Network::Mainnet),
+            "api.hyperliquid-testnet.xyz" | "rpc.hyperliquid-testnet.xyz" => Some(Network::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+        })
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        match network.to_ascii_lowercase().as_str() {
+            "mainnet" | "main" => Ok(Network::Mainnet),
+            "testnet" | "test" => Ok(Network::Testnet),
+            _ => Err(format!(
+                "unknown network '{}', expected mainnet or testnet",
+                network
+            )),
+        }
+    }
+}
+
+/// A WebSocket channel the client subscribes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Trades,
+    Bbo,
+    L2Book,
+    /// One subscription per `--interval`
+    Candle,
+    /// Every coin's mid in one message, not keyed by a coin
+    AllMids,
+    UserFills,
+    OrderUpdates,
+    UserFundings,
+    UserEvents,
+    ExplorerBlock,
+    ExplorerTxs,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 11] = [
+        Channel::Trades,
+        Channel::Bbo,
+        Channel::L2Book,
+        Channel::Candle,
+        Channel::AllMids,
+        Channel::UserFills,
+        Channel::OrderUpdates,
+        Channel::UserFundings,
+        Channel::UserEvents,
+        Channel::ExplorerBlock,
+        Channel::ExplorerTxs,
+    ];
+
+    /// The subscription `type`, which is also the `channel` of its messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Trades => "trades",
+            Channel::Bbo => "bbo",
+            Channel::L2Book => "l2Book",
+            Channel::Candle => "candle",
+            Channel::AllMids => "allMids",
+            Channel::UserFills => "userFills",
+            Channel::OrderUpdates => "orderUpdates",
+            Channel::UserFundings => "userFundings",
+            Channel::UserEvents => "userEvents",
+            Channel::ExplorerBlock => "explorerBlock",
+            Channel::ExplorerTxs => "explorerTxs",
+        }
+    }
+
+    /// Short name accepted by `--subscribe` next to the exchange's own
+    fn alias(self) -> &'static [&'static str] {
+        match self {
+            Channel::Trades | Channel::Bbo => &[],
+            Channel::L2Book => &["book"],
+            Channel::Candle => &["candles"],
+            Channel::AllMids => &["mids"],
+            Channel::UserFills => &["fills"],
+            Channel::OrderUpdates => &["orders"],
+            Channel::UserFundings => &["funding", "fundings"],
+            Channel::UserEvents => &["events"],
+            Channel::ExplorerBlock => &["blocks"],
+            Channel::ExplorerTxs => &["txs", "transactions"],
+        }
+    }
+
+    /// Keyed by a wallet address rather than a coin
+    pub fn is_user(self) -> bool {
+        matches!(
+            self,
+            Channel::UserFills
+                | Channel::OrderUpdates
+                | Channel::UserFundings
+                | Channel::UserEvents
+        )
+    }
+
+    /// Served by the explorer's RPC endpoint, with no coin or user
+    pub fn is_explorer(self) -> bool {
+        matches!(self, Channel::ExplorerBlock | Channel::ExplorerTxs)
+    }
+
+    /// Keyed by a coin
+    pub fn is_market(self) -> bool {
+        !self.is_user() && !self.is_explorer() && self != Channel::AllMids
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Channel {
+    type Err = String;
+
+    /// The exchange's name or a short alias, in any case
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|channel| {
+                channel.as_str().eq_ignore_ascii_case(value)
+                    || channel
+                        .alias()
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(value))
+            })
+            .ok_or_else(|| format!("unsupported channel '{}'", value))
+    }
+}
+
+/// Where a subscription block's messages go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionSink {
+    /// Shown in the terminal (or `--output`) but not recorded
+    Terminal,
+    /// Recorded to `--record` without being shown
+    Record,
+    #[default]
+    Both,
+}
+
+impl SubscriptionSink {
+    pub fn displays(self) -> bool {
+        self != SubscriptionSink::Record
+    }
+
+    pub fn records(self) -> bool {
+        self != SubscriptionSink::Terminal
+    }
+}
+
+/// One `[[subscriptions]]` block: market channels for a coin, its trade filter, and its sink
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionRoute {
+    pub coin: String,
+    pub channels: Vec<Channel>,
+    /// Bar lengths for the `candle` channel, one subscription each
+    pub intervals: Vec<String>,
+    /// Applied on top of `--min-size`, `--min-notional`, and `--side`
+    pub filter: TradeFilter,
+    pub sink: SubscriptionSink,
+}
+
+impl SubscriptionRoute {
+    /// Whether `channel`, as named in messages, is subscribed for `coin` by this block
+    pub fn matches(&self, channel: &str, coin: &str) -> bool {
+        self.coin == coin
+            && self
+                .channels
+                .iter()
+                .any(|existing| existing.as_str() == channel)
+    }
 }
 
 impl Config {
-    pub fn from_args(args: &Args) -> Result<Self> {
-        let url = Url::parse(&args.url)?;
+    pub fn from_args(args: &StreamArgs) -> Result<Self> {
+        let network = args
+            .network
+            .as_deref()
+            .map(str::parse::<Network>)
+            .transpose()
+            .map_err(HyperliquidError::ConfigError)?;
+        let channels = Self::channels_from_args(args)?;
+        let intervals = Self::intervals_from_args(args, &channels)?;
+        let explorer = channels.iter().all(|channel| channel.is_explorer());
+        let url = match &args.url {
+            Some(url) => Url::parse(url)?,
+            None if explorer => Url::parse(network.unwrap_or_default().explorer_url())?,
+            None => Url::parse(network.unwrap_or_default().default_url())?,
+        };
+        validate_endpoint(&url)?;
+        Self::validate_book_aggregation(args, &channels)?;
 
         Ok(Config {
             websocket: WebSocketConfig {
@@ -43,15 +331,1132 @@ impl Config {
                 timeout: Duration::from_secs(args.timeout),
                 reconnect_delay: Duration::from_secs(args.reconnect_delay),
                 max_reconnects: args.max_reconnects,
+                network,
+                user_agent: match &args.user_agent {
+                    Some(agent) => header_value("User-Agent", agent)?,
+                    None => format!("rs-hyperliquid/{}", env!("CARGO_PKG_VERSION")),
+                },
+                headers: Self::headers_from_args(args)?,
             },
             subscription: SubscriptionConfig {
                 coin: args.coin.clone(),
-                subscription_type: "trades".to_string(),
+                channels,
+                intervals,
+                user: args.user.as_deref().map(validate_address).transpose()?,
+                book_sig_figs: args.book_sigfigs,
+                book_mantissa: args.book_mantissa,
             },
             metrics: MetricsConfig {
                 enabled: args.metrics,
+                backend: args.metrics_backend,
+                bind: args.metrics_bind,
                 port: args.metrics_port,
+                auth: metrics_auth(args)?,
+                statsd_addr: args.statsd_addr.clone(),
+                pushgateway_url: Url::parse(&args.pushgateway_url).map_err(|e| {
+                    HyperliquidError::ConfigError(format!("invalid --pushgateway-url: {}", e))
+                })?,
+                pushgateway_job: args.pushgateway_job.clone(),
+                runtime: args.runtime_metrics,
             },
+            routes: Vec::new(),
         })
     }
+
+    /// `--header` values in order; a repeated name keeps the last value
+    fn headers_from_args(args: &StreamArgs) -> Result<Vec<(String, String)>, HyperliquidError> {
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for header in &args.headers {
+            let (name, value) = header.split_once(':').ok_or_else(|| {
+                HyperliquidError::ConfigError(format!(
+                    "invalid header '{}', expected 'Name: value'",
+                    header
+                ))
+            })?;
+            let name = header_name(name.trim())?;
+            let value = header_value(&name, value)?;
+            headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+            headers.push((name, value));
+        }
+        Ok(headers)
+    }
+
+    /// Subscribe to `routes` instead of `--coin`/`--subscribe`; user channels are kept
+    pub fn with_routes(mut self, routes: Vec<SubscriptionRoute>) -> Self {
+        let Some(first) = routes.first() else {
+            return self;
+        };
+        self.subscription.coin = first.coin.clone();
+        self.subscription
+            .channels
+            .retain(|channel| channel.is_user() || *channel == Channel::AllMids);
+        for channel in routes.iter().flat_map(|route| &route.channels) {
+            if !self.subscription.has_channel(*channel) {
+                self.subscription.channels.push(*channel);
+            }
+        }
+        self.routes = routes;
+        self
+    }
+
+    /// The block that receives `channel` messages for `coin`, if blocks are configured
+    pub fn route(&self, channel: &str, coin: &str) -> Option<&SubscriptionRoute> {
+        self.routes
+            .iter()
+            .find(|route| route.matches(channel, coin))
+    }
+
+    /// Every coin with a market data subscription
+    pub fn coins(&self) -> Vec<&str> {
+        if self.routes.is_empty() {
+            return vec![self.subscription.coin.as_str()];
+        }
+        let mut coins: Vec<&str> = Vec::new();
+        for route in &self.routes {
+            if !coins.contains(&route.coin.as_str()) {
+                coins.push(&route.coin);
+            }
+        }
+        coins
+    }
+
+    /// Channels from `--subscribe`, or the ones implied by the selected view
+    fn channels_from_args(args: &StreamArgs) -> Result<Vec<Channel>> {
+        if let Some(list) = &args.subscribe {
+            let mut channels = Vec::new();
+            for name in list
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                let channel: Channel = name.parse().map_err(|reason| {
+                    HyperliquidError::ConfigError(format!(
+                        "{} in --subscribe, expected trades, bbo, l2Book, candle, mids, fills, orders, funding, events, blocks, or txs",
+                        reason
+                    ))
+                })?;
+                if !channels.contains(&channel) {
+                    channels.push(channel);
+                }
+            }
+            if !args.intervals.is_empty() && !channels.contains(&Channel::Candle) {
+                channels.push(Channel::Candle);
+            }
+            if channels.is_empty() {
+                return Err(HyperliquidError::ConfigError(
+                    "--subscribe needs at least one channel".to_string(),
+                )
+                .into());
+            }
+
+            // The explorer is a different endpoint, so its channels cannot share a connection
+            let explorer = channels
+                .iter()
+                .filter(|channel| channel.is_explorer())
+                .count();
+            if explorer > 0 && explorer < channels.len() {
+                return Err(HyperliquidError::ConfigError(
+                    "blocks and txs come from the explorer endpoint and cannot be combined with market or user channels; run a second instance for those".to_string(),
+                )
+                .into());
+            }
+
+            let has_user_channel = channels.iter().any(|channel| channel.is_user());
+            if has_user_channel && args.user.is_none() {
+                return Err(HyperliquidError::ConfigError(
+                    "fills, orders, funding, and events channels need --user".to_string(),
+                )
+                .into());
+            }
+            if !has_user_channel && args.user.is_some() {
+                return Err(HyperliquidError::ConfigError(
+                    "--user needs a user channel in --subscribe: fills, orders, funding, or events"
+                        .to_string(),
+                )
+                .into());
+            }
+            return Ok(channels);
+        }
+
+        for (flag, set) in [
+            ("--user-fills", args.user_fills),
+            ("--user-events", args.user_events),
+        ] {
+            if set && args.user.is_none() {
+                return Err(
+                    HyperliquidError::ConfigError(format!("{} needs --user 0x...", flag)).into(),
+                );
+            }
+        }
+
+        // Account-centric streaming: every default user channel, no market data besides candles.
+        // The mode flags below are exclusive in clap, so at most one of these branches matches
+        let mut channels = if args.user_fills {
+            vec![Channel::UserFills]
+        } else if args.user_events {
+            vec![Channel::UserEvents]
+        } else if args.user.is_some() {
+            DEFAULT_USER_CHANNELS.to_vec()
+        } else if args.book_view || args.heatmap {
+            vec![Channel::L2Book]
+        } else if args.ticker {
+            vec![Channel::Trades, Channel::Bbo]
+        } else if args.bbo {
+            vec![Channel::Bbo]
+        } else if args.all_mids {
+            vec![Channel::AllMids]
+        } else if args.candles.is_some() {
+            vec![Channel::Candle]
+        } else {
+            vec![Channel::Trades]
+        };
+        if !args.intervals.is_empty() && !channels.contains(&Channel::Candle) {
+            channels.push(Channel::Candle);
+        }
+        Ok(channels)
+    }
+
+    /// `--interval` values in order without repeats; the candle channel needs at least one
+    fn intervals_from_args(args: &StreamArgs, channels: &[Channel]) -> Result<Vec<String>> {
+        let mut intervals: Vec<String> = Vec::new();
+        for interval in args
+            .intervals
+            .iter()
+            .map(|interval| interval.trim())
+            .filter(|interval| !interval.is_empty())
+        {
+            candle_interval(interval).map_err(|reason| {
+                HyperliquidError::ConfigError(format!("--interval: {}", reason))
+            })?;
+            if !intervals.iter().any(|existing| existing == interval) {
+                intervals.push(interval.to_string());
+            }
+        }
+        if intervals.is_empty() && channels.contains(&Channel::Candle) {
+            let needs = if args.candles.is_some() && args.subscribe.is_none() {
+                "--candles"
+            } else {
+                "the candle channel"
+            };
+            return Err(HyperliquidError::ConfigError(format!(
+                "{} needs --interval, e.g. --interval 1m",
+                needs
+            ))
+            .into());
+        }
+        Ok(intervals)
+    }
+
+    /// Reject aggregation settings the exchange would refuse, or that have no book to apply to
+    fn validate_book_aggregation(args: &StreamArgs, channels: &[Channel]) -> Result<()> {
+        if let Some(sig_figs) = args.book_sigfigs
+            && !(2..=5).contains(&sig_figs)
+        {
+            return Err(HyperliquidError::ConfigError(format!(
+                "--book-sigfigs {} is out of range, expected 2 to 5",
+                sig_figs
+            ))
+            .into());
+        }
+        if let Some(mantissa) = args.book_mantissa {
+            if ![1, 2, 5].contains(&mantissa) {
+                return Err(HyperliquidError::ConfigError(format!(
+                    "--book-mantissa {} is not supported, expected 1, 2, or 5",
+                    mantissa
+                ))
+                .into());
+            }
+            if args.book_sigfigs != Some(5) {
+                return Err(HyperliquidError::ConfigError(
+                    "--book-mantissa only applies with --book-sigfigs 5".to_string(),
+                )
+                .into());
+            }
+        }
+        if args.book_sigfigs.is_some() && !channels.contains(&Channel::L2Book) {
+            return Err(HyperliquidError::ConfigError(
+                "--book-sigfigs needs the l2Book channel (--subscribe l2Book, --book-view, or --heatmap)"
+                    .to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl WebSocketConfig {
+    /// Likely misconfigurations that still connect: a host from the other network, or a path
+    /// that is not the `/ws` endpoint on a proxy or local server
+    pub fn endpoint_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let host = self.url.host_str().unwrap_or_default();
+        if let (Some(network), Some(host_network)) = (self.network, Network::of_host(host))
+            && network != host_network
+        {
+            warnings.push(format!(
+                "--network {} but {} is a {} host; data and accounts differ between networks",
+                network, host, host_network
+            ));
+        }
+        if Network::of_host(host).is_none() && !self.url.path().ends_with("/ws") {
+            warnings.push(format!(
+                "--url path '{}' does not look like a Hyperliquid WebSocket endpoint (expected /ws)",
+                self.url.path()
+            ));
+        }
+        warnings
+    }
+}
+
+impl SubscriptionConfig {
+    pub fn has_channel(&self, channel: Channel) -> bool {
+        self.channels.contains(&channel)
+    }
+
+    /// Whether any subscribed channel is keyed by `coin`
+    pub fn has_coin_channel(&self) -> bool {
+        self.channels.iter().any(|channel| channel.is_market())
+    }
+}
+
+// Written by the client itself; a second copy would break the handshake
+const RESERVED_HEADERS: [&str; 8] = [
+    "host",
+    "upgrade",
+    "connection",
+    "content-length",
+    "content-type",
+    "sec-websocket-key",
+    "sec-websocket-version",
+    "user-agent",
+];
+
+/// A header name made of HTTP token characters that the client does not set itself
+fn header_name(name: &str) -> Result<String, HyperliquidError> {
+    let is_token = |ch: char| ch.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(ch);
+    if name.is_empty() || !name.chars().all(is_token) {
+        return Err(HyperliquidError::ConfigError(format!(
+            "invalid header name '{}'",
+            name
+        )));
+    }
+    if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+        let hint = if name.eq_ignore_ascii_case("user-agent") {
+            ", use --user-agent instead"
+        } else {
+            ""
+        };
+        return Err(HyperliquidError::ConfigError(format!(
+            "header '{}' is set by the client and cannot be overridden{}",
+            name, hint
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// A trimmed header value; control characters could inject extra header lines
+fn header_value(name: &str, value: &str) -> Result<String, HyperliquidError> {
+    let value = value.trim();
+    if value.chars().any(|ch| ch.is_control()) {
+        return Err(HyperliquidError::ConfigError(format!(
+            "header '{}' contains a control character",
+            name
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Credentials from `--metrics-basic-auth` and `--metrics-token`; either one is accepted
+fn metrics_auth(args: &StreamArgs) -> Result<Vec<MetricsAuth>, HyperliquidError> {
+    let mut auth = Vec::new();
+    if let Some(credentials) = &args.metrics_basic_auth {
+        auth.push(MetricsAuth::basic(credentials).map_err(HyperliquidError::ConfigError)?);
+    }
+    match args.metrics_token.as_deref().map(str::trim) {
+        Some("") => {
+            return Err(HyperliquidError::ConfigError(
+                "--metrics-token cannot be empty".to_string(),
+            ));
+        }
+        Some(token) => auth.push(MetricsAuth::Bearer(token.to_string())),
+        None => {}
+    }
+    Ok(auth)
+}
+
+/// Reject URLs that can never complete a Hyperliquid WebSocket handshake
+fn validate_endpoint(url: &Url) -> Result<(), HyperliquidError> {
+    let invalid =
+        |reason: String| HyperliquidError::ConfigError(format!("--url {}: {}", url, reason));
+    match url.scheme() {
+        "ws" | "wss" => {}
+        "http" | "https" => {
+            return Err(invalid(format!(
+                "use {}:// for the WebSocket endpoint",
+                if url.scheme() == "https" { "wss" } else { "ws" }
+            )));
+        }
+        other => {
+            return Err(invalid(format!(
+                "unsupported scheme '{}', expected ws:// or wss://",
+                other
+            )));
+        }
+    }
+    let host = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| invalid("missing host".to_string()))?;
+    if Network::of_host(host).is_some() {
+        if url.scheme() != "wss" {
+            return Err(invalid(format!("{} only accepts wss://", host)));
+        }
+        if url.path() != "/ws" {
+            return Err(invalid(format!(
+                "the WebSocket endpoint on {} is /ws, not {}",
+                host,
+                url.path()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check a bar length against the ones the `candle` channel offers
+fn candle_interval(interval: &str) -> Result<(), String> {
+    if CANDLE_INTERVALS.contains(&interval) {
+        return Ok(());
+    }
+    Err(format!(
+        "unknown interval '{}', expected one of {}",
+        interval,
+        CANDLE_INTERVALS.join(", ")
+    ))
+}
+
+/// Check for a `0x`-prefixed, 40 hex digit address before connecting
+pub(crate) fn validate_address(address: &str) -> Result<String, HyperliquidError> {
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or_default();
+    if hex.len() != 40 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(HyperliquidError::ConfigError(format!(
+            "invalid --user address '{}', expected 0x followed by 40 hex digits",
+            address
+        )));
+    }
+    Ok(format!("0x{}", hex.to_ascii_lowercase()))
+}
+
+/// Settings read from a TOML config file; every field is optional and sections mirror `Config`
+///
+/// ```toml
+/// [websocket]
+/// url = "wss://api.hyperliquid.xyz/ws"
+/// timeout_secs = 30
+/// user_agent = "desk-collector/1.0"
+///
+/// [websocket.headers]
+/// X-Api-Key = "..."
+///
+/// [subscription]
+/// coin = "ETH"
+/// channels = ["trades", "bbo"]
+///
+/// [metrics]
+/// enabled = true
+/// port = 9100
+///
+/// [output]
+/// format = "csv"
+/// record = "capture.jsonl"
+///
+/// [log]
+/// level = "debug"
+///
+/// [filter]
+/// min_notional = 10000
+/// side = "buy"
+/// ```
+///
+/// `[[subscriptions]]` blocks route several coins to the terminal, the recording, or both.
+/// `log` and `filter` can be changed at runtime with SIGHUP; the other sections need a restart.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub websocket: WebSocketFileConfig,
+    pub subscription: SubscriptionFileConfig,
+    pub metrics: MetricsFileConfig,
+    pub output: OutputFileConfig,
+    pub log: LogFileConfig,
+    pub filter: FilterFileConfig,
+    /// `[[subscriptions]]` blocks, each with its own coin, channels, filter, and sink
+    pub subscriptions: Vec<SubscriptionBlockConfig>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebSocketFileConfig {
+    pub url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub reconnect_delay_secs: Option<u64>,
+    pub max_reconnects: Option<u32>,
+    /// `mainnet` or `testnet`, as `--network`
+    pub network: Option<String>,
+    pub user_agent: Option<String>,
+    /// Extra handshake headers; `--header` wins for the same name
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionFileConfig {
+    pub coin: Option<String>,
+    pub channels: Option<Vec<String>>,
+    /// Candle bar lengths, as `--interval`
+    pub intervals: Option<Vec<String>>,
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsFileConfig {
+    pub enabled: Option<bool>,
+    /// `prometheus` or `statsd`, as `--metrics-backend`
+    pub backend: Option<MetricsBackend>,
+    pub port: Option<u16>,
+    /// As `--metrics-bind`
+    pub bind: Option<IpAddr>,
+    /// `USER:PASSWORD`, as `--metrics-basic-auth`
+    pub basic_auth: Option<String>,
+    /// As `--metrics-token`
+    pub token: Option<String>,
+    pub statsd_addr: Option<String>,
+    /// As `--pushgateway-url`
+    pub pushgateway_url: Option<String>,
+    /// As `--pushgateway-job`
+    pub pushgateway_job: Option<String>,
+    /// As `--runtime-metrics`
+    pub runtime: Option<bool>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputFileConfig {
+    /// Same values as `--format`
+    pub format: Option<String>,
+    /// Formatted trade stream destination, as `--output`
+    pub file: Option<PathBuf>,
+    /// Raw message recording, as `--record`
+    pub record: Option<PathBuf>,
+    pub record_rotate_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogFileConfig {
+    pub level: Option<String>,
+    /// As `--log-file`
+    pub file: Option<PathBuf>,
+    /// As `--log-target`
+    pub target: Option<LogTarget>,
+    pub rotate: Option<LogRotation>,
+    pub max_mb: Option<u64>,
+    pub keep: Option<usize>,
+    /// As `--log-max-age`
+    pub max_age_days: Option<u64>,
+    pub compress: Option<bool>,
+    /// As `--log-stats`
+    pub stats_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterFileConfig {
+    pub min_size: Option<f64>,
+    pub min_notional: Option<f64>,
+    /// `buy`, `sell`, or `all`
+    pub side: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionBlockConfig {
+    pub coin: String,
+    /// trades, bbo, l2Book, or candle
+    pub channels: Vec<String>,
+    /// Required with the `candle` channel, e.g. `1m` or `1h`, unless `intervals` is given
+    pub interval: Option<String>,
+    /// Several bar lengths for the `candle` channel, on top of `interval`
+    pub intervals: Vec<String>,
+    pub filter: FilterFileConfig,
+    /// `terminal`, `record`, or `both` (default)
+    pub sink: SubscriptionSink,
+}
+
+impl FilterFileConfig {
+    pub fn trade_filter(&self) -> Result<TradeFilter, HyperliquidError> {
+        let side = match self.side.as_deref() {
+            None => SideFilter::All,
+            Some(side) => side.parse().map_err(HyperliquidError::ConfigError)?,
+        };
+        Ok(TradeFilter {
+            min_size: self.min_size,
+            min_notional: self.min_notional,
+            side,
+        })
+    }
+}
+
+impl SubscriptionBlockConfig {
+    fn route(&self, number: usize) -> Result<SubscriptionRoute, HyperliquidError> {
+        let invalid = |reason: String| {
+            HyperliquidError::ConfigError(format!("[[subscriptions]] block {}: {}", number, reason))
+        };
+        let coin = self.coin.trim();
+        if coin.is_empty() {
+            return Err(invalid("coin is required".to_string()));
+        }
+
+        let mut channels = Vec::new();
+        for name in &self.channels {
+            let channel = match name.parse::<Channel>() {
+                Ok(channel) if channel.is_market() => channel,
+                _ => {
+                    return Err(invalid(format!(
+                        "unsupported channel '{}', expected trades, bbo, l2Book, or candle",
+                        name
+                    )));
+                }
+            };
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+        if channels.is_empty() {
+            return Err(invalid("channels needs at least one channel".to_string()));
+        }
+
+        let mut intervals: Vec<String> = Vec::new();
+        for interval in self.interval.iter().chain(&self.intervals) {
+            candle_interval(interval).map_err(invalid)?;
+            if !intervals.contains(interval) {
+                intervals.push(interval.clone());
+            }
+        }
+        let has_candle = channels.contains(&Channel::Candle);
+        if !intervals.is_empty() && !has_candle {
+            return Err(invalid(
+                "interval only applies to the candle channel".to_string(),
+            ));
+        }
+        if intervals.is_empty() && has_candle {
+            return Err(invalid("the candle channel needs an interval".to_string()));
+        }
+
+        Ok(SubscriptionRoute {
+            coin: coin.to_string(),
+            channels,
+            intervals,
+            filter: self
+                .filter
+                .trade_filter()
+                .map_err(|e| invalid(e.to_string()))?,
+            sink: self.sink,
+        })
+    }
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self, HyperliquidError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            HyperliquidError::ConfigError(format!("cannot read {}: {}", path.display(), e))
+        })?;
+        toml::from_str(&contents).map_err(|e| {
+            HyperliquidError::ConfigError(format!("cannot parse {}: {}", path.display(), e))
+        })
+    }
+
+    /// The explicit path, or the first default location that exists
+    pub fn locate(explicit: Option<&Path>) -> Option<PathBuf> {
+        if let Some(path) = explicit {
+            return Some(path.to_path_buf());
+        }
+        std::iter::once(PathBuf::from(LOCAL_CONFIG_FILE))
+            .chain(Self::user_path())
+            .find(|path| path.is_file())
+    }
+
+    /// `$XDG_CONFIG_HOME/rs-hyperliquid/config.toml`, falling back to `~/.config`
+    pub fn user_path() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .map(|dir| dir.join(USER_CONFIG_FILE))
+    }
+
+    /// Fill in arguments that were neither passed on the command line nor set in the
+    /// environment, so precedence is CLI > env > file > defaults
+    pub fn apply(&self, args: &mut StreamArgs, matches: &ArgMatches) {
+        let from_default = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                None | Some(ValueSource::DefaultValue)
+            )
+        };
+
+        if let Some(url) = self.websocket.url.clone().filter(|_| from_default("url")) {
+            args.url = Some(url);
+        }
+        if let Some(timeout) = self
+            .websocket
+            .timeout_secs
+            .filter(|_| from_default("timeout"))
+        {
+            args.timeout = timeout;
+        }
+        if let Some(delay) = self
+            .websocket
+            .reconnect_delay_secs
+            .filter(|_| from_default("reconnect_delay"))
+        {
+            args.reconnect_delay = delay;
+        }
+        if let Some(max) = self
+            .websocket
+            .max_reconnects
+            .filter(|_| from_default("max_reconnects"))
+        {
+            args.max_reconnects = max;
+        }
+        if let Some(network) = self
+            .websocket
+            .network
+            .clone()
+            .filter(|_| from_default("network"))
+        {
+            args.network = Some(network);
+        }
+        if let Some(agent) = self
+            .websocket
+            .user_agent
+            .clone()
+            .filter(|_| from_default("user_agent"))
+        {
+            args.user_agent = Some(agent);
+        }
+        // Listed first so a command-line header with the same name replaces the file's
+        let file_headers = self
+            .websocket
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value));
+        args.headers = file_headers.chain(args.headers.drain(..)).collect();
+        if let Some(coin) = self
+            .subscription
+            .coin
+            .clone()
+            .filter(|_| from_default("coin"))
+        {
+            args.coin = coin;
+        }
+        if let Some(channels) = self
+            .subscription
+            .channels
+            .as_ref()
+            .filter(|_| from_default("subscribe"))
+        {
+            args.subscribe = Some(channels.join(","));
+        }
+        if let Some(intervals) = self
+            .subscription
+            .intervals
+            .clone()
+            .filter(|_| from_default("intervals"))
+        {
+            args.intervals = intervals;
+        }
+        if let Some(user) = self
+            .subscription
+            .user
+            .clone()
+            .filter(|_| from_default("user"))
+        {
+            args.user = Some(user);
+        }
+        if let Some(enabled) = self.metrics.enabled.filter(|_| from_default("metrics")) {
+            args.metrics = enabled;
+        }
+        if let Some(backend) = self
+            .metrics
+            .backend
+            .filter(|_| from_default("metrics_backend"))
+        {
+            args.metrics_backend = backend;
+        }
+        if let Some(port) = self.metrics.port.filter(|_| from_default("metrics_port")) {
+            args.metrics_port = port;
+        }
+        if let Some(bind) = self.metrics.bind.filter(|_| from_default("metrics_bind")) {
+            args.metrics_bind = bind;
+        }
+        if let Some(credentials) = self
+            .metrics
+            .basic_auth
+            .clone()
+            .filter(|_| from_default("metrics_basic_auth"))
+        {
+            args.metrics_basic_auth = Some(credentials);
+        }
+        if let Some(token) = self
+            .metrics
+            .token
+            .clone()
+            .filter(|_| from_default("metrics_token"))
+        {
+            args.metrics_token = Some(token);
+        }
+        if let Some(runtime) = self
+            .metrics
+            .runtime
+            .filter(|_| from_default("runtime_metrics"))
+        {
+            args.runtime_metrics = runtime;
+        }
+        if let Some(addr) = self
+            .metrics
+            .statsd_addr
+            .clone()
+            .filter(|_| from_default("statsd_addr"))
+        {
+            args.statsd_addr = addr;
+        }
+        if let Some(url) = self
+            .metrics
+            .pushgateway_url
+            .clone()
+            .filter(|_| from_default("pushgateway_url"))
+        {
+            args.pushgateway_url = url;
+        }
+        if let Some(job) = self
+            .metrics
+            .pushgateway_job
+            .clone()
+            .filter(|_| from_default("pushgateway_job"))
+        {
+            args.pushgateway_job = job;
+        }
+        if let Some(format) = self
+            .output
+            .format
+            .clone()
+            .filter(|_| from_default("format"))
+        {
+            args.format = format;
+        }
+        if let Some(file) = self.output.file.clone().filter(|_| from_default("output")) {
+            args.output = Some(file);
+        }
+        if let Some(record) = self
+            .output
+            .record
+            .clone()
+            .filter(|_| from_default("record"))
+        {
+            args.record = Some(record);
+        }
+        if let Some(rotate) = self
+            .output
+            .record_rotate_secs
+            .filter(|_| from_default("record_rotate_secs"))
+        {
+            args.record_rotate_secs = rotate;
+        }
+        if let Some(level) = self.log.level.clone().filter(|_| from_default("log_level")) {
+            args.log_level = level;
+        }
+        if let Some(file) = self.log.file.clone().filter(|_| from_default("log_file")) {
+            args.log_file = Some(file);
+        }
+        if let Some(target) = self.log.target.filter(|_| from_default("log_target")) {
+            args.log_target = Some(target);
+        }
+        if let Some(rotate) = self.log.rotate.filter(|_| from_default("log_rotate")) {
+            args.log_rotate = rotate;
+        }
+        if let Some(max_mb) = self.log.max_mb.filter(|_| from_default("log_max_mb")) {
+            args.log_max_mb = max_mb;
+        }
+        if let Some(keep) = self.log.keep.filter(|_| from_default("log_keep")) {
+            args.log_keep = keep;
+        }
+        if let Some(days) = self
+            .log
+            .max_age_days
+            .filter(|_| from_default("log_max_age"))
+        {
+            args.log_max_age = days;
+        }
+        if let Some(compress) = self.log.compress.filter(|_| from_default("log_compress")) {
+            args.log_compress = compress;
+        }
+        if let Some(secs) = self.log.stats_secs.filter(|_| from_default("log_stats")) {
+            args.log_stats = secs;
+        }
+    }
+
+    /// Trade filter from the `[filter]` section
+    pub fn trade_filter(&self) -> Result<TradeFilter, HyperliquidError> {
+        self.filter.trade_filter()
+    }
+
+    /// Validated `[[subscriptions]]` blocks; a coin and channel may only appear in one block
+    pub fn routes(&self) -> Result<Vec<SubscriptionRoute>, HyperliquidError> {
+        let mut routes: Vec<SubscriptionRoute> = Vec::new();
+        for (index, block) in self.subscriptions.iter().enumerate() {
+            let route = block.route(index + 1)?;
+            for channel in &route.channels {
+                if let Some(earlier) = routes
+                    .iter()
+                    .position(|other| other.matches(channel.as_str(), &route.coin))
+                {
+                    return Err(HyperliquidError::ConfigError(format!(
+                        "[[subscriptions]] blocks {} and {} both subscribe to {} {}",
+                        earlier + 1,
+                        index + 1,
+                        route.coin,
+                        channel
+                    )));
+                }
+            }
+            routes.push(route);
+        }
+        Ok(routes)
+    }
+
+    /// Sections that differ from `other` and only take effect after a restart
+    pub fn restart_required(&self, other: &ConfigFile) -> Vec<&'static str> {
+        let mut sections = Vec::new();
+        if self.websocket != other.websocket {
+            sections.push("websocket");
+        }
+        if self.subscription != other.subscription {
+            sections.push("subscription");
+        }
+        if self.metrics != other.metrics {
+            sections.push("metrics");
+        }
+        if self.output != other.output {
+            sections.push("output");
+        }
+        if self.subscriptions != other.subscriptions {
+            sections.push("subscriptions");
+        }
+        // The level is applied on reload, but the log file and stats line are set up at startup
+        let log_file = |log: &LogFileConfig| {
+            (
+                log.file.clone(),
+                log.target,
+                log.rotate,
+                log.max_mb,
+                log.keep,
+                log.max_age_days,
+                log.compress,
+                log.stats_secs,
+            )
+        };
+        if log_file(&self.log) != log_file(&other.log) {
+            sections.push("log");
+        }
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Args;
+    use clap::{CommandFactory, FromArgMatches, Parser};
+
+    #[test]
+    fn accepts_addresses_and_lowercases_them() {
+        let mixed = "0x31Ca8395cF837dE08B24dA3f660e77761DfB974b";
+        assert_eq!(
+            validate_address(mixed).unwrap(),
+            "0x31ca8395cf837de08b24da3f660e77761dfb974b"
+        );
+        assert_eq!(
+            validate_address("0X010461C14E146AC35FE42271BDC1134EE31C703A").unwrap(),
+            "0x010461c14e146ac35fe42271bdc1134ee31c703a"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        for address in [
+            "31ca8395cf837de08b24da3f660e77761dfb974b",
+            "0x31ca8395cf837de08b24da3f660e77761dfb974",
+            "0x31ca8395cf837de08b24da3f660e77761dfb974b0",
+            "0x31ca8395cf837de08b24da3f660e77761dfb974g",
+            "0x",
+            "",
+        ] {
+            assert!(validate_address(address).is_err(), "{} accepted", address);
+        }
+    }
+
+    fn endpoint(url: &str) -> Result<(), HyperliquidError> {
+        validate_endpoint(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn accepts_websocket_endpoints() {
+        for url in [
+            "wss://api.hyperliquid.xyz/ws",
+            "wss://api.hyperliquid-testnet.xyz/ws",
+            "ws://127.0.0.1:8080/ws",
+            "wss://gateway.example.com/hl/stream",
+        ] {
+            assert!(endpoint(url).is_ok(), "{} rejected", url);
+        }
+    }
+
+    #[test]
+    fn rejects_endpoints_that_cannot_handshake() {
+        for (url, reason) in [
+            ("https://api.hyperliquid.xyz/ws", "use wss://"),
+            ("http://127.0.0.1:8080/ws", "use ws://"),
+            ("ftp://api.hyperliquid.xyz/ws", "unsupported scheme 'ftp'"),
+            ("ws://api.hyperliquid.xyz/ws", "only accepts wss://"),
+            ("wss://api.hyperliquid.xyz/info", "is /ws, not /info"),
+        ] {
+            let error = endpoint(url).unwrap_err().to_string();
+            assert!(error.contains(reason), "{}: {}", url, error);
+        }
+    }
+
+    #[test]
+    fn rejects_an_endpoint_without_a_host() {
+        for url in ["ws://", "wss://:443/ws"] {
+            let args = Args::try_parse_from(["rs-hyperliquid", "--url", url]).unwrap();
+            assert!(Config::from_args(&args.stream).is_err(), "{} accepted", url);
+        }
+    }
+
+    fn applied(file: &str, argv: &[&str]) -> StreamArgs {
+        let file: ConfigFile = toml::from_str(file).unwrap();
+        let matches = Args::command()
+            .try_get_matches_from(std::iter::once("rs-hyperliquid").chain(argv.iter().copied()))
+            .unwrap();
+        let mut args = Args::from_arg_matches(&matches).unwrap().stream;
+        file.apply(&mut args, &matches);
+        args
+    }
+
+    const FILE: &str = r#"
+        [websocket]
+        timeout_secs = 5
+        headers = { "X-Api-Key" = "from-file" }
+
+        [subscription]
+        coin = "ETH"
+        channels = ["trades", "bbo"]
+
+        [output]
+        format = "csv"
+
+        [log]
+        level = "debug"
+    "#;
+
+    #[test]
+    fn file_values_replace_defaults() {
+        let args = applied(FILE, &[]);
+        assert_eq!(args.coin, "ETH");
+        assert_eq!(args.subscribe.as_deref(), Some("trades,bbo"));
+        assert_eq!(args.timeout, 5);
+        assert_eq!(args.format, "csv");
+        assert_eq!(args.log_level, "debug");
+        assert_eq!(args.headers, ["X-Api-Key: from-file"]);
+    }
+
+    #[test]
+    fn explicit_flags_win_over_the_file() {
+        let args = applied(
+            FILE,
+            &[
+                "--coin",
+                "SOL",
+                "--timeout",
+                "30",
+                "--format",
+                "table",
+                "--header",
+                "X-Api-Key: from-flag",
+            ],
+        );
+        assert_eq!(args.coin, "SOL");
+        // Passing the default value explicitly still counts as a choice
+        assert_eq!(args.timeout, 30);
+        assert_eq!(args.format, "table");
+        assert_eq!(args.log_level, "debug");
+        // The flag's header comes last, so it replaces the file's one of the same name
+        assert_eq!(
+            args.headers,
+            ["X-Api-Key: from-file", "X-Api-Key: from-flag"]
+        );
+    }
+
+    #[test]
+    fn builds_the_trade_filter_from_the_filter_section() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            [filter]
+            min_notional = 10000
+            side = "sell"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.trade_filter().unwrap(),
+            TradeFilter {
+                min_size: None,
+                min_notional: Some(10000.0),
+                side: SideFilter::Sells,
+            }
+        );
+        assert_eq!(
+            ConfigFile::default().trade_filter().unwrap(),
+            TradeFilter::default()
+        );
+
+        let file: ConfigFile = toml::from_str("[filter]\nside = \"long\"").unwrap();
+        assert!(file.trade_filter().is_err());
+    }
+
+    #[test]
+    fn reload_reports_sections_that_need_a_restart() {
+        let before: ConfigFile = toml::from_str(FILE).unwrap();
+        let reloaded: ConfigFile = toml::from_str(&format!(
+            "{}\n[filter]\nmin_size = 1.5",
+            FILE.replace("\"debug\"", "\"trace\"")
+        ))
+        .unwrap();
+        // The level and filter are applied live
+        assert!(before.restart_required(&reloaded).is_empty());
+
+        let reloaded: ConfigFile = toml::from_str(
+            &FILE
+                .replace("\"ETH\"", "\"BTC\"")
+                .replace("\"csv\"", "\"json\"")
+                .replace("level = \"debug\"", "level = \"debug\"\nkeep = 3"),
+        )
+        .unwrap();
+        assert_eq!(
+            before.restart_required(&reloaded),
+            ["subscription", "output", "log"]
+        );
+    }
 }
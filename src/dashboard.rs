@@ -0,0 +1,306 @@
+/// file: src/dashboard.rs
+/// description: Embedded browser dashboard streaming client events over Server-Sent Events
+/// reference: https://html.spec.whatwg.org/multipage/server-sent-events.html
+use crate::{
+    error::HyperliquidError,
+    events::{BroadcastSender, ClientEvent},
+    monitoring::{EventConsumer, record_dropped_events},
+    recent::{self, SharedRecent},
+};
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+const DASHBOARD_HTML: &str = include_str!("../assets/dashboard.html");
+
+// Comment frames keep proxies and browsers from timing out idle streams
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// Limits on a request's line and headers, so a client cannot hold a connection or grow memory
+// without end; the listeners serve small GETs only
+const MAX_REQUEST_HEAD_BYTES: u64 = 8 * 1024;
+const MAX_REQUEST_HEADERS: usize = 64;
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Bind the dashboard listener and serve it in the background
+///
+/// A browser that opens the page mid-session first receives what `recent` holds.
+pub async fn serve_dashboard(port: u16, bus: BroadcastSender, recent: SharedRecent) -> Result<()> {
+    let addr: SocketAddr = ([0, 0, 0, 0], port).into();
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| HyperliquidError::ConfigError(format!("cannot bind {}: {}", addr, e)))?;
+    info!("Dashboard available at http://{}/", addr);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let (bus, recent) = (bus.clone(), recent.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, bus, recent).await {
+                            debug!("Dashboard connection from {} ended: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Dashboard accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    bus: BroadcastSender,
+    recent: SharedRecent,
+) -> std::io::Result<()> {
+    let (request, mut stream) = read_request(stream).await?;
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") | ("GET", "/index.html") => {
+            write_response(
+                &mut stream,
+                "200 OK",
+                "text/html; charset=utf-8",
+                DASHBOARD_HTML,
+            )
+            .await
+        }
+        ("GET", "/events") => stream_events(stream, bus, recent).await,
+        ("GET", _) => write_response(&mut stream, "404 Not Found", "text/plain", "not found").await,
+        _ => {
+            write_response(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed",
+            )
+            .await
+        }
+    }
+}
+
+/// The parts of a request the embedded listeners act on
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    /// `Authorization` header value, for listeners that require credentials
+    pub authorization: Option<String>,
+}
+
+/// Read the request line and headers; only `Authorization` is kept
+///
+/// Fails on a head over 8 KiB or 64 headers, or one not complete within 10 seconds.
+pub(crate) async fn read_request(stream: TcpStream) -> std::io::Result<(Request, TcpStream)> {
+    read_request_within(stream, REQUEST_HEAD_TIMEOUT).await
+}
+
+async fn read_request_within(
+    stream: TcpStream,
+    limit: Duration,
+) -> std::io::Result<(Request, TcpStream)> {
+    let mut reader = BufReader::new(stream).take(MAX_REQUEST_HEAD_BYTES);
+    let request = tokio::time::timeout(limit, read_head(&mut reader))
+        .await
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "request head not received in time",
+            )
+        })??;
+    Ok((request, reader.into_inner().into_inner()))
+}
+
+async fn read_head<R: AsyncBufRead + Unpin>(
+    reader: &mut tokio::io::Take<R>,
+) -> std::io::Result<Request> {
+    let too_large =
+        || std::io::Error::new(std::io::ErrorKind::InvalidData, "request head too large");
+    // A line cut short by the byte limit has no newline
+    let mut read_line = async |line: &mut String| -> std::io::Result<usize> {
+        let read = reader.read_line(line).await?;
+        if reader.limit() == 0 && !line.ends_with('\n') {
+            return Err(too_large());
+        }
+        Ok(read)
+    };
+
+    let mut request_line = String::new();
+    read_line(&mut request_line).await?;
+    let mut authorization = None;
+    let mut headers = 0;
+    loop {
+        let mut header = String::new();
+        if read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_REQUEST_HEADERS {
+            return Err(too_large());
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("authorization")
+        {
+            authorization = Some(value.trim().to_string());
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    Ok(Request {
+        method: parts.next().unwrap_or_default().to_string(),
+        path: parts.next().unwrap_or_default().to_string(),
+        authorization,
+    })
+}
+
+pub(crate) async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    respond(stream, status, "", content_type, body).await
+}
+
+/// 401 asking for credentials under `challenge`, e.g. `Basic realm="metrics"`
+pub(crate) async fn write_unauthorized(
+    stream: &mut TcpStream,
+    challenge: &str,
+) -> std::io::Result<()> {
+    respond(
+        stream,
+        "401 Unauthorized",
+        &format!("WWW-Authenticate: {}\r\n", challenge),
+        "text/plain",
+        "unauthorized\n",
+    )
+    .await
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    extra_headers: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\n{}Content-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        extra_headers,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn stream_events(
+    mut stream: TcpStream,
+    bus: BroadcastSender,
+    recent: SharedRecent,
+) -> std::io::Result<()> {
+    // Joining the bus under the lock the UI publishes with leaves no gap or overlap
+    let (backlog, mut receiver) = {
+        let recent = recent::lock(&recent);
+        (recent.replay(), bus.subscribe())
+    };
+    let mut head = String::from(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+    );
+    for json in backlog.iter().filter_map(event_json) {
+        head.push_str(&format!("data: {}\n\n", json));
+    }
+    stream.write_all(head.as_bytes()).await?;
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    loop {
+        let frame = tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => match event_json(&event) {
+                    Some(json) => format!("data: {}\n\n", json),
+                    None => continue,
+                },
+                // A slow browser skips ahead instead of holding back the bus
+                Err(RecvError::Lagged(skipped)) => {
+                    record_dropped_events(EventConsumer::Dashboard, skipped);
+                    format!(": skipped {} events\n\n", skipped)
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            _ = keepalive.tick() => ": keepalive\n\n".to_string(),
+        };
+        stream.write_all(frame.as_bytes()).await?;
+    }
+}
+
+/// Dashboard payload for an event, or `None` for events the page does not show
+fn event_json(event: &ClientEvent) -> Option<String> {
+    match event {
+        ClientEvent::BookReceived(_) | ClientEvent::MessageReceived { .. } => None,
+        event => Some(event.to_json().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Send `head` from a client and read it on the server side within `limit`
+    async fn serve(head: Vec<u8>, limit: Duration) -> std::io::Result<Request> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            // The server may close on us mid-write once it has seen enough
+            let _ = stream.write_all(&head).await;
+            stream
+        });
+        let (stream, _) = listener.accept().await.unwrap();
+        let result = read_request_within(stream, limit).await;
+        drop(client.await.unwrap());
+        result.map(|(request, _)| request)
+    }
+
+    #[tokio::test]
+    async fn reads_the_request_line_and_authorization() {
+        let head = b"GET /metrics HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer t\r\n\r\n";
+        let request = serve(head.to_vec(), REQUEST_HEAD_TIMEOUT).await.unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/metrics");
+        assert_eq!(request.authorization.as_deref(), Some("Bearer t"));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_endless_header_line() {
+        let mut head = b"GET / HTTP/1.1\r\nX-Long: ".to_vec();
+        head.extend(std::iter::repeat_n(b'a', 64 * 1024));
+        let error = serve(head, REQUEST_HEAD_TIMEOUT).await.err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn rejects_too_many_headers() {
+        let mut head = b"GET / HTTP/1.1\r\n".to_vec();
+        for _ in 0..=MAX_REQUEST_HEADERS {
+            head.extend(b"X: y\r\n");
+        }
+        head.extend(b"\r\n");
+        let error = serve(head, REQUEST_HEAD_TIMEOUT).await.err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_an_unfinished_head() {
+        let head = b"GET / HTTP/1.1\r\nHost: x\r\n".to_vec();
+        let error = serve(head, Duration::from_millis(50)).await.err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    }
+}
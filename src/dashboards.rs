@@ -0,0 +1,535 @@
+/// file: src/dashboards.rs
+/// description: Grafana dashboard and Prometheus alert rules for the crate's metrics
+/// reference: https://grafana.com/docs/grafana/latest/dashboards/build-dashboards/view-dashboard-json-model/
+use crate::cli::MetricsAction;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::io::Write;
+
+/// Selector added to every dashboard query, driven by the `instance` variable
+const SELECTOR: &str = r#"instance=~"$instance""#;
+
+/// Print the dashboard JSON or the alert rules for `metrics`
+pub fn print_metrics_config(action: MetricsAction) -> Result<()> {
+    let output = match action {
+        MetricsAction::Dashboard { datasource_uid } => {
+            serde_json::to_string_pretty(&grafana_dashboard(datasource_uid.as_deref()))?
+        }
+        MetricsAction::Alerts => alert_rules(),
+    };
+    std::io::stdout().write_all(format!("{}\n", output).as_bytes())?;
+    Ok(())
+}
+
+/// One query in a panel: PromQL and the legend shown for each series
+struct Query(String, &'static str);
+
+fn query(expr: &str, legend: &'static str) -> Query {
+    Query(expr.replace("SEL", SELECTOR), legend)
+}
+
+/// Dashboard panels are laid out on Grafana's 24-column grid
+struct Grid {
+    panels: Vec<Value>,
+    datasource: Value,
+    x: u32,
+    y: u32,
+    row_height: u32,
+}
+
+impl Grid {
+    fn row(&mut self, title: &str) {
+        self.next_line();
+        let id = self.panels.len() + 1;
+        self.panels.push(json!({
+            "id": id,
+            "type": "row",
+            "title": title,
+            "collapsed": false,
+            "gridPos": { "x": 0, "y": self.y, "w": 24, "h": 1 },
+            "panels": [],
+        }));
+        self.y += 1;
+    }
+
+    fn next_line(&mut self) {
+        if self.x > 0 {
+            self.x = 0;
+            self.y += self.row_height;
+        }
+    }
+
+    fn panel(&mut self, kind: &str, title: &str, unit: &str, width: u32, queries: &[Query]) {
+        let height = if kind == "stat" { 4 } else { 8 };
+        if self.x + width > 24 {
+            self.next_line();
+        }
+        let id = self.panels.len() + 1;
+        let targets: Vec<Value> = queries
+            .iter()
+            .zip('A'..)
+            .map(|(Query(expr, legend), ref_id)| {
+                json!({
+                    "datasource": self.datasource,
+                    "expr": expr,
+                    "legendFormat": legend,
+                    "refId": ref_id.to_string(),
+                })
+            })
+            .collect();
+        self.panels.push(json!({
+            "id": id,
+            "type": kind,
+            "title": title,
+            "datasource": self.datasource,
+            "gridPos": { "x": self.x, "y": self.y, "w": width, "h": height },
+            "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+            "targets": targets,
+        }));
+        self.x += width;
+        self.row_height = height;
+    }
+}
+
+/// Dashboard covering every metric the crate exports
+///
+/// Without `datasource_uid` the datasource is an import input, so Grafana asks for it when the
+/// JSON is imported; with it, the file can be provisioned as is.
+pub fn grafana_dashboard(datasource_uid: Option<&str>) -> Value {
+    let datasource = json!({
+        "type": "prometheus",
+        "uid": datasource_uid.unwrap_or("${DS_PROMETHEUS}"),
+    });
+    let mut grid = Grid {
+        panels: Vec::new(),
+        datasource: datasource.clone(),
+        x: 0,
+        y: 0,
+        row_height: 0,
+    };
+
+    grid.row("Connection");
+    grid.panel(
+        "stat",
+        "Connected",
+        "none",
+        6,
+        &[query("hyperliquid_connected{SEL}", "{{instance}}")],
+    );
+    grid.panel(
+        "stat",
+        "Session age",
+        "s",
+        6,
+        &[query(
+            "hyperliquid_connection_age_seconds{SEL}",
+            "{{instance}}",
+        )],
+    );
+    grid.panel(
+        "stat",
+        "Active subscriptions",
+        "none",
+        6,
+        &[query(
+            "hyperliquid_active_subscriptions{SEL}",
+            "{{instance}}",
+        )],
+    );
+    grid.panel(
+        "stat",
+        "Clock skew",
+        "ms",
+        6,
+        &[query(
+            "hyperliquid_clock_skew_milliseconds{SEL}",
+            "{{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Messages and trades",
+        "ops",
+        12,
+        &[
+            query(
+                "rate(hyperliquid_messages_received_total{SEL}[1m])",
+                "messages {{instance}}",
+            ),
+            query(
+                "rate(hyperliquid_trades_total{SEL}[1m])",
+                "trades {{instance}}",
+            ),
+        ],
+    );
+    grid.panel(
+        "timeseries",
+        "Time since last message",
+        "s",
+        12,
+        &[query(
+            "hyperliquid_seconds_since_last_message{SEL}",
+            "{{channel}} {{coin}} {{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Reconnects by reason",
+        "short",
+        12,
+        &[query(
+            "sum by (reason) (increase(hyperliquid_reconnects_total{SEL}[5m]))",
+            "{{reason}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Disconnects and handshake failures",
+        "short",
+        12,
+        &[
+            query(
+                "sum by (reason) (increase(hyperliquid_disconnects_total{SEL}[5m]))",
+                "{{reason}}",
+            ),
+            query(
+                "sum(increase(hyperliquid_handshake_failures_total{SEL}[5m]))",
+                "handshake failures",
+            ),
+        ],
+    );
+    grid.panel(
+        "timeseries",
+        "Session length before a drop",
+        "s",
+        12,
+        &[
+            query(
+                r#"hyperliquid_connection_duration_seconds{SEL,quantile="0.5"}"#,
+                "p50 {{instance}}",
+            ),
+            query(
+                r#"hyperliquid_connection_duration_seconds{SEL,quantile="0.99"}"#,
+                "p99 {{instance}}",
+            ),
+        ],
+    );
+
+    grid.panel(
+        "timeseries",
+        "Ping round trip",
+        "s",
+        12,
+        &[
+            query(
+                r#"hyperliquid_ping_rtt_seconds{SEL,quantile="0.5"}"#,
+                "p50 {{instance}}",
+            ),
+            query(
+                r#"hyperliquid_ping_rtt_seconds{SEL,quantile="0.99"}"#,
+                "p99 {{instance}}",
+            ),
+        ],
+    );
+    grid.panel(
+        "timeseries",
+        "Trade delay",
+        "s",
+        12,
+        &[
+            query(
+                r#"hyperliquid_trade_delay_seconds{SEL,quantile="0.5"}"#,
+                "p50 {{skew_corrected}} {{instance}}",
+            ),
+            query(
+                r#"hyperliquid_trade_delay_seconds{SEL,quantile="0.99"}"#,
+                "p99 {{skew_corrected}} {{instance}}",
+            ),
+        ],
+    );
+
+    grid.row("Backpressure");
+    grid.panel(
+        "timeseries",
+        "Queue depth",
+        "short",
+        8,
+        &[query(
+            "hyperliquid_event_channel_depth{SEL}",
+            "{{consumer}} {{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Queue high-water mark",
+        "short",
+        8,
+        &[query(
+            "hyperliquid_event_channel_high_water{SEL}",
+            "{{consumer}} {{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Dropped events",
+        "short",
+        8,
+        &[query(
+            "sum by (consumer) (increase(hyperliquid_events_dropped_total{SEL}[5m]))",
+            "{{consumer}}",
+        )],
+    );
+
+    grid.row("Messages");
+    grid.panel(
+        "timeseries",
+        "Message size p99",
+        "bytes",
+        12,
+        &[query(
+            r#"max by (channel) (hyperliquid_message_bytes{SEL,quantile="0.99"})"#,
+            "{{channel}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Parse time p99",
+        "s",
+        12,
+        &[query(
+            r#"max by (channel) (hyperliquid_message_parse_seconds{SEL,quantile="0.99"})"#,
+            "{{channel}}",
+        )],
+    );
+
+    grid.row("Output sinks");
+    grid.panel(
+        "timeseries",
+        "Write latency p99",
+        "s",
+        8,
+        &[query(
+            r#"hyperliquid_sink_write_seconds{SEL,quantile="0.99"}"#,
+            "{{sink}} {{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Batch size p50",
+        "short",
+        8,
+        &[query(
+            r#"hyperliquid_sink_batch_lines{SEL,quantile="0.5"}"#,
+            "{{sink}} {{instance}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Sink errors",
+        "short",
+        8,
+        &[query(
+            "sum by (sink) (increase(hyperliquid_sink_errors_total{SEL}[5m]))",
+            "{{sink}}",
+        )],
+    );
+
+    grid.row("Data integrity");
+    grid.panel(
+        "timeseries",
+        "Duplicate trades",
+        "short",
+        12,
+        &[query(
+            "sum(increase(hyperliquid_duplicate_trades_total{SEL}[5m]))",
+            "duplicates",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Invalid timestamps",
+        "short",
+        12,
+        &[query(
+            "sum(increase(hyperliquid_invalid_timestamps_total{SEL}[5m]))",
+            "invalid timestamps",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Data gaps",
+        "short",
+        12,
+        &[query(
+            "sum by (kind) (increase(hyperliquid_data_gaps_total{SEL}[1h]))",
+            "{{kind}}",
+        )],
+    );
+    grid.panel(
+        "timeseries",
+        "Time lost to gaps",
+        "s",
+        12,
+        &[query(
+            "sum by (kind) (increase(hyperliquid_data_gap_seconds_sum{SEL}[1h]))",
+            "{{kind}}",
+        )],
+    );
+
+    // Only populated with --runtime-metrics
+    grid.row("Tokio runtime");
+    grid.panel(
+        "timeseries",
+        "Tasks and queue",
+        "short",
+        12,
+        &[
+            query(
+                "hyperliquid_tokio_alive_tasks{SEL}",
+                "alive tasks {{instance}}",
+            ),
+            query(
+                "hyperliquid_tokio_global_queue_depth{SEL}",
+                "global queue {{instance}}",
+            ),
+        ],
+    );
+    grid.panel(
+        "timeseries",
+        "Worker busy ratio",
+        "percentunit",
+        12,
+        &[query(
+            "hyperliquid_tokio_worker_busy_ratio{SEL}",
+            "worker {{worker}} {{instance}}",
+        )],
+    );
+
+    let mut dashboard = json!({
+        "title": "rs-hyperliquid",
+        "uid": "rs-hyperliquid",
+        "tags": ["hyperliquid"],
+        "timezone": "utc",
+        "schemaVersion": 39,
+        "refresh": "30s",
+        "time": { "from": "now-6h", "to": "now" },
+        "templating": {
+            "list": [{
+                "name": "instance",
+                "label": "Instance",
+                "type": "query",
+                "datasource": datasource,
+                "query": "label_values(hyperliquid_connected, instance)",
+                "refresh": 2,
+                "multi": true,
+                "includeAll": true,
+                "current": { "text": "All", "value": "$__all" },
+            }],
+        },
+        "panels": grid.panels,
+    });
+    if datasource_uid.is_none() {
+        dashboard["__inputs"] = json!([{
+            "name": "DS_PROMETHEUS",
+            "label": "Prometheus",
+            "type": "datasource",
+            "pluginId": "prometheus",
+            "pluginName": "Prometheus",
+        }]);
+    }
+    dashboard
+}
+
+/// Prometheus rule file with alerts on the crate's metrics
+///
+/// Thresholds follow the defaults: a 10,000 event queue and the 1000ms `--max-clock-skew`.
+pub fn alert_rules() -> String {
+    let rules = [
+        (
+            "HyperliquidDisconnected",
+            "hyperliquid_connected == 0",
+            "2m",
+            "critical",
+            "{{ $labels.instance }} has had no WebSocket connection for 2 minutes",
+        ),
+        (
+            "HyperliquidStreamStalled",
+            "hyperliquid_connected == 1 and rate(hyperliquid_messages_received_total[5m]) == 0",
+            "10m",
+            "warning",
+            "{{ $labels.instance }} is connected but has received nothing for 10 minutes",
+        ),
+        (
+            "HyperliquidSubscriptionStale",
+            "hyperliquid_seconds_since_last_message > 300 and on (instance) hyperliquid_connected == 1",
+            "0m",
+            "warning",
+            "{{ $labels.channel }} {{ $labels.coin }} on {{ $labels.instance }} has sent nothing for 5 minutes",
+        ),
+        (
+            "HyperliquidReconnectStorm",
+            "sum by (instance) (increase(hyperliquid_reconnects_total[15m])) > 5",
+            "0m",
+            "warning",
+            "{{ $labels.instance }} reconnected {{ $value }} times in 15 minutes",
+        ),
+        (
+            "HyperliquidEventsDropped",
+            "sum by (instance, consumer) (increase(hyperliquid_events_dropped_total[5m])) > 0",
+            "0m",
+            "warning",
+            "{{ $labels.instance }} dropped {{ $value }} events for {{ $labels.consumer }}",
+        ),
+        (
+            "HyperliquidQueueBacklog",
+            "hyperliquid_event_channel_depth > 5000",
+            "2m",
+            "warning",
+            "The {{ $labels.consumer }} queue on {{ $labels.instance }} is over half full",
+        ),
+        (
+            "HyperliquidSinkErrors",
+            "increase(hyperliquid_sink_errors_total[5m]) > 0",
+            "0m",
+            "critical",
+            "The {{ $labels.sink }} sink on {{ $labels.instance }} failed to write",
+        ),
+        (
+            "HyperliquidSlowSink",
+            r#"hyperliquid_sink_write_seconds{quantile="0.99"} > 0.5"#,
+            "5m",
+            "warning",
+            "The {{ $labels.sink }} sink on {{ $labels.instance }} takes over 500ms per batch",
+        ),
+        (
+            "HyperliquidClockSkew",
+            "abs(hyperliquid_clock_skew_milliseconds) > 1000",
+            "5m",
+            "warning",
+            "The clock on {{ $labels.instance }} is {{ $value }}ms off exchange time",
+        ),
+        (
+            "HyperliquidDataGap",
+            r#"sum by (instance, kind) (increase(hyperliquid_data_gaps_total{kind!="disconnect"}[15m])) > 0"#,
+            "0m",
+            "warning",
+            "{{ $labels.instance }} has {{ $labels.kind }} gaps in its data over the last 15 minutes",
+        ),
+        (
+            "HyperliquidDuplicateTrades",
+            "increase(hyperliquid_duplicate_trades_total[15m]) > 0",
+            "0m",
+            "info",
+            "{{ $labels.instance }} received {{ $value }} duplicate trades in 15 minutes",
+        ),
+    ];
+
+    let mut yaml = String::from("groups:\n  - name: rs-hyperliquid\n    rules:\n");
+    for (name, expr, hold, severity, summary) in rules {
+        yaml.push_str(&format!(
+            "      - alert: {}\n        expr: '{}'\n        for: {}\n        labels:\n          severity: {}\n        annotations:\n          summary: '{}'\n",
+            name, expr, hold, severity, summary
+        ));
+    }
+    yaml
+}
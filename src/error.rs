@@ -32,6 +32,12 @@ pub enum HyperliquidError {
     #[error("Maximum reconnection attempts exceeded")]
     MaxReconnectsExceeded,
 
+    #[error("Could not connect: {0}")]
+    ConnectFailed(String),
+
+    #[error("Output sink failed: {0}")]
+    SinkError(String),
+
     #[error("Invalid message format: {0}")]
     InvalidMessage(String),
 
@@ -40,4 +46,58 @@ pub enum HyperliquidError {
 
     #[error("Metrics server error: {0}")]
     MetricsError(String),
+
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Invalid theme: {0}")]
+    ThemeError(String),
+
+    #[error("Invalid output template: {0}")]
+    TemplateError(String),
+
+    #[error("Invalid time setting: {0}")]
+    InvalidTimeSetting(String),
+
+    #[error("Invalid number format: {0}")]
+    InvalidNumberSetting(String),
+
+    #[error("Windows service error: {0}")]
+    ServiceError(String),
+}
+
+/// Process exit codes, kept stable for scripts and service managers
+pub mod exit_code {
+    /// Clean shutdown: Ctrl+C, `--max-trades`, `--duration`, or the end of a replay
+    pub const SUCCESS: u8 = 0;
+    /// Any failure without a more specific code
+    pub const FAILURE: u8 = 1;
+    /// Invalid command-line arguments, reported by clap before startup
+    pub const USAGE: u8 = 2;
+    /// Invalid configuration: flags, config file, theme, template, or time settings
+    pub const CONFIG: u8 = 3;
+    /// The endpoint was never reached before the reconnect limit
+    pub const CONNECT: u8 = 4;
+    /// An established connection was lost and `--max-reconnects` was used up
+    pub const MAX_RECONNECTS: u8 = 5;
+    /// The recorder or metrics exporter could not write its output
+    pub const SINK: u8 = 6;
+}
+
+impl HyperliquidError {
+    /// Exit code reported when this error ends the process
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::ConfigError(_)
+            | Self::ThemeError(_)
+            | Self::TemplateError(_)
+            | Self::InvalidTimeSetting(_)
+            | Self::InvalidNumberSetting(_)
+            | Self::UrlError(_) => exit_code::CONFIG,
+            Self::ConnectFailed(_) | Self::WebSocketError(_) | Self::Timeout => exit_code::CONNECT,
+            Self::MaxReconnectsExceeded => exit_code::MAX_RECONNECTS,
+            Self::SinkError(_) | Self::MetricsError(_) => exit_code::SINK,
+            _ => exit_code::FAILURE,
+        }
+    }
 }
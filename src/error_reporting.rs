@@ -0,0 +1,99 @@
+/// file: src/error_reporting.rs
+/// description: Sentry reports for panics and fatal errors, with the connection state attached
+/// reference: https://docs.sentry.io/platforms/rust/
+use crate::{client_state::SharedClientState, config::Config, error::HyperliquidError};
+use anyhow::Result;
+use sentry::{
+    ClientInitGuard, ClientOptions,
+    protocol::{Context, Event, Map, Value},
+    types::Dsn,
+};
+use std::sync::{Arc, Mutex, atomic::Ordering};
+use tracing::info;
+
+/// Held until `finish` so queued reports are flushed before the process exits
+static GUARD: Mutex<Option<ClientInitGuard>> = Mutex::new(None);
+
+/// Start reporting to `dsn`; panics from here on are captured by the SDK's panic hook
+///
+/// Each report carries the endpoint and, read when the report is made, the confirmed
+/// subscriptions, reconnect count, and connection status. `SENTRY_ENVIRONMENT` and the other
+/// standard variables are read by the SDK.
+pub fn init_error_reporting(dsn: &str, config: &Config, state: SharedClientState) -> Result<()> {
+    let dsn: Dsn = dsn
+        .parse()
+        .map_err(|e| HyperliquidError::ConfigError(format!("invalid Sentry DSN: {}", e)))?;
+    let guard = sentry::init(ClientOptions {
+        dsn: Some(dsn),
+        release: sentry::release_name!(),
+        before_send: Some(Arc::new(move |event| Some(with_connection(event, &state)))),
+        ..Default::default()
+    });
+
+    let mut endpoint = config.websocket.url.clone();
+    // Credentials in the URL stay out of the report
+    let _ = endpoint.set_username("");
+    let _ = endpoint.set_password(None);
+    sentry::configure_scope(|scope| {
+        scope.set_tag("endpoint", endpoint.as_str());
+        if let Some(network) = config.websocket.network {
+            scope.set_tag("network", network);
+        }
+        scope.set_tag("coin", &config.subscription.coin);
+    });
+
+    *GUARD.lock().unwrap_or_else(|e| e.into_inner()) = Some(guard);
+    info!("Reporting panics and fatal errors to Sentry");
+    Ok(())
+}
+
+/// Report the error that ended the run, if reporting is on, and flush queued reports
+///
+/// Configuration errors are left out; they are typos for the person running the command, not
+/// production issues.
+pub fn finish_error_reporting(error: Option<&anyhow::Error>) {
+    let Some(guard) = GUARD.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    if let Some(error) = error {
+        let is_config = error
+            .downcast_ref::<HyperliquidError>()
+            .is_some_and(|e| e.exit_code() == crate::error::exit_code::CONFIG);
+        if !is_config {
+            sentry::capture_error(error.as_ref() as &(dyn std::error::Error + 'static));
+        }
+    }
+    drop(guard);
+}
+
+/// Attach the connection state as it is when the report is made
+fn with_connection(mut event: Event<'static>, state: &SharedClientState) -> Event<'static> {
+    // A panic while the state is locked must still be reported, just without this context
+    let Ok(state) = state.try_lock() else {
+        return event;
+    };
+    let mut connection = Map::new();
+    connection.insert("connected".to_string(), Value::from(state.is_connected()));
+    connection.insert(
+        "session_id".to_string(),
+        Value::from(state.session_id.as_str()),
+    );
+    connection.insert(
+        "reconnects".to_string(),
+        Value::from(state.total_reconnects.load(Ordering::Relaxed)),
+    );
+    connection.insert(
+        "subscriptions".to_string(),
+        serde_json::to_value(&state.connection.subscriptions).unwrap_or_default(),
+    );
+    if let Some(at) = state.last_message_time {
+        connection.insert(
+            "last_message_age_secs".to_string(),
+            Value::from(at.elapsed().as_secs_f64()),
+        );
+    }
+    event
+        .contexts
+        .insert("connection".to_string(), Context::Other(connection));
+    event
+}
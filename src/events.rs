@@ -1,25 +1,357 @@
 /// file: src/events.rs
 /// description: Event system to decouple client logic from UI presentation
-use crate::types::Trade;
+use crate::{
+    recorder::RecordingSnapshot,
+    symbol::Symbol,
+    types::{
+        AllMids, Bbo, Book, Candle, ExplorerBlock, ExplorerTx, Fill, Liquidation, NonUserCancel,
+        OrderUpdate, Trade, UserFunding,
+    },
+};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 
 // Use Arc to avoid cloning trades (critical for performance)
 #[derive(Debug, Clone)]
 pub enum ClientEvent {
     Starting,
-    Connecting { url: String },
-    Connected { connection_id: String },
-    SubscriptionSent { message: String },
-    SubscriptionConfirmed { sub_type: String, coin: String },
+    Connecting {
+        url: String,
+    },
+    Connected {
+        connection_id: String,
+    },
+    SubscriptionSent {
+        message: String,
+    },
+    SubscriptionConfirmed {
+        sub_type: String,
+        coin: String,
+        /// Bar length of a `candle` subscription
+        interval: Option<String>,
+    },
     TradeReceived(Arc<Trade>), // Changed to Arc to avoid clone
-    MessageReceived { raw_message: String },
+    BookReceived(Arc<Book>),
+    BboReceived(Arc<Bbo>),
+    /// Every coin's mid from the `allMids` channel, as sent rather than only those that moved
+    AllMidsReceived(Arc<AllMids>),
+    /// A candle update; `closed` marks the bar's final state, sent once the next bar opens
+    CandleReceived {
+        candle: Arc<Candle>,
+        closed: bool,
+    },
+    /// A fill of the `--user` account; `snapshot` marks the earlier fills sent on subscribing
+    FillReceived {
+        fill: Arc<Fill>,
+        snapshot: bool,
+    },
+    /// A funding payment on a position of the `--user` account
+    FundingReceived(Arc<UserFunding>),
+    /// A liquidation involving the `--user` account
+    LiquidationReceived(Arc<Liquidation>),
+    /// An order of the `--user` account canceled by the exchange
+    NonUserCancelReceived(Arc<NonUserCancel>),
+    /// A status change to an order of the `--user` account
+    OrderUpdated(Arc<OrderUpdate>),
+    /// A block from the explorer's `explorerBlock` channel
+    BlockReceived(Arc<ExplorerBlock>),
+    /// A transaction from the explorer's `explorerTxs` channel
+    TxReceived(Arc<ExplorerTx>),
+    MessageReceived {
+        raw_message: String,
+    },
     ConnectionFailed(String),
-    Reconnecting { attempt: u32, delay_secs: u64 },
+    /// A request rejected by the server, with its description
+    ServerError(String),
+    Reconnecting {
+        attempt: u32,
+        delay_secs: u64,
+    },
+    /// The local clock moved beyond or back within `--max-clock-skew` of exchange time
+    ClockSkew {
+        skew_ms: i64,
+        threshold_ms: i64,
+    },
     Disconnected,
+    Stats(ConnectionStats),
     Stopping,
 }
 
+impl ClientEvent {
+    /// One JSON object with a snake_case `type` discriminator, as printed by `--format events`
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ClientEvent::Starting => serde_json::json!({ "type": "starting" }),
+            ClientEvent::Connecting { url } => {
+                serde_json::json!({ "type": "connecting", "url": url })
+            }
+            ClientEvent::Connected { connection_id } => {
+                serde_json::json!({ "type": "connected", "connection_id": connection_id })
+            }
+            ClientEvent::SubscriptionSent { message } => {
+                serde_json::json!({ "type": "subscription_sent", "message": message })
+            }
+            ClientEvent::SubscriptionConfirmed {
+                sub_type,
+                coin,
+                interval,
+            } => {
+                let mut json = serde_json::json!({
+                    "type": "subscription_confirmed",
+                    "sub_type": sub_type,
+                    "coin": coin,
+                });
+                if let Some(interval) = interval {
+                    json["interval"] = interval.as_str().into();
+                }
+                json
+            }
+            ClientEvent::TradeReceived(trade) => trade_json(trade),
+            ClientEvent::BookReceived(book) => book_json(book),
+            ClientEvent::BboReceived(bbo) => bbo_json(bbo),
+            // Every coin, unlike `print_mids`: an event stream consumer gets whole snapshots
+            // under the same `mids` tag, where the json format prints only the mids that moved
+            ClientEvent::AllMidsReceived(all_mids) => mids_json(
+                all_mids
+                    .mids
+                    .iter()
+                    .filter_map(|(coin, mid)| Some((coin, mid.parse().ok()?))),
+            ),
+            ClientEvent::CandleReceived { candle, closed } => candle_json(candle, *closed),
+            ClientEvent::FillReceived { fill, snapshot } => fill_json(fill, *snapshot),
+            ClientEvent::FundingReceived(funding) => funding_json(funding),
+            ClientEvent::LiquidationReceived(liquidation) => liquidation_json(liquidation),
+            ClientEvent::NonUserCancelReceived(cancel) => non_user_cancel_json(cancel),
+            ClientEvent::OrderUpdated(update) => order_json(update, None),
+            ClientEvent::BlockReceived(block) => block_json(block),
+            ClientEvent::TxReceived(tx) => tx_json(tx),
+            ClientEvent::MessageReceived { raw_message } => {
+                serde_json::json!({ "type": "message_received", "raw_message": raw_message })
+            }
+            ClientEvent::ConnectionFailed(error) => {
+                serde_json::json!({ "type": "connection_failed", "error": error })
+            }
+            ClientEvent::ServerError(error) => {
+                serde_json::json!({ "type": "server_error", "error": error })
+            }
+            ClientEvent::Reconnecting {
+                attempt,
+                delay_secs,
+            } => serde_json::json!({
+                "type": "reconnecting",
+                "attempt": attempt,
+                "delay_secs": delay_secs,
+            }),
+            ClientEvent::ClockSkew {
+                skew_ms,
+                threshold_ms,
+            } => serde_json::json!({
+                "type": "clock_skew",
+                "skew_ms": skew_ms,
+                "threshold_ms": threshold_ms,
+            }),
+            ClientEvent::Disconnected => serde_json::json!({ "type": "disconnected" }),
+            ClientEvent::Stats(stats) => serde_json::json!({
+                "type": "stats",
+                "connected": stats.connected,
+                "uptime_secs": stats.uptime.map(|uptime| uptime.as_secs_f64()),
+                "reconnects": stats.reconnects,
+                "total_messages": stats.total_messages,
+                "msgs_per_sec": stats.msgs_per_sec,
+                "last_message_age_ms": stats.last_message_age.map(|age| age.as_millis() as u64),
+                "ping_rtt_ms": stats.ping_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+            }),
+            ClientEvent::Stopping => serde_json::json!({ "type": "stopping" }),
+        }
+    }
+}
+
+/// Tagged JSON for a trade event
+pub fn trade_json(trade: &Trade) -> serde_json::Value {
+    serde_json::json!({
+        "type": "trade",
+        "coin": trade.coin,
+        "side": trade.side_formatted(),
+        "px": trade.px,
+        "sz": trade.sz,
+        "value": trade.value(),
+        "time": trade.time,
+        "tid": trade.tid,
+        "hash": trade.hash,
+        "users": trade.users,
+    })
+}
+
+/// Tagged JSON for an order book snapshot, best levels first
+pub fn book_json(book: &Book) -> serde_json::Value {
+    serde_json::json!({
+        "type": "book",
+        "coin": book.coin,
+        "time": book.time,
+        "bids": book.levels.0,
+        "asks": book.levels.1,
+    })
+}
+
+/// Tagged JSON for a BBO update; a missing side and the spread it leaves undefined are null
+pub fn bbo_json(bbo: &Bbo) -> serde_json::Value {
+    serde_json::json!({
+        "type": "bbo",
+        "coin": bbo.coin,
+        "time": bbo.time,
+        "bid": bbo.bbo.0,
+        "ask": bbo.bbo.1,
+        "spread": bbo.spread(),
+        "spread_bps": bbo.spread_bps(),
+    })
+}
+
+/// Tagged JSON for mid prices, an object keyed by coin
+pub fn mids_json<'a>(mids: impl IntoIterator<Item = (&'a Symbol, f64)>) -> serde_json::Value {
+    let mids: serde_json::Map<String, serde_json::Value> = mids
+        .into_iter()
+        .map(|(coin, mid)| (coin.to_string(), mid.into()))
+        .collect();
+    serde_json::json!({
+        "type": "mids",
+        "mids": mids,
+    })
+}
+
+/// Tagged JSON for a candle event
+pub fn candle_json(candle: &Candle, closed: bool) -> serde_json::Value {
+    serde_json::json!({
+        "type": "candle",
+        "coin": candle.s,
+        "interval": candle.i,
+        "open_time": candle.t,
+        "close_time": candle.close_time,
+        "o": candle.o,
+        "h": candle.h,
+        "l": candle.l,
+        "c": candle.c,
+        "v": candle.v,
+        "n": candle.n,
+        "closed": closed,
+    })
+}
+
+/// Tagged JSON for a fill of the `--user` account
+pub fn fill_json(fill: &Fill, snapshot: bool) -> serde_json::Value {
+    serde_json::json!({
+        "type": "fill",
+        "coin": fill.coin,
+        "side": fill.side_formatted(),
+        "dir": fill.dir,
+        "px": fill.px,
+        "sz": fill.sz,
+        "start_position": fill.start_position,
+        "closed_pnl": fill.closed_pnl,
+        "fee": fill.fee,
+        "fee_token": fill.fee_token,
+        "builder_fee": fill.builder_fee,
+        "crossed": fill.crossed,
+        "time": fill.time,
+        "oid": fill.oid,
+        "tid": fill.tid,
+        "hash": fill.hash,
+        "snapshot": snapshot,
+    })
+}
+
+/// Tagged JSON for a funding payment of the `--user` account
+pub fn funding_json(funding: &UserFunding) -> serde_json::Value {
+    serde_json::json!({
+        "type": "funding",
+        "coin": funding.coin,
+        "time": funding.time,
+        "usdc": funding.usdc,
+        "szi": funding.szi,
+        "funding_rate": funding.funding_rate,
+    })
+}
+
+/// Tagged JSON for a liquidation involving the `--user` account
+pub fn liquidation_json(liquidation: &Liquidation) -> serde_json::Value {
+    serde_json::json!({
+        "type": "liquidation",
+        "lid": liquidation.lid,
+        "liquidator": liquidation.liquidator,
+        "liquidated_user": liquidation.liquidated_user,
+        "liquidated_ntl_pos": liquidation.liquidated_ntl_pos,
+        "liquidated_account_value": liquidation.liquidated_account_value,
+    })
+}
+
+/// Tagged JSON for an order of the `--user` account canceled by the exchange
+pub fn non_user_cancel_json(cancel: &NonUserCancel) -> serde_json::Value {
+    serde_json::json!({
+        "type": "non_user_cancel",
+        "coin": cancel.coin,
+        "oid": cancel.oid,
+    })
+}
+
+/// Tagged JSON for an order update of the `--user` account, with the status before it when known
+pub fn order_json(update: &OrderUpdate, previous_status: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "type": "order",
+        "coin": update.order.coin,
+        "side": update.side_formatted(),
+        "limit_px": update.order.limit_px,
+        "sz": update.order.sz,
+        "orig_sz": update.order.orig_sz,
+        "filled_sz": update.filled_sz(),
+        "oid": update.order.oid,
+        "cloid": update.order.cloid,
+        "timestamp": update.order.timestamp,
+        "status": update.status,
+        "previous_status": previous_status,
+        "status_timestamp": update.status_timestamp,
+    })
+}
+
+/// Tagged JSON for an explorer block event
+pub fn block_json(block: &ExplorerBlock) -> serde_json::Value {
+    serde_json::json!({
+        "type": "block",
+        "height": block.height,
+        "time": block.block_time,
+        "hash": block.hash,
+        "proposer": block.proposer,
+        "num_txs": block.num_txs,
+    })
+}
+
+/// Tagged JSON for an explorer transaction event
+pub fn tx_json(tx: &ExplorerTx) -> serde_json::Value {
+    serde_json::json!({
+        "type": "tx",
+        "hash": tx.hash,
+        "block": tx.block,
+        "time": tx.time,
+        "user": tx.user,
+        "action": tx.action,
+        "error": tx.error,
+    })
+}
+
+/// Point-in-time connection health sampled from `ClientState`
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStats {
+    pub connected: bool,
+    pub uptime: Option<Duration>,
+    pub reconnects: u32,
+    pub total_messages: u64,
+    pub msgs_per_sec: f64,
+    pub last_message_age: Option<Duration>,
+    /// Latest ping to pong round trip, once one has completed on this connection
+    pub ping_rtt: Option<Duration>,
+    /// Present while raw recording is active
+    pub recording: Option<RecordingSnapshot>,
+}
+
 // Use bounded channel to prevent unbounded memory growth
 // For HFT: 10,000 events allows burst handling while preventing OOM
 // At 1000 trades/sec, this provides ~10 second buffer
@@ -31,3 +363,29 @@ pub type EventReceiver = mpsc::Receiver<ClientEvent>;
 pub fn create_event_channel() -> (EventSender, EventReceiver) {
     mpsc::channel(EVENT_CHANNEL_CAPACITY)
 }
+
+/// Control messages sent to the running client without reconnecting
+#[derive(Debug, Clone)]
+pub enum ClientCommand {
+    SwitchCoin(String),
+}
+
+// Commands are user-driven and infrequent
+const COMMAND_CHANNEL_CAPACITY: usize = 16;
+
+pub type CommandSender = mpsc::Sender<ClientCommand>;
+pub type CommandReceiver = mpsc::Receiver<ClientCommand>;
+
+pub fn create_command_channel() -> (CommandSender, CommandReceiver) {
+    mpsc::channel(COMMAND_CHANNEL_CAPACITY)
+}
+
+// Fan-out copy of every event for secondary consumers such as the dashboard;
+// slow subscribers lag and skip rather than back-pressuring the UI
+const BROADCAST_CAPACITY: usize = 1_024;
+
+pub type BroadcastSender = broadcast::Sender<ClientEvent>;
+
+pub fn create_broadcast_bus() -> BroadcastSender {
+    broadcast::channel(BROADCAST_CAPACITY).0
+}
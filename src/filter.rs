@@ -0,0 +1,103 @@
+/// file: src/filter.rs
+/// description: Trade filters for hiding dust and one-sided flow
+use crate::types::{Trade, TradeRef};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which aggressor side passes the filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SideFilter {
+    #[default]
+    All,
+    Buys,
+    Sells,
+}
+
+impl SideFilter {
+    /// All -> Buys -> Sells -> All, for a single toggle key
+    pub fn next(self) -> Self {
+        match self {
+            SideFilter::All => SideFilter::Buys,
+            SideFilter::Buys => SideFilter::Sells,
+            SideFilter::Sells => SideFilter::All,
+        }
+    }
+
+    fn matches(self, is_buy: bool) -> bool {
+        match self {
+            SideFilter::All => true,
+            SideFilter::Buys => is_buy,
+            SideFilter::Sells => !is_buy,
+        }
+    }
+}
+
+impl FromStr for SideFilter {
+    type Err = String;
+
+    fn from_str(side: &str) -> Result<Self, Self::Err> {
+        match side.to_ascii_lowercase().as_str() {
+            "all" => Ok(SideFilter::All),
+            "buy" | "buys" => Ok(SideFilter::Buys),
+            "sell" | "sells" => Ok(SideFilter::Sells),
+            _ => Err(format!(
+                "unknown side '{}', expected buy, sell, or all",
+                side
+            )),
+        }
+    }
+}
+
+/// Thresholds a trade must meet to be shown
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TradeFilter {
+    pub min_size: Option<f64>,
+    pub min_notional: Option<f64>,
+    pub side: SideFilter,
+}
+
+impl TradeFilter {
+    pub fn matches(&self, trade: &Trade) -> bool {
+        self.accepts(trade.sz, trade.value(), trade.is_buy())
+    }
+
+    /// The same check on a trade still borrowed from its frame
+    pub fn matches_ref(&self, trade: &TradeRef<'_>) -> bool {
+        self.accepts(trade.sz, trade.value(), trade.is_buy())
+    }
+
+    fn accepts(&self, size: f64, notional: f64, is_buy: bool) -> bool {
+        self.min_size.is_none_or(|min_size| size >= min_size)
+            && self
+                .min_notional
+                .is_none_or(|min_notional| notional >= min_notional)
+            && self.side.matches(is_buy)
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != TradeFilter::default()
+    }
+}
+
+impl fmt::Display for TradeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(min_size) = self.min_size {
+            parts.push(format!("size >= {}", min_size));
+        }
+        if let Some(min_notional) = self.min_notional {
+            parts.push(format!("notional >= {}", min_notional));
+        }
+        match self.side {
+            SideFilter::All => {}
+            SideFilter::Buys => parts.push("buys only".to_string()),
+            SideFilter::Sells => parts.push("sells only".to_string()),
+        }
+
+        if parts.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
@@ -1,7 +1,30 @@
 /// file: src/formatter.rs
 /// description: Trade data formatting and output display utilities for various formats
 /// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/websocket
-use crate::types::Trade;
+use crate::{
+    error::HyperliquidError,
+    events::{
+        ClientEvent, ConnectionStats, bbo_json, block_json, book_json, candle_json, fill_json,
+        funding_json, liquidation_json, mids_json, non_user_cancel_json, order_json, trade_json,
+        tx_json,
+    },
+    number_format::NumberFormat,
+    output::TradeOutput,
+    rolling::RollingStats,
+    sparkline::Sparkline,
+    summary::SessionSummary,
+    symbol::Symbol,
+    template::OutputTemplate,
+    theme::Theme,
+    time_display::TimeDisplay,
+    types::{
+        AllMids, Bbo, Book, Candle, ExplorerBlock, ExplorerTx, Fill, Level, Liquidation,
+        NonUserCancel, OrderState, OrderUpdate, Trade, UserFunding,
+    },
+};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::Write;
 
 // ANSI color codes
 pub struct Colors;
@@ -10,6 +33,7 @@ impl Colors {
     pub const RESET: &'static str = "\x1b[0m";
     pub const BOLD: &'static str = "\x1b[1m";
     pub const DIM: &'static str = "\x1b[2m";
+    pub const INVERSE: &'static str = "\x1b[7m";
 
     // Colors
     pub const RED: &'static str = "\x1b[31m";
@@ -31,12 +55,57 @@ impl Colors {
     pub const BRIGHT_WHITE: &'static str = "\x1b[97m";
 }
 
+/// Glyphs used to draw table borders
+#[derive(Debug, Clone, Copy)]
+pub struct BoxChars {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_mid: char,
+    pub top_right: char,
+    pub mid_left: char,
+    pub mid_mid: char,
+    pub mid_right: char,
+}
+
+impl BoxChars {
+    pub const UNICODE: BoxChars = BoxChars {
+        horizontal: '─',
+        vertical: '│',
+        top_left: '┌',
+        top_mid: '┬',
+        top_right: '┐',
+        mid_left: '├',
+        mid_mid: '┼',
+        mid_right: '┤',
+    };
+
+    pub const ASCII: BoxChars = BoxChars {
+        horizontal: '-',
+        vertical: '|',
+        top_left: '+',
+        top_mid: '+',
+        top_right: '+',
+        mid_left: '+',
+        mid_mid: '+',
+        mid_right: '+',
+    };
+
+    pub fn for_unicode(unicode: bool) -> Self {
+        if unicode { Self::UNICODE } else { Self::ASCII }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum OutputFormat {
     Table,
     Csv,
     Json,
+    /// Indented JSON objects for reading by eye
+    JsonPretty,
     Minimal,
+    /// Every client event as tagged JSON lines, not just trades
+    Events,
 }
 
 impl From<&str> for OutputFormat {
@@ -44,20 +113,166 @@ impl From<&str> for OutputFormat {
         match s.to_lowercase().as_str() {
             "csv" => OutputFormat::Csv,
             "json" => OutputFormat::Json,
+            "json-pretty" => OutputFormat::JsonPretty,
+            "events" => OutputFormat::Events,
             "minimal" => OutputFormat::Minimal,
             _ => OutputFormat::Table,
         }
     }
 }
 
+/// How much non-error output is suppressed, from `-q`, `-qq`, and `--silent`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuietLevel {
+    #[default]
+    Normal,
+    /// Banner, connection status, and subscription lines are hidden
+    NoBanners,
+    /// Also hides table and CSV headers and the exit summary
+    DataOnly,
+    /// Only errors reach the terminal; `--output` still receives the stream
+    Silent,
+}
+
+impl QuietLevel {
+    /// `--silent` wins over any number of `-q`
+    pub fn from_flags(count: u8, silent: bool) -> Self {
+        match (silent, count) {
+            (true, _) => QuietLevel::Silent,
+            (false, 0) => QuietLevel::Normal,
+            (false, 1) => QuietLevel::NoBanners,
+            (false, _) => QuietLevel::DataOnly,
+        }
+    }
+}
+
+/// Columns of the table output that can be toggled at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    Index,
+    Side,
+    Price,
+    Size,
+    Value,
+    Time,
+}
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 6] = [
+        TableColumn::Index,
+        TableColumn::Side,
+        TableColumn::Price,
+        TableColumn::Size,
+        TableColumn::Value,
+        TableColumn::Time,
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableColumn::Index => "#",
+            TableColumn::Side => "SIDE",
+            TableColumn::Price => "PRICE",
+            TableColumn::Size => "SIZE",
+            TableColumn::Value => "VALUE",
+            TableColumn::Time => "TIME",
+        }
+    }
+
+    fn width(&self, density: TableDensity) -> usize {
+        match self {
+            TableColumn::Index => 7,
+            TableColumn::Side => 4,
+            TableColumn::Price | TableColumn::Size | TableColumn::Value => 11,
+            TableColumn::Time if density == TableDensity::Full => 19,
+            TableColumn::Time => 8,
+        }
+    }
+}
+
+/// How much of the table fits in the terminal width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableDensity {
+    /// Every column at full width
+    Full,
+    /// No index column, short time column
+    Compact,
+    /// Compact without the value column
+    Narrow,
+}
+
+impl TableDensity {
+    const ALL: [TableDensity; 3] = [
+        TableDensity::Full,
+        TableDensity::Compact,
+        TableDensity::Narrow,
+    ];
+
+    fn hides(self, column: TableColumn) -> bool {
+        match self {
+            TableDensity::Full => false,
+            TableDensity::Compact => column == TableColumn::Index,
+            TableDensity::Narrow => matches!(column, TableColumn::Index | TableColumn::Value),
+        }
+    }
+
+    /// Rendered width of a row with every column this density keeps
+    fn row_width(self) -> usize {
+        TableColumn::ALL
+            .iter()
+            .filter(|column| !self.hides(**column))
+            .map(|column| column.width(self) + 3)
+            .sum::<usize>()
+            + 1
+    }
+
+    /// Widest layout that fits, falling back to the narrowest one
+    fn for_terminal_width(width: usize) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|density| density.row_width() <= width)
+            .unwrap_or(TableDensity::Narrow)
+    }
+}
+
+// Terminal width is re-checked after this many table rows to follow resizes
+const RESIZE_CHECK_INTERVAL: u64 = 64;
+
+fn table_border(widths: &[usize], horizontal: char, left: char, mid: char, right: char) -> String {
+    let segments: Vec<String> = widths
+        .iter()
+        .map(|width| horizontal.to_string().repeat(width + 2))
+        .collect();
+    format!("{}{}{}", left, segments.join(&mid.to_string()), right)
+}
+
 pub struct TradeFormatter {
     format: OutputFormat,
     colored: bool,
     verbose: bool,
-    quiet: bool,
+    quiet: QuietLevel,
     price_only: bool,
     csv_export: bool,
+    sparkline: Option<Sparkline>,
+    in_place: bool,
+    template: Option<OutputTemplate>,
+    box_chars: BoxChars,
+    unicode: bool,
+    time_display: TimeDisplay,
+    visible_columns: [bool; TableColumn::ALL.len()],
+    density: TableDensity,
     trade_count: u64,
+    theme: Theme,
+    jq_friendly: bool,
+    stats_footer: Option<RollingStats>,
+    highlight_notional: Option<f64>,
+    highlight_marker: Option<String>,
+    numbers: NumberFormat,
+    output: TradeOutput,
+    fills_header_printed: bool,
 }
 
 impl TradeFormatter {
@@ -65,9 +280,10 @@ impl TradeFormatter {
         format: OutputFormat,
         colored: bool,
         verbose: bool,
-        quiet: bool,
+        quiet: QuietLevel,
         price_only: bool,
         csv_export: bool,
+        sparkline_len: Option<usize>,
     ) -> Self {
         Self {
             format,
@@ -76,25 +292,123 @@ impl TradeFormatter {
             quiet,
             price_only,
             csv_export,
+            sparkline: sparkline_len.map(Sparkline::new),
+            in_place: true,
+            template: None,
+            box_chars: BoxChars::UNICODE,
+            unicode: true,
+            time_display: TimeDisplay::default(),
+            visible_columns: [true; TableColumn::ALL.len()],
+            density: TableDensity::Full,
             trade_count: 0,
+            theme: Theme::default(),
+            jq_friendly: false,
+            stats_footer: None,
+            highlight_notional: None,
+            highlight_marker: None,
+            numbers: NumberFormat::default(),
+            output: TradeOutput::stdout(),
+            fills_header_printed: false,
+        }
+    }
+
+    /// Write the trade stream to `output`; a file gets plain text at full table width
+    pub fn with_output(mut self, output: TradeOutput) -> Self {
+        if output.is_file() {
+            self.colored = false;
+            self.in_place = false;
         }
+        self.output = output;
+        self
+    }
+
+    /// Flush the trade stream, reporting any write error from the session
+    pub fn finish_output(&self) -> Result<(), HyperliquidError> {
+        self.output.finish()
+    }
+
+    /// Render table rows at or above `notional` in bold inverse video, optionally
+    /// prefixed with `marker` (other rows get matching padding to keep columns aligned)
+    pub fn with_highlight(mut self, notional: Option<f64>, marker: Option<String>) -> Self {
+        self.highlight_notional = notional;
+        self.highlight_marker = notional.and(marker);
+        self
+    }
+
+    /// Print a rolling stats footer in table mode after every `every` trades; 0 disables it
+    pub fn with_stats_footer(mut self, every: usize) -> Self {
+        self.stats_footer = (every > 0).then(|| RollingStats::new(every));
+        self
     }
 
-    pub fn print_header(&self) {
-        if self.quiet {
+    /// Use JSON keys that are valid jq identifiers (`seq` instead of `#`)
+    pub fn with_jq_friendly(mut self, jq_friendly: bool) -> Self {
+        self.jq_friendly = jq_friendly;
+        self
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Render minimal and price-only rows through a custom template
+    pub fn with_template(mut self, template: Option<OutputTemplate>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Select Unicode or ASCII table borders, arrows, and sparklines
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.box_chars = BoxChars::for_unicode(unicode);
+        self.unicode = unicode;
+        self
+    }
+
+    /// Allow carriage-return redraws; disabled when stdout is not a terminal
+    pub fn with_in_place(mut self, in_place: bool) -> Self {
+        self.in_place = in_place;
+        self
+    }
+
+    /// Render timestamps in the configured timezone and format
+    pub fn with_time_display(mut self, time_display: TimeDisplay) -> Self {
+        self.time_display = time_display;
+        self
+    }
+
+    /// Digit grouping and decimal places; grouping only reaches the table, history, stats footer,
+    /// and summary, while CSV, JSON, and the other lines take the decimal places alone
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    pub fn print_header(&mut self) {
+        if !self.shows_headers() || self.sparkline.is_some() {
             return;
         }
 
         match self.format {
             OutputFormat::Table => self.print_table_header(),
             OutputFormat::Csv => self.print_csv_header(),
-            OutputFormat::Json => {}    // JSON doesn't need headers
-            OutputFormat::Minimal => {} // Minimal doesn't need headers
+            OutputFormat::Json | OutputFormat::JsonPretty => {} // JSON doesn't need headers
+            OutputFormat::Minimal => {}                         // Minimal doesn't need headers
+            OutputFormat::Events => {}
         }
     }
 
     pub fn print_trade(&mut self, trade: &Trade) {
         self.trade_count += 1;
+        if !self.shows_data() {
+            return;
+        }
+
+        if self.sparkline.is_some() {
+            self.print_sparkline_line(trade);
+            return;
+        }
 
         if self.price_only {
             self.print_price_only(trade);
@@ -102,10 +416,20 @@ impl TradeFormatter {
         }
 
         match self.format {
-            OutputFormat::Table => self.print_table_row(trade),
+            OutputFormat::Table => {
+                // Follow terminal resizes, redrawing the header when the layout changes
+                if self.trade_count.is_multiple_of(RESIZE_CHECK_INTERVAL)
+                    && self.detect_density() != self.density
+                {
+                    self.print_table_header();
+                }
+                self.print_table_row(trade);
+                self.update_stats_footer(trade);
+            }
             OutputFormat::Csv => self.print_csv_row(trade),
-            OutputFormat::Json => self.print_json_row(trade),
+            OutputFormat::Json | OutputFormat::JsonPretty => self.print_json_row(trade),
             OutputFormat::Minimal => self.print_minimal_row(trade),
+            OutputFormat::Events => self.output.line(format_args!("{}", trade_json(trade))),
         }
 
         // Export to CSV on stderr if enabled
@@ -118,312 +442,2093 @@ impl TradeFormatter {
         }
     }
 
-    pub fn trade_count(&self) -> u64 {
-        self.trade_count
+    /// Whether every event, not just trades, is printed as a JSON line
+    pub fn prints_events(&self) -> bool {
+        matches!(self.format, OutputFormat::Events)
     }
 
-    fn print_table_header(&self) {
-        if !self.quiet {
-            let header = if self.colored {
-                format!(
-                    "{}{}┌─────────┬──────┬─────────────┬─────────────┬─────────────┬─────────────────────┐{}",
-                    Colors::BOLD,
-                    Colors::GRAY,
-                    Colors::RESET
-                )
-            } else {
-                "┌─────────┬──────┬─────────────┬─────────────┬─────────────┬─────────────────────┐"
-                    .to_string()
-            };
-            println!("{}", header);
+    /// Whether records go out as JSON or CSV rather than terminal views
+    pub fn prints_records(&self) -> bool {
+        matches!(
+            self.format,
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Csv
+        )
+    }
 
-            let labels = if self.colored {
-                format!(
-                    "{}{}│{} {:<7} {}│{} {:<4} {}│{} {:<11} {}│{} {:<11} {}│{} {:<11} {}│{} {:<19} {}│{}",
-                    Colors::BOLD,
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "#",
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "SIDE",
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "PRICE",
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "SIZE",
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "VALUE",
-                    Colors::GRAY,
-                    Colors::RESET,
-                    "TIME",
-                    Colors::GRAY,
-                    Colors::RESET,
-                )
-            } else {
-                format!(
-                    "│ {:<7} │ {:<4} │ {:<11} │ {:<11} │ {:<11} │ {:<19} │",
-                    "#", "SIDE", "PRICE", "SIZE", "VALUE", "TIME"
-                )
-            };
-            println!("{}", labels);
+    /// Whether trades reach an output; `--silent` keeps only a `--output` file
+    pub fn shows_data(&self) -> bool {
+        self.quiet < QuietLevel::Silent || self.output.is_file()
+    }
 
-            let separator = if self.colored {
-                format!(
-                    "{}{}├─────────┼──────┼─────────────┼─────────────┼─────────────┼─────────────────────┤{}",
-                    Colors::BOLD,
-                    Colors::GRAY,
-                    Colors::RESET
-                )
-            } else {
-                "├─────────┼──────┼─────────────┼─────────────┼─────────────┼─────────────────────┤"
-                    .to_string()
-            };
-            println!("{}", separator);
+    /// `-qq` drops headers, but a `--silent` run keeps them in its `--output` file
+    fn shows_headers(&self) -> bool {
+        match self.quiet {
+            QuietLevel::Normal | QuietLevel::NoBanners => true,
+            QuietLevel::DataOnly => false,
+            QuietLevel::Silent => self.output.is_file(),
         }
     }
 
-    fn print_csv_header(&self) {
-        if !self.quiet {
-            println!("#,side,price,size,value,local_time,unix_timestamp");
+    /// Print a non-trade event line for `--format events`
+    pub fn print_event(&self, event: &ClientEvent) {
+        if !self.shows_data() {
+            return;
         }
+        let mut json = event.to_json();
+        self.numbers.precision.round_event(&mut json);
+        self.output.line(format_args!("{}", json));
     }
 
-    fn print_table_row(&self, trade: &Trade) {
-        let side_color = if self.colored {
-            if trade.is_buy() {
-                Colors::BRIGHT_GREEN
-            } else {
-                Colors::BRIGHT_RED
+    /// Print a book snapshot: its JSON object, or a CSV row led by `book` per level; the table
+    /// and minimal formats draw the book as a ladder instead, and `--format events` prints its
+    /// `book` event
+    pub fn print_book(&self, book: &Book) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty => {
+                self.print_json_record(book_json(book))
             }
-        } else {
-            ""
-        };
-
-        let reset = if self.colored { Colors::RESET } else { "" };
-        let gray = if self.colored { Colors::GRAY } else { "" };
-
-        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
-        let local_time = trade.datetime_local();
-
-        let price = trade.px;
-        let size = trade.sz;
-        let value = price * size;
-
-        println!(
-            "{}│{} {:<7} {}│{} {}{:<4}{} {}│{} {:<11.2} {}│{} {:<11.6} {}│{} {:<11.2} {}│{} {:<19} {}│{}",
-            gray,
-            reset,
-            self.trade_count,
-            gray,
-            reset,
-            side_color,
-            side_text,
-            reset,
-            gray,
-            reset,
-            price,
-            gray,
-            reset,
-            size,
-            gray,
-            reset,
-            value,
-            gray,
-            reset,
-            local_time.format("%H:%M:%S"),
-            gray,
-            reset
-        );
+            OutputFormat::Csv => {
+                let precision = &self.numbers.precision;
+                let time = self.time_display.full(book.time);
+                for (side, levels) in [("bid", &book.levels.0), ("ask", &book.levels.1)] {
+                    for (index, level) in levels.iter().enumerate() {
+                        self.output.line(format_args!(
+                            "book,{},{},{},{},{:.*},{:.*},{}",
+                            book.coin,
+                            time,
+                            side,
+                            index + 1,
+                            precision.price(&book.coin, 2),
+                            level.px,
+                            precision.size(&book.coin, 6),
+                            level.sz,
+                            level.n
+                        ));
+                    }
+                }
+            }
+            OutputFormat::Table | OutputFormat::Minimal | OutputFormat::Events => {}
+        }
     }
 
-    fn print_csv_row(&self, trade: &Trade) {
-        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
-        let local_time = trade.datetime_local();
-
-        let price = trade.px;
-        let size = trade.sz;
-        let value = price * size;
-
-        println!(
-            "{},{},{:.2},{:.6},{:.2},{},{}",
-            self.trade_count,
-            side_text,
-            price,
-            size,
-            value,
-            local_time.format("%Y-%m-%d %H:%M:%S"),
-            trade.time
-        );
+    /// Print a BBO update with its spread: its JSON object or a CSV row led by `bbo`, with empty
+    /// fields for a missing side; the terminal formats use `BboFormatter`, and `--format events`
+    /// prints its `bbo` event
+    pub fn print_bbo(&self, bbo: &Bbo) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty => self.print_json_record(bbo_json(bbo)),
+            OutputFormat::Csv => {
+                let precision = &self.numbers.precision;
+                let price = precision.price(&bbo.coin, 2);
+                let size = precision.size(&bbo.coin, 6);
+                let side = |level: &Option<Level>| match level {
+                    Some(level) => format!("{:.*},{:.*}", price, level.px, size, level.sz),
+                    None => ",".to_string(),
+                };
+                let spread = match (bbo.spread(), bbo.spread_bps()) {
+                    (Some(spread), Some(bps)) => format!("{:.*},{:.2}", price, spread, bps),
+                    _ => ",".to_string(),
+                };
+                self.output.line(format_args!(
+                    "bbo,{},{},{},{},{}",
+                    bbo.coin,
+                    self.time_display.full(bbo.time),
+                    side(&bbo.bbo.0),
+                    side(&bbo.bbo.1),
+                    spread
+                ))
+            }
+            OutputFormat::Table | OutputFormat::Minimal | OutputFormat::Events => {}
+        }
     }
 
-    fn print_json_row(&self, trade: &Trade) {
-        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
-        let local_time = trade.datetime_local();
-
-        let price = trade.px;
-        let size = trade.sz;
-        let value = price * size;
-
-        let json_obj = serde_json::json!({
-            "#": self.trade_count,
-            "coin": trade.coin,
-            "side": side_text,
-            "price": price,
-            "size": size,
-            "value": value,
-            "local_time": local_time.format("%Y-%m-%d %H:%M:%S").to_string(),
-            "unix_timestamp": trade.time,
-            "trade_id": trade.tid,
-            "hash": trade.hash
-        });
-
-        println!("{}", serde_json::to_string(&json_obj).unwrap_or_default());
+    /// Print the mids that moved: one JSON object keyed by coin, or a CSV row led by `mid` per
+    /// coin; the terminal formats use `AllMidsFormatter`. The `mids` event of `--format events`
+    /// carries every coin instead, see `ClientEvent::to_json`
+    pub fn print_mids(&self, moved: &[(Symbol, Mid)]) {
+        if !self.shows_data() || moved.is_empty() {
+            return;
+        }
+        let precision = &self.numbers.precision;
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty => self.print_json_record(mids_json(
+                moved
+                    .iter()
+                    .map(|(coin, mid)| (coin, precision.round_price(coin, mid.px))),
+            )),
+            OutputFormat::Csv => {
+                for (coin, mid) in moved {
+                    self.output.line(format_args!(
+                        "mid,{},{:.*}",
+                        coin,
+                        precision.price(coin, mid.decimals),
+                        mid.px
+                    ));
+                }
+            }
+            OutputFormat::Table | OutputFormat::Minimal | OutputFormat::Events => {}
+        }
     }
 
-    fn print_minimal_row(&self, trade: &Trade) {
-        let side_symbol = if trade.is_buy() { "↗" } else { "↘" };
-        let side_color = if self.colored {
-            if trade.is_buy() {
-                Colors::BRIGHT_GREEN
-            } else {
-                Colors::BRIGHT_RED
+    /// Print a candle update: its JSON object, a CSV row led by `candle`, or one line
+    pub fn print_candle(&self, candle: &Candle, closed: bool) {
+        if !self.shows_data() {
+            return;
+        }
+        let status = if closed { "closed" } else { "open" };
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(candle_json(candle, closed))
             }
-        } else {
-            ""
-        };
-        let reset = if self.colored { Colors::RESET } else { "" };
-
-        let price = trade.px;
-        let size = trade.sz;
-        let local_time = trade.datetime_local();
-
-        println!(
-            "{} {}{}{} {:<8.2} {:<8.6} {}",
-            local_time.format("%H:%M:%S"),
-            side_color,
-            side_symbol,
-            reset,
-            price,
-            size,
-            trade.coin
-        );
+            OutputFormat::Csv => {
+                let precision = &self.numbers.precision;
+                let price = precision.price(&candle.s, 2);
+                self.output.line(format_args!(
+                    "candle,{},{},{},{:.*},{:.*},{:.*},{:.*},{:.*},{},{}",
+                    candle.s,
+                    candle.i,
+                    self.time_display.full(candle.t),
+                    price,
+                    candle.o,
+                    price,
+                    candle.h,
+                    price,
+                    candle.l,
+                    price,
+                    candle.c,
+                    precision.size(&candle.s, 6),
+                    candle.v,
+                    candle.n,
+                    status
+                ))
+            }
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                let numbers = &self.numbers;
+                let price = |value| numbers.price(&candle.s, value, 2);
+                self.output.line(format_args!(
+                    "{}{}{} {}CANDLE {} {:<3}{} O {}  H {}  L {}  C {}  V {}  {:>5} trades  {}{}{}",
+                    gray,
+                    self.time_display.short(candle.t),
+                    reset,
+                    bold,
+                    candle.s,
+                    candle.i,
+                    reset,
+                    price(candle.o),
+                    price(candle.h),
+                    price(candle.l),
+                    price(candle.c),
+                    numbers.size(&candle.s, candle.v, 4),
+                    candle.n,
+                    if closed { bold } else { gray },
+                    status,
+                    reset
+                ));
+            }
+        }
     }
 
-    fn print_price_only(&self, trade: &Trade) {
-        let price = trade.px;
-        let side_color = if self.colored {
-            if trade.is_buy() {
-                Colors::BRIGHT_GREEN
-            } else {
-                Colors::BRIGHT_RED
+    /// Print a fill of the `--user` account: its JSON object, a CSV row led by `fill`, or a row of
+    /// the fills table, whose header comes before the first one. Fills from the snapshot sent on
+    /// subscribing are dimmed and marked `earlier`.
+    pub fn print_fill(&mut self, fill: &Fill, snapshot: bool) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(fill_json(fill, snapshot))
             }
-        } else {
-            ""
-        };
-        let reset = if self.colored { Colors::RESET } else { "" };
-
-        println!("{}{:.2}{}", side_color, price, reset);
+            OutputFormat::Csv => {
+                let precision = &self.numbers.precision;
+                self.output.line(format_args!(
+                    "fill,{},{},{},{},{:.*},{:.*},{:.6},{:.6},{},{},{},{},{}",
+                    fill.coin,
+                    self.time_display.full(fill.time),
+                    fill.side_formatted(),
+                    fill.dir,
+                    precision.price(&fill.coin, 2),
+                    fill.px,
+                    precision.size(&fill.coin, 6),
+                    fill.sz,
+                    fill.closed_pnl,
+                    fill.fee,
+                    fill.fee_token,
+                    fill.oid,
+                    fill.tid,
+                    fill.hash,
+                    snapshot
+                ))
+            }
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                let time = self.time_display.short(fill.time);
+                if !self.fills_header_printed
+                    && matches!(self.format, OutputFormat::Table)
+                    && self.shows_headers()
+                {
+                    self.fills_header_printed = true;
+                    self.output.line(format_args!(
+                        "{}{:<time_width$} {:<10} {:<4} {:<14} {:>14} {:>12} {:>12} {:>10}{}",
+                        bold,
+                        "TIME",
+                        "COIN",
+                        "SIDE",
+                        "DIR",
+                        "PRICE",
+                        "SIZE",
+                        "CLOSED PNL",
+                        "FEE",
+                        reset,
+                        time_width = time.chars().count()
+                    ));
+                }
+                // Earlier fills are dimmed whole rather than colored by side and PnL
+                let row_color = if snapshot { gray } else { "" };
+                let (side_color, pnl_color) = match self.colored {
+                    false => ("", ""),
+                    true if snapshot => (gray, gray),
+                    true => (
+                        if fill.is_buy() {
+                            self.theme.buy
+                        } else {
+                            self.theme.sell
+                        },
+                        match fill.closed_pnl.partial_cmp(&0.0) {
+                            Some(Ordering::Greater) => self.theme.buy,
+                            Some(Ordering::Less) => self.theme.sell,
+                            _ => "",
+                        },
+                    ),
+                };
+                self.output.line(format_args!(
+                    "{}{}{} {}{:<10} {}{:<4}{}{} {:<14} {:>14} {:>12} {}{:>12}{}{} {:>10} {}{}{}",
+                    gray,
+                    time,
+                    reset,
+                    row_color,
+                    fill.coin.as_str(),
+                    side_color,
+                    fill.side_formatted(),
+                    reset,
+                    row_color,
+                    fill.dir,
+                    self.numbers.price(&fill.coin, fill.px, 2),
+                    self.numbers.size(&fill.coin, fill.sz, 4),
+                    pnl_color,
+                    self.numbers.format_signed(fill.closed_pnl, 2),
+                    reset,
+                    row_color,
+                    self.numbers.format(fill.fee, 4),
+                    fill.fee_token,
+                    if snapshot { " earlier" } else { "" },
+                    reset
+                ));
+            }
+        }
     }
 
-    fn export_csv_to_stderr(&self, trade: &Trade) {
-        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
-        let local_time = trade.datetime_local();
-
-        let price = trade.px;
-        let size = trade.sz;
-        let value = price * size;
-
-        eprintln!(
-            "{},{},{:.2},{:.6},{:.2},{},{}",
-            self.trade_count,
-            side_text,
-            price,
-            size,
-            value,
-            local_time.format("%Y-%m-%d %H:%M:%S"),
-            trade.time
-        );
+    /// Print a funding payment of the `--user` account: its JSON object, a CSV row led by
+    /// `funding`, or one line
+    pub fn print_funding(&self, funding: &UserFunding) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(funding_json(funding))
+            }
+            OutputFormat::Csv => self.output.line(format_args!(
+                "funding,{},{},{:.6},{},{}",
+                funding.coin,
+                self.time_display.full(funding.time),
+                funding.usdc,
+                funding.szi,
+                funding.funding_rate
+            )),
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                let color = match self.colored {
+                    true if funding.usdc > 0.0 => self.theme.buy,
+                    true if funding.usdc < 0.0 => self.theme.sell,
+                    _ => "",
+                };
+                self.output.line(format_args!(
+                    "{}{}{} {}FUNDING {:<10}{} {}{} USDC{}  rate {}%  position {}",
+                    gray,
+                    self.time_display.short(funding.time),
+                    reset,
+                    bold,
+                    funding.coin.as_str(),
+                    reset,
+                    color,
+                    self.numbers.format_signed(funding.usdc, 4),
+                    reset,
+                    self.numbers.format_signed(funding.funding_rate * 100.0, 4),
+                    self.numbers.size(&funding.coin, funding.szi, 4)
+                ));
+            }
+        }
     }
 
-    fn print_verbose_trade_details(&self, trade: &Trade) {
+    /// Print a liquidation involving the `--user` account: its JSON object, a CSV row led by
+    /// `liquidation`, or one line. The exchange sends no time with it.
+    pub fn print_liquidation(&self, liquidation: &Liquidation) {
+        if !self.shows_data() {
+            return;
+        }
         match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(liquidation_json(liquidation))
+            }
+            OutputFormat::Csv => self.output.line(format_args!(
+                "liquidation,{},{},{},{:.2},{:.2}",
+                liquidation.lid,
+                liquidation.liquidator,
+                liquidation.liquidated_user,
+                liquidation.liquidated_ntl_pos,
+                liquidation.liquidated_account_value
+            )),
             OutputFormat::Table | OutputFormat::Minimal => {
-                let (buyer, seller) = trade.buyer_seller();
-                match (buyer, seller) {
-                    (Some(buyer), Some(seller)) => {
-                        println!("  users: buyer={} seller={}", buyer, seller);
-                    }
-                    (Some(buyer), None) => {
-                        println!("  users: buyer={}", buyer);
-                    }
-                    _ => {}
-                }
+                let (bold, _, reset) = self.explorer_colors();
+                let color = if self.colored { self.theme.sell } else { "" };
+                self.output.line(format_args!(
+                    "{}{}LIQUIDATION {}{} user {} by {}  notional {}  account value {}",
+                    bold,
+                    color,
+                    liquidation.lid,
+                    reset,
+                    short_hex(&liquidation.liquidated_user),
+                    short_hex(&liquidation.liquidator),
+                    self.numbers.format(liquidation.liquidated_ntl_pos, 2),
+                    self.numbers.format(liquidation.liquidated_account_value, 2)
+                ));
             }
-            OutputFormat::Csv | OutputFormat::Json => {}
         }
     }
 
-    pub fn print_status(&self, status: &str, message: &str) {
-        if self.quiet && status != "ERROR" {
+    /// Print an order of the `--user` account canceled by the exchange: its JSON object, a CSV
+    /// row led by `cancel`, or one line
+    pub fn print_non_user_cancel(&self, cancel: &NonUserCancel) {
+        if !self.shows_data() {
             return;
         }
-
-        let symbol = if self.colored {
-            match status {
-                "CONNECTING" => (Colors::BRIGHT_YELLOW, "*"),
-                "CONNECTED" => (Colors::BRIGHT_GREEN, "+"),
-                "LISTENING" => (Colors::BRIGHT_BLUE, "~"),
-                "ERROR" => (Colors::BRIGHT_RED, "!"),
-                _ => (Colors::WHITE, "-"),
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(non_user_cancel_json(cancel))
+            }
+            OutputFormat::Csv => self
+                .output
+                .line(format_args!("cancel,{},{}", cancel.coin, cancel.oid)),
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                self.output.line(format_args!(
+                    "{}CANCEL  {:<10}{} order {}  {}canceled by the exchange{}",
+                    bold,
+                    cancel.coin.as_str(),
+                    reset,
+                    cancel.oid,
+                    gray,
+                    reset
+                ));
+            }
+        }
+    }
+
+    /// Print an order update of the `--user` account with the status it moved from: its JSON
+    /// object, a CSV row led by `order`, or one line with the filled and original size
+    pub fn print_order(&self, update: &OrderUpdate, previous_status: Option<&str>) {
+        if !self.shows_data() {
+            return;
+        }
+        let order = &update.order;
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(order_json(update, previous_status))
+            }
+            OutputFormat::Csv => {
+                let precision = &self.numbers.precision;
+                let size = precision.size(&order.coin, 6);
+                self.output.line(format_args!(
+                    "order,{},{},{},{:.*},{:.*},{:.*},{},{},{},{}",
+                    order.coin,
+                    self.time_display.full(update.status_timestamp),
+                    update.side_formatted(),
+                    precision.price(&order.coin, 2),
+                    order.limit_px,
+                    size,
+                    order.sz,
+                    size,
+                    order.orig_sz,
+                    order.oid,
+                    update.status,
+                    previous_status.unwrap_or_default(),
+                    order.cloid.as_deref().unwrap_or_default()
+                ))
+            }
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                let (side_color, status_color) = if self.colored {
+                    (
+                        if update.is_buy() {
+                            self.theme.buy
+                        } else {
+                            self.theme.sell
+                        },
+                        match update.state() {
+                            OrderState::Filled => self.theme.buy,
+                            OrderState::Canceled | OrderState::Rejected => self.theme.sell,
+                            OrderState::Triggered => self.theme.warning,
+                            OrderState::Open | OrderState::Other => bold,
+                        },
+                    )
+                } else {
+                    ("", "")
+                };
+                let transition = match previous_status {
+                    Some(previous) if previous != update.status => format!(
+                        "{}{} {}{} ",
+                        gray,
+                        previous,
+                        if self.unicode { "→" } else { "->" },
+                        reset
+                    ),
+                    _ => String::new(),
+                };
+                self.output.line(format_args!(
+                    "{}{}{} {}ORDER   {:<10}{} {}{:<4}{} {}/{} @ {}  {}{}{}{}  {}oid {}{}{}",
+                    gray,
+                    self.time_display.short(update.status_timestamp),
+                    reset,
+                    bold,
+                    order.coin.as_str(),
+                    reset,
+                    side_color,
+                    update.side_formatted(),
+                    reset,
+                    self.numbers.size(&order.coin, update.filled_sz(), 4),
+                    self.numbers.size(&order.coin, order.orig_sz, 4),
+                    self.numbers.price(&order.coin, order.limit_px, 2),
+                    transition,
+                    status_color,
+                    update.status,
+                    reset,
+                    gray,
+                    order.oid,
+                    order
+                        .cloid
+                        .as_deref()
+                        .map(|cloid| format!(" cloid {}", cloid))
+                        .unwrap_or_default(),
+                    reset
+                ));
+            }
+        }
+    }
+
+    /// Print an explorer block: its JSON object, a CSV row led by `block`, or one line
+    pub fn print_block(&self, block: &ExplorerBlock) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(block_json(block))
+            }
+            OutputFormat::Csv => self.output.line(format_args!(
+                "block,{},{},{},{},{}",
+                block.height,
+                block.num_txs,
+                block.hash,
+                block.proposer,
+                self.time_display.full(block.block_time)
+            )),
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (bold, gray, reset) = self.explorer_colors();
+                self.output.line(format_args!(
+                    "{}{}{} {}BLOCK {:<12}{} {:>5} txs  proposer {}  {}{}{}",
+                    gray,
+                    self.time_display.short(block.block_time),
+                    reset,
+                    bold,
+                    block.height,
+                    reset,
+                    block.num_txs,
+                    short_hex(&block.proposer),
+                    gray,
+                    block.hash,
+                    reset
+                ));
+            }
+        }
+    }
+
+    /// Print an explorer transaction: its JSON object, a CSV row led by `tx`, or one line
+    pub fn print_tx(&self, tx: &ExplorerTx) {
+        if !self.shows_data() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Events => {
+                self.print_json_record(tx_json(tx))
+            }
+            OutputFormat::Csv => self.output.line(format_args!(
+                "tx,{},{},{},{},{},{}",
+                tx.block,
+                tx.action_type(),
+                tx.hash,
+                tx.user,
+                if tx.error.is_some() { "error" } else { "ok" },
+                self.time_display.full(tx.time)
+            )),
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (_, gray, reset) = self.explorer_colors();
+                let status = match &tx.error {
+                    Some(error) if self.colored => format!("{}{}{}", self.theme.sell, error, reset),
+                    Some(error) => format!("error: {}", error),
+                    None if self.colored => format!("{}ok{}", self.theme.buy, reset),
+                    None => "ok".to_string(),
+                };
+                self.output.line(format_args!(
+                    "{}{}{} TX    {:<16} block {:<12} user {}  {}  {}{}{}",
+                    gray,
+                    self.time_display.short(tx.time),
+                    reset,
+                    tx.action_type(),
+                    tx.block,
+                    short_hex(&tx.user),
+                    status,
+                    gray,
+                    tx.hash,
+                    reset
+                ));
+            }
+        }
+    }
+
+    fn explorer_colors(&self) -> (&'static str, &'static str, &'static str) {
+        if self.colored {
+            (Colors::BOLD, self.theme.muted, Colors::RESET)
+        } else {
+            ("", "", "")
+        }
+    }
+
+    /// A candle or explorer record as one JSON line, or pretty-printed for `json-pretty`
+    fn print_json_record(&self, mut json: serde_json::Value) {
+        self.numbers.precision.round_event(&mut json);
+        let rendered = match self.format {
+            OutputFormat::JsonPretty => serde_json::to_string_pretty(&json),
+            _ => serde_json::to_string(&json),
+        };
+        self.output
+            .line(format_args!("{}", rendered.unwrap_or_default()));
+    }
+
+    pub fn trade_count(&self) -> u64 {
+        self.trade_count
+    }
+
+    fn print_table_header(&mut self) {
+        if self.shows_headers() {
+            self.density = self.detect_density();
+            let columns = self.visible_columns();
+            let widths: Vec<usize> = columns
+                .iter()
+                .map(|column| column.width(self.density))
+                .collect();
+            let (gray, reset) = if self.colored {
+                (self.theme.muted, Colors::RESET)
+            } else {
+                ("", "")
+            };
+            let bold = if self.colored { Colors::BOLD } else { "" };
+            let indent = self.marker_indent();
+
+            self.output.line(format_args!(
+                "{}{}{}{}{}",
+                indent,
+                bold,
+                gray,
+                table_border(
+                    &widths,
+                    self.box_chars.horizontal,
+                    self.box_chars.top_left,
+                    self.box_chars.top_mid,
+                    self.box_chars.top_right
+                ),
+                reset
+            ));
+
+            let mut labels = String::new();
+            for column in &columns {
+                labels.push_str(&format!(
+                    "{}{}{}{} {:<width$} ",
+                    bold,
+                    gray,
+                    self.box_chars.vertical,
+                    reset,
+                    column.label(),
+                    width = column.width(self.density)
+                ));
+            }
+            self.output.line(format_args!(
+                "{}{}{}{}{}{}",
+                indent, labels, bold, gray, self.box_chars.vertical, reset
+            ));
+
+            self.output.line(format_args!(
+                "{}{}{}{}{}",
+                indent,
+                bold,
+                gray,
+                table_border(
+                    &widths,
+                    self.box_chars.horizontal,
+                    self.box_chars.mid_left,
+                    self.box_chars.mid_mid,
+                    self.box_chars.mid_right
+                ),
+                reset
+            ));
+        }
+    }
+
+    /// Show or hide a table column, returning whether it is now visible
+    pub fn toggle_column(&mut self, column: TableColumn) -> bool {
+        let idx = column as usize;
+        self.visible_columns[idx] = !self.visible_columns[idx];
+        // Never hide every column, the table would collapse into borders only
+        if !self.visible_columns.iter().any(|visible| *visible) {
+            self.visible_columns[idx] = true;
+        }
+        self.visible_columns[idx]
+    }
+
+    /// Columns toggled on that also fit the current terminal width
+    fn visible_columns(&self) -> Vec<TableColumn> {
+        let toggled: Vec<TableColumn> = TableColumn::ALL
+            .into_iter()
+            .filter(|column| self.visible_columns[*column as usize])
+            .collect();
+        let fitted: Vec<TableColumn> = toggled
+            .iter()
+            .copied()
+            .filter(|column| !self.density.hides(*column))
+            .collect();
+        if fitted.is_empty() { toggled } else { fitted }
+    }
+
+    /// Pick the table density for the current terminal; redirected output stays full width
+    fn detect_density(&self) -> TableDensity {
+        if !self.in_place {
+            return TableDensity::Full;
+        }
+        match crossterm::terminal::size() {
+            Ok((width, _)) => TableDensity::for_terminal_width(width as usize),
+            Err(_) => TableDensity::Full,
+        }
+    }
+
+    /// Print a trade recalled from history, independent of the live output format
+    /// Columns of the history rows in display order, with their widths
+    fn history_columns(&self) -> [(TableColumn, usize); 6] {
+        [
+            (TableColumn::Index, 8),
+            (
+                TableColumn::Time,
+                self.time_display.short(0).chars().count(),
+            ),
+            (TableColumn::Side, 4),
+            (TableColumn::Price, 12),
+            (TableColumn::Size, 12),
+            (TableColumn::Value, 12),
+        ]
+    }
+
+    /// Labels above history rows, marking the column a page is sorted by and its direction
+    pub fn print_history_header(&self, sort: Option<(TableColumn, bool)>) {
+        let (bold, reset) = if self.colored {
+            (Colors::BOLD, Colors::RESET)
+        } else {
+            ("", "")
+        };
+        let labels: Vec<String> = self
+            .history_columns()
+            .into_iter()
+            .map(|(column, width)| {
+                let marker = match (sort, self.unicode) {
+                    (Some((sorted, true)), true) if sorted == column => "▼",
+                    (Some((sorted, false)), true) if sorted == column => "▲",
+                    (Some((sorted, true)), false) if sorted == column => "v",
+                    (Some((sorted, false)), false) if sorted == column => "^",
+                    _ => "",
+                };
+                let label = format!("{}{}", column.label(), marker);
+                match column {
+                    TableColumn::Price | TableColumn::Size | TableColumn::Value => {
+                        format!("{:>width$}", label)
+                    }
+                    _ => format!("{:<width$}", label),
+                }
+            })
+            .collect();
+        println!("{}{} USERS{}", bold, labels.join(" "), reset);
+    }
+
+    /// The history header column under terminal column `x`, counted from 0
+    pub fn history_column_at(&self, x: usize) -> Option<TableColumn> {
+        let mut start = 0;
+        for (column, width) in self.history_columns() {
+            if (start..start + width).contains(&x) {
+                return Some(column);
+            }
+            start += width + 1;
+        }
+        None
+    }
+
+    pub fn print_history_row(&self, index: u64, trade: &Trade) {
+        let (side_color, reset, gray) = if self.colored {
+            let side_color = if trade.is_buy() {
+                self.theme.buy
+            } else {
+                self.theme.sell
+            };
+            (side_color, Colors::RESET, self.theme.muted)
+        } else {
+            ("", "", "")
+        };
+
+        println!(
+            "{}#{:<7}{} {} {}{:<4}{} {:>12} {:>12} {:>12} {}{}{}",
+            gray,
+            index,
+            reset,
+            self.time_display.short(trade.time),
+            side_color,
+            trade.side_formatted(),
+            reset,
+            self.numbers.price(&trade.coin, trade.px, 2),
+            self.numbers.size(&trade.coin, trade.sz, 6),
+            self.numbers.format(trade.value(), 2),
+            gray,
+            trade.users.join(" "),
+            reset
+        );
+    }
+
+    fn print_csv_header(&self) {
+        if self.shows_headers() {
+            self.output.line(format_args!(
+                "#,side,price,size,value,local_time,unix_timestamp"
+            ));
+        }
+    }
+
+    fn print_table_row(&self, trade: &Trade) {
+        let side_color = if self.colored {
+            if trade.is_buy() {
+                self.theme.buy
+            } else {
+                self.theme.sell
+            }
+        } else {
+            ""
+        };
+
+        let reset = if self.colored { Colors::RESET } else { "" };
+        let gray = if self.colored { self.theme.muted } else { "" };
+
+        let mut row = String::new();
+        for column in self.visible_columns() {
+            let width = column.width(self.density);
+            let cell = match column {
+                TableColumn::Index => format!("{:<width$}", self.trade_count),
+                TableColumn::Side => {
+                    format!("{}{:<width$}{}", side_color, trade.side_formatted(), reset)
+                }
+                TableColumn::Price => {
+                    format!("{:<width$}", self.numbers.price(&trade.coin, trade.px, 2))
+                }
+                TableColumn::Size => {
+                    format!("{:<width$}", self.numbers.size(&trade.coin, trade.sz, 6))
+                }
+                TableColumn::Value => {
+                    format!("{:<width$}", self.numbers.format(trade.value(), 2))
+                }
+                TableColumn::Time if self.density == TableDensity::Full => {
+                    format!("{:<width$}", self.time_display.short(trade.time))
+                }
+                TableColumn::Time => {
+                    format!("{:<width$.width$}", self.time_display.short(trade.time))
+                }
+            };
+            row.push_str(&format!(
+                "{}{}{} {} ",
+                gray, self.box_chars.vertical, reset, cell
+            ));
+        }
+
+        let row = format!("{}{}{}{}", row, gray, self.box_chars.vertical, reset);
+        let highlighted = self
+            .highlight_notional
+            .is_some_and(|notional| trade.value() >= notional);
+        let row = if highlighted && self.colored {
+            // Cells reset their colors, so re-apply the highlight after every reset
+            let highlight = format!("{}{}", Colors::BOLD, Colors::INVERSE);
+            format!(
+                "{}{}",
+                highlight,
+                row.replace(Colors::RESET, &format!("{}{}", Colors::RESET, highlight))
+            ) + Colors::RESET
+        } else {
+            row
+        };
+
+        match &self.highlight_marker {
+            Some(marker) if highlighted => self.output.line(format_args!("{} {}", marker, row)),
+            _ => self
+                .output
+                .line(format_args!("{}{}", self.marker_indent(), row)),
+        }
+    }
+
+    /// Padding that keeps unmarked table lines aligned with marked rows
+    fn marker_indent(&self) -> String {
+        self.highlight_marker
+            .as_ref()
+            .map(|marker| " ".repeat(marker.chars().count() + 1))
+            .unwrap_or_default()
+    }
+
+    fn update_stats_footer(&mut self, trade: &Trade) {
+        let Some(stats) = self.stats_footer.as_mut() else {
+            return;
+        };
+        stats.push(trade);
+        // The window holds exactly one interval, so a full window means it is time to print
+        if !self.trade_count.is_multiple_of(stats.capacity() as u64) {
+            return;
+        }
+
+        let vwap = stats.vwap().map_or_else(
+            || "-".to_string(),
+            |vwap| self.numbers.price(&trade.coin, vwap, 2),
+        );
+        let ratio = stats
+            .buy_sell_ratio()
+            .map_or_else(|| "-".to_string(), |ratio| format!("{:.2}", ratio));
+        let rate = stats
+            .trades_per_sec()
+            .map_or_else(|| "-".to_string(), |rate| format!("{:.1}", rate));
+        let text = format!(
+            "last {}: vwap {}  buy/sell {}  {} trades/s",
+            stats.len(),
+            vwap,
+            ratio,
+            rate
+        );
+
+        // Same inner width as a data row: each cell is "| value " plus the closing border
+        let inner: usize = self
+            .visible_columns()
+            .iter()
+            .map(|column| column.width(self.density) + 3)
+            .sum::<usize>()
+            - 1;
+        let (dim, gray, reset) = if self.colored {
+            (Colors::DIM, self.theme.muted, Colors::RESET)
+        } else {
+            ("", "", "")
+        };
+        self.output.line(format_args!(
+            "{}{}{}{}{} {:<width$.width$}{}{}{}",
+            self.marker_indent(),
+            gray,
+            self.box_chars.vertical,
+            reset,
+            dim,
+            text,
+            reset,
+            gray,
+            self.box_chars.vertical,
+            width = inner.saturating_sub(1)
+        ));
+    }
+
+    fn print_csv_row(&self, trade: &Trade) {
+        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
+
+        let price = trade.px;
+        let size = trade.sz;
+        let value = price * size;
+
+        let precision = &self.numbers.precision;
+        self.output.line(format_args!(
+            "{},{},{:.*},{:.*},{:.2},{},{}",
+            self.trade_count,
+            side_text,
+            precision.price(&trade.coin, 2),
+            price,
+            precision.size(&trade.coin, 6),
+            size,
+            value,
+            self.time_display.full(trade.time),
+            trade.time
+        ));
+    }
+
+    fn print_json_row(&self, trade: &Trade) {
+        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
+
+        let price = trade.px;
+        let size = trade.sz;
+        let value = price * size;
+
+        let precision = &self.numbers.precision;
+        let mut json_obj = serde_json::json!({
+            "#": self.trade_count,
+            "coin": trade.coin,
+            "side": side_text,
+            "price": precision.round_price(&trade.coin, price),
+            "size": precision.round_size(&trade.coin, size),
+            "value": value,
+            "local_time": self.time_display.full(trade.time),
+            "unix_timestamp": trade.time,
+            "trade_id": trade.tid,
+            "hash": trade.hash
+        });
+
+        if self.jq_friendly
+            && let Some(fields) = json_obj.as_object_mut()
+            && let Some(seq) = fields.remove("#")
+        {
+            fields.insert("seq".to_string(), seq);
+        }
+
+        let rendered = match self.format {
+            OutputFormat::JsonPretty => serde_json::to_string_pretty(&json_obj),
+            _ => serde_json::to_string(&json_obj),
+        };
+        self.output
+            .line(format_args!("{}", rendered.unwrap_or_default()));
+    }
+
+    fn print_minimal_row(&self, trade: &Trade) {
+        if let Some(template) = &self.template {
+            self.output.line(format_args!(
+                "{}",
+                template.render(trade, &self.time_display, &self.numbers.precision)
+            ));
+            return;
+        }
+
+        let side_symbol = match (trade.is_buy(), self.unicode) {
+            (true, true) => "↗",
+            (false, true) => "↘",
+            (true, false) => "^",
+            (false, false) => "v",
+        };
+        let side_color = if self.colored {
+            if trade.is_buy() {
+                self.theme.buy
+            } else {
+                self.theme.sell
+            }
+        } else {
+            ""
+        };
+        let reset = if self.colored { Colors::RESET } else { "" };
+
+        let price = trade.px;
+        let size = trade.sz;
+
+        let precision = &self.numbers.precision;
+        self.output.line(format_args!(
+            "{} {}{}{} {:<8.*} {:<8.*} {}",
+            self.time_display.short(trade.time),
+            side_color,
+            side_symbol,
+            reset,
+            precision.price(&trade.coin, 2),
+            price,
+            precision.size(&trade.coin, 6),
+            size,
+            trade.coin
+        ));
+    }
+
+    fn print_price_only(&self, trade: &Trade) {
+        if let Some(template) = &self.template {
+            self.output.line(format_args!(
+                "{}",
+                template.render(trade, &self.time_display, &self.numbers.precision)
+            ));
+            return;
+        }
+
+        let price = trade.px;
+        let side_color = if self.colored {
+            if trade.is_buy() {
+                self.theme.buy
+            } else {
+                self.theme.sell
+            }
+        } else {
+            ""
+        };
+        let reset = if self.colored { Colors::RESET } else { "" };
+
+        self.output.line(format_args!(
+            "{}{:.*}{}",
+            side_color,
+            self.numbers.precision.price(&trade.coin, 2),
+            price,
+            reset
+        ));
+    }
+
+    fn print_sparkline_line(&mut self, trade: &Trade) {
+        let Some(sparkline) = self.sparkline.as_mut() else {
+            return;
+        };
+        sparkline.push(trade.px);
+
+        let change = sparkline.change_pct().unwrap_or(0.0);
+        let (change_color, reset) = if self.colored {
+            let color = if change >= 0.0 {
+                self.theme.buy
+            } else {
+                self.theme.sell
+            };
+            (color, Colors::RESET)
+        } else {
+            ("", "")
+        };
+
+        let line = format!(
+            "{} {}{}{} {:.*} {}{:+.2}%{}",
+            trade.coin,
+            change_color,
+            if self.unicode {
+                sparkline.render()
+            } else {
+                sparkline.render_ascii()
+            },
+            reset,
+            self.numbers.precision.price(&trade.coin, 2),
+            trade.px,
+            change_color,
+            change,
+            reset
+        );
+
+        // Carriage return plus clear-line keeps the output on a single refreshed line,
+        // redirected output gets one line per update instead
+        if self.in_place {
+            let mut stdout = std::io::stdout().lock();
+            let _ = write!(stdout, "\r\x1b[2K{}", line);
+            let _ = stdout.flush();
+        } else {
+            self.output.line(format_args!("{}", line));
+        }
+    }
+
+    fn export_csv_to_stderr(&self, trade: &Trade) {
+        let side_text = if trade.is_buy() { "BUY" } else { "SELL" };
+
+        let price = trade.px;
+        let size = trade.sz;
+        let value = price * size;
+
+        let precision = &self.numbers.precision;
+        eprintln!(
+            "{},{},{:.*},{:.*},{:.2},{},{}",
+            self.trade_count,
+            side_text,
+            precision.price(&trade.coin, 2),
+            price,
+            precision.size(&trade.coin, 6),
+            size,
+            value,
+            self.time_display.full(trade.time),
+            trade.time
+        );
+    }
+
+    fn print_verbose_trade_details(&self, trade: &Trade) {
+        match self.format {
+            OutputFormat::Table | OutputFormat::Minimal => {
+                let (buyer, seller) = trade.buyer_seller();
+                match (buyer, seller) {
+                    (Some(buyer), Some(seller)) => {
+                        self.output
+                            .line(format_args!("  users: buyer={} seller={}", buyer, seller));
+                    }
+                    (Some(buyer), None) => {
+                        self.output.line(format_args!("  users: buyer={}", buyer));
+                    }
+                    _ => {}
+                }
+            }
+            OutputFormat::Csv
+            | OutputFormat::Json
+            | OutputFormat::JsonPretty
+            | OutputFormat::Events => {}
+        }
+    }
+
+    pub fn print_status(&self, status: &str, message: &str) {
+        if self.quiet >= QuietLevel::NoBanners && status != "ERROR" {
+            return;
+        }
+
+        let symbol = if self.colored {
+            match status {
+                "CONNECTING" => (self.theme.warning, "*"),
+                "CONNECTED" => (self.theme.buy, "+"),
+                "LISTENING" => (self.theme.info, "~"),
+                "ERROR" => (self.theme.sell, "!"),
+                _ => (self.theme.text, "-"),
+            }
+        } else {
+            (
+                "",
+                match status {
+                    "CONNECTING" => "*",
+                    "CONNECTED" => "+",
+                    "LISTENING" => "~",
+                    "ERROR" => "!",
+                    _ => "-",
+                },
+            )
+        };
+
+        let _reset = if self.colored { Colors::RESET } else { "" };
+
+        println!("[{}] {} {}", status, symbol.1, message);
+    }
+
+    /// Print the end-of-session summary block
+    ///
+    /// CSV and JSON streams stay machine-readable, so their summary goes to stderr.
+    pub fn print_summary(&self, summary: &SessionSummary) {
+        if self.quiet >= QuietLevel::DataOnly {
+            return;
+        }
+
+        let (bold, accent, gray, reset) = if self.colored {
+            (
+                Colors::BOLD,
+                self.theme.accent,
+                self.theme.muted,
+                Colors::RESET,
+            )
+        } else {
+            ("", "", "", "")
+        };
+
+        let duration = summary.duration();
+        let secs = duration.as_secs();
+        let total_trades = summary.total_trades();
+        let rate = if duration.as_secs_f64() > 0.0 {
+            total_trades as f64 / duration.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        let mut lines = vec![
+            String::new(),
+            format!(
+                "{}{}Summary: {} trades in {:02}:{:02}:{:02} ({:.2} trades/sec){}",
+                bold,
+                accent,
+                total_trades,
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                rate,
+                reset
+            ),
+        ];
+
+        if !summary.coins.is_empty() {
+            lines.push(format!(
+                "{}  {:<10} {:>10} {:>16} {:>16} {:>14} {:>14}{}",
+                gray, "COIN", "TRADES", "VOLUME", "NOTIONAL", "HIGH", "LOW", reset
+            ));
+            for (coin, stats) in &summary.coins {
+                lines.push(format!(
+                    "  {:<10} {:>10} {:>16} {:>16} {:>14} {:>14}",
+                    coin,
+                    self.numbers.format(stats.trades as f64, 0),
+                    self.numbers.size(coin, stats.volume, 6),
+                    self.numbers.format(stats.notional, 2),
+                    self.numbers.price(coin, stats.high.unwrap_or_default(), 2),
+                    self.numbers.price(coin, stats.low.unwrap_or_default(), 2)
+                ));
+            }
+        }
+
+        lines.push(format!(
+            "{}  Connections: {} ({:.1}s connected) | Reconnects: {} | Data gaps: {} ({:.1}s disconnected) | Duplicates dropped: {}{}",
+            gray,
+            summary.connections,
+            summary.connected.as_secs_f64(),
+            summary.reconnects,
+            summary.gaps,
+            summary.downtime.as_secs_f64(),
+            summary.duplicate_trades,
+            reset
+        ));
+
+        let block = lines.join("\n");
+        match self.format {
+            OutputFormat::Csv
+            | OutputFormat::Json
+            | OutputFormat::JsonPretty
+            | OutputFormat::Events => eprintln!("{}", block),
+            OutputFormat::Table | OutputFormat::Minimal => println!("{}", block),
+        }
+    }
+}
+
+/// Renders l2Book snapshots as a vertical price ladder that redraws in place
+pub struct BookLadderFormatter {
+    colored: bool,
+    depth: usize,
+    bar_width: usize,
+    mids: Sparkline,
+    in_place: bool,
+    unicode: bool,
+    time_display: TimeDisplay,
+    theme: Theme,
+    numbers: NumberFormat,
+}
+
+impl BookLadderFormatter {
+    pub fn new(colored: bool, depth: usize, sparkline_len: usize) -> Self {
+        Self {
+            colored,
+            depth: depth.max(1),
+            bar_width: 30,
+            mids: Sparkline::new(sparkline_len),
+            in_place: true,
+            unicode: true,
+            time_display: TimeDisplay::default(),
+            theme: Theme::default(),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    /// Digit grouping and decimal places for prices, sizes, and the spread row
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Render the snapshot time in the configured timezone and format
+    pub fn with_time_display(mut self, time_display: TimeDisplay) -> Self {
+        self.time_display = time_display;
+        self
+    }
+
+    /// Redraw over the previous ladder; disabled when stdout is not a terminal
+    pub fn with_in_place(mut self, in_place: bool) -> Self {
+        self.in_place = in_place;
+        self
+    }
+
+    /// Draw depth bars and the mid sparkline with block characters or plain ASCII
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Clear the screen and draw the ladder for the latest book snapshot
+    pub fn print_ladder(&mut self, book: &Book) {
+        if let Some(mid) = book.mid() {
+            self.mids.push(mid);
+        }
+
+        // Move cursor home and clear the screen so the ladder updates in place
+        if self.in_place {
+            print!("\x1b[H\x1b[2J");
+        } else {
+            println!();
+        }
+        println!("{}", self.render(book));
+    }
+
+    pub fn render(&self, book: &Book) -> String {
+        let asks: Vec<&Level> = book.levels.1.iter().take(self.depth).collect();
+        let bids: Vec<&Level> = book.levels.0.iter().take(self.depth).collect();
+
+        let max_size = asks
+            .iter()
+            .chain(bids.iter())
+            .map(|level| level.sz)
+            .fold(0.0_f64, f64::max);
+
+        let (bold, reset, gray) = if self.colored {
+            (Colors::BOLD, Colors::RESET, self.theme.muted)
+        } else {
+            ("", "", "")
+        };
+        let ask_color = if self.colored { self.theme.sell } else { "" };
+        let bid_color = if self.colored { self.theme.buy } else { "" };
+
+        let mut lines = Vec::with_capacity(asks.len() + bids.len() + 4);
+        lines.push(format!(
+            "{}{} order book{} {}{}{}  {}",
+            bold,
+            book.coin,
+            reset,
+            gray,
+            self.time_display.short(book.time),
+            reset,
+            if self.unicode {
+                self.mids.render()
+            } else {
+                self.mids.render_ascii()
             }
+        ));
+        lines.push(format!(
+            "{}{:>14} {:>14} {:>14}  {}{}",
+            bold, "CUM", "SIZE", "PRICE", "DEPTH", reset
+        ));
+
+        // Asks are accumulated outward from the best ask, then printed furthest-first
+        let mut cumulative = 0.0;
+        let ask_rows: Vec<String> = asks
+            .iter()
+            .map(|level| {
+                cumulative += level.sz;
+                self.render_level(&book.coin, level, cumulative, max_size, ask_color, reset)
+            })
+            .collect();
+        lines.extend(ask_rows.into_iter().rev());
+
+        lines.push(self.render_spread_row(book, gray, reset));
+
+        let mut cumulative = 0.0;
+        for level in &bids {
+            cumulative += level.sz;
+            lines
+                .push(self.render_level(&book.coin, level, cumulative, max_size, bid_color, reset));
+        }
+
+        lines.join("\n")
+    }
+
+    fn render_level(
+        &self,
+        coin: &str,
+        level: &Level,
+        cumulative: f64,
+        max_size: f64,
+        color: &str,
+        reset: &str,
+    ) -> String {
+        let bar_len = if max_size > 0.0 {
+            ((level.sz / max_size) * self.bar_width as f64).round() as usize
+        } else {
+            0
+        };
+
+        format!(
+            "{:>14} {:>14} {}{:>14}{}  {}{}{}",
+            self.numbers.size(coin, cumulative, 4),
+            self.numbers.size(coin, level.sz, 4),
+            color,
+            self.numbers.price(coin, level.px, 2),
+            reset,
+            color,
+            if self.unicode { "\u{2588}" } else { "#" }.repeat(bar_len.max(1)),
+            reset
+        )
+    }
+
+    fn render_spread_row(&self, book: &Book, gray: &str, reset: &str) -> String {
+        match (book.spread(), book.mid()) {
+            (Some(spread), Some(mid)) if mid > 0.0 => format!(
+                "{}{:-^60}{}",
+                gray,
+                format!(
+                    " spread {} ({} bps) mid {} ",
+                    self.numbers.price(&book.coin, spread, 2),
+                    self.numbers.format(spread / mid * 10_000.0, 2),
+                    self.numbers.price(&book.coin, mid, 2)
+                ),
+                reset
+            ),
+            _ => format!("{}{:-^60}{}", gray, " spread n/a ", reset),
+        }
+    }
+}
+
+/// Rewrites a single terminal line with the latest price, change, and spread
+pub struct TickerFormatter {
+    colored: bool,
+    in_place: bool,
+    coin: Symbol,
+    session_open: Option<f64>,
+    last_price: Option<f64>,
+    previous_price: Option<f64>,
+    last_bbo: Option<Bbo>,
+    theme: Theme,
+    numbers: NumberFormat,
+}
+
+impl TickerFormatter {
+    /// `coin` is the subscribed coin, so BBO updates for it show before the first trade
+    pub fn new(colored: bool, in_place: bool, coin: &str) -> Self {
+        Self {
+            colored,
+            in_place,
+            coin: Symbol::from(coin),
+            session_open: None,
+            last_price: None,
+            previous_price: None,
+            last_bbo: None,
+            theme: Theme::default(),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    /// Digit grouping and decimal places for the price and spread
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn on_trade(&mut self, trade: &Trade) {
+        if self.coin != trade.coin {
+            // A coin switch starts a new session baseline
+            self.coin = trade.coin.clone();
+            self.session_open = None;
+            self.last_bbo = None;
+        }
+        self.session_open.get_or_insert(trade.px);
+        self.previous_price = self.last_price.replace(trade.px);
+        self.redraw();
+    }
+
+    pub fn on_bbo(&mut self, bbo: &Bbo) {
+        if bbo.coin == self.coin {
+            self.last_bbo = Some(bbo.clone());
+            self.redraw();
+        }
+    }
+
+    fn redraw(&self) {
+        let Some(line) = self.line() else {
+            return;
+        };
+        let mut stdout = std::io::stdout().lock();
+        if self.in_place {
+            let _ = write!(stdout, "\r\x1b[2K{}", line);
+        } else {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        let _ = stdout.flush();
+    }
+
+    /// The ticker line, once a trade or a BBO with both sides gives a price
+    fn line(&self) -> Option<String> {
+        let price = self.last_price.or_else(|| self.last_bbo.as_ref()?.mid())?;
+
+        let (arrow, direction_color) = match self.previous_price {
+            Some(previous) if price > previous => ("+", self.theme.buy),
+            Some(previous) if price < previous => ("-", self.theme.sell),
+            _ => ("=", self.theme.text),
+        };
+        let change_pct = self
+            .session_open
+            .filter(|open| *open != 0.0)
+            .map(|open| (price - open) / open * 100.0)
+            .unwrap_or(0.0);
+        let change_color = if change_pct >= 0.0 {
+            self.theme.buy
         } else {
+            self.theme.sell
+        };
+
+        let (direction_color, change_color, bold, gray, reset) = if self.colored {
             (
-                "",
-                match status {
-                    "CONNECTING" => "*",
-                    "CONNECTED" => "+",
-                    "LISTENING" => "~",
-                    "ERROR" => "!",
-                    _ => "-",
-                },
+                direction_color,
+                change_color,
+                Colors::BOLD,
+                self.theme.muted,
+                Colors::RESET,
             )
+        } else {
+            ("", "", "", "", "")
         };
 
-        let _reset = if self.colored { Colors::RESET } else { "" };
+        let spread = match self.last_bbo.as_ref() {
+            Some(bbo) => match (bbo.spread(), bbo.mid()) {
+                (Some(spread), Some(mid)) if mid > 0.0 => format!(
+                    "spread {} ({} bps)",
+                    self.numbers.price(&self.coin, spread, 2),
+                    self.numbers.format(spread / mid * 10_000.0, 2)
+                ),
+                _ => "spread n/a".to_string(),
+            },
+            None => "spread n/a".to_string(),
+        };
 
-        println!("[{}] {} {}", status, symbol.1, message);
+        Some(format!(
+            "{}{}{} {}{}{}{} {}{}%{} {}{}{}",
+            bold,
+            self.coin,
+            reset,
+            direction_color,
+            arrow,
+            self.numbers.price(&self.coin, price, 2),
+            reset,
+            change_color,
+            self.numbers.format_signed(change_pct, 2),
+            reset,
+            gray,
+            spread,
+            reset
+        ))
+    }
+}
+
+/// An address shortened to its first and last few digits, e.g. `0x1234..abcd`
+fn short_hex(address: &str) -> String {
+    let tail = address.len().saturating_sub(4);
+    match (address.get(..6), address.get(tail..)) {
+        (Some(head), Some(tail)) if address.len() > 12 => format!("{}..{}", head, tail),
+        _ => address.to_string(),
+    }
+}
+
+/// Renders best bid/offer updates as a single status line
+pub struct BboFormatter {
+    colored: bool,
+    time_display: TimeDisplay,
+    theme: Theme,
+    numbers: NumberFormat,
+}
+
+impl BboFormatter {
+    pub fn new(colored: bool, time_display: TimeDisplay) -> Self {
+        Self {
+            colored,
+            time_display,
+            theme: Theme::default(),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Digit grouping and decimal places for both sides and the spread
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    pub fn render_line(&self, bbo: &Bbo) -> String {
+        let (bold, bid_color, ask_color, gray, reset) = if self.colored {
+            (
+                Colors::BOLD,
+                self.theme.buy,
+                self.theme.sell,
+                self.theme.muted,
+                Colors::RESET,
+            )
+        } else {
+            ("", "", "", "", "")
+        };
+
+        let side = |level: &Option<Level>| match level {
+            Some(level) => format!(
+                "{} x {}",
+                self.numbers.price(&bbo.coin, level.px, 2),
+                self.numbers.size(&bbo.coin, level.sz, 4)
+            ),
+            None => "-".to_string(),
+        };
+        let spread = match (bbo.spread(), bbo.spread_bps()) {
+            (Some(spread), Some(bps)) => format!(
+                "{} ({} bps)",
+                self.numbers.price(&bbo.coin, spread, 2),
+                self.numbers.format(bps, 2)
+            ),
+            _ => "n/a".to_string(),
+        };
+
+        format!(
+            "{}{} BBO{} bid {}{}{} | ask {}{}{} | spread {} {}{}{}",
+            bold,
+            bbo.coin,
+            reset,
+            bid_color,
+            side(&bbo.bbo.0),
+            reset,
+            ask_color,
+            side(&bbo.bbo.1),
+            reset,
+            spread,
+            gray,
+            self.time_display.short(bbo.time),
+            reset
+        )
+    }
+}
+
+/// A coin's latest mid with the decimals the exchange sent it with, and its last move
+#[derive(Debug, Clone, Copy)]
+pub struct Mid {
+    pub px: f64,
+    pub decimals: usize,
+    direction: Ordering,
+}
+
+/// Renders `allMids` as a grid of every coin's mid that redraws in place, colored by each mid's
+/// last move; off a terminal it prints only the mids that moved, one line each
+pub struct AllMidsFormatter {
+    colored: bool,
+    in_place: bool,
+    theme: Theme,
+    numbers: NumberFormat,
+    /// Latest mid per coin, in name order
+    mids: BTreeMap<Symbol, Mid>,
+    /// Coin picked by clicking its cell, shown highlighted
+    focused: Option<Symbol>,
+}
+
+/// Where the mids grid puts its cells on a terminal of a given size
+struct MidsGrid {
+    coin_width: usize,
+    price_width: usize,
+    /// A cell with the gap after it
+    cell_width: usize,
+    columns: usize,
+    /// Coins drawn, the rest are counted on the last line
+    shown: usize,
+}
+
+// Spaces between grid cells
+const MIDS_GAP: usize = 3;
+
+impl AllMidsFormatter {
+    pub fn new(colored: bool) -> Self {
+        Self {
+            colored,
+            in_place: true,
+            theme: Theme::default(),
+            numbers: NumberFormat::default(),
+            mids: BTreeMap::new(),
+            focused: None,
+        }
+    }
+
+    /// Redraw over the previous grid; disabled when stdout is not a terminal
+    pub fn with_in_place(mut self, in_place: bool) -> Self {
+        self.in_place = in_place;
+        self
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
     }
 
-    pub fn print_summary(&self, total_trades: u64, duration_secs: u64) {
-        if self.quiet {
+    /// Digit grouping, and decimal places where `--price-decimals` overrides the exchange's
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Take in an update and return the coins whose mid changed, in name order; every coin is
+    /// new on the first update. Mids that do not parse are skipped.
+    pub fn update(&mut self, all_mids: &AllMids) -> Vec<(Symbol, Mid)> {
+        let mut moved: Vec<(Symbol, Mid)> = all_mids
+            .mids
+            .iter()
+            .filter_map(|(coin, text)| {
+                let px: f64 = text.parse().ok()?;
+                let decimals = text
+                    .split_once('.')
+                    .map_or(0, |(_, fraction)| fraction.len());
+                let direction = match self.mids.get(coin) {
+                    Some(previous) if previous.px == px => return None,
+                    Some(previous) => px.partial_cmp(&previous.px).unwrap_or(Ordering::Equal),
+                    None => Ordering::Equal,
+                };
+                let mid = Mid {
+                    px,
+                    decimals,
+                    direction,
+                };
+                self.mids.insert(coin.clone(), mid);
+                Some((coin.clone(), mid))
+            })
+            .collect();
+        moved.sort_by(|a, b| a.0.cmp(&b.0));
+        moved
+    }
+
+    /// Redraw the grid, or off a terminal print the mids that just moved
+    pub fn print(&self, moved: &[(Symbol, Mid)]) {
+        if !self.in_place {
+            for (coin, mid) in moved {
+                println!("{}", self.render_mid(coin, mid, coin.len(), 0));
+            }
             return;
         }
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        print!("\x1b[H\x1b[2J");
+        println!("{}", self.render(width as usize, height as usize));
+    }
+
+    /// Redraw the grid from the latest mids, as after a pause
+    pub fn redraw(&self) {
+        if self.in_place {
+            self.print(&[]);
+        }
+    }
+
+    /// Highlight `coin` in the grid
+    pub fn focus(&mut self, coin: Symbol) {
+        self.focused = Some(coin);
+    }
+
+    /// The coin whose cell is at `column` and `row`, counted from 0 at the top left, on a grid
+    /// drawn in place for a terminal of `width` by `height`
+    pub fn coin_at(
+        &self,
+        column: usize,
+        row: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<Symbol> {
+        // The first row is the title
+        if !self.in_place || row == 0 {
+            return None;
+        }
+        let grid = self.grid(width, height);
+        let cell = column / grid.cell_width;
+        if cell >= grid.columns || column % grid.cell_width >= grid.cell_width - MIDS_GAP {
+            return None;
+        }
+        let index = (row - 1) * grid.columns + cell;
+        if index >= grid.shown {
+            return None;
+        }
+        self.mids.keys().nth(index).cloned()
+    }
+
+    fn grid(&self, width: usize, height: usize) -> MidsGrid {
+        let coin_width = self.mids.keys().map(|coin| coin.len()).max().unwrap_or(0);
+        let price_width = self
+            .mids
+            .iter()
+            .map(|(coin, mid)| self.price(coin, mid).len())
+            .max()
+            .unwrap_or(0);
+        let cell_width = coin_width + 1 + price_width + MIDS_GAP;
+        let columns = ((width + MIDS_GAP) / cell_width).max(1);
+        // Header above, prompt below
+        let rows = height.saturating_sub(2).max(1);
+        let shown = if self.mids.len() > columns * rows {
+            columns * (rows - 1).max(1)
+        } else {
+            self.mids.len()
+        };
+        MidsGrid {
+            coin_width,
+            price_width,
+            cell_width,
+            columns,
+            shown,
+        }
+    }
 
-        let rate = if duration_secs > 0 {
-            total_trades as f64 / duration_secs as f64
+    /// The grid for a terminal of `width` by `height`, with a line counting the coins left out
+    /// when they do not all fit
+    pub fn render(&self, width: usize, height: usize) -> String {
+        let (bold, gray, reset) = if self.colored {
+            (Colors::BOLD, self.theme.muted, Colors::RESET)
         } else {
-            0.0
+            ("", "", "")
         };
+        let MidsGrid {
+            coin_width,
+            price_width,
+            columns,
+            shown,
+            ..
+        } = self.grid(width, height);
 
-        println!();
-        if self.colored {
-            println!(
-                "{}{}Summary: {} trades in {}s ({:.2} trades/sec){}",
-                Colors::BOLD,
-                Colors::BRIGHT_CYAN,
-                total_trades,
-                duration_secs,
-                rate,
-                Colors::RESET
-            );
+        let mut lines = vec![format!(
+            "{}All mids{} {}{} coins{}",
+            bold,
+            reset,
+            gray,
+            self.mids.len(),
+            reset
+        )];
+        let cells: Vec<String> = self
+            .mids
+            .iter()
+            .take(shown)
+            .map(|(coin, mid)| self.render_mid(coin, mid, coin_width, price_width))
+            .collect();
+        lines.extend(
+            cells
+                .chunks(columns)
+                .map(|row| row.join(&" ".repeat(MIDS_GAP))),
+        );
+        if shown < self.mids.len() {
+            lines.push(format!(
+                "{}... {} more; widen or heighten the terminal{}",
+                gray,
+                self.mids.len() - shown,
+                reset
+            ));
+        }
+        lines.join("\n")
+    }
+
+    fn price(&self, coin: &str, mid: &Mid) -> String {
+        self.numbers.price(coin, mid.px, mid.decimals)
+    }
+
+    fn render_mid(&self, coin: &str, mid: &Mid, coin_width: usize, price_width: usize) -> String {
+        let (color, reset) = match mid.direction {
+            _ if !self.colored => ("", ""),
+            Ordering::Greater => (self.theme.buy, Colors::RESET),
+            Ordering::Less => (self.theme.sell, Colors::RESET),
+            Ordering::Equal => ("", ""),
+        };
+        // Reverse video marks the focused coin; without color, a star before its mid does
+        let focused = self.focused.as_deref() == Some(coin);
+        let (open, close, gap) = match (focused, self.colored) {
+            (true, true) => (Colors::INVERSE, Colors::RESET, " "),
+            (true, false) => ("", "", "*"),
+            _ => ("", "", " "),
+        };
+        format!(
+            "{}{:<coin_width$}{}{}{}{:>price_width$}{}",
+            open,
+            coin,
+            close,
+            gap,
+            color,
+            self.price(coin, mid),
+            reset
+        )
+    }
+}
+
+/// Renders connection health as a one-line status bar
+pub struct StatusBarFormatter {
+    colored: bool,
+    unicode: bool,
+    theme: Theme,
+}
+
+impl StatusBarFormatter {
+    pub fn new(colored: bool, unicode: bool) -> Self {
+        Self {
+            colored,
+            unicode,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Map semantic roles (buy, sell, muted, ...) to the selected palette
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn render_line(&self, stats: &ConnectionStats) -> String {
+        let (state_color, gray, reset) = match (self.colored, stats.connected) {
+            (true, true) => (self.theme.buy, self.theme.muted, Colors::RESET),
+            (true, false) => (self.theme.sell, self.theme.muted, Colors::RESET),
+            (false, _) => ("", "", ""),
+        };
+        let (dot, sep) = if self.unicode {
+            ("●", "│")
         } else {
-            println!(
-                "Summary: {} trades in {}s ({:.2} trades/sec)",
-                total_trades, duration_secs, rate
-            );
+            ("*", "|")
+        };
+        let state = if stats.connected {
+            "CONNECTED"
+        } else {
+            "DISCONNECTED"
+        };
+        let uptime = match stats.uptime {
+            Some(uptime) => {
+                let secs = uptime.as_secs();
+                format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+            }
+            None => "--:--:--".to_string(),
+        };
+        let age = match stats.last_message_age {
+            Some(age) => format!("{:.1}s ago", age.as_secs_f64()),
+            None => "never".to_string(),
+        };
+        let rtt = match stats.ping_rtt {
+            Some(rtt) => format!("{:.0}ms", rtt.as_secs_f64() * 1000.0),
+            None => "--".to_string(),
+        };
+
+        let recording = match &stats.recording {
+            Some(recording) => format!(
+                " {}{}{} REC {} {} {} events{} {}",
+                gray,
+                sep,
+                reset,
+                recording.file,
+                format_bytes(recording.bytes_written),
+                recording.events_persisted,
+                if recording.events_dropped > 0 {
+                    format!(" ({} dropped)", recording.events_dropped)
+                } else {
+                    String::new()
+                },
+                match recording.rotation_in {
+                    Some(remaining) => {
+                        let secs = remaining.as_secs();
+                        format!("rotate in {:02}:{:02}", secs / 60, secs % 60)
+                    }
+                    None => String::new(),
+                }
+            ),
+            None => String::new(),
+        };
+
+        format!(
+            "{}{} {}{} {}{}{} up {} {}{}{} reconnects {} {}{}{} {:.1} msg/s {}{}{} rtt {} {}{}{} last msg {}{}",
+            state_color,
+            dot,
+            state,
+            reset,
+            gray,
+            sep,
+            reset,
+            uptime,
+            gray,
+            sep,
+            reset,
+            stats.reconnects,
+            gray,
+            sep,
+            reset,
+            stats.msgs_per_sec,
+            gray,
+            sep,
+            reset,
+            rtt,
+            gray,
+            sep,
+            reset,
+            age,
+            recording.trim_end()
+        )
+    }
+}
+
+/// Human-readable byte count using binary units
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_mids(json: &str) -> AllMids {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn only_changed_mids_move() {
+        let mut formatter = AllMidsFormatter::new(false);
+        let first = all_mids(r#"{"mids":{"BTC":"112449.5","ETH":"4120.15"}}"#);
+        let moved = formatter.update(&first);
+        assert_eq!(moved.len(), 2);
+        assert!(formatter.update(&first).is_empty());
+
+        let moved = formatter.update(&all_mids(r#"{"mids":{"BTC":"112450","ETH":"4120.15"}}"#));
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].0.as_str(), "BTC");
+        assert_eq!(moved[0].1.px, 112450.0);
+        assert_eq!(moved[0].1.decimals, 0);
+    }
+
+    fn bbo(coin: &str, bid: f64, ask: f64) -> Bbo {
+        let level = |px| Level { px, sz: 1.0, n: 1 };
+        Bbo {
+            coin: Symbol::from(coin),
+            time: 1_700_000_000_000,
+            bbo: (Some(level(bid)), Some(level(ask))),
         }
     }
+
+    #[test]
+    fn ticker_shows_the_subscribed_bbo_before_the_first_trade() {
+        let mut ticker = TickerFormatter::new(false, false, "BTC");
+        assert_eq!(ticker.line(), None);
+
+        ticker.on_bbo(&bbo("ETH", 4120.0, 4120.5));
+        assert_eq!(ticker.line(), None);
+
+        ticker.on_bbo(&bbo("BTC", 112449.0, 112451.0));
+        assert_eq!(
+            ticker.line().unwrap(),
+            "BTC =112450.00 +0.00% spread 2.00 (0.18 bps)"
+        );
+
+        ticker.on_trade(&Trade {
+            coin: Symbol::from("BTC"),
+            px: 112452.0,
+            ..Trade::default()
+        });
+        assert!(ticker.line().unwrap().starts_with("BTC =112452.00 +0.00%"));
+    }
 }
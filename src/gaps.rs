@@ -0,0 +1,55 @@
+/// file: src/gaps.rs
+/// description: Data gaps within a connection: silent streams and skipped candles
+use crate::{symbol::Symbol, types::Candle};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Finds holes in the received data that a reconnect does not explain
+///
+/// Trade ids are not sequential, so a missed trade cannot be seen directly; a stream that went
+/// quiet for longer than `max_silence` stands in for it. Candles carry their own open and close
+/// times, so a skipped bar is exact.
+#[derive(Debug, Default)]
+pub struct GapDetector {
+    max_silence: Option<Duration>,
+    connected_at: Option<Instant>,
+    /// Close time of the latest candle by coin and interval
+    candle_closes: HashMap<(Symbol, String), i64>,
+}
+
+impl GapDetector {
+    pub fn new(max_silence: Option<Duration>) -> Self {
+        Self {
+            max_silence,
+            ..Self::default()
+        }
+    }
+
+    /// Called on every (re)connect; silence spanning a disconnect is counted as downtime instead
+    pub fn reset_connection(&mut self) {
+        self.connected_at = Some(Instant::now());
+    }
+
+    /// Silence before a data message, given when its channel and coin last delivered data
+    pub fn observe_message(&self, previous: Option<Instant>) -> Option<Duration> {
+        let threshold = self.max_silence?;
+        let previous = previous.filter(|at| self.connected_at.is_some_and(|since| *at >= since))?;
+        let silence = previous.elapsed();
+        (silence > threshold).then_some(silence)
+    }
+
+    /// Time covered by bars missing before `candle`, if any were skipped
+    ///
+    /// A bar closes the millisecond before the next one opens. Updates to the current bar and
+    /// older bars resent after a reconnect are not gaps.
+    pub fn observe_candle(&mut self, candle: &Candle) -> Option<Duration> {
+        let key = (candle.s.clone(), candle.i.clone());
+        let previous = self.candle_closes.get(&key).copied();
+        if previous.is_none_or(|close| candle.close_time > close) {
+            self.candle_closes.insert(key, candle.close_time);
+        }
+        let missing = candle.t - (previous? + 1);
+        (missing > 0).then(|| Duration::from_millis(missing as u64))
+    }
+}
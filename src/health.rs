@@ -0,0 +1,102 @@
+/// file: src/health.rs
+/// description: HTTP liveness and status endpoints for load balancers and dashboards
+use crate::{
+    client_state::SharedClientState,
+    dashboard::{read_request, write_response},
+    error::HyperliquidError,
+    monitoring::HealthStatus,
+    recent::{self, SharedRecent},
+};
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+/// Bind the health listener and serve `/healthz` and `/status` in the background
+///
+/// The stream counts as unhealthy once no message has arrived for `stale_after`. `/status` also
+/// carries what `recent` holds.
+pub async fn serve_health(
+    port: u16,
+    state: SharedClientState,
+    stale_after: Duration,
+    recent: SharedRecent,
+) -> Result<()> {
+    let addr: SocketAddr = ([0, 0, 0, 0], port).into();
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| HyperliquidError::ConfigError(format!("cannot bind {}: {}", addr, e)))?;
+    info!(
+        "Health endpoints available at http://{}/healthz and /status",
+        addr
+    );
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let (state, recent) = (state.clone(), recent.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, state, stale_after, recent).await
+                        {
+                            debug!("Health connection from {} ended: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Health accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    state: SharedClientState,
+    stale_after: Duration,
+    recent: SharedRecent,
+) -> std::io::Result<()> {
+    let (request, mut stream) = read_request(stream).await?;
+    // Probes sometimes add cache-busting query strings
+    let path = request.path.split('?').next().unwrap_or_default();
+    if request.method != "GET" {
+        return write_response(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed",
+        )
+        .await;
+    }
+
+    let health = HealthStatus::from_state(&*state.lock().await, stale_after);
+    match path {
+        // Status code only matters to load balancers; the body is for people running curl
+        "/healthz" if health.is_healthy => {
+            write_response(&mut stream, "200 OK", "text/plain", "ok\n").await
+        }
+        "/healthz" => {
+            write_response(
+                &mut stream,
+                "503 Service Unavailable",
+                "text/plain",
+                "unhealthy\n",
+            )
+            .await
+        }
+        "/status" => {
+            let mut status = health.to_json();
+            status["recent"] = recent::lock(&recent).to_json();
+            write_response(
+                &mut stream,
+                "200 OK",
+                "application/json",
+                &status.to_string(),
+            )
+            .await
+        }
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "not found").await,
+    }
+}
@@ -0,0 +1,308 @@
+/// file: src/heatmap.rs
+/// description: Scrolling order book depth heatmap built from l2Book snapshots
+use crate::{
+    formatter::Colors, number_format::NumberFormat, time_display::TimeDisplay, types::Book,
+};
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+// Shades from empty to most liquid, used for both colored and plain output
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+const ASCII_SHADES: [char; 5] = [' ', '.', ':', '*', '#'];
+
+/// One book snapshot reduced to (price, size, is_bid) points
+#[derive(Debug, Clone)]
+struct DepthColumn {
+    points: Vec<(f64, f64, bool)>,
+}
+
+pub struct DepthHeatmap {
+    columns: VecDeque<DepthColumn>,
+    width: usize,
+    rows: usize,
+    colored: bool,
+    unicode: bool,
+    in_place: bool,
+    time_display: TimeDisplay,
+    numbers: NumberFormat,
+}
+
+impl DepthHeatmap {
+    pub fn new(width: usize, rows: usize, colored: bool) -> Self {
+        let width = width.max(2);
+        Self {
+            columns: VecDeque::with_capacity(width),
+            width,
+            rows: rows.max(4),
+            colored,
+            unicode: true,
+            in_place: true,
+            time_display: TimeDisplay::default(),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    pub fn with_in_place(mut self, in_place: bool) -> Self {
+        self.in_place = in_place;
+        self
+    }
+
+    pub fn with_time_display(mut self, time_display: TimeDisplay) -> Self {
+        self.time_display = time_display;
+        self
+    }
+
+    /// Digit grouping and decimal places for the mid and the price axis
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    pub fn push(&mut self, book: &Book) {
+        if self.columns.len() == self.width {
+            self.columns.pop_front();
+        }
+        let points = book
+            .levels
+            .0
+            .iter()
+            .map(|level| (level.px, level.sz, true))
+            .chain(
+                book.levels
+                    .1
+                    .iter()
+                    .map(|level| (level.px, level.sz, false)),
+            )
+            .collect();
+        self.columns.push_back(DepthColumn { points });
+    }
+
+    /// Add the snapshot and redraw the whole panel
+    pub fn print(&mut self, book: &Book) {
+        self.push(book);
+        if self.in_place {
+            print!("\x1b[H\x1b[2J");
+        } else {
+            println!();
+        }
+        println!("{}", self.render(book));
+    }
+
+    /// Render the heatmap with the price window centered on the latest book
+    pub fn render(&self, latest: &Book) -> String {
+        let (Some(mid), Some((low, high))) = (latest.mid(), price_range(latest)) else {
+            return format!(
+                "{} depth heatmap: waiting for a two-sided book",
+                latest.coin
+            );
+        };
+
+        // Keep the latest book's full range on screen, centered on the mid
+        let half_span = (mid - low).max(high - mid).max(f64::EPSILON);
+        let step = 2.0 * half_span / self.rows as f64;
+        let top = mid + half_span;
+
+        // grid[row][col] = (bid size, ask size)
+        let mut grid = vec![vec![(0.0_f64, 0.0_f64); self.columns.len()]; self.rows];
+        for (col, column) in self.columns.iter().enumerate() {
+            for (px, sz, is_bid) in &column.points {
+                let mut row = ((top - px) / step).floor();
+                // The latest book's lowest level sits exactly on the bottom edge
+                if row == self.rows as f64 {
+                    row -= 1.0;
+                }
+                if row < 0.0 || row >= self.rows as f64 {
+                    continue;
+                }
+                let cell = &mut grid[row as usize][col];
+                if *is_bid {
+                    cell.0 += sz;
+                } else {
+                    cell.1 += sz;
+                }
+            }
+        }
+
+        let max_size = grid
+            .iter()
+            .flatten()
+            .map(|(bid, ask)| bid.max(*ask))
+            .fold(0.0_f64, f64::max);
+
+        let reset = if self.colored { Colors::RESET } else { "" };
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} depth heatmap  {}  mid {}  {} snapshots, bids green / asks red",
+            latest.coin,
+            self.time_display.short(latest.time),
+            self.numbers.price(&latest.coin, mid, 2),
+            self.columns.len()
+        );
+
+        for (row, cells) in grid.iter().enumerate() {
+            let row_price = top - (row as f64 + 0.5) * step;
+            let _ = write!(
+                out,
+                "{:>12} ",
+                self.numbers.price(&latest.coin, row_price, 2)
+            );
+            for (bid, ask) in cells {
+                let (size, is_bid) = if bid >= ask {
+                    (*bid, true)
+                } else {
+                    (*ask, false)
+                };
+                out.push_str(&self.cell(size, max_size, is_bid));
+            }
+            out.push_str(reset);
+            out.push('\n');
+        }
+
+        out
+    }
+
+    fn cell(&self, size: f64, max_size: f64, is_bid: bool) -> String {
+        let shades = if self.unicode { &SHADES } else { &ASCII_SHADES };
+        if size <= 0.0 || max_size <= 0.0 {
+            return shades[0].to_string();
+        }
+
+        let intensity = (size / max_size).clamp(0.0, 1.0);
+        let shade_idx = 1 + (intensity * (shades.len() - 2) as f64).round() as usize;
+        let shade = shades[shade_idx.min(shades.len() - 1)];
+
+        if !self.colored {
+            return shade.to_string();
+        }
+
+        // 256-color ramps: dark to bright green for bids, dark to bright red for asks
+        const GREEN_RAMP: [u8; 5] = [22, 28, 34, 40, 46];
+        const RED_RAMP: [u8; 5] = [52, 88, 124, 160, 196];
+        let ramp = if is_bid { &GREEN_RAMP } else { &RED_RAMP };
+        let color =
+            ramp[((intensity * (ramp.len() - 1) as f64).round() as usize).min(ramp.len() - 1)];
+        format!("\x1b[38;5;{}m{}{}", color, shade, Colors::RESET)
+    }
+}
+
+fn price_range(book: &Book) -> Option<(f64, f64)> {
+    let prices = book
+        .levels
+        .0
+        .iter()
+        .chain(book.levels.1.iter())
+        .map(|level| level.px);
+    let (low, high) = prices.fold((f64::MAX, f64::MIN), |(lo, hi), px| {
+        (lo.min(px), hi.max(px))
+    });
+    (low <= high).then_some((low, high))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{symbol::Symbol, types::Level};
+
+    fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Book {
+        let levels = |side: &[(f64, f64)]| {
+            side.iter()
+                .map(|&(px, sz)| Level { px, sz, n: 1 })
+                .collect()
+        };
+        Book {
+            coin: Symbol::from("BTC"),
+            levels: (levels(bids), levels(asks)),
+            time: 1_700_000_000_000,
+        }
+    }
+
+    fn heatmap(width: usize, rows: usize) -> DepthHeatmap {
+        DepthHeatmap::new(width, rows, false).with_unicode(false)
+    }
+
+    /// The grid under the title line, one string per row
+    fn grid(rendered: &str) -> Vec<&str> {
+        rendered.lines().skip(1).collect()
+    }
+
+    #[test]
+    fn every_level_of_the_latest_book_is_on_screen() {
+        let latest = book(&[(99.0, 2.0), (98.0, 1.0)], &[(101.0, 1.0), (102.0, 2.0)]);
+        let mut map = heatmap(4, 4);
+        map.push(&latest);
+        map.push(&latest);
+
+        let rendered = map.render(&latest);
+        assert!(rendered.starts_with("BTC depth heatmap"));
+        assert!(rendered.lines().next().unwrap().contains("mid 100.00"));
+        assert!(rendered.lines().next().unwrap().contains("2 snapshots"));
+        // 99 and the bottom-edge 98 share the lowest row
+        assert_eq!(
+            grid(&rendered),
+            [
+                "      101.50 **",
+                "      100.50 ::",
+                "       99.50   ",
+                "       98.50 ##",
+            ]
+        );
+    }
+
+    #[test]
+    fn books_without_both_sides_wait() {
+        let map = heatmap(4, 4);
+        for latest in [
+            book(&[], &[]),
+            book(&[(99.0, 1.0)], &[]),
+            book(&[], &[(101.0, 1.0)]),
+        ] {
+            assert_eq!(
+                map.render(&latest),
+                "BTC depth heatmap: waiting for a two-sided book"
+            );
+        }
+    }
+
+    #[test]
+    fn a_flat_book_renders_every_row() {
+        // Bid and ask locked at one price leaves no price range to scale over
+        let latest = book(&[(100.0, 1.0)], &[(100.0, 1.0)]);
+        let mut map = heatmap(4, 4);
+        map.push(&latest);
+
+        let rendered = map.render(&latest);
+        let rows = grid(&rendered);
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|row| !row.contains("NaN")));
+        assert_eq!(rows.iter().filter(|row| row.ends_with('#')).count(), 1);
+    }
+
+    #[test]
+    fn a_zero_width_panel_still_keeps_two_snapshots() {
+        let latest = book(&[(99.0, 1.0)], &[(101.0, 1.0)]);
+        let mut map = heatmap(0, 0);
+        for _ in 0..3 {
+            map.push(&latest);
+        }
+
+        let rendered = map.render(&latest);
+        assert!(rendered.lines().next().unwrap().contains("2 snapshots"));
+        let rows = grid(&rendered);
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|row| row.len() == 15));
+    }
+
+    #[test]
+    fn rendering_before_any_snapshot_draws_an_empty_grid() {
+        let latest = book(&[(99.0, 1.0)], &[(101.0, 1.0)]);
+        let rendered = heatmap(4, 4).render(&latest);
+        assert!(rendered.lines().next().unwrap().contains("0 snapshots"));
+        assert!(grid(&rendered).iter().all(|row| row.len() == 13));
+    }
+}
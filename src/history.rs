@@ -0,0 +1,215 @@
+/// file: src/history.rs
+/// description: Bounded in-memory trade history with scrolling and search for interactive mode
+use crate::{
+    budget::{Buffer, MemoryBudget, push_ring, trade_bytes},
+    formatter::TableColumn,
+    types::Trade,
+};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub index: u64,
+    pub trade: Arc<Trade>,
+}
+
+/// Ring buffer of the most recent trades, newest last
+#[derive(Debug)]
+pub struct TradeHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+    budget: Option<Arc<MemoryBudget>>,
+    // Number of trades between the newest entry and the bottom of the current page
+    scroll_offset: usize,
+}
+
+impl TradeHistory {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(4096)),
+            capacity,
+            budget: None,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Count the scrollback against `budget`, giving up the oldest trades while it is over
+    pub fn with_budget(mut self, budget: Option<Arc<MemoryBudget>>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn push(&mut self, index: u64, trade: Arc<Trade>) {
+        push_ring(
+            &mut self.entries,
+            self.capacity,
+            HistoryEntry { index, trade },
+            self.budget
+                .as_deref()
+                .map(|budget| (budget, Buffer::History)),
+            |entry| trade_bytes(&entry.trade),
+        );
+        // Budget eviction can shrink the ring below a page scrolled far back
+        self.scroll_offset = self.scroll_offset.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Move the view by `pages` pages (negative is older) and return the visible page
+    pub fn scroll(&mut self, pages: i32, page_size: usize) -> Vec<&HistoryEntry> {
+        let page_size = page_size.max(1);
+        let max_offset = self.entries.len().saturating_sub(page_size);
+        let delta = pages.unsigned_abs() as usize * page_size;
+
+        self.scroll_offset = if pages < 0 {
+            (self.scroll_offset + delta).min(max_offset)
+        } else {
+            self.scroll_offset.saturating_sub(delta)
+        };
+
+        let end = self.entries.len() - self.scroll_offset;
+        let start = end.saturating_sub(page_size);
+        self.entries.range(start..end).collect()
+    }
+
+    /// Jump back to the newest trades
+    pub fn reset_scroll(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    /// Find trades matching a query, newest first, capped at `limit`
+    ///
+    /// Query forms:
+    /// - `>N`: size of at least N
+    /// - `0x...`: buyer or seller address containing the text
+    /// - anything else: price or size text containing the query
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&HistoryEntry> {
+        let query = query.trim();
+        let matcher: Box<dyn Fn(&Trade) -> bool> = if let Some(min) = query.strip_prefix('>') {
+            match min.trim().parse::<f64>() {
+                Ok(min) => Box::new(move |trade: &Trade| trade.sz >= min),
+                Err(_) => return Vec::new(),
+            }
+        } else if query.len() > 2 && query.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("0x")) {
+            let needle = query.to_lowercase();
+            Box::new(move |trade: &Trade| {
+                trade
+                    .users
+                    .iter()
+                    .any(|user| user.to_lowercase().contains(&needle))
+            })
+        } else {
+            let needle = query.to_string();
+            Box::new(move |trade: &Trade| {
+                format!("{:.2}", trade.px).contains(&needle)
+                    || format!("{:.6}", trade.sz).contains(&needle)
+            })
+        };
+
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| matcher(&entry.trade))
+            .take(limit)
+            .collect()
+    }
+}
+
+/// Order `entries` by `column`, largest or latest first when `descending`; ties keep their order
+pub fn sort_entries(entries: &mut [HistoryEntry], column: TableColumn, descending: bool) {
+    entries.sort_by(|a, b| {
+        let ordering = match column {
+            TableColumn::Index => a.index.cmp(&b.index),
+            TableColumn::Side => a.trade.side.cmp(&b.trade.side),
+            TableColumn::Price => a.trade.px.total_cmp(&b.trade.px),
+            TableColumn::Size => a.trade.sz.total_cmp(&b.trade.sz),
+            TableColumn::Value => a.trade.value().total_cmp(&b.trade.value()),
+            TableColumn::Time => a.trade.time.cmp(&b.trade.time),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(px: f64, sz: f64, users: [&str; 2]) -> Arc<Trade> {
+        Arc::new(Trade {
+            coin: "BTC".into(),
+            side: "B".to_string(),
+            px,
+            sz,
+            time: 0,
+            hash: String::new(),
+            tid: 0,
+            users: users.iter().map(|user| user.to_string()).collect(),
+        })
+    }
+
+    fn history() -> TradeHistory {
+        let mut history = TradeHistory::new(8);
+        history.push(0, trade(100.5, 0.25, ["0xAAAA", "0xbbbb"]));
+        history.push(1, trade(101.25, 3.0, ["0xcccc", "0xdddd"]));
+        history.push(2, trade(99.75, 1.5, ["0xbbbb", "0xeeee"]));
+        history
+    }
+
+    fn indices(entries: Vec<&HistoryEntry>) -> Vec<u64> {
+        entries.iter().map(|entry| entry.index).collect()
+    }
+
+    #[test]
+    fn search_by_minimum_size() {
+        let history = history();
+        assert_eq!(indices(history.search(">1.5", 10)), [2, 1]);
+        assert_eq!(indices(history.search("> 2", 10)), [1]);
+        assert!(history.search(">lots", 10).is_empty());
+    }
+
+    #[test]
+    fn search_by_address_ignores_case() {
+        let history = history();
+        assert_eq!(indices(history.search("0xBBBB", 10)), [2, 0]);
+        assert_eq!(indices(history.search("0Xaaaa", 10)), [0]);
+        assert_eq!(indices(history.search("0xbbbb", 1)), [2]);
+    }
+
+    #[test]
+    fn search_by_price_or_size_text() {
+        let history = history();
+        assert_eq!(indices(history.search("101.25", 10)), [1]);
+        assert_eq!(indices(history.search("0.250000", 10)), [0]);
+    }
+
+    #[test]
+    fn sorts_a_page_by_column() {
+        let history = history();
+        let mut page: Vec<HistoryEntry> = history.search("", 10).into_iter().cloned().collect();
+        sort_entries(&mut page, TableColumn::Price, false);
+        assert_eq!(page.iter().map(|e| e.index).collect::<Vec<_>>(), [2, 0, 1]);
+        sort_entries(&mut page, TableColumn::Size, true);
+        assert_eq!(page.iter().map(|e| e.index).collect::<Vec<_>>(), [1, 2, 0]);
+        sort_entries(&mut page, TableColumn::Index, false);
+        assert_eq!(page.iter().map(|e| e.index).collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn search_starting_with_a_multibyte_character_does_not_panic() {
+        let history = history();
+        assert!(history.search("€1", 10).is_empty());
+        assert!(history.search("é0", 10).is_empty());
+    }
+}
@@ -0,0 +1,436 @@
+/// file: src/input.rs
+/// description: Keyboard input handling for interactive runtime controls
+/// reference: https://docs.rs/crossterm/latest/crossterm/event/
+use crate::{filter::TradeFilter, formatter::TableColumn};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEventKind,
+};
+use std::io::Write;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Commands produced by key presses and consumed by the UI controller
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputCommand {
+    TogglePause,
+    ToggleColumn(TableColumn),
+    SetMinSize(Option<f64>),
+    SetMinNotional(Option<f64>),
+    CycleSide,
+    /// Replace the whole filter, e.g. from a config reload
+    SetFilter(TradeFilter),
+    SwitchCoin(String),
+    ScrollHistory(i32),
+    Search(String),
+    /// Sort the history page on screen by a column, flipping the order when already sorted by it
+    SortHistory(TableColumn),
+    /// A left click at a terminal cell, counted from 0 at the top left; the UI knows what it drew
+    /// there
+    Click {
+        column: u16,
+        row: u16,
+    },
+    Quit,
+}
+
+pub type InputSender = mpsc::Sender<InputCommand>;
+pub type InputReceiver = mpsc::Receiver<InputCommand>;
+
+// Key presses are rare compared to market data, a small buffer is plenty
+const INPUT_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PromptKind {
+    Coin,
+    MinSize,
+    MinNotional,
+    Search,
+}
+
+/// Restores the terminal to its original mode when dropped
+pub struct TerminalGuard {
+    #[cfg(unix)]
+    original: rustix::termios::Termios,
+    mouse: bool,
+}
+
+impl TerminalGuard {
+    /// Switch stdin to unbuffered, no-echo input while keeping output processing
+    /// and signal generation intact so regular output and Ctrl+C keep working
+    #[cfg(unix)]
+    pub fn enable() -> std::io::Result<Self> {
+        use rustix::termios::{LocalModes, OptionalActions, tcgetattr, tcsetattr};
+
+        let stdin = std::io::stdin();
+        let original = tcgetattr(&stdin)?;
+        let mut cbreak = original.clone();
+        cbreak
+            .local_modes
+            .remove(LocalModes::ICANON | LocalModes::ECHO);
+        tcsetattr(&stdin, OptionalActions::Now, &cbreak)?;
+
+        Ok(Self {
+            original,
+            mouse: false,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn enable() -> std::io::Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        Ok(Self { mouse: false })
+    }
+
+    /// Report mouse wheel and click events; this disables the terminal's own text selection
+    fn capture_mouse(&mut self) -> std::io::Result<()> {
+        crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
+        self.mouse = true;
+        Ok(())
+    }
+}
+
+impl Drop for TerminalGuard {
+    #[cfg(unix)]
+    fn drop(&mut self) {
+        use rustix::termios::{OptionalActions, tcsetattr};
+        if self.mouse {
+            let _ = crossterm::execute!(std::io::stdout(), DisableMouseCapture);
+        }
+        let _ = tcsetattr(std::io::stdin(), OptionalActions::Now, &self.original);
+    }
+
+    #[cfg(not(unix))]
+    fn drop(&mut self) {
+        if self.mouse {
+            let _ = crossterm::execute!(std::io::stdout(), DisableMouseCapture);
+        }
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+/// Channel shared by every input source (keyboard, signals) feeding the UI controller
+pub fn create_input_channel() -> (InputSender, InputReceiver) {
+    mpsc::channel(INPUT_CHANNEL_CAPACITY)
+}
+
+/// Start reading key presses on a dedicated thread and forward them as commands
+///
+/// With `mouse`, wheel scrolling pages through the trade history like `[` and `]`, and left
+/// clicks are passed on for the UI to resolve.
+pub fn spawn_keyboard_listener(mouse: bool, sender: InputSender) -> std::io::Result<TerminalGuard> {
+    let mut guard = TerminalGuard::enable()?;
+    if mouse {
+        guard.capture_mouse()?;
+    }
+
+    std::thread::Builder::new()
+        .name("keyboard-input".to_string())
+        .spawn(move || keyboard_loop(sender))?;
+
+    Ok(guard)
+}
+
+/// Toggle pause on every SIGUSR1, e.g. `kill -USR1 <pid>` from another shell
+#[cfg(unix)]
+pub fn spawn_pause_signal_listener(sender: InputSender) -> std::io::Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut user1 = signal(SignalKind::user_defined1())?;
+    tokio::spawn(async move {
+        while user1.recv().await.is_some() {
+            if sender.send(InputCommand::TogglePause).await.is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn keyboard_loop(sender: InputSender) {
+    let mut prompt: Option<(PromptKind, String)> = None;
+
+    loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => key,
+            Ok(Event::Mouse(mouse)) => {
+                let command = match mouse.kind {
+                    MouseEventKind::ScrollUp => InputCommand::ScrollHistory(-1),
+                    MouseEventKind::ScrollDown => InputCommand::ScrollHistory(1),
+                    MouseEventKind::Down(MouseButton::Left) => InputCommand::Click {
+                        column: mouse.column,
+                        row: mouse.row,
+                    },
+                    _ => continue,
+                };
+                if sender.blocking_send(command).is_err() {
+                    return;
+                }
+                continue;
+            }
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("Keyboard input stopped: {}", e);
+                return;
+            }
+        };
+
+        let command = match prompt.take() {
+            Some((kind, buffer)) => handle_prompt_key(key, kind, buffer, &mut prompt),
+            None => handle_key(key, &mut prompt),
+        };
+
+        if let Some(command) = command {
+            debug!("Keyboard command: {:?}", command);
+            let quit = command == InputCommand::Quit;
+            if sender.blocking_send(command).is_err() || quit {
+                return;
+            }
+        }
+    }
+}
+
+fn handle_key(key: KeyEvent, prompt: &mut Option<(PromptKind, String)>) -> Option<InputCommand> {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Some(InputCommand::Quit);
+    }
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => Some(InputCommand::Quit),
+        KeyCode::Char('p') | KeyCode::Char(' ') => Some(InputCommand::TogglePause),
+        KeyCode::Char(c @ '1'..='6') => {
+            TableColumn::from_index(c as usize - '1' as usize).map(InputCommand::ToggleColumn)
+        }
+        KeyCode::Char('c') => {
+            *prompt = Some((PromptKind::Coin, String::new()));
+            echo_prompt(PromptKind::Coin, "");
+            None
+        }
+        KeyCode::Char('m') => {
+            *prompt = Some((PromptKind::MinSize, String::new()));
+            echo_prompt(PromptKind::MinSize, "");
+            None
+        }
+        KeyCode::Char('n') => {
+            *prompt = Some((PromptKind::MinNotional, String::new()));
+            echo_prompt(PromptKind::MinNotional, "");
+            None
+        }
+        KeyCode::Char('s') => Some(InputCommand::CycleSide),
+        KeyCode::Char('/') => {
+            *prompt = Some((PromptKind::Search, String::new()));
+            echo_prompt(PromptKind::Search, "");
+            None
+        }
+        KeyCode::PageUp | KeyCode::Char('[') => Some(InputCommand::ScrollHistory(-1)),
+        KeyCode::PageDown | KeyCode::Char(']') => Some(InputCommand::ScrollHistory(1)),
+        _ => None,
+    }
+}
+
+fn handle_prompt_key(
+    key: KeyEvent,
+    kind: PromptKind,
+    mut buffer: String,
+    prompt: &mut Option<(PromptKind, String)>,
+) -> Option<InputCommand> {
+    match key.code {
+        KeyCode::Enter => {
+            eprintln!();
+            let input = buffer.trim();
+            match kind {
+                PromptKind::Coin if !input.is_empty() => {
+                    Some(InputCommand::SwitchCoin(input.to_uppercase()))
+                }
+                PromptKind::Coin => None,
+                PromptKind::MinSize if input.is_empty() => Some(InputCommand::SetMinSize(None)),
+                PromptKind::MinNotional if input.is_empty() => {
+                    Some(InputCommand::SetMinNotional(None))
+                }
+                PromptKind::Search if input.is_empty() => None,
+                PromptKind::Search => Some(InputCommand::Search(input.to_string())),
+                PromptKind::MinSize => match input.parse::<f64>() {
+                    Ok(size) if size >= 0.0 => Some(InputCommand::SetMinSize(Some(size))),
+                    _ => {
+                        warn!("Invalid minimum size: {}", input);
+                        None
+                    }
+                },
+                PromptKind::MinNotional => match input.parse::<f64>() {
+                    Ok(notional) if notional >= 0.0 => {
+                        Some(InputCommand::SetMinNotional(Some(notional)))
+                    }
+                    _ => {
+                        warn!("Invalid minimum notional: {}", input);
+                        None
+                    }
+                },
+            }
+        }
+        KeyCode::Esc => {
+            eprintln!();
+            None
+        }
+        KeyCode::Backspace => {
+            buffer.pop();
+            echo_prompt(kind, &buffer);
+            *prompt = Some((kind, buffer));
+            None
+        }
+        KeyCode::Char(c) => {
+            buffer.push(c);
+            echo_prompt(kind, &buffer);
+            *prompt = Some((kind, buffer));
+            None
+        }
+        _ => {
+            *prompt = Some((kind, buffer));
+            None
+        }
+    }
+}
+
+fn echo_prompt(kind: PromptKind, buffer: &str) {
+    let label = match kind {
+        PromptKind::Coin => "coin",
+        PromptKind::MinSize => "min size (empty clears)",
+        PromptKind::MinNotional => "min notional (empty clears)",
+        PromptKind::Search => "search (price, size, >min size, 0x address)",
+    };
+    let mut stderr = std::io::stderr().lock();
+    let _ = write!(stderr, "\r\x1b[2K> {}: {}", label, buffer);
+    let _ = stderr.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Feed keys the way `keyboard_loop` does and collect the commands they produce
+    fn press(codes: &[KeyCode]) -> (Vec<InputCommand>, Option<(PromptKind, String)>) {
+        let mut prompt = None;
+        let mut commands = Vec::new();
+        for code in codes {
+            let command = match prompt.take() {
+                Some((kind, buffer)) => handle_prompt_key(key(*code), kind, buffer, &mut prompt),
+                None => handle_key(key(*code), &mut prompt),
+            };
+            commands.extend(command);
+        }
+        (commands, prompt)
+    }
+
+    fn typed(text: &str) -> Vec<KeyCode> {
+        text.chars().map(KeyCode::Char).collect()
+    }
+
+    #[test]
+    fn single_keys_map_to_commands() {
+        let (commands, prompt) = press(&[
+            KeyCode::Char('p'),
+            KeyCode::Char('2'),
+            KeyCode::Char('s'),
+            KeyCode::PageUp,
+            KeyCode::Char(']'),
+            KeyCode::Char('x'),
+            KeyCode::Char('q'),
+        ]);
+        assert_eq!(
+            commands,
+            [
+                InputCommand::TogglePause,
+                InputCommand::ToggleColumn(TableColumn::from_index(1).unwrap()),
+                InputCommand::CycleSide,
+                InputCommand::ScrollHistory(-1),
+                InputCommand::ScrollHistory(1),
+                InputCommand::Quit,
+            ]
+        );
+        assert!(prompt.is_none());
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(handle_key(ctrl_c, &mut None), Some(InputCommand::Quit));
+    }
+
+    #[test]
+    fn enter_submits_the_prompt() {
+        let mut keys = vec![KeyCode::Char('c')];
+        keys.extend(typed(" eth "));
+        keys.push(KeyCode::Enter);
+        keys.push(KeyCode::Char('m'));
+        keys.extend(typed("1.5"));
+        keys.push(KeyCode::Enter);
+        keys.push(KeyCode::Char('n'));
+        keys.push(KeyCode::Enter);
+        keys.push(KeyCode::Char('/'));
+        keys.extend(typed(">10"));
+        keys.push(KeyCode::Enter);
+
+        let (commands, prompt) = press(&keys);
+        assert_eq!(
+            commands,
+            [
+                InputCommand::SwitchCoin("ETH".to_string()),
+                InputCommand::SetMinSize(Some(1.5)),
+                InputCommand::SetMinNotional(None),
+                InputCommand::Search(">10".to_string()),
+            ]
+        );
+        assert!(prompt.is_none());
+    }
+
+    #[test]
+    fn invalid_or_empty_input_submits_nothing() {
+        for keys in [
+            vec![KeyCode::Char('c'), KeyCode::Enter],
+            vec![KeyCode::Char('/'), KeyCode::Enter],
+            [vec![KeyCode::Char('m')], typed("-1"), vec![KeyCode::Enter]].concat(),
+            [vec![KeyCode::Char('n')], typed("abc"), vec![KeyCode::Enter]].concat(),
+        ] {
+            let (commands, prompt) = press(&keys);
+            assert!(commands.is_empty(), "{:?} gave {:?}", keys, commands);
+            assert!(prompt.is_none());
+        }
+    }
+
+    #[test]
+    fn esc_cancels_the_prompt_without_quitting() {
+        let mut keys = vec![KeyCode::Char('c')];
+        keys.extend(typed("sol"));
+        keys.push(KeyCode::Esc);
+        let (commands, prompt) = press(&keys);
+        assert!(commands.is_empty());
+        assert!(prompt.is_none());
+
+        // Outside a prompt, Esc quits
+        let (commands, _) = press(&keys[keys.len() - 1..]);
+        assert_eq!(commands, [InputCommand::Quit]);
+    }
+
+    #[test]
+    fn backspace_edits_the_buffer() {
+        let mut keys = vec![KeyCode::Char('c')];
+        keys.extend(typed("btcx"));
+        keys.push(KeyCode::Backspace);
+        let (_, prompt) = press(&keys);
+        assert_eq!(prompt, Some((PromptKind::Coin, "btc".to_string())));
+
+        // Backspace on an empty buffer keeps the prompt open
+        let (commands, prompt) = press(&[KeyCode::Char('m'), KeyCode::Backspace]);
+        assert!(commands.is_empty());
+        assert_eq!(prompt, Some((PromptKind::MinSize, String::new())));
+
+        let (commands, _) = press(&[
+            KeyCode::Char('c'),
+            KeyCode::Backspace,
+            KeyCode::Backspace,
+            KeyCode::Char('q'),
+            KeyCode::Enter,
+        ]);
+        assert_eq!(commands, [InputCommand::SwitchCoin("Q".to_string())]);
+    }
+}
@@ -0,0 +1,56 @@
+/// file: src/layout.rs
+/// description: Two-region terminal layout with a pinned status area above scrolling output
+/// reference: https://vt100.net/docs/vt510-rm/DECSTBM.html
+use std::io::Write;
+
+/// Pins `header_rows` lines at the top of the terminal and confines regular
+/// output to a scroll region below them. The scroll region is reset on drop.
+pub struct SplitLayout {
+    header_rows: u16,
+}
+
+impl SplitLayout {
+    pub fn enable(header_rows: u16) -> std::io::Result<Self> {
+        let (_, height) = crossterm::terminal::size()?;
+        let header_rows = header_rows.max(1);
+        if height <= header_rows + 2 {
+            return Err(std::io::Error::other("terminal too short for split layout"));
+        }
+
+        let mut stdout = std::io::stdout().lock();
+        // Clear, restrict scrolling to the rows below the header, park the cursor there
+        write!(
+            stdout,
+            "\x1b[2J\x1b[{};{}r\x1b[{};1H",
+            header_rows + 1,
+            height,
+            header_rows + 1
+        )?;
+        stdout.flush()?;
+
+        Ok(Self { header_rows })
+    }
+
+    pub fn header_rows(&self) -> u16 {
+        self.header_rows
+    }
+
+    /// Replace the pinned header line `row` (0-based) without disturbing the scroll region
+    pub fn update_header(&self, row: u16, line: &str) {
+        if row >= self.header_rows {
+            return;
+        }
+        let mut stdout = std::io::stdout().lock();
+        // Save cursor, jump to the header row, rewrite it, restore cursor
+        let _ = write!(stdout, "\x1b7\x1b[{};1H\x1b[2K{}\x1b8", row + 1, line);
+        let _ = stdout.flush();
+    }
+}
+
+impl Drop for SplitLayout {
+    fn drop(&mut self) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = write!(stdout, "\x1b[r");
+        let _ = stdout.flush();
+    }
+}
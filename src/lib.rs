@@ -1,27 +1,130 @@
 #![doc = include_str!("../docs/rustdoc.md")]
 
+/// Memory budget shared by queues and buffers.
+pub mod budget;
+/// Candle bar tracking and closed-bar emission.
+pub mod candles;
 /// Command-line argument definitions.
 pub mod cli;
 /// WebSocket client implementation and runtime loop.
 pub mod client;
 /// Shared client state and integrity counters.
 pub mod client_state;
+/// Local clock skew checks against exchange timestamps.
+pub mod clock;
+/// Normalization of known message format variations.
+pub mod compat;
+/// Shell completion scripts.
+pub mod completions;
 /// Runtime configuration model.
 pub mod config;
+/// Browser dashboard served over HTTP with Server-Sent Events.
+pub mod dashboard;
+/// Grafana dashboard and Prometheus alert rules for the exported metrics.
+pub mod dashboards;
 /// Error types used across the crate.
 pub mod error;
+/// Sentry reports for panics and fatal errors.
+#[cfg(feature = "sentry")]
+pub mod error_reporting;
 /// Event bus messages between client and UI.
 pub mod events;
+/// Trade filters by size, notional, and side.
+pub mod filter;
 /// Terminal output formatters.
 pub mod formatter;
+/// Silent streams and skipped candles within a connection.
+pub mod gaps;
+/// HTTP liveness and status endpoints.
+pub mod health;
+/// Scrolling order book depth heatmap.
+pub mod heatmap;
+/// Scrollable, searchable trade history for interactive mode.
+pub mod history;
+/// Keyboard input handling for interactive mode.
+pub mod input;
+/// Split-screen terminal layout.
+pub mod layout;
+/// Advisory lock files that stop two instances sharing an output path.
+pub mod lock;
+/// Log file output with rotation and retention.
+pub mod log_file;
 /// Metrics and health status structures.
 pub mod monitoring;
+/// Digit grouping, decimal marks, and decimal places for displayed numbers.
+pub mod number_format;
+/// Order lifecycle tracking for `orderUpdates`.
+pub mod orders;
+/// OpenTelemetry OTLP export of metrics and spans.
+#[cfg(feature = "otlp")]
+pub mod otlp;
+/// Trade stream destination for `--output`.
+pub mod output;
+/// Worker threads that parse large frames off the read loop.
+pub mod parse_pool;
+/// Prometheus Pushgateway metrics push for short-lived runs.
+pub mod pushgateway;
+/// Python bindings for the client and its events.
+#[cfg(feature = "python")]
+pub mod python;
+/// One-off info endpoint queries for the REST subcommands.
+pub mod queries;
+/// Recent trades and book states for consumers that attach mid-session.
+pub mod recent;
+/// Raw message recording with rotation.
+pub mod recorder;
+/// Config file reload on SIGHUP.
+#[cfg(unix)]
+pub mod reload;
+/// Playback of recorded raw messages.
+pub mod replay;
+/// HTTP client for the info endpoint.
+pub mod rest;
+/// Rolling trade statistics for the table footer.
+pub mod rolling;
+/// Scheduled collection windows.
+pub mod schedule;
+/// Strict schema checks for fields the message types do not know.
+pub mod schema;
+/// Windows service install, uninstall, and service-manager entry point.
+#[cfg(all(windows, feature = "windows-service"))]
+pub mod service;
+/// Price history sparkline rendering.
+pub mod sparkline;
+/// StatsD/DogStatsD metrics push over UDP.
+pub mod statsd;
+/// Session totals for the exit summary.
+pub mod summary;
+/// Interned coin symbols.
+pub mod symbol;
+/// Log output to the systemd journal or syslog.
+#[cfg(unix)]
+pub mod system_log;
+/// systemd readiness and watchdog notifications.
+pub mod systemd;
+/// Placeholder templates for custom trade output.
+pub mod template;
+/// Terminal capability detection.
+pub mod terminal;
+/// Color themes for terminal output.
+pub mod theme;
+/// Exchange timestamp bounds and skew-corrected delays.
+pub mod time;
+/// Timezone and format selection for displayed timestamps.
+pub mod time_display;
 /// Tracing/logging initialization.
 pub mod tracing_setup;
+/// TCP and TLS connection setup.
+pub mod transport;
 /// Hyperliquid protocol data models.
 pub mod types;
 /// UI controller and presentation loop.
 pub mod ui;
+/// Coin validation against the exchange universe.
+pub mod universe;
+
+/// Interactive config file setup.
+pub mod wizard;
 
 /// Primary crate error type.
 pub use error::HyperliquidError;
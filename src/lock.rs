@@ -0,0 +1,65 @@
+/// file: src/lock.rs
+/// description: Advisory lock files that keep two instances from writing the same output path
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// Exclusive lock on `<path>.lock`, held until dropped
+///
+/// The operating system releases the lock when the process exits, so a lock file left behind by
+/// a crash does not block the next start. The file itself is left in place; deleting it while
+/// another instance is opening it would let two instances lock different files.
+#[derive(Debug)]
+pub struct OutputLock {
+    _file: Option<File>,
+}
+
+impl OutputLock {
+    /// Take the lock for `path`, failing at once if another instance holds it
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        // Pipes and devices such as /dev/null are not captures, and their directory is not ours
+        if std::fs::metadata(path).is_ok_and(|meta| !meta.is_file()) {
+            return Ok(Self { _file: None });
+        }
+        let lock_path = lock_path(path);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&lock_path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = match holder.trim() {
+                    "" => String::new(),
+                    pid => format!(" (pid {})", pid),
+                };
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!(
+                        "another instance{} is already writing here; lock held on {}",
+                        holder,
+                        lock_path.display()
+                    ),
+                ));
+            }
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+
+        // The pid is only for the message above; the lock is what keeps instances apart
+        file.set_len(0)?;
+        file.rewind()?;
+        writeln!(file, "{}", std::process::id())?;
+        Ok(Self { _file: Some(file) })
+    }
+}
+
+/// `capture.jsonl` -> `capture.jsonl.lock`
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
@@ -0,0 +1,437 @@
+/// file: src/log_file.rs
+/// description: Log file writer with daily, hourly, or size-based rotation, retention, and gzip
+use crate::lock::OutputLock;
+use crate::recorder::timestamped_path;
+use chrono::{DateTime, DurationRound, NaiveDateTime, TimeDelta, Utc};
+use flate2::{Compression, write::GzEncoder};
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+
+/// When the log file is closed and a new one started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// At midnight UTC
+    #[default]
+    Daily,
+    /// At the top of every hour, UTC
+    Hourly,
+    /// Only when the size limit is reached, if one is set
+    Never,
+}
+
+impl LogRotation {
+    fn period(self) -> Option<TimeDelta> {
+        match self {
+            LogRotation::Daily => Some(TimeDelta::days(1)),
+            LogRotation::Hourly => Some(TimeDelta::hours(1)),
+            LogRotation::Never => None,
+        }
+    }
+
+    fn period_start(self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.period()
+            .and_then(|period| at.duration_trunc(period).ok())
+    }
+
+    fn period_end(self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        Some(self.period_start(at)? + self.period()?)
+    }
+}
+
+impl FromStr for LogRotation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "daily" => Ok(LogRotation::Daily),
+            "hourly" => Ok(LogRotation::Hourly),
+            "never" => Ok(LogRotation::Never),
+            _ => Err(format!(
+                "unknown rotation '{}', expected daily, hourly, or never",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFileSettings {
+    /// The active file; rotated files sit next to it as `name-YYYYMMDD-HHMMSS.ext`
+    pub path: PathBuf,
+    pub rotation: LogRotation,
+    /// Rotate before a write would take the file past this size
+    pub max_bytes: Option<u64>,
+    /// Rotated files to keep, oldest deleted first; 0 keeps all of them
+    pub keep: usize,
+    /// Rotated files closed longer ago than this are deleted
+    pub max_age: Option<TimeDelta>,
+    /// Gzip rotated files to `name-YYYYMMDD-HHMMSS.ext.gz`
+    pub compress: bool,
+}
+
+impl LogFileSettings {
+    fn needs_housekeeping(&self) -> bool {
+        self.compress || self.keep > 0 || self.max_age.is_some()
+    }
+}
+
+/// Appends to the active log file and rotates it by time and size
+///
+/// The active file keeps its name, so `tail -F` follows it across rotations.
+#[derive(Debug)]
+pub struct RollingLog {
+    settings: LogFileSettings,
+    file: File,
+    size: u64,
+    rotate_at: Option<DateTime<Utc>>,
+    /// Wakes the thread that compresses and prunes rotated files
+    housekeeping: Option<mpsc::Sender<()>>,
+    _lock: OutputLock,
+}
+
+impl RollingLog {
+    /// Open the active file for appending, rotating it first if it was last written in an
+    /// earlier period
+    pub fn open(settings: LogFileSettings) -> io::Result<Self> {
+        let lock = OutputLock::acquire(&settings.path)?;
+        let now = Utc::now();
+        if let Ok(meta) = fs::metadata(&settings.path)
+            && meta.len() > 0
+            && let Some(start) = settings.rotation.period_start(now)
+        {
+            let modified = DateTime::<Utc>::from(meta.modified()?);
+            if modified < start {
+                archive(&settings.path, modified)?;
+            }
+        }
+
+        let file = open_append(&settings.path)?;
+        let housekeeping = settings
+            .needs_housekeeping()
+            .then(|| spawn_housekeeper(settings.clone()))
+            .transpose()?;
+        let log = Self {
+            size: file.metadata()?.len(),
+            rotate_at: settings.rotation.period_end(now),
+            settings,
+            file,
+            housekeeping,
+            _lock: lock,
+        };
+        // Also picks up files an earlier run rotated but did not get to compress or prune
+        log.tidy();
+        Ok(log)
+    }
+
+    fn tidy(&self) {
+        if let Some(housekeeping) = &self.housekeeping {
+            let _ = housekeeping.send(());
+        }
+    }
+
+    fn rotation_due(&self, incoming: usize) -> bool {
+        let period_over = self.rotate_at.is_some_and(|at| Utc::now() >= at);
+        let full = self
+            .settings
+            .max_bytes
+            .is_some_and(|max| self.size > 0 && self.size + incoming as u64 > max);
+        period_over || full
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let now = Utc::now();
+        // Both triggers are reset before trying, so a failed rotation is retried after another
+        // period or another max_bytes instead of on every line
+        self.rotate_at = self.settings.rotation.period_end(now);
+        self.size = 0;
+
+        self.file.flush()?;
+        archive(&self.settings.path, now)?;
+        self.file = open_append(&self.settings.path)?;
+        self.tidy();
+        Ok(())
+    }
+}
+
+impl Write for RollingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.rotation_due(buf.len())
+            && let Err(e) = self.rotate()
+        {
+            // The subscriber is what would report this, so it goes to stderr
+            eprintln!(
+                "Log rotation failed, continuing {}: {}",
+                self.settings.path.display(),
+                e
+            );
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Rename the active file, stamped with when it was closed
+fn archive(path: &Path, closed_at: DateTime<Utc>) -> io::Result<()> {
+    let mut target = timestamped_path(path, closed_at);
+    // Size rotation can close more than one file within a second
+    let mut n = 1;
+    while target.exists() || with_suffix(&target, ".gz").exists() {
+        let stamped = timestamped_path(path, closed_at);
+        let stem = stamped.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match stamped.extension() {
+            Some(ext) => format!("{}-{}.{}", stem, n, ext.to_string_lossy()),
+            None => format!("{}-{}", stem, n),
+        };
+        target = stamped.with_file_name(name);
+        n += 1;
+    }
+    fs::rename(path, target)
+}
+
+/// Compression can take seconds for a large file, so it runs off the logging path
+///
+/// Rotations that arrive while a pass is running are folded into the next one.
+fn spawn_housekeeper(settings: LogFileSettings) -> io::Result<mpsc::Sender<()>> {
+    let (sender, receiver) = mpsc::channel::<()>();
+    std::thread::Builder::new()
+        .name("log-housekeeping".to_string())
+        .spawn(move || {
+            while receiver.recv().is_ok() {
+                while receiver.try_recv().is_ok() {}
+                housekeeping(&settings);
+            }
+        })?;
+    Ok(sender)
+}
+
+/// Compress rotated files if enabled, then delete the ones beyond `keep` or older than `max_age`
+fn housekeeping(settings: &LogFileSettings) {
+    let dir = match settings.path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    // Only this thread compresses, and the output lock keeps other instances out, so any
+    // partial file is left from an interrupted run
+    for path in &paths {
+        if let Some(source) = path
+            .to_str()
+            .and_then(|path| path.strip_suffix(".gz.partial"))
+            && closed_at(&settings.path, Path::new(source)).is_some()
+        {
+            let _ = fs::remove_file(path);
+        }
+    }
+    let mut rotated: Vec<(DateTime<Utc>, PathBuf)> = paths
+        .into_iter()
+        .filter_map(|path| Some((closed_at(&settings.path, &path)?, path)))
+        .collect();
+
+    if settings.compress {
+        for (_, path) in rotated.iter_mut() {
+            if path.extension().is_some_and(|ext| ext == "gz") {
+                continue;
+            }
+            match compress(path) {
+                Ok(compressed) => *path = compressed,
+                // The subscriber may be what is writing this file, so it goes to stderr
+                Err(e) => eprintln!("Cannot compress log file {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    rotated.sort();
+    let excess = match settings.keep {
+        0 => 0,
+        keep => rotated.len().saturating_sub(keep),
+    };
+    let cutoff = settings.max_age.map(|age| Utc::now() - age);
+    let expired = rotated.iter().enumerate().filter(|(index, (closed, _))| {
+        *index < excess || cutoff.is_some_and(|cutoff| *closed < cutoff)
+    });
+    for (_, (_, path)) in expired {
+        if let Err(e) = fs::remove_file(path) {
+            eprintln!("Cannot remove old log file {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Gzip `path` to `path.gz` and remove the original
+///
+/// Output goes to a `.partial` file first, so an interrupted run leaves the original in place
+/// to be compressed again next time.
+fn compress(path: &Path) -> io::Result<PathBuf> {
+    let partial = with_suffix(path, ".gz.partial");
+    let mut encoder = GzEncoder::new(File::create(&partial)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    let compressed = with_suffix(path, ".gz");
+    fs::rename(&partial, &compressed)?;
+    fs::remove_file(path)?;
+    Ok(compressed)
+}
+
+/// `app.log` -> `app.log.gz`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// When a rotated file of `active` was closed, or `None` if `candidate` is not one
+///
+/// `app-20240101-120000.log`, `app-20240101-120000-1.log`, and either with `.gz` belong to
+/// `app.log`.
+fn closed_at(active: &Path, candidate: &Path) -> Option<DateTime<Utc>> {
+    let stem = active.file_stem()?.to_string_lossy();
+    let name = candidate.file_name()?.to_string_lossy();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    let name = match active.extension() {
+        Some(ext) => name.strip_suffix(&format!(".{}", ext.to_string_lossy()))?,
+        None => name,
+    };
+    let stamp = name.strip_prefix(&format!("{}-", stem))?;
+    let bytes = stamp.as_bytes();
+    if bytes.len() < 15 || (bytes.len() > 15 && bytes[15] != b'-') {
+        return None;
+    }
+    NaiveDateTime::parse_from_str(stamp.get(..15)?, "%Y%m%d-%H%M%S")
+        .ok()
+        .map(|closed| closed.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 15, hour, minute, 0).unwrap()
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn periods_end_on_utc_boundaries() {
+        assert_eq!(LogRotation::Hourly.period_end(at(12, 34)), Some(at(13, 0)));
+        assert_eq!(
+            LogRotation::Daily.period_end(at(12, 34)),
+            Some(Utc.with_ymd_and_hms(2024, 1, 16, 0, 0, 0).unwrap())
+        );
+        assert_eq!(LogRotation::Daily.period_start(at(0, 0)), Some(at(0, 0)));
+        assert_eq!(LogRotation::Never.period_end(at(12, 34)), None);
+    }
+
+    #[test]
+    fn archives_are_stamped_and_numbered_within_a_second() {
+        let dir = tempfile::tempdir().unwrap();
+        let active = dir.path().join("app.log");
+        fs::write(dir.path().join("app-20240115-123400-2.log.gz"), "").unwrap();
+        for _ in 0..3 {
+            fs::write(&active, "line\n").unwrap();
+            archive(&active, at(12, 34)).unwrap();
+        }
+        assert_eq!(
+            names(dir.path()),
+            [
+                "app-20240115-123400-1.log",
+                "app-20240115-123400-2.log.gz",
+                "app-20240115-123400-3.log",
+                "app-20240115-123400.log",
+            ]
+        );
+    }
+
+    #[test]
+    fn recognizes_only_rotated_files_of_the_active_one() {
+        let active = Path::new("logs/app.log");
+        for name in [
+            "app-20240115-123400.log",
+            "app-20240115-123400-1.log",
+            "app-20240115-123400.log.gz",
+            "app-20240115-123400-12.log.gz",
+        ] {
+            assert_eq!(
+                closed_at(active, Path::new(name)),
+                Some(at(12, 34)),
+                "{}",
+                name
+            );
+        }
+        for name in [
+            "app.log",
+            "app-20240115.log",
+            "app-20240115-123400x.log",
+            "app-20240115-123400.txt",
+            "other-20240115-123400.log",
+            "app-20240115-123400.log.gz.partial",
+        ] {
+            assert_eq!(closed_at(active, Path::new(name)), None, "{}", name);
+        }
+        assert_eq!(
+            closed_at(Path::new("app"), Path::new("app-20240115-123400-1")),
+            Some(at(12, 34))
+        );
+    }
+
+    #[test]
+    fn housekeeping_compresses_and_keeps_the_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = LogFileSettings {
+            path: dir.path().join("app.log"),
+            rotation: LogRotation::Never,
+            max_bytes: None,
+            keep: 2,
+            max_age: None,
+            compress: true,
+        };
+        for name in [
+            "app.log",
+            "app-20240115-100000.log",
+            "app-20240115-110000.log.gz",
+            "app-20240115-120000.log",
+            "app-20240115-120000.log.gz.partial",
+            "other.log",
+        ] {
+            fs::write(dir.path().join(name), "line\n").unwrap();
+        }
+
+        housekeeping(&settings);
+        assert_eq!(
+            names(dir.path()),
+            [
+                "app-20240115-110000.log.gz",
+                "app-20240115-120000.log.gz",
+                "app.log",
+                "other.log",
+            ]
+        );
+    }
+}
@@ -2,91 +2,808 @@
 /// description: Application entry point and startup configuration for the Hyperliquid WebSocket client
 /// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/websocket
 use anyhow::Result;
-use clap::Parser;
+use chrono::{DateTime, Local, Utc};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, parser::ValueSource};
+#[cfg(all(windows, feature = "windows-service"))]
+use rs_hyperliquid::cli::{ServiceAction, ServiceArgs};
+#[cfg(feature = "sentry")]
+use rs_hyperliquid::error_reporting::{finish_error_reporting, init_error_reporting};
+#[cfg(feature = "otlp")]
+use rs_hyperliquid::otlp::OtlpExport;
 use rs_hyperliquid::{
-    cli::Args,
+    budget::MemoryBudget,
+    cli::{Args, Command, FileArgs, MetricsArgs, RecordArgs, ReplayArgs, StreamArgs},
     client::HyperliquidWebSocketClient,
-    client_state::ClientState,
-    config::Config,
-    events::create_event_channel,
-    formatter::OutputFormat,
-    monitoring::setup_metrics,
-    tracing_setup::setup_tracing,
+    client_state::{ClientState, spawn_stats_logger, spawn_stats_reporter},
+    completions::print_completions,
+    config::{Channel, Config, ConfigFile, SubscriptionSink},
+    dashboard::serve_dashboard,
+    dashboards::print_metrics_config,
+    error::{HyperliquidError, exit_code},
+    events::{create_broadcast_bus, create_command_channel, create_event_channel},
+    formatter::{OutputFormat, QuietLevel},
+    health::serve_health,
+    input::{create_input_channel, spawn_keyboard_listener},
+    log_file::LogFileSettings,
+    monitoring::{
+        MetricsBackend, install_metrics, setup_metrics, spawn_runtime_metrics,
+        spawn_staleness_metrics,
+    },
+    number_format::{NumberFormat, NumberLocale, Precision},
+    output::TradeOutput,
+    parse_pool::ParsePool,
+    pushgateway::setup_pushgateway,
+    queries::{run_info, run_orders, run_snapshot},
+    recent,
+    recorder::{Recorder, RecordingConfig},
+    replay::{ReplayConfig, ReplaySpeed, replay_recording},
+    rest::InfoClient,
+    schedule::{CollectionWindow, sleep_until},
+    statsd::setup_statsd,
+    systemd::{SystemdNotifier, spawn_watchdog},
+    template::OutputTemplate,
+    terminal::{AlternateScreen, TerminalCapabilities},
+    theme::Theme,
+    time_display::TimeDisplay,
+    tracing_setup::{LogTarget, SampleRate, setup_tracing},
     ui::{UIController, UIOptions},
+    universe::{SpotMarket, is_perp_name, resolve_spot, validate_coin},
+    wizard::run_init,
 };
+#[cfg(unix)]
+use rs_hyperliquid::{
+    input::spawn_pause_signal_listener, reload::spawn_config_reloader,
+    tracing_setup::spawn_log_level_cycler,
+};
+use std::io::IsTerminal;
+use std::process::ExitCode;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tracing::{error, info};
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+
+/// Stop requests from outside the terminal, such as the Windows service manager
+static STOP_REQUESTED: Notify = Notify::const_new();
+
+/// Fine enough to see a burst that starves the read loop, cheap enough to leave on
+const RUNTIME_METRICS_INTERVAL: Duration = Duration::from_secs(1);
+/// Per-subscription age resolution for alert rules
+const STALENESS_INTERVAL: Duration = Duration::from_secs(1);
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+async fn main() -> ExitCode {
+    let result = dispatch().await;
+    #[cfg(feature = "sentry")]
+    finish_error_reporting(result.as_ref().err());
+    match result {
+        Ok(()) => ExitCode::from(exit_code::SUCCESS),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            // Distinct codes let scripts and systemd tell a typo from a network outage
+            ExitCode::from(
+                e.downcast_ref::<HyperliquidError>()
+                    .map_or(exit_code::FAILURE, HyperliquidError::exit_code),
+            )
+        }
+    }
+}
+
+/// Parse arguments and run the selected command
+async fn dispatch() -> Result<()> {
+    // Keep the raw matches so config file values only fill in flags left at their defaults
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let stream_matches = matches.subcommand().map_or(&matches, |(_, sub)| sub);
+
+    match args.into_command() {
+        Command::Stream(args) => run(args, None, stream_matches).await,
+        Command::Record(RecordArgs { path, mut stream }) => {
+            stream.record = Some(path);
+            run(stream, None, stream_matches).await
+        }
+        Command::Replay(ReplayArgs {
+            path,
+            speed,
+            looped,
+            seek,
+            stream,
+        }) => {
+            let replay = ReplayConfig {
+                path,
+                speed,
+                looped,
+                seek,
+                filter: stream.trade_filter(),
+                compat: stream.api_compat,
+                candles: stream.candles.unwrap_or_default(),
+            };
+            run(stream, Some(replay), stream_matches).await
+        }
+        Command::Export(FileArgs { path, stream }) => {
+            let replay = ReplayConfig {
+                path,
+                speed: ReplaySpeed::Max,
+                looped: false,
+                seek: None,
+                filter: stream.trade_filter(),
+                compat: stream.api_compat,
+                candles: stream.candles.unwrap_or_default(),
+            };
+            run(stream, Some(replay), stream_matches).await
+        }
+        Command::Completions(args) => print_completions(args, Args::command()).await,
+        Command::Metrics(MetricsArgs { action }) => print_metrics_config(action),
+        Command::Init(args) => run_init(args),
+        #[cfg(all(windows, feature = "windows-service"))]
+        Command::Service(ServiceArgs { action }) => run_service_action(action, stream_matches),
+        Command::Snapshot(args) => run_snapshot(args).await,
+        Command::Orders(args) => run_orders(args).await,
+        Command::Info(args) => run_info(args).await,
+    }
+}
+
+/// Stream live data, or play a recording back through the same output when `replay` is set
+async fn run(
+    mut args: StreamArgs,
+    replay: Option<ReplayConfig>,
+    matches: &ArgMatches,
+) -> Result<()> {
+    let config_file = ConfigFile::locate(args.config.as_deref())
+        .map(|path| ConfigFile::load(&path).map(|file| (path, file)))
+        .transpose()?;
+    if let Some((_, file)) = &config_file {
+        file.apply(&mut args, matches);
+    }
+    let trade_filter = config_file
+        .as_ref()
+        .map(|(_, file)| file.trade_filter())
+        .transpose()?
+        .unwrap_or_default();
 
     // Setup tracing/logging
-    setup_tracing(&args.log_level, args.json_logs)?;
+    let log_file = args.log_file.clone().map(|path| LogFileSettings {
+        path,
+        rotation: args.log_rotate,
+        max_bytes: (args.log_max_mb > 0).then(|| args.log_max_mb.saturating_mul(1024 * 1024)),
+        keep: args.log_keep,
+        // An age too large to represent is no limit at all
+        max_age: i64::try_from(args.log_max_age)
+            .ok()
+            .filter(|days| *days > 0)
+            .and_then(chrono::TimeDelta::try_days),
+        compress: args.log_compress,
+    });
+    // OTLP export starts before logging so spans from startup reach the collector too
+    #[cfg(feature = "otlp")]
+    let otlp = args.otlp.then(OtlpExport::from_env).transpose()?;
+    #[cfg(feature = "otlp")]
+    let export_layer = otlp.as_ref().map(OtlpExport::tracing_layer);
+    #[cfg(not(feature = "otlp"))]
+    let export_layer = None;
+    let log_target = LogTarget::resolve(args.log_target, log_file.is_some());
+    let ignored_log_file = log_target != LogTarget::File && log_file.is_some();
+    let log_handle = setup_tracing(
+        &args.log_level,
+        args.json_logs,
+        log_target,
+        log_file,
+        export_layer,
+    )?;
+    if ignored_log_file {
+        warn!("--log-file is ignored when logging to the journal or syslog");
+    }
 
     info!(
         "Starting Hyperliquid WebSocket Client v{}",
         env!("CARGO_PKG_VERSION")
     );
 
+    if let Some((path, _)) = &config_file {
+        info!("Loaded config file {}", path.display());
+    }
+
     // Load configuration
-    let config = Config::from_args(&args)?;
+    let mut config = Config::from_args(&args)?;
+    for warning in config.websocket.endpoint_warnings() {
+        warn!("{}", warning);
+    }
+    // [[subscriptions]] blocks stand in for --coin and --subscribe unless either was given
+    let routes = match &config_file {
+        Some((_, file)) if replay.is_none() => file.routes()?,
+        _ => Vec::new(),
+    };
+    if !routes.is_empty() {
+        let overridden = ["coin", "subscribe"].into_iter().any(|id| {
+            matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        });
+        if overridden {
+            warn!("[[subscriptions]] ignored: --coin or --subscribe given");
+        } else {
+            if args.record.is_none()
+                && routes
+                    .iter()
+                    .any(|route| route.sink == SubscriptionSink::Record)
+            {
+                return Err(HyperliquidError::ConfigError(
+                    "a [[subscriptions]] block has sink = \"record\" but nothing is recorded; set --record or [output] record".to_string(),
+                )
+                .into());
+            }
+            info!("Subscribing from {} [[subscriptions]] blocks", routes.len());
+            config = config.with_routes(routes);
+        }
+    }
+    let window = CollectionWindow::resolve(args.start_at, args.stop_at, Utc::now())?;
+    let window = if replay.is_some() && window.is_set() {
+        warn!("--start-at and --stop-at ignored: they schedule live collection");
+        CollectionWindow::default()
+    } else {
+        window
+    };
+    if window.is_set() {
+        info!("Collection window: {}", window);
+    }
+
+    // Spot pairs stream under an `@index` id; keep the pair name for display
+    if args.spot && !config.routes.is_empty() {
+        warn!("--spot ignored with [[subscriptions]] blocks; use @index coins in the blocks");
+    }
+    let spot = if args.spot
+        && replay.is_none()
+        && config.routes.is_empty()
+        && config.subscription.has_coin_channel()
+    {
+        let market = resolve_spot_coin(&config).await?;
+        info!("Streaming spot pair {} as {}", market.name, market.coin);
+        config.subscription.coin = market.coin.clone();
+        Some(market)
+    } else {
+        None
+    };
     let config = Arc::new(config);
 
-    // Setup metrics server if enabled
+    // A typo'd coin subscribes fine and then never receives anything, so catch it up front
+    if replay.is_none() && !args.no_validate && config.subscription.has_coin_channel() {
+        validate_startup_coins(&config).await?;
+    }
+
+    // Setup metrics backends if enabled
+    let mut metrics_recorders = Vec::new();
+    let mut pushgateway = None;
     if config.metrics.enabled {
-        setup_metrics(config.metrics.port).await?;
-        info!("Metrics server started on port {}", config.metrics.port);
+        match config.metrics.backend {
+            MetricsBackend::Prometheus => {
+                metrics_recorders.push(
+                    setup_metrics(
+                        config.metrics.bind,
+                        config.metrics.port,
+                        config.metrics.auth.clone(),
+                    )
+                    .await?,
+                );
+            }
+            MetricsBackend::Statsd => {
+                metrics_recorders.push(setup_statsd(&config.metrics.statsd_addr).await?);
+            }
+            MetricsBackend::Pushgateway => {
+                let (recorder, push) = setup_pushgateway(
+                    &config.metrics.pushgateway_url,
+                    &config.metrics.pushgateway_job,
+                )?;
+                metrics_recorders.push(recorder);
+                pushgateway = Some(push);
+            }
+        }
+    }
+    #[cfg(feature = "otlp")]
+    if let Some(otlp) = &otlp {
+        metrics_recorders.push(otlp.metrics_recorder());
+        info!("Exporting metrics and spans over OTLP");
+    }
+    let have_metrics = !metrics_recorders.is_empty();
+    install_metrics(metrics_recorders)?;
+    if config.metrics.runtime {
+        if have_metrics {
+            spawn_runtime_metrics(RUNTIME_METRICS_INTERVAL);
+        } else {
+            warn!("--runtime-metrics has no effect without a metrics backend");
+        }
+    }
+
+    // Validate the output template before connecting; the other formats have fixed columns
+    let template = args
+        .template
+        .as_deref()
+        .map(OutputTemplate::parse)
+        .transpose()?;
+    if template.is_some()
+        && !args.price_only
+        && !matches!(
+            OutputFormat::from(args.format.as_str()),
+            OutputFormat::Minimal
+        )
+    {
+        return Err(HyperliquidError::ConfigError(
+            "--template only shapes --format minimal and --price-only output".to_string(),
+        )
+        .into());
     }
+    let time_display = TimeDisplay::new(&args.tz, args.time_format.clone())?;
+    let number_format = NumberFormat {
+        locale: NumberLocale::parse(&args.number_locale)?,
+        precision: Precision::parse(&args.price_decimals, &args.size_decimals)?,
+    };
+    let theme = Theme::resolve(&args.theme)?;
 
     // Create event channel for communication between client and UI
     let (event_sender, event_receiver) = create_event_channel();
 
     // Create client state
     let client_state = Arc::new(tokio::sync::Mutex::new(ClientState::new()));
+    #[cfg(feature = "sentry")]
+    if let Some(dsn) = &args.sentry_dsn {
+        init_error_reporting(dsn, &config, client_state.clone())?;
+    }
+
+    // Detect whether stdout can take colors, box drawing, and in-place redraws
+    let terminal = TerminalCapabilities::detect(args.no_color, args.ascii);
+
+    // Pin BBO above the trade tape when both stream into one terminal
+    let split_layout = match args.layout.as_str() {
+        "split" => true,
+        "scroll" => false,
+        "auto" => {
+            terminal.is_tty
+                && config.subscription.has_channel(Channel::Trades)
+                && config.subscription.has_channel(Channel::Bbo)
+                && !args.ticker
+        }
+        other => {
+            return Err(HyperliquidError::ConfigError(format!(
+                "unknown layout '{}', expected auto, split, or scroll",
+                other
+            ))
+            .into());
+        }
+    };
+
+    let status_bar = args.status_bar && terminal.is_tty;
+
+    // Full-screen views draw on the alternate screen so the user's scrollback survives
+    let full_screen = args.book_view || args.heatmap || split_layout || status_bar;
+    let alternate_screen = if full_screen && terminal.is_tty && !args.no_alt_screen {
+        Some(AlternateScreen::enter()?)
+    } else {
+        None
+    };
+
+    // One estimate across queues and buffers, so a burst trims them instead of growing the process
+    let budget = (args.memory_budget_mb > 0).then(|| {
+        Arc::new(MemoryBudget::new(
+            args.memory_budget_mb.saturating_mul(1_048_576),
+        ))
+    });
 
     // Create UI controller
     let mut ui_controller = UIController::new(
         event_receiver,
         OutputFormat::from(args.format.as_str()),
         UIOptions {
-            colored: !args.no_color,
+            colored: terminal.color,
+            unicode: terminal.unicode,
+            in_place: terminal.is_tty,
             verbose: args.verbose_trades,
-            quiet: args.quiet,
+            quiet: QuietLevel::from_flags(args.quiet, args.silent),
             price_only: args.price_only,
             csv_export: args.csv_export,
             max_trades: args.max_trades,
+            book_view: args.book_view,
+            book_depth: args.book_depth,
+            ticker: args.ticker,
+            coin: config.subscription.coin.clone(),
+            heatmap: args.heatmap,
+            heatmap_width: args.heatmap_width,
+            heatmap_rows: args.heatmap_rows,
+            split_layout,
+            status_bar,
+            sparkline: args.sparkline,
+            sparkline_len: args.sparkline_len,
+            history_size: args.history_size,
+            template,
+            time_display,
+            number_format,
+            theme,
+            jq_friendly: args.jq_friendly,
+            stats_every: args.stats_every,
+            highlight_notional: args.highlight_notional,
+            highlight_marker: args.highlight_marker.clone(),
+            trace_sample_rate: args.trace_sample_rate,
         },
-    );
+    )
+    .with_client_state(client_state.clone())
+    .with_filter(trade_filter);
+    if let Some(path) = &args.output {
+        ui_controller = ui_controller.with_output(TradeOutput::create(path)?);
+    }
+    if let Some(budget) = &budget {
+        ui_controller = ui_controller.with_memory_budget(budget.clone());
+    }
+
+    // Start the raw recorder before connecting so a bad path fails fast
+    if replay.is_some() && args.record.is_some() {
+        warn!("--record ignored: nothing is received from the network during replay");
+    }
+    let recorder = args
+        .record
+        .clone()
+        .filter(|_| replay.is_none())
+        .map(|path| {
+            Recorder::spawn(RecordingConfig {
+                path: path.clone(),
+                rotate_every: (args.record_rotate_secs > 0)
+                    .then(|| Duration::from_secs(args.record_rotate_secs)),
+                log_progress: !status_bar,
+                budget: budget.clone(),
+            })
+            .map_err(|e| {
+                HyperliquidError::SinkError(format!("cannot record to {}: {}", path.display(), e))
+            })
+        })
+        .transpose()?;
+
+    // Listeners that attach mid-session start from recent trades and books, not an empty view
+    let recent = recent::shared(args.recent_trades, budget.clone());
+    if args.serve_http.is_some() || args.health_port.is_some() {
+        ui_controller = ui_controller.with_recent(recent.clone());
+    }
+
+    // Browser dashboard fed from the broadcast bus
+    if let Some(port) = args.serve_http {
+        let bus = create_broadcast_bus();
+        serve_dashboard(port, bus.clone(), recent.clone()).await?;
+        ui_controller = ui_controller.with_broadcast(bus);
+    }
+
+    // A stream with no message for a whole read timeout is about to be reconnected anyway
+    if let Some(port) = args.health_port {
+        serve_health(port, client_state.clone(), config.websocket.timeout, recent).await?;
+    }
+
+    // Feed the status bar and dashboard from sampled client state
+    if args.status_bar && !terminal.is_tty {
+        warn!("--status-bar ignored: stdout is not a terminal");
+    }
+    if replay.is_none() && (status_bar || args.serve_http.is_some()) {
+        spawn_stats_reporter(
+            client_state.clone(),
+            recorder.as_ref().map(Recorder::progress),
+            event_sender.clone(),
+            Duration::from_secs(1),
+        );
+    }
 
-    // Create WebSocket client
-    let mut client = HyperliquidWebSocketClient::new(config.clone(), event_sender, client_state);
+    if replay.is_none() && args.log_stats > 0 {
+        spawn_stats_logger(client_state.clone(), Duration::from_secs(args.log_stats));
+    }
+    if replay.is_none() && have_metrics {
+        spawn_staleness_metrics(client_state.clone(), STALENESS_INTERVAL);
+    }
+
+    // Under systemd with Type=notify, report readiness and keep the watchdog fed
+    let systemd = SystemdNotifier::from_env().filter(|_| replay.is_none());
+    if let Some(notifier) = &systemd
+        && let Some(interval) = SystemdNotifier::watchdog_interval()
+    {
+        info!(
+            "systemd watchdog every {:.1}s",
+            interval.as_secs_f64() / 2.0
+        );
+        spawn_watchdog(notifier.clone(), client_state.clone(), interval);
+    }
+
+    // Create WebSocket client; a replay feeds the event channel from the file instead
+    let (mut client, replay_sender) = match replay {
+        Some(_) => (None, Some(event_sender)),
+        None => (
+            Some(
+                HyperliquidWebSocketClient::new(config.clone(), event_sender, client_state)
+                    .with_trade_filter(args.trade_filter())
+                    .with_collect_from(window.start),
+            ),
+            None,
+        ),
+    };
+    if let Some(recorder) = &recorder {
+        client = client.map(|client| client.with_recorder(recorder.sender(), recorder.progress()));
+    }
+    if let Some(market) = spot {
+        client = client.map(|client| client.with_coin_label(market.coin, market.name));
+    }
+    if args.max_clock_skew > 0 {
+        let threshold = Duration::from_millis(args.max_clock_skew);
+        client = client.map(|client| client.with_clock_skew_check(threshold));
+    }
+    if args.trace_sample_rate != SampleRate::default() {
+        client = client.map(|client| client.with_trace_sampling(args.trace_sample_rate));
+    }
+    if args.strict_schema {
+        client = client.map(HyperliquidWebSocketClient::with_strict_schema);
+    }
+    client = client.map(|client| client.with_api_compat(args.api_compat));
+    if let Some(mode) = args.candles {
+        client = client.map(|client| client.with_candle_mode(mode));
+    }
+    if let Some(budget) = budget {
+        client = client.map(|client| client.with_memory_budget(budget));
+    }
+    if args.parse_workers > 0 && client.is_some() {
+        let pool = ParsePool::new(args.parse_workers, args.api_compat)?;
+        client = client.map(|client| client.with_parse_pool(pool));
+    }
+    if args.max_silence > 0 {
+        let threshold = Duration::from_secs(args.max_silence);
+        client = client.map(|client| client.with_max_silence(threshold));
+    }
+    if let Some(notifier) = &systemd {
+        client = client.map(|client| client.with_systemd(notifier.clone()));
+    }
+
+    // SIGUSR1 toggles pause in every mode; keys feed the same channel when interactive
+    let (input_sender, input_receiver) = create_input_channel();
+    #[cfg(unix)]
+    spawn_pause_signal_listener(input_sender.clone())?;
+
+    // SIGUSR2 steps the log level up; SIGHUP re-reads the config file for log level and
+    // filter changes
+    #[cfg(unix)]
+    spawn_log_level_cycler(log_handle.clone())?;
+    #[cfg(unix)]
+    if let Some((path, file)) = config_file {
+        spawn_config_reloader(path, file, log_handle, input_sender.clone())?;
+    }
+    #[cfg(not(unix))]
+    drop(log_handle);
+
+    // Enable keyboard controls; the guard restores the terminal mode on exit
+    let mut _terminal_guard = None;
+    if args.interactive && std::io::stdin().is_terminal() {
+        _terminal_guard = Some(spawn_keyboard_listener(args.mouse, input_sender)?);
+        let (command_sender, command_receiver) = create_command_channel();
+        ui_controller = ui_controller.with_controls(input_receiver, command_sender);
+        if args.mouse {
+            ui_controller = ui_controller.with_mouse();
+        }
+        client = client.map(|client| client.with_command_receiver(command_receiver));
+    } else {
+        if args.interactive {
+            warn!("--interactive ignored: stdin is not a terminal");
+        } else if args.mouse {
+            warn!("--mouse ignored: it requires --interactive");
+        }
+        ui_controller = ui_controller.with_input(input_receiver);
+    }
 
     // Setup graceful shutdown
     let shutdown_signal = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
+        tokio::select! {
+            result = signal::ctrl_c() => {
+                // A service has no console to send Ctrl+C from; wait for a stop request instead
+                if let Err(e) = result {
+                    warn!("Ctrl+C handler unavailable: {}", e);
+                    STOP_REQUESTED.notified().await;
+                }
+            }
+            _ = STOP_REQUESTED.notified() => {}
+        }
         info!("Shutdown signal received");
     };
 
-    // Run client and UI concurrently
-    tokio::select! {
-        result = client.run() => {
-            if let Err(e) = result {
-                error!("WebSocket client error: {}", e);
-                return Err(e);
+    // Run the data source and UI concurrently; the UI drains the channel after a replay ends
+    let source = async {
+        match client.as_mut() {
+            Some(client) => {
+                if let Some(connect_at) = window.connect_at() {
+                    info!(
+                        "Waiting until {} to connect",
+                        connect_at.with_timezone(&Local)
+                    );
+                    sleep_until(connect_at).await;
+                }
+                client
+                    .run()
+                    .await
+                    .inspect_err(|e| error!("WebSocket client error: {}", e))
+            }
+            None => {
+                if let (Some(replay), Some(sender)) = (replay, replay_sender) {
+                    replay_recording(replay, sender).await?;
+                }
+                // Dropping the sender lets the UI finish once it has drained every event
+                std::future::pending().await
             }
         }
+    };
+    let result = tokio::select! {
+        result = source => result,
         _ = ui_controller.run() => {
             info!("UI controller stopped");
+            Ok(())
         }
         _ = shutdown_signal => {
             info!("Graceful shutdown initiated");
+            Ok(())
+        }
+        _ = run_deadline(args.duration) => {
+            info!("Configured duration elapsed, stopping");
+            Ok(())
+        }
+        _ = run_until(window.stop) => {
+            info!("Scheduled stop time reached, stopping");
+            Ok(())
         }
+        reason = recording_failure(recorder.as_ref()) => {
+            Err(HyperliquidError::SinkError(reason).into())
+        }
+    };
+
+    if let Some(notifier) = &systemd {
+        notifier.stopping();
     }
 
+    // Back on the main screen before the summary so it stays in the scrollback
+    drop(alternate_screen);
+    ui_controller.print_exit_summary().await;
+    let written = ui_controller.finish_output();
+
+    // The client owns the last recorder sender; drop it so the writer can drain and flush
+    drop(client);
+    let recorded = recorder.map(Recorder::finish).transpose();
+    // Short runs may never have reached a periodic push
+    if let Some(pushgateway) = &pushgateway {
+        pushgateway.push_final().await;
+    }
+    result?;
+    recorded?;
+    written?;
+
     info!("Application stopped successfully");
     Ok(())
 }
+
+/// `service install|uninstall|run`; `run` blocks in the service dispatcher until Windows stops it
+#[cfg(all(windows, feature = "windows-service"))]
+fn run_service_action(action: ServiceAction, service_matches: &ArgMatches) -> Result<()> {
+    use clap::Parser;
+    use rs_hyperliquid::service::{self, ServiceMain};
+    use std::ffi::OsString;
+
+    match action {
+        ServiceAction::Install { name, options } => {
+            // Bad options fail here instead of on every start of the service
+            let mut argv: Vec<OsString> =
+                ["rs-hyperliquid", "service", "run", "--name", name.as_str()]
+                    .into_iter()
+                    .map(OsString::from)
+                    .collect();
+            argv.extend(options.iter().cloned());
+            Args::try_parse_from(argv).map_err(|e| {
+                HyperliquidError::ConfigError(format!("invalid service options: {}", e))
+            })?;
+            service::install(&name, options)?;
+            println!(
+                "Installed service {}. Start it with: sc start {}",
+                name, name
+            );
+            Ok(())
+        }
+        ServiceAction::Uninstall { name } => {
+            service::uninstall(&name)?;
+            println!("Removed service {}.", name);
+            Ok(())
+        }
+        ServiceAction::Run { name, stream } => {
+            let run_matches = service_matches
+                .subcommand()
+                .map_or(service_matches, |(_, sub)| sub)
+                .clone();
+            // Services start in System32; resolve relative paths and hyperliquid.toml next to the exe
+            if let Some(dir) = std::env::current_exe()?.parent() {
+                std::env::set_current_dir(dir)?;
+            }
+            let runtime = tokio::runtime::Handle::current();
+            tokio::task::block_in_place(|| {
+                service::run(
+                    &name,
+                    ServiceMain {
+                        body: Box::new(move || runtime.block_on(run(stream, None, &run_matches))),
+                        stop: Box::new(|| STOP_REQUESTED.notify_one()),
+                    },
+                )
+            })
+        }
+    }
+}
+
+/// Info endpoint client on the WebSocket host, sending the same identifying headers
+fn info_client(config: &Config) -> Result<InfoClient> {
+    let websocket = &config.websocket;
+    Ok(InfoClient::from_ws_url(&websocket.url, websocket.timeout)?
+        .with_headers(&websocket.user_agent, &websocket.headers))
+}
+
+/// Look the coins up in `meta`; an unreachable info endpoint only warns so streaming still starts
+async fn validate_startup_coins(config: &Config) -> Result<()> {
+    let perps: Vec<&str> = config
+        .coins()
+        .into_iter()
+        .filter(|coin| is_perp_name(coin))
+        .collect();
+    if perps.is_empty() {
+        return Ok(());
+    }
+    let client = info_client(config)?;
+    match client.meta().await {
+        Ok(meta) => {
+            for coin in perps {
+                validate_coin(&meta, coin)?;
+            }
+        }
+        Err(e) => warn!(
+            "Skipping coin validation, could not fetch exchange metadata: {}",
+            e
+        ),
+    }
+    Ok(())
+}
+
+/// Look up `--coin` among the spot pairs; an `@index` id is used as-is when the lookup fails
+async fn resolve_spot_coin(config: &Config) -> Result<SpotMarket> {
+    let coin = &config.subscription.coin;
+    let client = info_client(config)?;
+    match client.spot_meta().await {
+        Ok(meta) => Ok(resolve_spot(&meta, coin)?),
+        Err(e) if coin.starts_with('@') => {
+            warn!(
+                "Could not fetch spot pairs, streaming {} unnamed: {}",
+                coin, e
+            );
+            Ok(SpotMarket {
+                coin: coin.clone(),
+                name: coin.clone(),
+            })
+        }
+        Err(e) => Err(HyperliquidError::ConnectFailed(format!(
+            "could not fetch spot pairs to resolve '{}': {}",
+            coin, e
+        ))
+        .into()),
+    }
+}
+
+/// Resolves with the reason once the recorder stops writing, or never without one
+async fn recording_failure(recorder: Option<&Recorder>) -> String {
+    match recorder {
+        Some(recorder) => recorder.failed().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolves at `--stop-at`, or never without one
+async fn run_until(stop: Option<DateTime<Utc>>) {
+    match stop {
+        Some(stop) => sleep_until(stop).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolves once `--duration` has elapsed, or never without one
+async fn run_deadline(duration: Option<Duration>) {
+    match duration {
+        Some(duration) => tokio::time::sleep(duration).await,
+        None => std::future::pending().await,
+    }
+}
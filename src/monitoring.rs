@@ -1,68 +1,633 @@
 /// file: src/monitoring.rs
 /// description: prometheus metrics collection and health monitoring for production observability
 /// reference: https://docs.rs/metrics-exporter-prometheus/latest/metrics_exporter_prometheus/
-use crate::error::HyperliquidError;
+use crate::{
+    client_state::{ClientState, SharedClientState},
+    dashboard::{read_request, write_response, write_unauthorized},
+    error::HyperliquidError,
+    time::Delay,
+    types::Subscription,
+};
 use anyhow::Result;
-use metrics::{Counter, Gauge, counter, gauge};
-use metrics_exporter_prometheus::PrometheusBuilder;
-use std::{net::SocketAddr, sync::LazyLock};
-use tracing::{error, info};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use metrics::{Counter, Gauge, Histogram, Recorder, counter, gauge, histogram};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle, PrometheusRecorder};
+use metrics_util::layers::FanoutBuilder;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{
+    Arc, LazyLock,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, error, info, warn};
 
 // Global metrics
 pub static MESSAGES_RECEIVED_COUNTER: LazyLock<Counter> =
     LazyLock::new(|| counter!("hyperliquid_messages_received_total"));
 pub static TRADE_COUNTER: LazyLock<Counter> =
     LazyLock::new(|| counter!("hyperliquid_trades_total"));
-pub static RECONNECT_COUNTER: LazyLock<Counter> =
-    LazyLock::new(|| counter!("hyperliquid_reconnects_total"));
 pub static CONNECTED_GAUGE: LazyLock<Gauge> = LazyLock::new(|| gauge!("hyperliquid_connected"));
+pub static CONNECTION_AGE: LazyLock<Gauge> =
+    LazyLock::new(|| gauge!("hyperliquid_connection_age_seconds"));
+pub static CONNECTION_DURATION: LazyLock<Histogram> =
+    LazyLock::new(|| histogram!("hyperliquid_connection_duration_seconds"));
+pub static HANDSHAKE_FAILURES: LazyLock<Counter> =
+    LazyLock::new(|| counter!("hyperliquid_handshake_failures_total"));
+pub static ACTIVE_SUBSCRIPTIONS: LazyLock<Gauge> =
+    LazyLock::new(|| gauge!("hyperliquid_active_subscriptions"));
+pub static PING_RTT: LazyLock<Histogram> =
+    LazyLock::new(|| histogram!("hyperliquid_ping_rtt_seconds"));
 
 // Data integrity metrics
 pub static DUPLICATE_TRADES: LazyLock<Counter> =
     LazyLock::new(|| counter!("hyperliquid_duplicate_trades_total"));
 pub static INVALID_TIMESTAMPS: LazyLock<Counter> =
     LazyLock::new(|| counter!("hyperliquid_invalid_timestamps_total"));
-pub static EVENTS_DROPPED: LazyLock<Counter> =
-    LazyLock::new(|| counter!("hyperliquid_events_dropped_total"));
+pub static CLOCK_SKEW_MS: LazyLock<Gauge> =
+    LazyLock::new(|| gauge!("hyperliquid_clock_skew_milliseconds"));
 
-pub async fn setup_metrics(port: u16) -> Result<()> {
-    let addr: SocketAddr = ([0, 0, 0, 0], port).into();
+/// Why a connection attempt ended, the `reason` label on the reconnect and disconnect counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// TCP connect, TLS, or the WebSocket upgrade failed, so no session was established
+    Handshake,
+    /// The server sent a close frame
+    ServerClose,
+    /// Nothing arrived within `--timeout`
+    Timeout,
+    /// The socket failed mid-session
+    ReadError,
+}
 
-    let builder = PrometheusBuilder::new()
-        .with_http_listener(addr)
-        .add_global_label("service", "hyperliquid-ws-client")
-        .add_global_label("version", env!("CARGO_PKG_VERSION"));
+impl DisconnectReason {
+    const ALL: [DisconnectReason; 4] = [
+        DisconnectReason::Handshake,
+        DisconnectReason::ServerClose,
+        DisconnectReason::Timeout,
+        DisconnectReason::ReadError,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisconnectReason::Handshake => "handshake",
+            DisconnectReason::ServerClose => "server_close",
+            DisconnectReason::Timeout => "timeout",
+            DisconnectReason::ReadError => "read_error",
+        }
+    }
+
+    /// Whether a session was lost, as opposed to never established
+    pub fn ends_session(self) -> bool {
+        self != DisconnectReason::Handshake
+    }
+
+    /// Classify the error that ended an attempt; `established` is whether the handshake finished
+    pub fn classify(error: &anyhow::Error, established: bool) -> Self {
+        if !established {
+            return DisconnectReason::Handshake;
+        }
+        match error.downcast_ref::<HyperliquidError>() {
+            Some(HyperliquidError::ConnectionClosed) => DisconnectReason::ServerClose,
+            Some(HyperliquidError::Timeout) => DisconnectReason::Timeout,
+            _ => DisconnectReason::ReadError,
+        }
+    }
+}
+
+/// What left a hole in the received data, the `kind` label on the gap metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapKind {
+    /// The connection was down until a reconnect succeeded
+    Disconnect,
+    /// A subscribed stream sent nothing for longer than `--max-silence` while connected
+    Silence,
+    /// Candle bars were skipped between two updates
+    Candle,
+}
+
+impl GapKind {
+    const ALL: [GapKind; 3] = [GapKind::Disconnect, GapKind::Silence, GapKind::Candle];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GapKind::Disconnect => "disconnect",
+            GapKind::Silence => "silence",
+            GapKind::Candle => "candle",
+        }
+    }
+}
+
+/// Count a data gap and how much time it covered
+pub fn record_data_gap(kind: GapKind, duration: Duration) {
+    counter!("hyperliquid_data_gaps_total", "kind" => kind.as_str()).increment(1);
+    histogram!("hyperliquid_data_gap_seconds", "kind" => kind.as_str())
+        .record(duration.as_secs_f64());
+}
+
+/// Record how long a live trade took to arrive, labelled by whether the skew estimate was applied
+pub fn record_trade_delay(delay: &Delay) {
+    let corrected = if delay.skew_ms.is_some() {
+        "true"
+    } else {
+        "false"
+    };
+    histogram!("hyperliquid_trade_delay_seconds", "skew_corrected" => corrected)
+        .record(delay.corrected_ms() as f64 / 1000.0);
+}
+
+/// Who reads from a bounded event queue, the `consumer` label on the channel metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventConsumer {
+    /// The terminal UI, behind the client's event channel
+    Ui,
+    /// The `--record` writer
+    Recorder,
+    /// A browser connected to the dashboard event stream
+    Dashboard,
+}
+
+impl EventConsumer {
+    const ALL: [EventConsumer; 3] = [
+        EventConsumer::Ui,
+        EventConsumer::Recorder,
+        EventConsumer::Dashboard,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventConsumer::Ui => "ui",
+            EventConsumer::Recorder => "recorder",
+            EventConsumer::Dashboard => "dashboard",
+        }
+    }
+
+    /// Whether it sits behind an mpsc channel whose depth the sender can see; dashboard
+    /// browsers read a broadcast bus and only report how far they fell behind
+    fn has_depth(self) -> bool {
+        self != EventConsumer::Dashboard
+    }
+}
+
+/// Count events a full queue made us discard
+pub fn record_dropped_events(consumer: EventConsumer, count: u64) {
+    counter!("hyperliquid_events_dropped_total", "consumer" => consumer.as_str()).increment(count);
+}
+
+/// Current depth and high-water mark of one bounded channel
+///
+/// `observe` is cheap enough to call on every send, so short bursts between flushes still raise
+/// the high-water mark; `sample` also exports the depth itself.
+pub struct ChannelDepth {
+    depth: Gauge,
+    high_water: Gauge,
+    peak: AtomicUsize,
+}
+
+impl ChannelDepth {
+    pub fn new(consumer: EventConsumer) -> Self {
+        Self {
+            depth: gauge!("hyperliquid_event_channel_depth", "consumer" => consumer.as_str()),
+            high_water: gauge!(
+                "hyperliquid_event_channel_high_water",
+                "consumer" => consumer.as_str()
+            ),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn observe(&self, depth: usize) {
+        // A plain load first keeps the common case free of read-modify-write traffic
+        if depth > self.peak.load(Ordering::Relaxed)
+            && self.peak.fetch_max(depth, Ordering::Relaxed) < depth
+        {
+            self.high_water.set(depth as f64);
+        }
+    }
+
+    pub fn sample(&self, depth: usize) {
+        self.observe(depth);
+        self.depth.set(depth as f64);
+    }
+}
+
+/// Write latency, batch size, and error handles for one output sink, labelled `sink`
+#[derive(Debug)]
+pub struct SinkMetrics {
+    write_seconds: Histogram,
+    batch_lines: Histogram,
+    errors: Counter,
+}
+
+impl SinkMetrics {
+    /// `sink` is `output` for `--output` or `recorder` for `--record`
+    pub fn new(sink: &'static str) -> Self {
+        Self {
+            write_seconds: histogram!("hyperliquid_sink_write_seconds", "sink" => sink),
+            batch_lines: histogram!("hyperliquid_sink_batch_lines", "sink" => sink),
+            errors: counter!("hyperliquid_sink_errors_total", "sink" => sink),
+        }
+    }
+
+    /// A batch of `lines` reached the operating system in `elapsed`
+    pub fn record_batch(&self, lines: usize, elapsed: Duration) {
+        self.write_seconds.record(elapsed.as_secs_f64());
+        self.batch_lines.record(lines as f64);
+    }
+
+    pub fn record_error(&self) {
+        self.errors.increment(1);
+    }
+}
+
+/// Count a failed attempt that will be retried, and the lost session if there was one
+///
+/// `session` is how long the connection was up; `None` means the handshake never finished.
+pub fn record_reconnect(reason: DisconnectReason, session: Option<Duration>) {
+    counter!("hyperliquid_reconnects_total", "reason" => reason.as_str()).increment(1);
+    if reason.ends_session() {
+        counter!("hyperliquid_disconnects_total", "reason" => reason.as_str()).increment(1);
+    } else {
+        HANDSHAKE_FAILURES.increment(1);
+    }
+    if let Some(session) = session {
+        CONNECTION_DURATION.record(session.as_secs_f64());
+    }
+    CONNECTED_GAUGE.set(0.0);
+    CONNECTION_AGE.set(0.0);
+}
+
+/// Sample the current tokio runtime into gauges and counters every `interval`
+///
+/// Only the metrics tokio exposes without `--cfg tokio_unstable` are available: worker count,
+/// alive tasks, global queue depth, and per-worker busy ratio and park count. A read loop starved
+/// by the formatter or a sink shows up as workers pinned near a busy ratio of 1 with tasks queued.
+pub fn spawn_runtime_metrics(interval: Duration) {
+    let runtime = tokio::runtime::Handle::current().metrics();
+    tokio::spawn(async move {
+        let workers = runtime.num_workers();
+        gauge!("hyperliquid_tokio_workers").set(workers as f64);
+        let mut last_busy = vec![Duration::ZERO; workers];
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            gauge!("hyperliquid_tokio_alive_tasks").set(runtime.num_alive_tasks() as f64);
+            gauge!("hyperliquid_tokio_global_queue_depth").set(runtime.global_queue_depth() as f64);
+            for (worker, last) in last_busy.iter_mut().enumerate() {
+                let label = worker.to_string();
+                let busy = runtime.worker_total_busy_duration(worker);
+                let ratio = busy.saturating_sub(*last).as_secs_f64() / interval.as_secs_f64();
+                *last = busy;
+                gauge!("hyperliquid_tokio_worker_busy_ratio", "worker" => label.clone())
+                    .set(ratio.min(1.0));
+                counter!("hyperliquid_tokio_worker_parks_total", "worker" => label)
+                    .absolute(runtime.worker_park_count(worker));
+            }
+        }
+    });
+}
+
+/// Export the time since each confirmed subscription last delivered data, every `interval`
+///
+/// One dead subscription shows up here even while others keep the connection looking healthy.
+/// A subscription that has not delivered yet counts from when it was first confirmed. Across a
+/// disconnect the gauges keep growing until data arrives again; one unsubscribed on purpose, such
+/// as the old coin after a switch, is reset to 0 and no longer updated.
+pub fn spawn_staleness_metrics(state: SharedClientState, interval: Duration) {
+    tokio::spawn(async move {
+        let mut exported: HashMap<(String, String), (Gauge, tokio::time::Instant)> = HashMap::new();
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let state = state.lock().await;
+            for subscription in &state.connection.subscriptions {
+                let key = (
+                    subscription.subscription_type.clone(),
+                    subscription.coin.clone(),
+                );
+                exported.entry(key).or_insert_with_key(|(channel, coin)| {
+                    let gauge = gauge!(
+                        "hyperliquid_seconds_since_last_message",
+                        "channel" => channel.clone(),
+                        "coin" => coin.clone()
+                    );
+                    (gauge, tokio::time::Instant::now())
+                });
+            }
+            // Confirmations come back just after a reconnect, so an empty list is not a removal
+            let reconnecting = !state.is_connected() || state.connection.subscriptions.is_empty();
+            exported.retain(|(channel, coin), (gauge, confirmed_at)| {
+                let subscribed = state.connection.subscriptions.iter().any(|subscription| {
+                    subscription.subscription_type == *channel && subscription.coin == *coin
+                });
+                if !subscribed && !reconnecting {
+                    gauge.set(0.0);
+                    return false;
+                }
+                let last = state
+                    .last_seen
+                    .get(channel)
+                    .and_then(|coins| coins.get(coin))
+                    .unwrap_or(confirmed_at);
+                gauge.set(last.elapsed().as_secs_f64());
+                true
+            });
+        }
+    });
+}
+
+/// Payload size and parse time histograms labelled by channel
+///
+/// Handles are cached per channel so the hot path skips the recorder's key lookup. Messages that
+/// fail to parse are recorded under `invalid`.
+#[derive(Default)]
+pub struct MessageHistograms {
+    by_channel: HashMap<String, (Histogram, Histogram)>,
+}
 
-    match builder.install() {
-        Ok(_handle) => {
-            info!(
-                "Prometheus metrics server started on http://{}/metrics",
-                addr
+impl MessageHistograms {
+    pub fn record(&mut self, channel: &str, bytes: usize, parse_time: Duration) {
+        if !self.by_channel.contains_key(channel) {
+            let label = channel.to_string();
+            self.by_channel.insert(
+                label.clone(),
+                (
+                    histogram!("hyperliquid_message_bytes", "channel" => label.clone()),
+                    histogram!("hyperliquid_message_parse_seconds", "channel" => label),
+                ),
             );
+        }
+        if let Some((size, parse)) = self.by_channel.get(channel) {
+            size.record(bytes as f64);
+            parse.record(parse_time.as_secs_f64());
+        }
+    }
+}
+
+/// Where `--metrics` sends the crate's metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsBackend {
+    /// Scrape endpoint on `--metrics-port`
+    #[default]
+    Prometheus,
+    /// UDP push to a StatsD or DogStatsD agent at `--statsd-addr`
+    Statsd,
+    /// Periodic and final push to a Prometheus Pushgateway at `--pushgateway-url`
+    Pushgateway,
+}
 
-            // Initialize metrics with default values
-            MESSAGES_RECEIVED_COUNTER.absolute(0);
-            TRADE_COUNTER.absolute(0);
-            RECONNECT_COUNTER.absolute(0);
-            CONNECTED_GAUGE.set(0.0);
+impl FromStr for MetricsBackend {
+    type Err = String;
 
-            Ok(())
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "prometheus" => Ok(MetricsBackend::Prometheus),
+            "statsd" | "dogstatsd" => Ok(MetricsBackend::Statsd),
+            "pushgateway" => Ok(MetricsBackend::Pushgateway),
+            _ => Err(format!(
+                "unknown metrics backend '{}', expected prometheus, statsd, or pushgateway",
+                value
+            )),
         }
-        Err(e) => {
-            error!("Failed to start metrics server: {}", e);
-            Err(HyperliquidError::MetricsError(e.to_string()).into())
+    }
+}
+
+/// One metrics backend; every enabled backend receives the same updates
+pub type MetricsRecorder = Box<dyn Recorder + Send + Sync>;
+
+/// Credentials a scrape of `/metrics` must present
+#[derive(Clone, PartialEq, Eq)]
+pub enum MetricsAuth {
+    Basic { user: String, password: String },
+    Bearer(String),
+}
+
+impl MetricsAuth {
+    /// `--metrics-basic-auth` value, `USER:PASSWORD`
+    pub fn basic(credentials: &str) -> Result<Self, String> {
+        match credentials.split_once(':') {
+            Some((user, password)) if !user.is_empty() && !password.is_empty() => {
+                Ok(MetricsAuth::Basic {
+                    user: user.to_string(),
+                    password: password.to_string(),
+                })
+            }
+            _ => Err("metrics basic auth must be USER:PASSWORD".to_string()),
         }
     }
+
+    fn accepts(&self, authorization: &str) -> bool {
+        let expected = match self {
+            MetricsAuth::Basic { user, password } => {
+                format!("Basic {}", BASE64.encode(format!("{}:{}", user, password)))
+            }
+            MetricsAuth::Bearer(token) => format!("Bearer {}", token),
+        };
+        // Every byte is compared so the response time says nothing about a partial match
+        expected.len() == authorization.len()
+            && expected
+                .bytes()
+                .zip(authorization.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+impl std::fmt::Debug for MetricsAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsAuth::Basic { user, .. } => write!(f, "Basic({}:***)", user),
+            MetricsAuth::Bearer(_) => write!(f, "Bearer(***)"),
+        }
+    }
+}
+
+/// Start the Prometheus scrape listener; its recorder still has to be installed
+///
+/// With `auth` set, a scrape must present one of the credentials or gets a 401.
+pub async fn setup_metrics(
+    bind: IpAddr,
+    port: u16,
+    auth: Vec<MetricsAuth>,
+) -> Result<MetricsRecorder> {
+    let addr = SocketAddr::new(bind, port);
+    let listener = TcpListener::bind(addr).await.map_err(|e| {
+        error!("Failed to start metrics server: {}", e);
+        HyperliquidError::MetricsError(format!("cannot bind {}: {}", addr, e))
+    })?;
+
+    let recorder = prometheus_recorder();
+    let handle = recorder.handle();
+
+    info!(
+        "Prometheus metrics server started on http://{}/metrics{}",
+        addr,
+        if auth.is_empty() {
+            ""
+        } else {
+            " (authentication required)"
+        }
+    );
+    let auth = Arc::new(auth);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let (handle, auth) = (handle.clone(), auth.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_scrape(stream, handle, &auth).await {
+                            debug!("Metrics connection from {} ended: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Metrics accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(Box::new(recorder))
+}
+
+/// Recorder rendering the Prometheus text format, with upkeep running in the background
+pub(crate) fn prometheus_recorder() -> PrometheusRecorder {
+    let recorder = PrometheusBuilder::new()
+        .add_global_label("service", "hyperliquid-ws-client")
+        .add_global_label("version", env!("CARGO_PKG_VERSION"))
+        .build_recorder();
+
+    // Summaries and histograms only roll their windows forward during upkeep
+    let upkeep = recorder.handle();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            ticker.tick().await;
+            upkeep.run_upkeep();
+        }
+    });
+    recorder
+}
+
+async fn serve_scrape(
+    stream: TcpStream,
+    handle: PrometheusHandle,
+    auth: &[MetricsAuth],
+) -> std::io::Result<()> {
+    let (request, mut stream) = read_request(stream).await?;
+    let authorized = auth.is_empty()
+        || request
+            .authorization
+            .as_deref()
+            .is_some_and(|presented| auth.iter().any(|auth| auth.accepts(presented)));
+    if !authorized {
+        let challenge = if auth
+            .iter()
+            .any(|auth| matches!(auth, MetricsAuth::Basic { .. }))
+        {
+            "Basic realm=\"metrics\""
+        } else {
+            "Bearer"
+        };
+        return write_unauthorized(&mut stream, challenge).await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => {
+            write_response(
+                &mut stream,
+                "200 OK",
+                "text/plain; version=0.0.4",
+                &handle.render(),
+            )
+            .await
+        }
+        ("GET", _) => write_response(&mut stream, "404 Not Found", "text/plain", "not found").await,
+        _ => {
+            write_response(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain",
+                "method not allowed",
+            )
+            .await
+        }
+    }
+}
+
+/// Make the backends the global recorder; without any, metric updates are no-ops
+///
+/// Must run before the first metric is touched, since the handles bind to the recorder
+/// installed at that moment.
+pub fn install_metrics(mut recorders: Vec<MetricsRecorder>) -> Result<()> {
+    let installed = match recorders.len() {
+        0 => return Ok(()),
+        1 => metrics::set_global_recorder(recorders.remove(0)).map_err(|e| e.to_string()),
+        _ => {
+            let fanout = recorders
+                .into_iter()
+                .fold(FanoutBuilder::default(), |fanout, recorder| {
+                    fanout.add_recorder(recorder)
+                })
+                .build();
+            metrics::set_global_recorder(fanout).map_err(|e| e.to_string())
+        }
+    };
+    installed.map_err(HyperliquidError::MetricsError)?;
+
+    // Initialize metrics with default values
+    MESSAGES_RECEIVED_COUNTER.absolute(0);
+    TRADE_COUNTER.absolute(0);
+    for reason in DisconnectReason::ALL {
+        counter!("hyperliquid_reconnects_total", "reason" => reason.as_str()).absolute(0);
+        if reason.ends_session() {
+            counter!("hyperliquid_disconnects_total", "reason" => reason.as_str()).absolute(0);
+        }
+    }
+    CONNECTED_GAUGE.set(0.0);
+    CONNECTION_AGE.set(0.0);
+    HANDSHAKE_FAILURES.absolute(0);
+    for kind in GapKind::ALL {
+        counter!("hyperliquid_data_gaps_total", "kind" => kind.as_str()).absolute(0);
+    }
+    for sink in ["output", "recorder"] {
+        counter!("hyperliquid_sink_errors_total", "sink" => sink).absolute(0);
+    }
+    ACTIVE_SUBSCRIPTIONS.set(0.0);
+    for consumer in EventConsumer::ALL {
+        counter!("hyperliquid_events_dropped_total", "consumer" => consumer.as_str()).absolute(0);
+        if consumer.has_depth() {
+            ChannelDepth::new(consumer).sample(0);
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
 pub struct HealthStatus {
+    /// Connected, with a message inside the staleness window
     pub is_healthy: bool,
     pub last_message_time: Option<chrono::DateTime<chrono::Utc>>,
     pub total_messages: u64,
     pub total_trades: u64,
     pub reconnect_count: u64,
     pub uptime: chrono::Duration,
+    pub connected: bool,
+    pub connection_id: String,
+    pub session_id: String,
+    /// Time connected over the session, across reconnects
+    pub session_uptime: chrono::Duration,
+    /// Handshakes completed over the session
+    pub connections: u32,
+    pub subscriptions: Vec<Subscription>,
+    pub duplicate_trades: u64,
+    pub invalid_timestamps: u64,
+    pub gap_count: u32,
+    pub total_downtime: chrono::Duration,
+    pub ping_rtt: Option<Duration>,
 }
 
 impl Default for HealthStatus {
@@ -80,6 +645,48 @@ impl HealthStatus {
             total_trades: 0,
             reconnect_count: 0,
             uptime: chrono::Duration::zero(),
+            connected: false,
+            connection_id: String::new(),
+            session_id: String::new(),
+            session_uptime: chrono::Duration::zero(),
+            connections: 0,
+            subscriptions: Vec::new(),
+            duplicate_trades: 0,
+            invalid_timestamps: 0,
+            gap_count: 0,
+            total_downtime: chrono::Duration::zero(),
+            ping_rtt: None,
+        }
+    }
+
+    /// Sample the client state; healthy means no message gap longer than `stale_after`
+    pub fn from_state(state: &ClientState, stale_after: Duration) -> Self {
+        let connected = state.is_connected();
+        let last_message_age = state.last_message_time.map(|at| at.elapsed());
+        let to_chrono =
+            |duration: Duration| chrono::Duration::from_std(duration).unwrap_or_default();
+        Self {
+            is_healthy: connected && last_message_age.is_some_and(|age| age <= stale_after),
+            last_message_time: last_message_age.map(|age| chrono::Utc::now() - to_chrono(age)),
+            total_messages: state.total_messages_received.load(Ordering::Relaxed),
+            total_trades: state.trade_count.load(Ordering::Relaxed),
+            reconnect_count: state.total_reconnects.load(Ordering::Relaxed).into(),
+            uptime: state
+                .connection
+                .connected_since
+                .map(|since| to_chrono(since.elapsed()))
+                .unwrap_or_default(),
+            connected,
+            connection_id: state.connection.id.clone(),
+            session_id: state.session_id.clone(),
+            session_uptime: to_chrono(state.session_uptime()),
+            connections: state.connections,
+            subscriptions: state.connection.subscriptions.clone(),
+            duplicate_trades: state.duplicate_trades.load(Ordering::Relaxed),
+            invalid_timestamps: state.invalid_timestamps.load(Ordering::Relaxed),
+            gap_count: state.gap_count,
+            total_downtime: to_chrono(state.total_downtime),
+            ping_rtt: state.connection.ping_rtt,
         }
     }
 
@@ -91,6 +698,18 @@ impl HealthStatus {
             "total_trades": self.total_trades,
             "reconnect_count": self.reconnect_count,
             "uptime_seconds": self.uptime.num_seconds(),
+            "connected": self.connected,
+            "connection_id": self.connection_id,
+            "session_id": self.session_id,
+            "session_uptime_seconds": self.session_uptime.num_seconds(),
+            "connections": self.connections,
+            "subscriptions": self.subscriptions,
+            "duplicate_trades": self.duplicate_trades,
+            "invalid_timestamps": self.invalid_timestamps,
+            "gap_count": self.gap_count,
+            "downtime_seconds": self.total_downtime.num_seconds(),
+            "ping_rtt_ms": self.ping_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+            "version": env!("CARGO_PKG_VERSION"),
             "timestamp": chrono::Utc::now()
         })
     }
@@ -0,0 +1,407 @@
+/// file: src/number_format.rs
+/// description: Digit grouping, decimal marks, and decimal places for displayed numbers
+use crate::{error::HyperliquidError, symbol::Symbol};
+use std::collections::HashMap;
+
+/// How numbers meant for people are written
+///
+/// Only the table, ladder, ticker, BBO, heatmap, and summary views use this; CSV, JSON, events,
+/// and templates keep `Plain` so their output still parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// `1234567.89`
+    #[default]
+    Plain,
+    /// `1,234,567.89`
+    En,
+    /// `1.234.567,89`
+    De,
+    /// `1 234 567,89`
+    Fr,
+    /// `1'234'567.89`
+    Ch,
+}
+
+impl NumberLocale {
+    const ALL: [NumberLocale; 5] = [
+        NumberLocale::Plain,
+        NumberLocale::En,
+        NumberLocale::De,
+        NumberLocale::Fr,
+        NumberLocale::Ch,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NumberLocale::Plain => "plain",
+            NumberLocale::En => "en",
+            NumberLocale::De => "de",
+            NumberLocale::Fr => "fr",
+            NumberLocale::Ch => "ch",
+        }
+    }
+
+    /// Parse a style name, a locale such as `de_DE.UTF-8` or `fr-CA`, or `auto` for the
+    /// environment's
+    pub fn parse(value: &str) -> Result<Self, HyperliquidError> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(Self::from_env());
+        }
+        Self::from_tag(value).ok_or_else(|| {
+            HyperliquidError::InvalidNumberSetting(format!(
+                "unknown number locale '{}', expected auto, plain, en, de, fr, ch, or a locale such as de_DE",
+                value
+            ))
+        })
+    }
+
+    /// The style of the locale `LC_ALL`, `LC_NUMERIC`, or `LANG` names; plain when unset or unknown
+    fn from_env() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_tag(&value))
+            .unwrap_or_default()
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.split(['.', '@']).next()?.replace('-', "_");
+        let tag = tag.to_ascii_lowercase();
+        if let Some(locale) = Self::ALL.into_iter().find(|locale| locale.as_str() == tag) {
+            return Some(locale);
+        }
+        let (language, region) = tag.split_once('_').unwrap_or((&tag, ""));
+        Some(match (language, region) {
+            ("c" | "posix", _) => NumberLocale::Plain,
+            // Switzerland and Liechtenstein group with an apostrophe whatever the language
+            (_, "ch" | "li") => NumberLocale::Ch,
+            ("en" | "ja" | "ko" | "zh" | "he" | "th", _) => NumberLocale::En,
+            ("de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el", _) => NumberLocale::De,
+            ("fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "hu", _) => {
+                NumberLocale::Fr
+            }
+            _ => return None,
+        })
+    }
+
+    /// Group separator and decimal mark, or `None` to leave numbers as Rust prints them
+    fn marks(self) -> Option<(char, char)> {
+        match self {
+            NumberLocale::Plain => None,
+            NumberLocale::En => Some((',', '.')),
+            NumberLocale::De => Some(('.', ',')),
+            NumberLocale::Fr => Some((' ', ',')),
+            NumberLocale::Ch => Some(('\'', '.')),
+        }
+    }
+
+    /// `value` rounded to `decimals` places, grouped in thousands and marked for this locale
+    pub fn format(self, value: f64, decimals: usize) -> String {
+        let plain = format!("{:.*}", decimals, value);
+        let Some((group, decimal)) = self.marks().filter(|_| value.is_finite()) else {
+            return plain;
+        };
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", plain.as_str()),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+
+        let mut out = String::with_capacity(plain.len() + integer.len() / 3);
+        out.push_str(sign);
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index).is_multiple_of(3) {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// `format` with a sign on positive values too, for changes
+    pub fn format_signed(self, value: f64, decimals: usize) -> String {
+        let formatted = self.format(value, decimals);
+        if formatted.starts_with('-') {
+            formatted
+        } else {
+            format!("+{}", formatted)
+        }
+    }
+}
+
+/// Most decimal places `--price-decimals` and `--size-decimals` accept
+const MAX_DECIMALS: usize = 12;
+
+/// A decimal count for every coin and for named coins, from `N` and `COIN=N` values
+#[derive(Debug, Clone, Default)]
+struct Decimals {
+    all: Option<usize>,
+    coins: HashMap<Symbol, usize>,
+}
+
+impl Decimals {
+    fn parse(flag: &str, values: &[String]) -> Result<Self, HyperliquidError> {
+        let mut decimals = Decimals::default();
+        for value in values {
+            let (coin, count) = match value.split_once('=') {
+                Some((coin, count)) => (Some(coin.trim()), count),
+                None => (None, value.as_str()),
+            };
+            let count = count
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|count| *count <= MAX_DECIMALS)
+                .ok_or_else(|| {
+                    HyperliquidError::InvalidNumberSetting(format!(
+                        "{} '{}' must be N or COIN=N with N from 0 to {}",
+                        flag, value, MAX_DECIMALS
+                    ))
+                })?;
+            match coin {
+                Some(coin) => {
+                    decimals.coins.insert(Symbol::intern(coin), count);
+                }
+                None => decimals.all = Some(count),
+            }
+        }
+        Ok(decimals)
+    }
+
+    fn get(&self, coin: &str) -> Option<usize> {
+        self.coins.get(coin).copied().or(self.all)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.all.is_none() && self.coins.is_empty()
+    }
+
+    /// Round `field` of a JSON object in place when `coin` has a setting
+    fn round_field(&self, coin: &str, object: &mut serde_json::Value, field: &str) {
+        let Some(decimals) = self.get(coin) else {
+            return;
+        };
+        if let Some(value) = object.get_mut(field)
+            && let Some(number) = value.as_f64()
+        {
+            *value = round(number, decimals).into();
+        }
+    }
+}
+
+/// Decimal places for prices and sizes set by `--price-decimals` and `--size-decimals`
+///
+/// A coin's own setting wins over the one for every coin, which wins over the view's built-in
+/// precision. Notional values stay in quote currency cents and are not affected.
+#[derive(Debug, Clone, Default)]
+pub struct Precision {
+    price: Decimals,
+    size: Decimals,
+}
+
+impl Precision {
+    pub fn parse(price: &[String], size: &[String]) -> Result<Self, HyperliquidError> {
+        Ok(Self {
+            price: Decimals::parse("--price-decimals", price)?,
+            size: Decimals::parse("--size-decimals", size)?,
+        })
+    }
+
+    /// Decimal places for a price of `coin`, or `default` when nothing overrides it
+    pub fn price(&self, coin: &str, default: usize) -> usize {
+        self.price.get(coin).unwrap_or(default)
+    }
+
+    pub fn size(&self, coin: &str, default: usize) -> usize {
+        self.size.get(coin).unwrap_or(default)
+    }
+
+    /// A price for output that keeps full precision unless overridden, such as JSON
+    pub fn round_price(&self, coin: &str, value: f64) -> f64 {
+        self.price
+            .get(coin)
+            .map_or(value, |decimals| round(value, decimals))
+    }
+
+    pub fn round_size(&self, coin: &str, value: f64) -> f64 {
+        self.size
+            .get(coin)
+            .map_or(value, |decimals| round(value, decimals))
+    }
+
+    /// Round the prices and sizes of a trade, book, BBO, or candle event line from
+    /// `ClientEvent::to_json`
+    pub fn round_event(&self, event: &mut serde_json::Value) {
+        if self.price.is_empty() && self.size.is_empty() {
+            return;
+        }
+        let Some(coin) = event.get("coin").and_then(|coin| coin.as_str()) else {
+            return;
+        };
+        let coin = Symbol::intern(coin);
+        let mut round_level = |level: &mut serde_json::Value| {
+            self.price.round_field(&coin, level, "px");
+            self.size.round_field(&coin, level, "sz");
+        };
+        round_level(event);
+        for field in ["o", "h", "l", "c"] {
+            self.price.round_field(&coin, event, field);
+        }
+        self.size.round_field(&coin, event, "v");
+        for side in ["bid", "ask"] {
+            if let Some(level) = event.get_mut(side) {
+                round_level(level);
+            }
+        }
+        for side in ["bids", "asks"] {
+            if let Some(serde_json::Value::Array(levels)) = event.get_mut(side) {
+                levels.iter_mut().for_each(&mut round_level);
+            }
+        }
+    }
+}
+
+fn round(value: f64, decimals: usize) -> f64 {
+    let scale = 10_f64.powi(decimals as i32);
+    (value * scale).round() / scale
+}
+
+/// Locale and precision together, as each view holds them
+#[derive(Debug, Clone, Default)]
+pub struct NumberFormat {
+    pub locale: NumberLocale,
+    pub precision: Precision,
+}
+
+impl NumberFormat {
+    /// A price of `coin` with its decimal places, `default` unless overridden
+    pub fn price(&self, coin: &str, value: f64, default: usize) -> String {
+        self.locale
+            .format(value, self.precision.price(coin, default))
+    }
+
+    pub fn size(&self, coin: &str, value: f64, default: usize) -> String {
+        self.locale
+            .format(value, self.precision.size(coin, default))
+    }
+
+    /// Any other number, such as a notional, spread in basis points, or count
+    pub fn format(&self, value: f64, decimals: usize) -> String {
+        self.locale.format(value, decimals)
+    }
+
+    pub fn format_signed(&self, value: f64, decimals: usize) -> String {
+        self.locale.format_signed(value, decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_thousands_per_locale() {
+        for (locale, expected) in [
+            (NumberLocale::Plain, "1234567.89"),
+            (NumberLocale::En, "1,234,567.89"),
+            (NumberLocale::De, "1.234.567,89"),
+            (NumberLocale::Fr, "1 234 567,89"),
+            (NumberLocale::Ch, "1'234'567.89"),
+        ] {
+            assert_eq!(locale.format(1234567.891, 2), expected, "{:?}", locale);
+        }
+        assert_eq!(NumberLocale::En.format(999.0, 2), "999.00");
+        assert_eq!(NumberLocale::En.format(1000.0, 0), "1,000");
+        assert_eq!(NumberLocale::De.format(123456.0, 0), "123.456");
+    }
+
+    #[test]
+    fn keeps_the_sign_outside_the_groups() {
+        assert_eq!(NumberLocale::En.format(-1234567.891, 2), "-1,234,567.89");
+        assert_eq!(NumberLocale::De.format(-123456.0, 1), "-123.456,0");
+        assert_eq!(NumberLocale::Fr.format(-999.5, 1), "-999,5");
+        assert_eq!(NumberLocale::En.format_signed(1234.5, 1), "+1,234.5");
+        assert_eq!(NumberLocale::De.format_signed(-1234.5, 1), "-1.234,5");
+        assert_eq!(NumberLocale::Plain.format_signed(0.0, 2), "+0.00");
+    }
+
+    #[test]
+    fn leaves_non_finite_values_alone() {
+        assert_eq!(NumberLocale::En.format(f64::NAN, 2), "NaN");
+        assert_eq!(NumberLocale::De.format(f64::NEG_INFINITY, 2), "-inf");
+    }
+
+    #[test]
+    fn parses_styles_and_locale_tags() {
+        for (value, expected) in [
+            ("plain", NumberLocale::Plain),
+            ("EN", NumberLocale::En),
+            ("de_DE.UTF-8", NumberLocale::De),
+            ("fr-CA", NumberLocale::Fr),
+            ("de_CH", NumberLocale::Ch),
+            ("it_CH.UTF-8@euro", NumberLocale::Ch),
+            ("en_US", NumberLocale::En),
+            ("pt_BR", NumberLocale::De),
+            ("C.UTF-8", NumberLocale::Plain),
+            ("POSIX", NumberLocale::Plain),
+        ] {
+            assert_eq!(NumberLocale::parse(value).unwrap(), expected, "{}", value);
+        }
+        assert!(NumberLocale::parse("xx_YY").is_err());
+        assert!(NumberLocale::parse("").is_err());
+    }
+
+    #[test]
+    fn a_coin_setting_wins_over_every_coin() {
+        let precision = Precision::parse(
+            &["4".to_string(), "BTC=1".to_string()],
+            &["ETH = 3".to_string()],
+        )
+        .unwrap();
+        assert_eq!(precision.price("BTC", 2), 1);
+        assert_eq!(precision.price("ETH", 2), 4);
+        assert_eq!(precision.size("ETH", 5), 3);
+        assert_eq!(precision.size("BTC", 5), 5);
+        assert_eq!(precision.round_price("BTC", 112450.26), 112450.3);
+        assert_eq!(precision.round_size("BTC", 0.123456), 0.123456);
+
+        for value in ["13", "BTC=", "-1", "BTC=two"] {
+            assert!(
+                Precision::parse(&[value.to_string()], &[]).is_err(),
+                "{} accepted",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn rounds_event_levels_for_the_event_coin() {
+        let precision = Precision::parse(&["BTC=0".to_string()], &["1".to_string()]).unwrap();
+        let mut event = serde_json::json!({
+            "coin": "BTC",
+            "px": 112450.6,
+            "sz": 0.26,
+            "bids": [{"px": 112449.4, "sz": 1.04}],
+            "ask": {"px": 112451.5, "sz": 2.0},
+        });
+        precision.round_event(&mut event);
+        assert_eq!(
+            event,
+            serde_json::json!({
+                "coin": "BTC",
+                "px": 112451.0,
+                "sz": 0.3,
+                "bids": [{"px": 112449.0, "sz": 1.0}],
+                "ask": {"px": 112452.0, "sz": 2.0},
+            })
+        );
+    }
+}
@@ -0,0 +1,31 @@
+/// file: src/orders.rs
+/// description: Order lifecycle tracking that pairs each `orderUpdates` status with the one before
+use crate::types::OrderUpdate;
+use std::collections::HashMap;
+
+/// Last status of each of the account's live orders
+///
+/// `orderUpdates` sends an order's new status without the old one, so the transition is
+/// recovered from the previous update for the same `oid`. Orders leave the map once filled,
+/// canceled, or rejected, which keeps it to the account's open orders. An order placed before
+/// subscribing has no previous status.
+#[derive(Debug, Default)]
+pub struct OrderTracker {
+    statuses: HashMap<i64, String>,
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an update and return the order's status before it, if one was seen
+    pub fn observe(&mut self, update: &OrderUpdate) -> Option<String> {
+        let oid = update.order.oid;
+        if update.state().is_final() {
+            self.statuses.remove(&oid)
+        } else {
+            self.statuses.insert(oid, update.status.clone())
+        }
+    }
+}
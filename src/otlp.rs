@@ -0,0 +1,230 @@
+/// file: src/otlp.rs
+/// description: OpenTelemetry OTLP export of metrics and spans, configured from OTEL_* variables
+/// reference: https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/
+use crate::{error::HyperliquidError, monitoring::MetricsRecorder, tracing_setup::ExportLayer};
+use anyhow::Result;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use opentelemetry::{
+    KeyValue,
+    metrics::{Meter, MeterProvider},
+    trace::TracerProvider,
+};
+use opentelemetry_otlp::{MetricExporter, SpanExporter};
+use opentelemetry_sdk::{Resource, metrics::SdkMeterProvider, trace::SdkTracerProvider};
+use std::collections::HashMap;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+use tracing::warn;
+use tracing_subscriber::Layer;
+
+const SERVICE_NAME: &str = "rs-hyperliquid";
+
+/// Span and metric pipelines to an OTLP collector
+///
+/// Both export over HTTP/protobuf from background threads. Dropping it flushes whatever is still
+/// batched, so keep it alive until the session ends.
+pub struct OtlpExport {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+}
+
+impl OtlpExport {
+    /// Build the exporters from the `OTEL_*` environment
+    ///
+    /// The collector defaults to `http://localhost:4318`; `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// `OTEL_EXPORTER_OTLP_HEADERS`, `OTEL_METRIC_EXPORT_INTERVAL`, and the other standard
+    /// variables are read by the SDK.
+    pub fn from_env() -> Result<Self> {
+        let mut resource = Resource::builder()
+            .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")));
+        // The SDK's fallback is unknown_service; an explicit name in the environment still wins
+        let named_in_env = std::env::var_os("OTEL_SERVICE_NAME").is_some()
+            || std::env::var("OTEL_RESOURCE_ATTRIBUTES")
+                .is_ok_and(|attributes| attributes.contains("service.name="));
+        if !named_in_env {
+            resource = resource.with_service_name(SERVICE_NAME);
+        }
+        let resource = resource.build();
+
+        let spans = SpanExporter::builder()
+            .with_http()
+            .build()
+            .map_err(|e| HyperliquidError::MetricsError(format!("OTLP span exporter: {}", e)))?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans)
+            .with_resource(resource.clone())
+            .build();
+
+        let metrics = MetricExporter::builder()
+            .with_http()
+            .build()
+            .map_err(|e| HyperliquidError::MetricsError(format!("OTLP metric exporter: {}", e)))?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics)
+            .with_resource(resource)
+            .build();
+
+        Ok(Self {
+            tracer_provider,
+            meter_provider,
+        })
+    }
+
+    /// Forwards spans to the collector; goes under the log filter like every other layer
+    pub fn tracing_layer(&self) -> ExportLayer {
+        tracing_opentelemetry::layer()
+            .with_tracer(self.tracer_provider.tracer(SERVICE_NAME))
+            .boxed()
+    }
+
+    /// Mirrors the crate's `metrics` updates into OpenTelemetry instruments
+    pub fn metrics_recorder(&self) -> MetricsRecorder {
+        Box::new(OtlpRecorder {
+            meter: self.meter_provider.meter(SERVICE_NAME),
+            instruments: Mutex::default(),
+        })
+    }
+}
+
+impl Drop for OtlpExport {
+    /// Flush batched spans and send a final metrics export; the global subscriber and recorder
+    /// keep the providers alive, so they would never shut down on their own
+    fn drop(&mut self) {
+        if let Err(e) = self.tracer_provider.shutdown() {
+            warn!("OTLP span export shutdown failed: {}", e);
+        }
+        if let Err(e) = self.meter_provider.shutdown() {
+            warn!("OTLP metric export shutdown failed: {}", e);
+        }
+    }
+}
+
+/// Instruments created so far, so a key registered twice keeps one running total
+#[derive(Default)]
+struct Instruments {
+    counters: HashMap<Key, Arc<OtlpCounter>>,
+    gauges: HashMap<Key, Arc<OtlpGauge>>,
+    histograms: HashMap<Key, Arc<OtlpHistogram>>,
+}
+
+struct OtlpRecorder {
+    meter: Meter,
+    instruments: Mutex<Instruments>,
+}
+
+fn attributes(key: &Key) -> Vec<KeyValue> {
+    key.labels()
+        .map(|label| KeyValue::new(label.key().to_string(), label.value().to_string()))
+        .collect()
+}
+
+impl Recorder for OtlpRecorder {
+    // Names carry the meaning here; descriptions are not used by this crate
+    fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+    fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+    fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let mut instruments = self.instruments.lock().unwrap_or_else(|e| e.into_inner());
+        let counter = instruments.counters.entry(key.clone()).or_insert_with(|| {
+            Arc::new(OtlpCounter {
+                counter: self.meter.u64_counter(key.name().to_string()).build(),
+                attributes: attributes(key),
+                total: AtomicU64::new(0),
+            })
+        });
+        Counter::from_arc(counter.clone())
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let mut instruments = self.instruments.lock().unwrap_or_else(|e| e.into_inner());
+        let gauge = instruments.gauges.entry(key.clone()).or_insert_with(|| {
+            Arc::new(OtlpGauge {
+                gauge: self.meter.f64_gauge(key.name().to_string()).build(),
+                attributes: attributes(key),
+                value: Mutex::new(0.0),
+            })
+        });
+        Gauge::from_arc(gauge.clone())
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let mut instruments = self.instruments.lock().unwrap_or_else(|e| e.into_inner());
+        let histogram = instruments
+            .histograms
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(OtlpHistogram {
+                    histogram: self.meter.f64_histogram(key.name().to_string()).build(),
+                    attributes: attributes(key),
+                })
+            });
+        Histogram::from_arc(histogram.clone())
+    }
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    /// OpenTelemetry counters only take increments, so `absolute` adds the difference
+    total: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.total.fetch_add(value, Ordering::Relaxed);
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.total.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    gauge: opentelemetry::metrics::Gauge<f64>,
+    attributes: Vec<KeyValue>,
+    /// OpenTelemetry gauges only take readings, so increments are applied here first
+    value: Mutex<f64>,
+}
+
+impl OtlpGauge {
+    fn update(&self, apply: impl FnOnce(f64) -> f64) {
+        let mut value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+        *value = apply(*value);
+        self.gauge.record(*value, &self.attributes);
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value);
+    }
+
+    fn set(&self, value: f64) {
+        self.update(|_| value);
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
@@ -0,0 +1,120 @@
+/// file: src/output.rs
+/// description: Destination for the formatted trade stream, stdout or a buffered file
+use crate::error::HyperliquidError;
+use crate::lock::OutputLock;
+use crate::monitoring::SinkMetrics;
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::error;
+
+// Someone tailing the file sees new rows within this long even when trades are sparse
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where trade rows, table headers, and event lines are written
+///
+/// Banners, status lines, and the exit summary always stay on the terminal, so `--output` gets
+/// only the formatted stream.
+#[derive(Debug, Default)]
+pub struct TradeOutput {
+    file: Option<RefCell<FileOutput>>,
+}
+
+#[derive(Debug)]
+struct FileOutput {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    last_flush: Instant,
+    /// Rows written since the last flush, reported as the batch size
+    pending: usize,
+    metrics: SinkMetrics,
+    error: Option<String>,
+    _lock: OutputLock,
+}
+
+impl TradeOutput {
+    pub fn stdout() -> Self {
+        Self::default()
+    }
+
+    /// Create (or truncate) `path` up front so a bad path fails at startup
+    ///
+    /// The lock is taken first, so a second instance fails before truncating the first one's file.
+    pub fn create(path: &Path) -> Result<Self, HyperliquidError> {
+        let sink_error = |e: std::io::Error| {
+            HyperliquidError::SinkError(format!("cannot write to {}: {}", path.display(), e))
+        };
+        let lock = OutputLock::acquire(path).map_err(sink_error)?;
+        let file = File::create(path).map_err(sink_error)?;
+        Ok(Self {
+            file: Some(RefCell::new(FileOutput {
+                path: path.to_path_buf(),
+                writer: BufWriter::new(file),
+                last_flush: Instant::now(),
+                pending: 0,
+                metrics: SinkMetrics::new("output"),
+                error: None,
+                _lock: lock,
+            })),
+        })
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file.is_some()
+    }
+
+    pub fn line(&self, line: fmt::Arguments) {
+        let Some(file) = &self.file else {
+            println!("{}", line);
+            return;
+        };
+        let mut file = file.borrow_mut();
+        // After the first failure the rest of the stream is dropped and reported on exit
+        if file.error.is_some() {
+            return;
+        }
+        let mut result = writeln!(file.writer, "{}", line);
+        file.pending += 1;
+        if result.is_ok() && file.last_flush.elapsed() >= FLUSH_INTERVAL {
+            result = file.flush();
+        }
+        if let Err(e) = result {
+            let reason = format!("writing {} failed: {}", file.path.display(), e);
+            error!("{}", reason);
+            file.metrics.record_error();
+            file.error = Some(reason);
+        }
+    }
+
+    /// Flush buffered rows; reports the first write error of the session
+    pub fn finish(&self) -> Result<(), HyperliquidError> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let mut file = file.borrow_mut();
+        if file.error.is_none()
+            && let Err(e) = file.flush()
+        {
+            file.metrics.record_error();
+            file.error = Some(format!("writing {} failed: {}", file.path.display(), e));
+        }
+        match &file.error {
+            Some(reason) => Err(HyperliquidError::SinkError(reason.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FileOutput {
+    fn flush(&mut self) -> std::io::Result<()> {
+        let started = Instant::now();
+        self.writer.flush()?;
+        self.metrics
+            .record_batch(std::mem::take(&mut self.pending), started.elapsed());
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+/// file: src/parse_pool.rs
+/// description: Worker threads that decode large frames off the read loop, in order per stream
+use crate::{
+    compat::{ApiCompat, Decoded},
+    types::WebSocketMessage,
+};
+use chrono::{DateTime, Utc};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::Span;
+
+/// Workers started when `--parse-workers` is not given
+pub const DEFAULT_PARSE_WORKERS: usize = 2;
+/// Frames each worker queues before the read loop waits for it
+const WORKER_QUEUE: usize = 32;
+/// Channels whose frames are worth the hand-off, every coin's mid or both sides of a book, and
+/// whether their streams are split by `coin`
+const OFFLOADED_CHANNELS: [(&str, bool); 2] = [("allMids", false), ("l2Book", true)];
+/// Where the server puts `channel` and a book's `coin`; fields past this are not looked for
+const PEEK_BYTES: usize = 128;
+const CHANNEL_KEY: &str = "\"channel\":\"";
+const COIN_KEY: &str = "\"coin\":\"";
+
+struct Job {
+    text: String,
+    received: DateTime<Utc>,
+    span: Span,
+}
+
+/// A frame decoded on a worker, with what the read loop needs to finish handling it
+pub struct Parsed {
+    pub text: String,
+    pub message: serde_json::Result<WebSocketMessage<'static>>,
+    /// The frame in the canonical format, when `ApiCompat` rewrote it
+    pub normalized: Option<String>,
+    pub parse_time: Duration,
+    pub received: DateTime<Utc>,
+    pub span: Span,
+}
+
+/// What the read loop does with a frame after offering it to the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    /// A worker has it; the result comes back from `next`
+    Queued,
+    /// Not an offloaded stream, so parse it inline
+    Inline,
+    /// Its stream could not be read from its first bytes, so it may belong to one with frames
+    /// still on a worker: finish those, then parse it inline
+    AfterQueued,
+}
+
+/// A few threads that parse `allMids` and `l2Book` frames while the read loop keeps reading
+///
+/// Each stream, a channel and its coin, always goes to the same worker, and a worker handles its
+/// queue in order, so a stream's messages come back in the order they arrived. Different streams
+/// may finish out of order with each other and with frames parsed inline, such as trades. Queues
+/// are bounded: when a worker falls behind, the read loop waits for it rather than buffering.
+#[derive(Debug)]
+pub struct ParsePool {
+    workers: Vec<mpsc::Sender<Job>>,
+    results: mpsc::UnboundedReceiver<Parsed>,
+    /// Frames queued whose results `next` has not returned yet
+    in_flight: usize,
+}
+
+impl ParsePool {
+    /// Start `workers` threads, at least one, normalizing the variations `compat` allows
+    pub fn new(workers: usize, compat: ApiCompat) -> std::io::Result<Self> {
+        // Unbounded so a worker never waits on the read loop while the loop waits on its queue;
+        // results are at most what the worker queues held
+        let (result_sender, results) = mpsc::unbounded_channel();
+        let workers = (0..workers.max(1))
+            .map(|index| {
+                let (sender, jobs) = mpsc::channel(WORKER_QUEUE);
+                let results = result_sender.clone();
+                std::thread::Builder::new()
+                    .name(format!("parse-{}", index))
+                    .spawn(move || run(jobs, results, compat))?;
+                Ok(sender)
+            })
+            .collect::<std::io::Result<_>>()?;
+        Ok(Self {
+            workers,
+            results,
+            in_flight: 0,
+        })
+    }
+
+    /// Hand a frame to its stream's worker, or say how the caller should parse it instead
+    pub async fn submit(&mut self, text: &str, received: DateTime<Utc>, span: &Span) -> Submission {
+        let stream = match stream_key(text) {
+            Route::Stream(stream) => stream,
+            Route::Other => return Submission::Inline,
+            Route::Unknown if self.in_flight == 0 => return Submission::Inline,
+            Route::Unknown => return Submission::AfterQueued,
+        };
+        let mut hasher = DefaultHasher::new();
+        stream.hash(&mut hasher);
+        let worker = &self.workers[hasher.finish() as usize % self.workers.len()];
+        let job = Job {
+            text: text.to_string(),
+            received,
+            span: span.clone(),
+        };
+        // A worker only stops by panicking; its frames are then parsed inline
+        if worker.send(job).await.is_err() {
+            return Submission::Inline;
+        }
+        self.in_flight += 1;
+        Submission::Queued
+    }
+
+    /// Frames queued whose results have not been returned by `next`
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// The next decoded frame; cancel safe, so it can race the socket read
+    pub async fn next(&mut self) -> Option<Parsed> {
+        let parsed = self.results.recv().await?;
+        self.in_flight = self.in_flight.saturating_sub(1);
+        Some(parsed)
+    }
+}
+
+fn run(mut jobs: mpsc::Receiver<Job>, results: mpsc::UnboundedSender<Parsed>, compat: ApiCompat) {
+    while let Some(job) = jobs.blocking_recv() {
+        let started = Instant::now();
+        let Decoded {
+            message,
+            normalized,
+        } = compat.parse(&job.text);
+        let message = message.map(WebSocketMessage::into_owned);
+        let parsed = Parsed {
+            parse_time: started.elapsed(),
+            message,
+            normalized,
+            text: job.text,
+            received: job.received,
+            span: job.span,
+        };
+        if results.send(parsed).is_err() {
+            break;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route<'a> {
+    /// An offloaded channel and its coin, empty for channels not split by coin
+    Stream((&'a str, &'a str)),
+    /// A channel that is parsed inline
+    Other,
+    /// No channel, or an offloaded channel without its coin, in the frame's first bytes
+    Unknown,
+}
+
+/// Stream of a frame, read from its first bytes without parsing it
+///
+/// The server writes `channel` first and a book's `coin` first in `data`, so a plain search of
+/// the frame's start finds both. A frame laid out otherwise, such as one `--api-compat`
+/// normalizes, is `Unknown`.
+fn stream_key(text: &str) -> Route<'_> {
+    let head = match text.char_indices().nth(PEEK_BYTES) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+    let Some(channel) = string_field(head, CHANNEL_KEY) else {
+        return Route::Unknown;
+    };
+    let Some((_, by_coin)) = OFFLOADED_CHANNELS
+        .iter()
+        .find(|(offloaded, _)| *offloaded == channel)
+    else {
+        return Route::Other;
+    };
+    if !by_coin {
+        return Route::Stream((channel, ""));
+    }
+    match string_field(head, COIN_KEY) {
+        Some(coin) => Route::Stream((channel, coin)),
+        None => Route::Unknown,
+    }
+}
+
+/// The string value after `key`, a quoted field name with its colon and opening quote
+fn string_field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let start = text.find(key)? + key.len();
+    let len = text[start..].find('"')?;
+    Some(&text[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn books_are_keyed_by_channel_and_coin() {
+        let book = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1,"levels":[[],[]]}}"#;
+        assert_eq!(stream_key(book), Route::Stream(("l2Book", "ETH")));
+        let mids = r#"{"channel":"allMids","data":{"mids":{"BTC":"1"}}}"#;
+        assert_eq!(stream_key(mids), Route::Stream(("allMids", "")));
+        let trades = r#"{"channel":"trades","data":[]}"#;
+        assert_eq!(stream_key(trades), Route::Other);
+    }
+
+    #[test]
+    fn frames_without_a_readable_stream_are_unknown() {
+        let renamed = r#"{"type":"l2Book","data":{"coin":"ETH"}}"#;
+        assert_eq!(stream_key(renamed), Route::Unknown);
+        let late_coin = format!(
+            r#"{{"channel":"l2Book","data":{{"levels":[[{}],[]],"coin":"ETH"}}}}"#,
+            r#"{"px":"1","sz":"1","n":1},"#.repeat(8).trim_end_matches(',')
+        );
+        assert_eq!(stream_key(&late_coin), Route::Unknown);
+    }
+
+    #[tokio::test]
+    async fn unknown_frames_wait_for_queued_ones() {
+        let mut pool = ParsePool::new(1, ApiCompat::default()).unwrap();
+        let unknown = r#"{"type":"l2Book","data":{"coin":"ETH"}}"#;
+        let span = Span::none();
+        assert_eq!(
+            pool.submit(unknown, Utc::now(), &span).await,
+            Submission::Inline
+        );
+        let book = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1,"levels":[[],[]]}}"#;
+        assert_eq!(
+            pool.submit(book, Utc::now(), &span).await,
+            Submission::Queued
+        );
+        assert_eq!(
+            pool.submit(unknown, Utc::now(), &span).await,
+            Submission::AfterQueued
+        );
+        assert!(pool.next().await.unwrap().message.is_ok());
+        assert_eq!(pool.in_flight(), 0);
+    }
+}
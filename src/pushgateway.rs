@@ -0,0 +1,121 @@
+/// file: src/pushgateway.rs
+/// description: Prometheus Pushgateway backend for runs too short-lived to be scraped
+/// reference: https://github.com/prometheus/pushgateway#api
+use crate::{
+    error::HyperliquidError,
+    monitoring::{MetricsRecorder, prometheus_recorder},
+    rest::send_request,
+};
+use anyhow::Result;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use url::Url;
+
+// A long run still shows up on the gateway between the first and the final push
+const PUSH_INTERVAL: Duration = Duration::from_secs(10);
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pushes the rendered metrics to one Pushgateway job group
+///
+/// Each push replaces the whole group, so the last one before exit is what the gateway keeps.
+#[derive(Clone)]
+pub struct Pushgateway {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// `<gateway>/metrics/job/<job>`, without credentials
+    url: Url,
+    /// From `user:password@` in `--pushgateway-url`
+    authorization: Option<String>,
+    handle: PrometheusHandle,
+}
+
+/// Build the recorder and start pushing to `endpoint` every few seconds under `job`
+pub fn setup_pushgateway(endpoint: &Url, job: &str) -> Result<(MetricsRecorder, Pushgateway)> {
+    let invalid =
+        |reason: &str| HyperliquidError::ConfigError(format!("--pushgateway-url {}", reason));
+    if !matches!(endpoint.scheme(), "http" | "https") {
+        return Err(invalid("must be an http:// or https:// URL").into());
+    }
+    if job.is_empty() {
+        return Err(
+            HyperliquidError::ConfigError("--pushgateway-job cannot be empty".to_string()).into(),
+        );
+    }
+
+    let mut url = endpoint.clone();
+    let authorization = (!url.username().is_empty()).then(|| {
+        let credentials = format!("{}:{}", url.username(), url.password().unwrap_or_default());
+        format!("Basic {}", BASE64.encode(credentials))
+    });
+    // Kept out of the request line and every log message
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url.path_segments_mut()
+        .map_err(|_| invalid("cannot be a base URL"))?
+        .pop_if_empty()
+        .extend(["metrics", "job", job]);
+
+    let recorder = prometheus_recorder();
+    let pushgateway = Pushgateway {
+        inner: Arc::new(Inner {
+            url,
+            authorization,
+            handle: recorder.handle(),
+        }),
+    };
+
+    let periodic = pushgateway.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PUSH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = periodic.push().await {
+                warn!("Pushgateway push failed: {}", e);
+            }
+        }
+    });
+
+    info!("Pushing metrics to {}", pushgateway.inner.url);
+    Ok((Box::new(recorder), pushgateway))
+}
+
+impl Pushgateway {
+    /// Replace the job group with the current values
+    pub async fn push(&self) -> Result<()> {
+        let headers = self
+            .inner
+            .authorization
+            .as_ref()
+            .map(|authorization| format!("Authorization: {}\r\n", authorization))
+            .unwrap_or_default();
+        let body = self.inner.handle.render();
+        tokio::time::timeout(
+            PUSH_TIMEOUT,
+            send_request(
+                &self.inner.url,
+                "PUT",
+                "text/plain; version=0.0.4",
+                &headers,
+                &body,
+                PUSH_TIMEOUT,
+            ),
+        )
+        .await
+        .map_err(|_| HyperliquidError::Timeout)??;
+        debug!("Pushed {} bytes of metrics", body.len());
+        Ok(())
+    }
+
+    /// Last push on the way out; a failure is logged rather than changing the exit code
+    pub async fn push_final(&self) {
+        match self.push().await {
+            Ok(()) => info!("Final metrics pushed to {}", self.inner.url),
+            Err(e) => warn!("Final Pushgateway push failed: {}", e),
+        }
+    }
+}
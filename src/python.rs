@@ -0,0 +1,493 @@
+/// file: src/python.rs
+/// description: Python module exposing the client and its typed events as an async iterator
+/// reference: https://pyo3.rs
+use crate::{
+    candles::CandleMode,
+    cli::Args,
+    client::HyperliquidWebSocketClient,
+    client_state::ClientState,
+    config::Config,
+    events::{ClientEvent, EventReceiver, create_event_channel},
+    types::{Bbo, Book, Candle, Level, Trade},
+};
+use clap::Parser;
+use pyo3::exceptions::{PyRuntimeError, PyStopAsyncIteration, PyValueError};
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::task::{AbortHandle, JoinHandle};
+use tracing::debug;
+
+/// Runtime the clients run on, started with the first `Client`
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+/// Results being handed to an event loop from a runtime thread
+static DELIVERING: AtomicUsize = AtomicUsize::new(0);
+/// Longest the interpreter waits at exit for those deliveries
+const EXIT_WAIT: Duration = Duration::from_secs(1);
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("rs-hyperliquid")
+            .build()
+            .expect("failed to start the tokio runtime")
+    })
+}
+
+/// A live connection, iterated with `async for` to receive trades, books, BBOs, and candles
+///
+/// Settings are given as keyword arguments, with `args` taking any further command-line flag,
+/// and go through the same parsing and validation as the CLI. Config files are not read and the
+/// coin is not checked against the exchange's listed markets. Connection status events are not
+/// yielded; iteration ends when the client gives up reconnecting, raising its error, or after
+/// `close()`.
+#[pyclass(name = "Client", module = "rs_hyperliquid")]
+pub struct PyClient {
+    events: Arc<tokio::sync::Mutex<EventReceiver>>,
+    task: Arc<Mutex<Option<JoinHandle<anyhow::Result<()>>>>>,
+}
+
+#[pymethods]
+impl PyClient {
+    #[new]
+    #[pyo3(signature = (coin = "BTC", channels = None, intervals = None, user = None, url = None, candles = "all", args = None))]
+    fn new(
+        coin: &str,
+        channels: Option<Vec<String>>,
+        intervals: Option<Vec<String>>,
+        user: Option<String>,
+        url: Option<String>,
+        candles: &str,
+        args: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let mut argv = vec![
+            "rs-hyperliquid".to_string(),
+            "--coin".to_string(),
+            coin.to_string(),
+        ];
+        if let Some(channels) = channels {
+            argv.extend(["--subscribe".to_string(), channels.join(",")]);
+        }
+        for interval in intervals.unwrap_or_default() {
+            argv.extend(["--interval".to_string(), interval]);
+        }
+        for (flag, value) in [("--user", user), ("--url", url)] {
+            if let Some(value) = value {
+                argv.extend([flag.to_string(), value]);
+            }
+        }
+        argv.extend(args.unwrap_or_default());
+
+        let candles: CandleMode = candles.parse().map_err(PyValueError::new_err)?;
+
+        let stream = Args::try_parse_from(argv)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
+            .stream;
+        let config =
+            Config::from_args(&stream).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let (event_sender, event_receiver) = create_event_channel();
+        let state = Arc::new(tokio::sync::Mutex::new(ClientState::new()));
+        let mut client = HyperliquidWebSocketClient::new(Arc::new(config), event_sender, state)
+            .with_trade_filter(stream.trade_filter())
+            .with_api_compat(stream.api_compat)
+            .with_candle_mode(candles);
+        let task = runtime().spawn(async move { client.run().await });
+
+        Ok(Self {
+            events: Arc::new(tokio::sync::Mutex::new(event_receiver)),
+            task: Arc::new(Mutex::new(Some(task))),
+        })
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// An asyncio future for the next event, fetched on the runtime
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+        let events = Arc::clone(&self.events);
+        let task = Arc::clone(&self.task);
+        let (event_loop, target) = (event_loop.unbind(), future.clone().unbind());
+        let next = runtime().spawn(async move {
+            let result = next_event(&events, &task).await;
+            deliver(event_loop, target, result);
+        });
+        future.call_method1("add_done_callback", (AbortOnCancel(next.abort_handle()),))?;
+        Ok(future)
+    }
+
+    /// Disconnect; a pending or later `__anext__` ends the iteration
+    fn close(&self) {
+        abort(&self.task);
+    }
+}
+
+impl Drop for PyClient {
+    fn drop(&mut self) {
+        abort(&self.task);
+    }
+}
+
+fn abort(task: &Mutex<Option<JoinHandle<anyhow::Result<()>>>>) {
+    if let Some(task) = task.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        task.abort();
+    }
+}
+
+/// The next data event, or why the stream ended
+async fn next_event(
+    events: &tokio::sync::Mutex<EventReceiver>,
+    task: &Mutex<Option<JoinHandle<anyhow::Result<()>>>>,
+) -> PyResult<PyEvent> {
+    loop {
+        // Once closed, events still queued are dropped
+        if task.lock().unwrap_or_else(|e| e.into_inner()).is_none() {
+            return Err(PyStopAsyncIteration::new_err(()));
+        }
+        let Some(event) = events.lock().await.recv().await else {
+            return Err(finished(task).await);
+        };
+        if let Some(event) = PyEvent::from_event(event) {
+            return Ok(event);
+        }
+    }
+}
+
+/// Why the event channel closed: a normal stop, or the error that ended the client
+async fn finished(task: &Mutex<Option<JoinHandle<anyhow::Result<()>>>>) -> PyErr {
+    let task = task.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match task {
+        Some(task) => match task.await {
+            Ok(Err(e)) => PyRuntimeError::new_err(e.to_string()),
+            Err(e) if e.is_panic() => PyRuntimeError::new_err("the client task panicked"),
+            _ => PyStopAsyncIteration::new_err(()),
+        },
+        None => PyStopAsyncIteration::new_err(()),
+    }
+}
+
+/// Hand a result to its future on the event loop's thread
+///
+/// `call_soon_threadsafe` lets go of the GIL while it wakes the loop, and an interpreter that
+/// finalizes meanwhile tears this thread down as it takes the GIL back, crashing the process. The
+/// count lets `wait_for_deliveries` hold off exit until the thread is done with Python.
+fn deliver(event_loop: Py<PyAny>, future: Py<PyAny>, result: PyResult<PyEvent>) {
+    DELIVERING.fetch_add(1, Ordering::SeqCst);
+    Python::with_gil(move |py| {
+        let (value, error) = match result.and_then(|event| event.into_object(py)) {
+            Ok(value) => (value, None),
+            Err(error) => (py.None(), Some(error.into_value(py))),
+        };
+        let scheduled = wrap_pyfunction!(resolve, py).and_then(|resolve| {
+            event_loop.call_method1(py, "call_soon_threadsafe", (resolve, future, value, error))
+        });
+        if let Err(e) = scheduled {
+            // A closed loop has nothing left waiting on the result
+            debug!("Dropping an event for a closed event loop: {}", e);
+        }
+    });
+    DELIVERING.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// Settle a future unless it was cancelled in the meantime
+#[pyfunction]
+fn resolve(
+    future: &Bound<'_, PyAny>,
+    value: Bound<'_, PyAny>,
+    error: Option<Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    match error {
+        Some(error) => future.call_method1("set_exception", (error,))?,
+        None => future.call_method1("set_result", (value,))?,
+    };
+    Ok(())
+}
+
+/// Registered with `atexit`, so deliveries under way finish before the interpreter finalizes
+#[pyfunction]
+fn wait_for_deliveries(py: Python<'_>) {
+    py.allow_threads(|| {
+        let deadline = Instant::now() + EXIT_WAIT;
+        while DELIVERING.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    });
+}
+
+/// Done callback stopping the fetch for a future that was cancelled, such as by a timeout, so
+/// the event it would take goes to the next `__anext__`
+#[pyclass]
+struct AbortOnCancel(AbortHandle);
+
+#[pymethods]
+impl AbortOnCancel {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_truthy()? {
+            self.0.abort();
+        }
+        Ok(())
+    }
+}
+
+/// A data event as a Python class, built without the GIL
+enum PyEvent {
+    Trade(PyTrade),
+    Book(PyBook),
+    Bbo(PyBbo),
+    Candle(PyCandle),
+}
+
+impl PyEvent {
+    /// `None` for status events, which are not yielded
+    fn from_event(event: ClientEvent) -> Option<Self> {
+        let event = match event {
+            ClientEvent::TradeReceived(trade) => PyEvent::Trade(PyTrade::from(&*trade)),
+            ClientEvent::BookReceived(book) => PyEvent::Book(PyBook::from(&*book)),
+            ClientEvent::BboReceived(bbo) => PyEvent::Bbo(PyBbo::from(&*bbo)),
+            ClientEvent::CandleReceived { candle, closed } => {
+                PyEvent::Candle(PyCandle::new(&candle, closed))
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    fn into_object(self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            PyEvent::Trade(trade) => Py::new(py, trade).map(Py::into_any),
+            PyEvent::Book(book) => Py::new(py, book).map(Py::into_any),
+            PyEvent::Bbo(bbo) => Py::new(py, bbo).map(Py::into_any),
+            PyEvent::Candle(candle) => Py::new(py, candle).map(Py::into_any),
+        }
+    }
+}
+
+/// A trade; `side` is the exchange's `B` (buy) or `A` (sell)
+#[pyclass(name = "Trade", module = "rs_hyperliquid", frozen, get_all)]
+pub struct PyTrade {
+    coin: String,
+    side: String,
+    px: f64,
+    sz: f64,
+    /// Exchange time in milliseconds
+    time: i64,
+    tid: i64,
+    hash: String,
+    /// Buyer and seller addresses
+    users: Vec<String>,
+}
+
+impl From<&Trade> for PyTrade {
+    fn from(trade: &Trade) -> Self {
+        Self {
+            coin: trade.coin.to_string(),
+            side: trade.side.clone(),
+            px: trade.px,
+            sz: trade.sz,
+            time: trade.time,
+            tid: trade.tid,
+            hash: trade.hash.clone(),
+            users: trade.users.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyTrade {
+    #[getter]
+    fn is_buy(&self) -> bool {
+        Trade {
+            side: self.side.clone(),
+            ..Trade::default()
+        }
+        .is_buy()
+    }
+
+    /// Price times size, in quote currency
+    #[getter]
+    fn value(&self) -> f64 {
+        self.px * self.sz
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Trade(coin='{}', side='{}', px={}, sz={}, time={}, tid={})",
+            self.coin, self.side, self.px, self.sz, self.time, self.tid
+        )
+    }
+}
+
+/// One price level of a book or BBO
+#[pyclass(name = "Level", module = "rs_hyperliquid", frozen, get_all)]
+#[derive(Clone)]
+pub struct PyLevel {
+    px: f64,
+    sz: f64,
+    /// Number of orders at the level
+    n: i32,
+}
+
+impl From<&Level> for PyLevel {
+    fn from(level: &Level) -> Self {
+        Self {
+            px: level.px,
+            sz: level.sz,
+            n: level.n,
+        }
+    }
+}
+
+#[pymethods]
+impl PyLevel {
+    fn __repr__(&self) -> String {
+        format!("Level(px={}, sz={}, n={})", self.px, self.sz, self.n)
+    }
+}
+
+/// An order book snapshot, best levels first
+#[pyclass(name = "Book", module = "rs_hyperliquid", frozen, get_all)]
+pub struct PyBook {
+    coin: String,
+    time: i64,
+    bids: Vec<PyLevel>,
+    asks: Vec<PyLevel>,
+}
+
+impl From<&Book> for PyBook {
+    fn from(book: &Book) -> Self {
+        Self {
+            coin: book.coin.to_string(),
+            time: book.time,
+            bids: book.levels.0.iter().map(PyLevel::from).collect(),
+            asks: book.levels.1.iter().map(PyLevel::from).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyBook {
+    fn __repr__(&self) -> String {
+        format!(
+            "Book(coin='{}', time={}, bids={} levels, asks={} levels)",
+            self.coin,
+            self.time,
+            self.bids.len(),
+            self.asks.len()
+        )
+    }
+}
+
+/// Best bid and offer; a side is `None` while it is empty
+#[pyclass(name = "Bbo", module = "rs_hyperliquid", frozen, get_all)]
+pub struct PyBbo {
+    coin: String,
+    time: i64,
+    bid: Option<PyLevel>,
+    ask: Option<PyLevel>,
+}
+
+impl From<&Bbo> for PyBbo {
+    fn from(bbo: &Bbo) -> Self {
+        Self {
+            coin: bbo.coin.to_string(),
+            time: bbo.time,
+            bid: bbo.bbo.0.as_ref().map(PyLevel::from),
+            ask: bbo.bbo.1.as_ref().map(PyLevel::from),
+        }
+    }
+}
+
+#[pymethods]
+impl PyBbo {
+    fn __repr__(&self) -> String {
+        let px = |level: &Option<PyLevel>| {
+            level
+                .as_ref()
+                .map_or("None".to_string(), |level| level.px.to_string())
+        };
+        format!(
+            "Bbo(coin='{}', time={}, bid={}, ask={})",
+            self.coin,
+            self.time,
+            px(&self.bid),
+            px(&self.ask)
+        )
+    }
+}
+
+/// A candle update; `closed` marks the bar's final state, as with `--candles`
+#[pyclass(name = "Candle", module = "rs_hyperliquid", frozen, get_all)]
+pub struct PyCandle {
+    coin: String,
+    interval: String,
+    open_time: i64,
+    close_time: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: f64,
+    trades: i32,
+    closed: bool,
+}
+
+impl PyCandle {
+    fn new(candle: &Candle, closed: bool) -> Self {
+        Self {
+            coin: candle.s.to_string(),
+            interval: candle.i.clone(),
+            open_time: candle.t,
+            close_time: candle.close_time,
+            open: candle.o,
+            high: candle.h,
+            low: candle.l,
+            close: candle.c,
+            volume: candle.v,
+            trades: candle.n,
+            closed,
+        }
+    }
+}
+
+#[pymethods]
+impl PyCandle {
+    fn __repr__(&self) -> String {
+        format!(
+            "Candle(coin='{}', interval='{}', open_time={}, o={}, h={}, l={}, c={}, v={}, closed={})",
+            self.coin,
+            self.interval,
+            self.open_time,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            self.volume,
+            if self.closed { "True" } else { "False" }
+        )
+    }
+}
+
+#[pymodule]
+fn rs_hyperliquid(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    module.add_class::<PyClient>()?;
+    module.add_class::<PyTrade>()?;
+    module.add_class::<PyBook>()?;
+    module.add_class::<PyBbo>()?;
+    module.add_class::<PyLevel>()?;
+    module.add_class::<PyCandle>()?;
+    module.py().import("atexit")?.call_method1(
+        "register",
+        (wrap_pyfunction!(wait_for_deliveries, module)?,),
+    )?;
+    Ok(())
+}
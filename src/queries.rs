@@ -0,0 +1,122 @@
+/// file: src/queries.rs
+/// description: One-off info endpoint queries behind the snapshot, orders, and info subcommands
+/// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/info-endpoint
+use crate::{
+    cli::{InfoArgs, OrdersArgs, RestArgs, SnapshotArgs},
+    config::validate_address,
+    error::HyperliquidError,
+    formatter::BookLadderFormatter,
+    rest::InfoClient,
+    time_display::TimeDisplay,
+    types::{BasicOrder, Book, Meta, SpotMeta},
+    universe::spot_pair_name,
+};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::time::Duration;
+
+/// Print the order book of `args.coin` as a ladder
+pub async fn run_snapshot(args: SnapshotArgs) -> Result<()> {
+    let body = json!({ "type": "l2Book", "coin": args.coin });
+    // The exchange answers null for a coin it does not list
+    let Some(book) = query::<Option<Book>>(&args.rest, body).await? else {
+        return Ok(());
+    };
+    let book = book.ok_or_else(|| {
+        HyperliquidError::ConfigError(format!("no order book for coin '{}'", args.coin))
+    })?;
+    let ladder = BookLadderFormatter::new(false, args.depth, 0)
+        .with_in_place(false)
+        .with_unicode(false);
+    println!("{}", ladder.render(&book));
+    Ok(())
+}
+
+/// List the open orders of `args.user`, oldest first
+pub async fn run_orders(args: OrdersArgs) -> Result<()> {
+    let user = validate_address(&args.user)?;
+    let body = json!({ "type": "frontendOpenOrders", "user": user });
+    let Some(mut orders) = query::<Vec<BasicOrder>>(&args.rest, body).await? else {
+        return Ok(());
+    };
+    if orders.is_empty() {
+        println!("No open orders for {}", user);
+        return Ok(());
+    }
+    orders.sort_by_key(|order| order.timestamp);
+    let time_display = TimeDisplay::default();
+    println!(
+        "{:<19} {:<10} {:<4} {:>15} {:>14} {:>12}  CLOID",
+        "PLACED", "COIN", "SIDE", "FILLED/ORIG", "PRICE", "OID"
+    );
+    for order in &orders {
+        println!(
+            "{:<19} {:<10} {:<4} {:>15} {:>14.2} {:>12}  {}",
+            time_display.full(order.timestamp),
+            order.coin.as_str(),
+            order.side_formatted(),
+            format!("{:.4}/{:.4}", order.filled_sz(), order.orig_sz),
+            order.limit_px,
+            order.oid,
+            order.cloid.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// List the listed perpetuals, or the spot pairs with `--spot`
+pub async fn run_info(args: InfoArgs) -> Result<()> {
+    if args.spot {
+        let body = json!({ "type": "spotMeta" });
+        let Some(spot) = query::<SpotMeta>(&args.rest, body).await? else {
+            return Ok(());
+        };
+        println!("{:<12} {:<20} {:>6}", "PAIR", "MARKET", "INDEX");
+        for pair in &spot.universe {
+            let market = spot_pair_name(&spot, pair).unwrap_or_default();
+            println!("{:<12} {:<20} {:>6}", pair.name, market, pair.index);
+        }
+        return Ok(());
+    }
+
+    let body = json!({ "type": "meta" });
+    let Some(meta) = query::<Meta>(&args.rest, body).await? else {
+        return Ok(());
+    };
+    println!(
+        "{:<12} {:>11} {:>12}",
+        "COIN", "SZ DECIMALS", "MAX LEVERAGE"
+    );
+    for asset in meta
+        .universe
+        .iter()
+        .filter(|asset| args.delisted || !asset.is_delisted)
+    {
+        println!(
+            "{:<12} {:>11} {:>11}x{}",
+            asset.name,
+            asset.sz_decimals,
+            asset.max_leverage,
+            if asset.is_delisted { "  delisted" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// Send one info request; with `--json` print the response as is and return `None`
+async fn query<T: DeserializeOwned>(args: &RestArgs, body: serde_json::Value) -> Result<Option<T>> {
+    let client = InfoClient::from_ws_url(
+        &url::Url::parse(&args.url)?,
+        Duration::from_secs(args.timeout.max(1)),
+    )?
+    .with_headers(concat!("rs-hyperliquid/", env!("CARGO_PKG_VERSION")), &[]);
+    let response: serde_json::Value = client.post(&body).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(None);
+    }
+    Ok(Some(
+        serde_json::from_value(response).map_err(HyperliquidError::SerdeError)?,
+    ))
+}
@@ -0,0 +1,110 @@
+/// file: src/recent.rs
+/// description: Recent trades and latest book states kept for consumers that attach mid-session
+use crate::{
+    budget::{Buffer, MemoryBudget, push_ring, trade_bytes},
+    events::{ClientEvent, trade_json},
+    symbol::Symbol,
+    types::{Bbo, Book, Trade},
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Trades kept when `--recent-trades` is not given
+pub const DEFAULT_RECENT_TRADES: usize = 100;
+
+/// The last trades and the newest book and BBO per coin, newest trade last
+///
+/// A book or BBO replaces the whole previous state, so one per coin is enough to redraw it.
+#[derive(Debug)]
+pub struct RecentEvents {
+    trades: VecDeque<Arc<Trade>>,
+    capacity: usize,
+    budget: Option<Arc<MemoryBudget>>,
+    books: BTreeMap<Symbol, Arc<Book>>,
+    bbos: BTreeMap<Symbol, Arc<Bbo>>,
+}
+
+impl RecentEvents {
+    /// Keep up to `capacity` trades, fewer while `budget` is over; 0 keeps none but still tracks
+    /// the latest books
+    pub fn new(capacity: usize, budget: Option<Arc<MemoryBudget>>) -> Self {
+        Self {
+            trades: VecDeque::with_capacity(capacity.min(4096)),
+            capacity,
+            budget,
+            books: BTreeMap::new(),
+            bbos: BTreeMap::new(),
+        }
+    }
+
+    /// Remember a trade, book, or BBO event; other events are not kept
+    pub fn record(&mut self, event: &ClientEvent) {
+        match event {
+            ClientEvent::TradeReceived(trade) if self.capacity > 0 => push_ring(
+                &mut self.trades,
+                self.capacity,
+                Arc::clone(trade),
+                self.budget
+                    .as_deref()
+                    .map(|budget| (budget, Buffer::Recent)),
+                |trade| trade_bytes(trade),
+            ),
+            ClientEvent::BookReceived(book) => {
+                self.books.insert(book.coin.clone(), Arc::clone(book));
+            }
+            ClientEvent::BboReceived(bbo) => {
+                self.bbos.insert(bbo.coin.clone(), Arc::clone(bbo));
+            }
+            _ => {}
+        }
+    }
+
+    /// Events that rebuild the current view: books and BBOs, then trades oldest first
+    pub fn replay(&self) -> Vec<ClientEvent> {
+        let books = self
+            .books
+            .values()
+            .map(|book| ClientEvent::BookReceived(Arc::clone(book)));
+        let bbos = self
+            .bbos
+            .values()
+            .map(|bbo| ClientEvent::BboReceived(Arc::clone(bbo)));
+        let trades = self
+            .trades
+            .iter()
+            .map(|trade| ClientEvent::TradeReceived(Arc::clone(trade)));
+        books.chain(bbos).chain(trades).collect()
+    }
+
+    /// The kept events as JSON arrays, in the same shape as `--format events`
+    pub fn to_json(&self) -> serde_json::Value {
+        let trades: Vec<_> = self.trades.iter().map(|trade| trade_json(trade)).collect();
+        let books: Vec<_> = self
+            .books
+            .values()
+            .map(|book| ClientEvent::BookReceived(Arc::clone(book)).to_json())
+            .collect();
+        let bbos: Vec<_> = self
+            .bbos
+            .values()
+            .map(|bbo| ClientEvent::BboReceived(Arc::clone(bbo)).to_json())
+            .collect();
+        serde_json::json!({ "trades": trades, "books": books, "bbo": bbos })
+    }
+}
+
+/// Shared between the UI, which records every event, and the listeners that read it
+///
+/// A std mutex, since nothing awaits while holding it: the UI records and republishes an event
+/// under one lock, and a new subscriber copies the backlog and joins the bus under the same lock,
+/// so each event reaches it exactly once.
+pub type SharedRecent = Arc<Mutex<RecentEvents>>;
+
+pub fn shared(capacity: usize, budget: Option<Arc<MemoryBudget>>) -> SharedRecent {
+    Arc::new(Mutex::new(RecentEvents::new(capacity, budget)))
+}
+
+/// Lock the buffer; a panic elsewhere while it was held leaves it usable
+pub fn lock(recent: &SharedRecent) -> MutexGuard<'_, RecentEvents> {
+    recent.lock().unwrap_or_else(|e| e.into_inner())
+}
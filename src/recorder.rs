@@ -0,0 +1,289 @@
+/// file: src/recorder.rs
+/// description: Raw message recording to JSONL files with optional time-based rotation
+use crate::budget::{Buffer, MemoryBudget, line_bytes};
+use crate::error::HyperliquidError;
+use crate::lock::OutputLock;
+use crate::monitoring::SinkMetrics;
+use chrono::{DateTime, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{error, info, warn};
+
+// Raw frames queued for the writer thread; full queues drop frames instead of stalling the reader
+const RECORD_CHANNEL_CAPACITY: usize = 10_000;
+
+// Progress is logged on this cadence when no status bar is showing it
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+pub type RecordSender = mpsc::Sender<String>;
+
+#[derive(Debug, Clone)]
+pub struct RecordingConfig {
+    pub path: PathBuf,
+    /// Start a new timestamped file after this long; `None` writes a single file
+    pub rotate_every: Option<Duration>,
+    /// Periodically log progress (for unattended sessions without a status bar)
+    pub log_progress: bool,
+    /// Queued frames count against this; the sender charges them and the writer releases them
+    pub budget: Option<Arc<MemoryBudget>>,
+}
+
+/// Counters shared between the writer thread and status displays
+#[derive(Debug, Default)]
+pub struct RecordingProgress {
+    bytes_written: AtomicU64,
+    events_persisted: AtomicU64,
+    events_dropped: AtomicU64,
+    current_file: Mutex<String>,
+    next_rotation: Mutex<Option<Instant>>,
+}
+
+/// Point-in-time view of recording progress for display
+#[derive(Debug, Clone, Default)]
+pub struct RecordingSnapshot {
+    pub file: String,
+    pub bytes_written: u64,
+    pub events_persisted: u64,
+    pub events_dropped: u64,
+    pub rotation_in: Option<Duration>,
+}
+
+impl RecordingProgress {
+    pub fn snapshot(&self) -> RecordingSnapshot {
+        RecordingSnapshot {
+            file: self
+                .current_file
+                .lock()
+                .map(|file| file.clone())
+                .unwrap_or_default(),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            events_persisted: self.events_persisted.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            rotation_in: self
+                .next_rotation
+                .lock()
+                .ok()
+                .and_then(|next| *next)
+                .map(|next| next.saturating_duration_since(Instant::now())),
+        }
+    }
+
+    pub fn record_dropped(&self) {
+        self.events_dropped.fetch_add(1, Ordering::Relaxed);
+        crate::monitoring::record_dropped_events(crate::monitoring::EventConsumer::Recorder, 1);
+    }
+}
+
+/// Handle to the background writer; `finish` flushes and closes the current file
+pub struct Recorder {
+    sender: RecordSender,
+    progress: Arc<RecordingProgress>,
+    failure: watch::Receiver<Option<String>>,
+    handle: JoinHandle<()>,
+    /// Keyed on the configured path, so rotated files stay covered
+    _lock: OutputLock,
+}
+
+impl Recorder {
+    pub fn spawn(config: RecordingConfig) -> std::io::Result<Self> {
+        let progress = Arc::new(RecordingProgress::default());
+        let (sender, receiver) = mpsc::channel(RECORD_CHANNEL_CAPACITY);
+        let (failure_sender, failure) = watch::channel(None);
+
+        let lock = OutputLock::acquire(&config.path)?;
+        // Open the first file up front so a bad path fails at startup
+        let writer = RecordingWriter::open(config, progress.clone(), failure_sender)?;
+        let handle = std::thread::Builder::new()
+            .name("recorder".to_string())
+            .spawn(move || writer.run(receiver))?;
+
+        Ok(Self {
+            sender,
+            progress,
+            failure,
+            handle,
+            _lock: lock,
+        })
+    }
+
+    pub fn sender(&self) -> RecordSender {
+        self.sender.clone()
+    }
+
+    pub fn progress(&self) -> Arc<RecordingProgress> {
+        self.progress.clone()
+    }
+
+    /// Resolves with the error once the writer has given up; pending forever while it is healthy
+    pub async fn failed(&self) -> String {
+        let mut failure = self.failure.clone();
+        match failure.wait_for(Option::is_some).await {
+            Ok(reason) => reason.clone().unwrap_or_default(),
+            // The writer exited cleanly without reporting a failure
+            Err(_) => std::future::pending().await,
+        }
+    }
+
+    /// Wait for queued frames to be written; every other sender must already be dropped
+    pub fn finish(self) -> Result<(), HyperliquidError> {
+        drop(self.sender);
+        if self.handle.join().is_err() {
+            error!("Recorder thread panicked");
+        }
+        match self.failure.borrow().clone() {
+            Some(reason) => Err(HyperliquidError::SinkError(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+struct RecordingWriter {
+    config: RecordingConfig,
+    progress: Arc<RecordingProgress>,
+    writer: BufWriter<File>,
+    rotate_at: Option<Instant>,
+    failure: watch::Sender<Option<String>>,
+    metrics: SinkMetrics,
+}
+
+impl RecordingWriter {
+    fn open(
+        config: RecordingConfig,
+        progress: Arc<RecordingProgress>,
+        failure: watch::Sender<Option<String>>,
+    ) -> std::io::Result<Self> {
+        let (writer, rotate_at) = Self::open_file(&config, &progress)?;
+        Ok(Self {
+            config,
+            progress,
+            writer,
+            rotate_at,
+            failure,
+            metrics: SinkMetrics::new("recorder"),
+        })
+    }
+
+    fn open_file(
+        config: &RecordingConfig,
+        progress: &RecordingProgress,
+    ) -> std::io::Result<(BufWriter<File>, Option<Instant>)> {
+        let path = match config.rotate_every {
+            Some(_) => timestamped_path(&config.path, Utc::now()),
+            None => config.path.clone(),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        info!("Recording raw messages to {}", path.display());
+
+        let rotate_at = config.rotate_every.map(|every| Instant::now() + every);
+        if let Ok(mut current) = progress.current_file.lock() {
+            *current = path.display().to_string();
+        }
+        if let Ok(mut next) = progress.next_rotation.lock() {
+            *next = rotate_at;
+        }
+        Ok((BufWriter::new(file), rotate_at))
+    }
+
+    fn run(mut self, mut receiver: mpsc::Receiver<String>) {
+        let mut last_progress_log = Instant::now();
+
+        while let Some(line) = receiver.blocking_recv() {
+            // Drain whatever queued up, then flush once per batch
+            let started = Instant::now();
+            let mut lines = 1;
+            let mut batch = self.write_line(&line);
+            while batch.is_ok()
+                && let Ok(line) = receiver.try_recv()
+            {
+                batch = self.write_line(&line);
+                lines += 1;
+            }
+            if let Err(e) = batch.and_then(|_| self.writer.flush()) {
+                // A disk that stopped accepting writes will not recover mid-session
+                self.fail(format!("recording write failed: {}", e));
+                return;
+            }
+            self.metrics.record_batch(lines, started.elapsed());
+
+            if self.config.log_progress && last_progress_log.elapsed() >= PROGRESS_LOG_INTERVAL {
+                let snapshot = self.progress.snapshot();
+                info!(
+                    "Recording progress: {} events, {} bytes, {} dropped, file {}",
+                    snapshot.events_persisted,
+                    snapshot.bytes_written,
+                    snapshot.events_dropped,
+                    snapshot.file
+                );
+                last_progress_log = Instant::now();
+            }
+        }
+
+        if let Err(e) = self.writer.flush() {
+            self.fail(format!("recording flush failed: {}", e));
+        }
+    }
+
+    fn fail(&self, reason: String) {
+        error!("{}", reason);
+        self.metrics.record_error();
+        self.failure.send_replace(Some(reason));
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if let Some(budget) = &self.config.budget {
+            budget.release(Buffer::Recorder, line_bytes(line));
+        }
+        if self.rotate_at.is_some_and(|at| Instant::now() >= at) {
+            self.writer.flush()?;
+            match Self::open_file(&self.config, &self.progress) {
+                Ok((writer, rotate_at)) => {
+                    self.writer = writer;
+                    self.rotate_at = rotate_at;
+                }
+                Err(e) => {
+                    warn!("Recording rotation failed, continuing current file: {}", e);
+                    self.metrics.record_error();
+                }
+            }
+        }
+
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.progress
+            .bytes_written
+            .fetch_add(line.len() as u64 + 1, Ordering::Relaxed);
+        self.progress
+            .events_persisted
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// `capture.jsonl` -> `capture-20240101-120000.jsonl`
+pub(crate) fn timestamped_path(path: &Path, at: DateTime<Utc>) -> PathBuf {
+    let stamp = at.format("%Y%m%d-%H%M%S");
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "recording".to_string());
+    let name = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, stamp, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, stamp),
+    };
+    path.with_file_name(name)
+}
+
+/// Wrap a raw frame with its receive time: `{"recv_ms":..,"msg":..}`
+pub fn record_line(raw: &str, received: DateTime<Utc>) -> String {
+    format!(
+        "{{\"recv_ms\":{},\"msg\":{}}}",
+        received.timestamp_millis(),
+        raw
+    )
+}
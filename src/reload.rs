@@ -0,0 +1,73 @@
+/// file: src/reload.rs
+/// description: Re-read the config file on SIGHUP and apply the settings that are safe to change live
+use crate::{
+    config::ConfigFile,
+    input::{InputCommand, InputSender},
+    tracing_setup::{LogLevelHandle, set_log_level},
+};
+use std::path::PathBuf;
+use tokio::signal::unix::{SignalKind, signal};
+use tracing::{info, warn};
+
+/// Reload `path` on every SIGHUP, keeping the WebSocket connection open
+///
+/// The log level and trade filter are applied immediately. Changes to connection, subscription,
+/// or metrics settings are reported but wait for the next restart.
+pub fn spawn_config_reloader(
+    path: PathBuf,
+    loaded: ConfigFile,
+    log_handle: LogLevelHandle,
+    input_sender: InputSender,
+) -> std::io::Result<()> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        // Compared against the file as it was at startup, which is what the connection uses
+        let startup = loaded;
+        while hangup.recv().await.is_some() {
+            let reloaded = match ConfigFile::load(&path) {
+                Ok(reloaded) => reloaded,
+                Err(e) => {
+                    warn!("Config reload failed, keeping current settings: {}", e);
+                    continue;
+                }
+            };
+            let filter = match reloaded.trade_filter() {
+                Ok(filter) => filter,
+                Err(e) => {
+                    warn!("Config reload failed, keeping current settings: {}", e);
+                    continue;
+                }
+            };
+
+            if let Some(level) = &reloaded.log.level
+                && let Err(e) = set_log_level(&log_handle, level)
+            {
+                warn!(
+                    "Ignoring log level '{}' from {}: {}",
+                    level,
+                    path.display(),
+                    e
+                );
+            }
+            if input_sender
+                .send(InputCommand::SetFilter(filter))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let restart = reloaded.restart_required(&startup);
+            if restart.is_empty() {
+                info!("Reloaded config file {}", path.display());
+            } else {
+                warn!(
+                    "Reloaded config file {}; changes to [{}] apply after a restart",
+                    path.display(),
+                    restart.join("], [")
+                );
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,348 @@
+/// file: src/replay.rs
+/// description: Play recorded raw messages back through the event bus as if they were live
+use crate::{
+    candles::{CandleMode, CandleTracker},
+    cli::parse_duration,
+    compat::ApiCompat,
+    error::HyperliquidError,
+    events::{ClientEvent, EventSender},
+    filter::TradeFilter,
+    types::{Candle, UserEvent, WebSocketMessage},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{debug, info, warn};
+
+/// One line of a recording as written by the recorder
+#[derive(Debug, Deserialize)]
+struct RecordedLine<'a> {
+    recv_ms: i64,
+    #[serde(borrow)]
+    msg: &'a serde_json::value::RawValue,
+}
+
+/// Playback rate relative to the recorded timing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Gaps between messages are divided by this factor
+    Factor(f64),
+    /// No sleeping at all
+    Max,
+}
+
+impl ReplaySpeed {
+    /// How long to wait for a recorded gap, or `None` when playing as fast as possible
+    fn delay(self, gap_ms: i64) -> Option<Duration> {
+        match self {
+            ReplaySpeed::Factor(factor) => Some(Duration::from_secs_f64(
+                gap_ms.max(0) as f64 / 1000.0 / factor,
+            )),
+            ReplaySpeed::Max => None,
+        }
+    }
+}
+
+impl Default for ReplaySpeed {
+    fn default() -> Self {
+        ReplaySpeed::Factor(1.0)
+    }
+}
+
+impl fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplaySpeed::Factor(factor) => write!(f, "{}x", factor),
+            ReplaySpeed::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    /// `max`, or a positive factor such as `2`, `0.5`, or `10x`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase();
+        if value == "max" {
+            return Ok(ReplaySpeed::Max);
+        }
+        value
+            .strip_suffix('x')
+            .unwrap_or(&value)
+            .parse::<f64>()
+            .ok()
+            .filter(|factor| factor.is_finite() && *factor > 0.0)
+            .map(ReplaySpeed::Factor)
+            .ok_or_else(|| {
+                format!(
+                    "invalid speed '{}', expected a positive factor such as 2 or 0.5, or max",
+                    value
+                )
+            })
+    }
+}
+
+/// Where in a recording playback starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekPoint {
+    /// First message received at or after this time
+    At(DateTime<Utc>),
+    /// This far past the first message of the recording
+    Offset(Duration),
+}
+
+impl SeekPoint {
+    fn start_ms(self, first_recv_ms: i64) -> i64 {
+        match self {
+            SeekPoint::At(at) => at.timestamp_millis(),
+            SeekPoint::Offset(offset) => {
+                first_recv_ms.saturating_add(offset.as_millis().min(i64::MAX as u128) as i64)
+            }
+        }
+    }
+}
+
+impl FromStr for SeekPoint {
+    type Err = String;
+
+    /// `+90s`/`+15m` from the start, an RFC3339 time, or unix milliseconds
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Some(offset) = value.strip_prefix('+') {
+            return parse_duration(offset).map(SeekPoint::Offset);
+        }
+        if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+            return Ok(SeekPoint::At(at.with_timezone(&Utc)));
+        }
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+            .map(SeekPoint::At)
+            .ok_or_else(|| {
+                format!(
+                    "invalid seek point '{}', expected +OFFSET (e.g. +5m), RFC3339, or unix milliseconds",
+                    value
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    pub path: PathBuf,
+    /// `Max` replays as fast as possible, as `export` does
+    pub speed: ReplaySpeed,
+    /// Start over from the seek point each time the file is exhausted
+    pub looped: bool,
+    pub seek: Option<SeekPoint>,
+    /// Same pre-UI filter the live client applies
+    pub filter: TradeFilter,
+    /// Recordings from before a format change parse as they did live
+    pub compat: ApiCompat,
+    pub candles: CandleMode,
+}
+
+/// How one pass over the recording ended
+enum Pass {
+    /// End of file, with the number of messages played
+    Finished(u64),
+    /// The UI hung up (quit key or max trades), nothing left to feed
+    Closed,
+}
+
+/// Read a recording and send its messages as client events; returns once the file is exhausted
+/// (never, with `looped`, unless the UI stops first)
+pub async fn replay_recording(config: ReplayConfig, sender: EventSender) -> Result<()> {
+    info!("Replaying {} at {}", config.path.display(), config.speed);
+
+    let _ = sender.send(ClientEvent::Starting).await;
+    loop {
+        let played = match replay_pass(&config, &sender).await? {
+            Pass::Finished(played) => played,
+            Pass::Closed => return Ok(()),
+        };
+        // An empty pass would also spin forever with --loop
+        if played == 0 {
+            warn!(
+                "Nothing to replay in {} at or after the seek point",
+                config.path.display()
+            );
+            break;
+        }
+        if !config.looped {
+            break;
+        }
+        debug!(
+            "Looping {} after {} messages",
+            config.path.display(),
+            played
+        );
+    }
+
+    let _ = sender.send(ClientEvent::Stopping).await;
+    Ok(())
+}
+
+async fn replay_pass(config: &ReplayConfig, sender: &EventSender) -> Result<Pass> {
+    let file = tokio::fs::File::open(&config.path).await.map_err(|e| {
+        HyperliquidError::ConfigError(format!("cannot open {}: {}", config.path.display(), e))
+    })?;
+    let mut lines = BufReader::new(file).lines();
+    let mut previous_ms: Option<i64> = None;
+    let mut seek_ms: Option<i64> = None;
+    let mut line_number = 0u64;
+    let mut played = 0u64;
+    // Per pass, so a looped replay closes its bars again from the start
+    let mut candles = CandleTracker::new(config.candles);
+
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let recorded: RecordedLine = match serde_json::from_str(&line) {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                warn!("Skipping malformed recording line {}: {}", line_number, e);
+                continue;
+            }
+        };
+
+        let start_ms = *seek_ms.get_or_insert_with(|| {
+            config
+                .seek
+                .map_or(i64::MIN, |seek| seek.start_ms(recorded.recv_ms))
+        });
+        let message = match config.compat.parse(recorded.msg.get()).message {
+            Ok(message) => message,
+            Err(e) => {
+                debug!("Skipping unparsed message on line {}: {}", line_number, e);
+                continue;
+            }
+        };
+        // Before the seek point only subscription confirmations pass, so headers still print
+        if recorded.recv_ms < start_ms {
+            if matches!(message, WebSocketMessage::SubscriptionResponse(_)) {
+                for event in message_events(message, &mut candles) {
+                    if sender.send(event).await.is_err() {
+                        return Ok(Pass::Closed);
+                    }
+                }
+            }
+            continue;
+        }
+
+        if let Some(previous_ms) = previous_ms
+            && let Some(delay) = config.speed.delay(recorded.recv_ms - previous_ms)
+        {
+            tokio::time::sleep(delay).await;
+        }
+        previous_ms = Some(recorded.recv_ms);
+        played += 1;
+
+        for event in message_events(message, &mut candles) {
+            if let ClientEvent::TradeReceived(trade) = &event
+                && !config.filter.matches(trade)
+            {
+                continue;
+            }
+            if sender.send(event).await.is_err() {
+                return Ok(Pass::Closed);
+            }
+        }
+    }
+
+    Ok(Pass::Finished(played))
+}
+
+/// Events the live client would emit for a parsed message
+fn message_events(message: WebSocketMessage<'_>, candles: &mut CandleTracker) -> Vec<ClientEvent> {
+    match message {
+        WebSocketMessage::SubscriptionResponse(response) => {
+            vec![ClientEvent::SubscriptionConfirmed {
+                sub_type: response.data.subscription.subscription_type,
+                coin: response.data.subscription.coin,
+                interval: response.data.subscription.interval,
+            }]
+        }
+        WebSocketMessage::TradeData(trade_data) => trade_data
+            .data
+            .into_iter()
+            .map(|trade| ClientEvent::TradeReceived(Arc::new(trade.into_owned())))
+            .collect(),
+        WebSocketMessage::DirectTrades(trades) => trades
+            .into_iter()
+            .map(|trade| ClientEvent::TradeReceived(Arc::new(trade.into_owned())))
+            .collect(),
+        WebSocketMessage::BookData(book_data) => {
+            vec![ClientEvent::BookReceived(Arc::new(book_data.data))]
+        }
+        WebSocketMessage::BboData(bbo_data) => {
+            vec![ClientEvent::BboReceived(Arc::new(bbo_data.data))]
+        }
+        WebSocketMessage::AllMidsData(all_mids_data) => {
+            vec![ClientEvent::AllMidsReceived(Arc::new(all_mids_data.data))]
+        }
+        WebSocketMessage::CandleData(candle_data) => candle_events(candle_data.data, candles),
+        WebSocketMessage::DirectCandles(candle_list) => candle_events(candle_list, candles),
+        WebSocketMessage::UserEvent(message) => match message.data {
+            UserEvent::Fills {
+                fills, is_snapshot, ..
+            } => fills
+                .into_iter()
+                .map(|fill| ClientEvent::FillReceived {
+                    fill: Arc::new(fill),
+                    snapshot: is_snapshot.unwrap_or(false),
+                })
+                .collect(),
+            UserEvent::Funding { funding } => vec![ClientEvent::FundingReceived(Arc::new(funding))],
+            UserEvent::Liquidation { liquidation } => {
+                vec![ClientEvent::LiquidationReceived(Arc::new(liquidation))]
+            }
+            UserEvent::NonUserCancel { non_user_cancel } => non_user_cancel
+                .into_iter()
+                .map(|cancel| ClientEvent::NonUserCancelReceived(Arc::new(cancel)))
+                .collect(),
+        },
+        WebSocketMessage::UserFundings(message) => message
+            .data
+            .fundings
+            .into_iter()
+            .map(|funding| ClientEvent::FundingReceived(Arc::new(funding)))
+            .collect(),
+        WebSocketMessage::OrderUpdates(message) => message
+            .data
+            .into_iter()
+            .map(|update| ClientEvent::OrderUpdated(Arc::new(update)))
+            .collect(),
+        WebSocketMessage::DirectBlocks(blocks) => blocks
+            .into_iter()
+            .map(|block| ClientEvent::BlockReceived(Arc::new(block)))
+            .collect(),
+        WebSocketMessage::DirectTxs(txs) => txs
+            .into_iter()
+            .map(|tx| ClientEvent::TxReceived(Arc::new(tx)))
+            .collect(),
+        WebSocketMessage::Error(message) => vec![ClientEvent::ServerError(message.data)],
+        _ => Vec::new(),
+    }
+}
+
+fn candle_events(candle_list: Vec<Candle>, candles: &mut CandleTracker) -> Vec<ClientEvent> {
+    candle_list
+        .into_iter()
+        .flat_map(|candle| candles.observe(candle))
+        .map(|update| ClientEvent::CandleReceived {
+            candle: Arc::new(update.candle),
+            closed: update.closed,
+        })
+        .collect()
+}
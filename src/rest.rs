@@ -0,0 +1,257 @@
+/// file: src/rest.rs
+/// description: Minimal HTTP client for the Hyperliquid info endpoint
+/// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/info-endpoint
+use crate::{
+    error::HyperliquidError,
+    transport::{connect, header_lines},
+    types::{Meta, SpotMeta},
+};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout;
+use tracing::{Instrument, debug, info_span};
+
+/// Largest response read, headers included; the biggest info responses are a few hundred KB
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Sends `POST /info` requests on the same host as the WebSocket endpoint
+#[derive(Debug, Clone)]
+pub struct InfoClient {
+    url: url::Url,
+    timeout: Duration,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl InfoClient {
+    /// Derive the info endpoint from a WebSocket URL (`wss://host/ws` becomes `https://host/info`)
+    pub fn from_ws_url(ws_url: &url::Url, timeout: Duration) -> Result<Self> {
+        let mut url = ws_url.clone();
+        let scheme = match url.scheme() {
+            "wss" | "https" => "https",
+            _ => "http",
+        };
+        url.set_scheme(scheme).map_err(|_| {
+            HyperliquidError::HttpError(format!("cannot derive REST URL from {}", ws_url))
+        })?;
+        url.set_path("/info");
+        url.set_query(None);
+        Ok(Self {
+            url,
+            timeout,
+            user_agent: None,
+            headers: Vec::new(),
+        })
+    }
+
+    /// Identify requests the same way as the WebSocket handshake
+    pub fn with_headers(mut self, user_agent: &str, headers: &[(String, String)]) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self.headers = headers.to_vec();
+        self
+    }
+
+    /// Perpetuals universe with every listed coin
+    pub async fn meta(&self) -> Result<Meta> {
+        self.post(&json!({ "type": "meta" })).await
+    }
+
+    /// Spot tokens and the pairs built from them
+    pub async fn spot_meta(&self) -> Result<SpotMeta> {
+        self.post(&json!({ "type": "spotMeta" })).await
+    }
+
+    /// Send one info request and decode the JSON response
+    pub async fn post<T: DeserializeOwned>(&self, body: &serde_json::Value) -> Result<T> {
+        let request_type = body["type"].as_str().unwrap_or_default();
+        let response = timeout(self.timeout, self.exchange(body.to_string()))
+            .instrument(info_span!("info_request", request_type))
+            .await
+            .map_err(|_| HyperliquidError::Timeout)??;
+        Ok(serde_json::from_slice(&response).map_err(HyperliquidError::SerdeError)?)
+    }
+
+    async fn exchange(&self, body: String) -> Result<Vec<u8>> {
+        debug!("POST {} {}", self.url, body);
+        let headers = format!(
+            "{}{}",
+            self.user_agent
+                .as_ref()
+                .map(|agent| format!("User-Agent: {}\r\n", agent))
+                .unwrap_or_default(),
+            header_lines(&self.headers)
+        );
+        send_request(
+            &self.url,
+            "POST",
+            "application/json",
+            &headers,
+            &body,
+            self.timeout,
+        )
+        .await
+    }
+}
+
+/// Send one request and return the body of a 2xx response
+///
+/// `headers` are extra CRLF-terminated lines. One request per connection keeps the response
+/// framing to "read until close".
+pub(crate) async fn send_request(
+    url: &url::Url,
+    method: &str,
+    content_type: &str,
+    headers: &str,
+    body: &str,
+    connect_timeout: Duration,
+) -> Result<Vec<u8>> {
+    let host = url.host_str().unwrap_or_default();
+    let mut stream = connect(url, connect_timeout).await?;
+    let request = format!(
+        "{} {} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         {}\r\n\
+         {}",
+        method,
+        url.path(),
+        host,
+        content_type,
+        body.len(),
+        headers,
+        body
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let raw = read_capped(stream, MAX_RESPONSE_BYTES).await?;
+    parse_response(&raw)
+}
+
+/// Read until close, failing once more than `limit` bytes arrive
+async fn read_capped(reader: impl AsyncRead + Unpin, limit: u64) -> Result<Vec<u8>> {
+    let mut raw = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut raw).await?;
+    if raw.len() as u64 > limit {
+        return Err(
+            HyperliquidError::HttpError(format!("response larger than {} bytes", limit)).into(),
+        );
+    }
+    Ok(raw)
+}
+
+/// Split an HTTP/1.1 response into status and body, undoing chunked transfer encoding
+fn parse_response(raw: &[u8]) -> Result<Vec<u8>> {
+    let header_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| HyperliquidError::HttpError("truncated response headers".to_string()))?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if !status.starts_with('2') {
+        return Err(HyperliquidError::HttpError(format!(
+            "{}: {}",
+            status_line,
+            String::from_utf8_lossy(body).trim()
+        ))
+        .into());
+    }
+
+    let chunked = head.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>> {
+    let malformed = || HyperliquidError::HttpError("malformed chunked response".to_string());
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(malformed)?;
+        let size_field = String::from_utf8_lossy(&body[..line_end]);
+        let size_hex = size_field.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16).map_err(|_| malformed())?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        if body.len() < size {
+            return Err(malformed().into());
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_the_body_of_a_2xx_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"a\":1}";
+        assert_eq!(parse_response(raw).unwrap(), b"{\"a\":1}");
+    }
+
+    #[test]
+    fn reports_the_status_line_and_body_of_an_error() {
+        let raw = b"HTTP/1.1 429 Too Many Requests\r\n\r\nslow down\n";
+        let error = parse_response(raw).unwrap_err().to_string();
+        assert!(
+            error.contains("HTTP/1.1 429 Too Many Requests: slow down"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_headers() {
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n").is_err());
+    }
+
+    #[test]
+    fn decodes_chunks_with_extensions_and_trailers() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: Chunked\r\n\r\n\
+            4;name=value\r\n{\"a\"\r\nB\r\n:[1,2,3,4]}\r\n0\r\nX-Trailer: yes\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap(), b"{\"a\":[1,2,3,4]}");
+    }
+
+    #[test]
+    fn rejects_truncated_chunks_and_bad_sizes() {
+        for body in [
+            &b"a\r\nshort"[..],
+            b"zz\r\nabc\r\n0\r\n\r\n",
+            b"3\r\nabc\r\n",
+            b"3",
+        ] {
+            assert!(
+                decode_chunked(body).is_err(),
+                "{:?}",
+                String::from_utf8_lossy(body)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn caps_the_response_size() {
+        assert_eq!(read_capped(&b"12345"[..], 5).await.unwrap(), b"12345");
+        assert!(read_capped(&b"123456"[..], 5).await.is_err());
+    }
+}
@@ -0,0 +1,82 @@
+/// file: src/rolling.rs
+/// description: Rolling trade statistics over the most recent trades for the table footer
+use crate::types::Trade;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy)]
+struct WindowTrade {
+    px: f64,
+    sz: f64,
+    is_buy: bool,
+    time: i64,
+}
+
+/// VWAP, buy/sell volume ratio and trade rate over a bounded window
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    trades: VecDeque<WindowTrade>,
+    capacity: usize,
+}
+
+impl RollingStats {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            trades: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append a trade, evicting the oldest one once the window is full
+    pub fn push(&mut self, trade: &Trade) {
+        if self.trades.len() == self.capacity {
+            self.trades.pop_front();
+        }
+        self.trades.push_back(WindowTrade {
+            px: trade.px,
+            sz: trade.sz,
+            is_buy: trade.is_buy(),
+            time: trade.time,
+        });
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    pub fn vwap(&self) -> Option<f64> {
+        let volume: f64 = self.trades.iter().map(|trade| trade.sz).sum();
+        if volume <= 0.0 {
+            return None;
+        }
+        let notional: f64 = self.trades.iter().map(|trade| trade.px * trade.sz).sum();
+        Some(notional / volume)
+    }
+
+    /// Buy volume divided by sell volume; `None` until both sides have traded
+    pub fn buy_sell_ratio(&self) -> Option<f64> {
+        let (buys, sells) = self.trades.iter().fold((0.0, 0.0), |(buys, sells), trade| {
+            if trade.is_buy {
+                (buys + trade.sz, sells)
+            } else {
+                (buys, sells + trade.sz)
+            }
+        });
+        (sells > 0.0).then(|| buys / sells)
+    }
+
+    /// Trades per second between the oldest and newest exchange timestamps
+    pub fn trades_per_sec(&self) -> Option<f64> {
+        let (first, last) = (self.trades.front()?, self.trades.back()?);
+        let span_ms = last.time - first.time;
+        (span_ms > 0).then(|| (self.trades.len() - 1) as f64 * 1000.0 / span_ms as f64)
+    }
+}
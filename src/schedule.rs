@@ -0,0 +1,222 @@
+/// file: src/schedule.rs
+/// description: Collection windows from --start-at and --stop-at
+use crate::error::HyperliquidError;
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The connection opens this long before `--start-at` so collection starts on a warm stream
+pub const CONNECT_LEAD: Duration = Duration::from_secs(30);
+
+/// A point in time from the command line: an exact RFC3339 instant or a local wall-clock time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTime {
+    At(DateTime<Utc>),
+    /// The next occurrence of this local time
+    Daily(NaiveTime),
+}
+
+impl ScheduleTime {
+    /// The instant this refers to, taking `Daily` times as the first occurrence after `after`
+    pub fn resolve(self, after: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            ScheduleTime::At(at) => at,
+            ScheduleTime::Daily(time) => {
+                let local_date = after.with_timezone(&Local).date_naive();
+                // Both days are needed when the time already passed today
+                [local_date, local_date + chrono::Days::new(1)]
+                    .into_iter()
+                    .filter_map(|date| Local.from_local_datetime(&date.and_time(time)).earliest())
+                    .map(|at| at.with_timezone(&Utc))
+                    .find(|at| *at > after)
+                    // Only reachable when a DST jump skips the time on both days
+                    .unwrap_or(after)
+            }
+        }
+    }
+}
+
+impl FromStr for ScheduleTime {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+            return Ok(ScheduleTime::At(at.with_timezone(&Utc)));
+        }
+        ["%H:%M", "%H:%M:%S"]
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+            .map(ScheduleTime::Daily)
+            .ok_or_else(|| {
+                format!(
+                    "invalid time '{}', expected RFC3339 (2024-05-01T14:30:00Z) or local HH:MM",
+                    value
+                )
+            })
+    }
+}
+
+/// When collection starts and stops; either end may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectionWindow {
+    pub start: Option<DateTime<Utc>>,
+    pub stop: Option<DateTime<Utc>>,
+}
+
+impl CollectionWindow {
+    /// Resolve both ends against `now`; a local `--stop-at` is the first occurrence after the start
+    pub fn resolve(
+        start: Option<ScheduleTime>,
+        stop: Option<ScheduleTime>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, HyperliquidError> {
+        let start = start.map(|start| start.resolve(now));
+        let stop = stop.map(|stop| stop.resolve(start.unwrap_or(now)));
+
+        if let Some(stop) = stop
+            && stop <= now
+        {
+            return Err(HyperliquidError::ConfigError(format!(
+                "--stop-at {} is already in the past",
+                stop.with_timezone(&Local)
+            )));
+        }
+        if let (Some(start), Some(stop)) = (start, stop)
+            && stop <= start
+        {
+            return Err(HyperliquidError::ConfigError(format!(
+                "--stop-at {} is not after --start-at {}",
+                stop.with_timezone(&Local),
+                start.with_timezone(&Local)
+            )));
+        }
+        Ok(Self { start, stop })
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.start.is_some() || self.stop.is_some()
+    }
+
+    /// When to open the connection, `CONNECT_LEAD` ahead of the start
+    pub fn connect_at(&self) -> Option<DateTime<Utc>> {
+        self.start.map(|start| start - CONNECT_LEAD)
+    }
+}
+
+impl fmt::Display for CollectionWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |at: Option<DateTime<Utc>>, open: &str| {
+            at.map_or_else(
+                || open.to_string(),
+                |at| {
+                    at.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                },
+            )
+        };
+        write!(
+            f,
+            "{} to {}",
+            format(self.start, "now"),
+            format(self.stop, "shutdown")
+        )
+    }
+}
+
+/// Sleep until `at`; returns immediately when it has already passed
+pub async fn sleep_until(at: DateTime<Utc>) {
+    let wait = (at - Utc::now()).to_std().unwrap_or_default();
+    tokio::time::sleep(wait).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// A local wall-clock time in mid January, clear of DST changes
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        let at = NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap();
+        Local
+            .from_local_datetime(&at)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn time(value: &str) -> Option<ScheduleTime> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn parses_instants_and_local_times() {
+        assert_eq!(
+            "2024-05-01T14:30:00+02:00".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::At(Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap())
+        );
+        assert_eq!(
+            " 14:30 ".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::Daily(NaiveTime::from_hms_opt(14, 30, 0).unwrap())
+        );
+        assert_eq!(
+            "14:30:15".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::Daily(NaiveTime::from_hms_opt(14, 30, 15).unwrap())
+        );
+        for value in ["25:00", "2pm", "2024-05-01", ""] {
+            assert!(value.parse::<ScheduleTime>().is_err(), "{} accepted", value);
+        }
+    }
+
+    #[test]
+    fn a_window_may_cross_midnight() {
+        let now = local(15, 23, 0);
+        let window = CollectionWindow::resolve(time("23:30"), time("00:30"), now).unwrap();
+        assert_eq!(window.start, Some(local(15, 23, 30)));
+        assert_eq!(window.stop, Some(local(16, 0, 30)));
+        assert_eq!(window.connect_at(), Some(local(15, 23, 30) - CONNECT_LEAD));
+    }
+
+    #[test]
+    fn a_start_that_already_passed_today_is_tomorrow() {
+        let now = local(15, 23, 0);
+        let window = CollectionWindow::resolve(time("22:00"), time("06:00"), now).unwrap();
+        assert_eq!(window.start, Some(local(16, 22, 0)));
+        // The stop follows the start, not now
+        assert_eq!(window.stop, Some(local(17, 6, 0)));
+    }
+
+    #[test]
+    fn a_stop_alone_is_its_next_occurrence() {
+        let now = local(15, 23, 0);
+        let window = CollectionWindow::resolve(None, time("01:00"), now).unwrap();
+        assert_eq!(window.start, None);
+        assert_eq!(window.stop, Some(local(16, 1, 0)));
+        assert_eq!(window.connect_at(), None);
+        assert!(window.is_set());
+        assert!(!CollectionWindow::default().is_set());
+    }
+
+    #[test]
+    fn rejects_stops_in_the_past_or_before_the_start() {
+        let now = local(15, 23, 0);
+        let error = CollectionWindow::resolve(None, time("2024-01-01T00:00:00Z"), now)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("already in the past"), "{}", error);
+
+        let error = CollectionWindow::resolve(
+            Some(ScheduleTime::At(local(16, 12, 0))),
+            Some(ScheduleTime::At(local(16, 11, 0))),
+            now,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("is not after --start-at"), "{}", error);
+    }
+}
@@ -0,0 +1,101 @@
+/// file: src/schema.rs
+/// description: Strict schema checks that report fields the message types do not know about
+use crate::types::WebSocketMessage;
+use metrics::counter;
+use serde_json::Value;
+use std::collections::HashSet;
+use tracing::{debug, warn};
+
+// Enough of the payload to see the new field in context without flooding the log
+const SNIPPET_CHARS: usize = 256;
+
+/// Runtime stand-in for `deny_unknown_fields` on the WebSocket types
+///
+/// The parsed message is serialized again and compared with the raw payload; any field in the
+/// payload that did not survive is one the types dropped. That also covers channels the client
+/// has no payload type for, whose `data` shows up as unknown.
+#[derive(Debug, Default)]
+pub struct SchemaChecker {
+    /// Channel and field pairs already logged at warn level
+    reported: HashSet<(String, String)>,
+}
+
+impl SchemaChecker {
+    /// Whether `message` kept every field of `text`; unknown fields are logged and counted
+    pub fn check(&mut self, text: &str, message: &WebSocketMessage<'_>) -> bool {
+        let unknown = unknown_fields(text, message);
+        if unknown.is_empty() {
+            return true;
+        }
+
+        let channel = message.channel();
+        counter!("hyperliquid_schema_violations_total", "channel" => channel.to_string())
+            .increment(1);
+        for field in unknown {
+            if self.reported.insert((channel.to_string(), field.clone())) {
+                warn!(
+                    "Unknown field {} in {} message: {}",
+                    field,
+                    channel,
+                    snippet(text)
+                );
+            } else {
+                debug!("Unknown field {} in {} message", field, channel);
+            }
+        }
+        false
+    }
+}
+
+/// Sorted paths such as `data[].newField` that are in `text` but not in the message parsed from it
+pub fn unknown_fields(text: &str, message: &WebSocketMessage<'_>) -> Vec<String> {
+    let (Ok(raw), Ok(kept)) = (
+        serde_json::from_str::<Value>(text),
+        serde_json::to_value(message),
+    ) else {
+        return Vec::new();
+    };
+    let mut unknown = Vec::new();
+    collect_unknown(&raw, &kept, "", &mut unknown);
+    unknown.sort();
+    unknown.dedup();
+    unknown
+}
+
+fn collect_unknown(raw: &Value, kept: &Value, path: &str, unknown: &mut Vec<String>) {
+    match (raw, kept) {
+        (Value::Object(raw), Value::Object(kept)) => {
+            for (key, value) in raw {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match kept.get(key) {
+                    Some(kept) => collect_unknown(value, kept, &field, unknown),
+                    None => unknown.push(field),
+                }
+            }
+        }
+        (Value::Array(raw), Value::Array(kept)) => {
+            let path = format!("{}[]", path);
+            for (raw, kept) in raw.iter().zip(kept) {
+                collect_unknown(raw, kept, &path, unknown);
+            }
+        }
+        // Candle pushes carry one object where the type keeps a list
+        (Value::Object(_), Value::Array(kept)) => {
+            if let Some(first) = kept.first() {
+                collect_unknown(raw, first, path, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn snippet(text: &str) -> String {
+    match text.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
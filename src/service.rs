@@ -0,0 +1,177 @@
+/// file: src/service.rs
+/// description: Windows service install, uninstall, and the entry point used by the service manager
+/// reference: https://docs.rs/windows-service/latest/windows_service/
+use crate::error::{HyperliquidError, exit_code};
+use anyhow::Result;
+use std::ffi::OsString;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::error;
+use windows_service::{
+    define_windows_service,
+    service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    },
+    service_control_handler::{self, ServiceControlHandlerResult},
+    service_dispatcher,
+    service_manager::{ServiceManager, ServiceManagerAccess},
+};
+
+pub const DEFAULT_SERVICE_NAME: &str = "rs-hyperliquid";
+
+/// What the service runs once the service manager starts it
+pub struct ServiceMain {
+    /// The collector; returns when it stops on its own or after `stop`
+    pub body: Box<dyn FnOnce() -> Result<()> + Send>,
+    /// Called from the control handler when Windows asks the service to stop
+    pub stop: Box<dyn Fn() + Send>,
+}
+
+// The dispatcher calls back through a plain function, so the body waits here until then
+static PENDING: Mutex<Option<(String, ServiceMain)>> = Mutex::new(None);
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Register the current executable as an auto-start service
+///
+/// `arguments` are stream options passed to `service run` on every start.
+pub fn install(name: &str, arguments: Vec<OsString>) -> Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .map_err(service_error)?;
+
+    let mut launch_arguments: Vec<OsString> = ["service", "run", "--name", name]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    launch_arguments.extend(arguments);
+
+    let info = ServiceInfo {
+        name: OsString::from(name),
+        display_name: OsString::from(format!("Hyperliquid collector ({})", name)),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: std::env::current_exe()?,
+        launch_arguments,
+        dependencies: Vec::new(),
+        // LocalSystem
+        account_name: None,
+        account_password: None,
+    };
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+        .map_err(service_error)?;
+    service
+        .set_description("Streams Hyperliquid market data with rs-hyperliquid")
+        .map_err(service_error)?;
+    Ok(())
+}
+
+/// Stop the service if it is running and remove it
+///
+/// Windows deletes it once the last open handle closes, which may be after this returns.
+pub fn uninstall(name: &str) -> Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .map_err(service_error)?;
+    let service = manager
+        .open_service(
+            name,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .map_err(service_error)?;
+    service.delete().map_err(service_error)?;
+    if service.query_status().map_err(service_error)?.current_state != ServiceState::Stopped {
+        service.stop().map_err(service_error)?;
+    }
+    Ok(())
+}
+
+/// Hand the thread to the service control dispatcher; blocks until the service stops
+///
+/// Fails when not started by the service manager, e.g. when run by hand from a console.
+pub fn run(name: &str, main: ServiceMain) -> Result<()> {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some((name.to_string(), main));
+    service_dispatcher::start(name, ffi_service_main).map_err(|e| {
+        HyperliquidError::ServiceError(format!(
+            "{} (`service run` is started by the service manager, use `service install`)",
+            reason(e)
+        ))
+    })?;
+    Ok(())
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    let Some((name, main)) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    if let Err(e) = run_service(&name, main) {
+        error!("Windows service {} failed: {}", name, e);
+    }
+}
+
+fn run_service(name: &str, main: ServiceMain) -> windows_service::Result<()> {
+    let ServiceMain { body, stop } = main;
+    let handler = move |control: ServiceControl| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            stop();
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let status = service_control_handler::register(name, handler)?;
+    status.set_service_status(service_status(
+        ServiceState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        ServiceExitCode::NO_ERROR,
+    ))?;
+
+    let exit = match body() {
+        Ok(()) => ServiceExitCode::NO_ERROR,
+        Err(e) => {
+            error!("{:?}", e);
+            // Same codes as a console run, so a failed start reads the same in the event log
+            let code = e
+                .downcast_ref::<HyperliquidError>()
+                .map_or(exit_code::FAILURE, HyperliquidError::exit_code);
+            ServiceExitCode::ServiceSpecific(code.into())
+        }
+    };
+    status.set_service_status(service_status(
+        ServiceState::Stopped,
+        ServiceControlAccept::empty(),
+        exit,
+    ))
+}
+
+fn service_status(
+    state: ServiceState,
+    controls_accepted: ServiceControlAccept,
+    exit_code: ServiceExitCode,
+) -> ServiceStatus {
+    ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted,
+        exit_code,
+        checkpoint: 0,
+        wait_hint: Duration::ZERO,
+        process_id: None,
+    }
+}
+
+fn service_error(e: windows_service::Error) -> HyperliquidError {
+    HyperliquidError::ServiceError(reason(e))
+}
+
+/// The crate's own text for a failed Win32 call leaves out the cause, such as access denied
+fn reason(e: windows_service::Error) -> String {
+    match e {
+        windows_service::Error::Winapi(io) => io.to_string(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,139 @@
+/// file: src/sparkline.rs
+/// description: Bounded price history rendered as a compact unicode or ASCII sparkline
+use std::collections::VecDeque;
+
+const BARS: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+
+// Plain-ASCII ramp for terminals without block elements
+const ASCII_BARS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl Sparkline {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append a value, evicting the oldest one once the window is full
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn last(&self) -> Option<f64> {
+        self.values.back().copied()
+    }
+
+    /// Percentage change from the oldest to the newest value in the window
+    pub fn change_pct(&self) -> Option<f64> {
+        match (self.values.front(), self.values.back()) {
+            (Some(first), Some(last)) if *first != 0.0 => Some((last - first) / first * 100.0),
+            _ => None,
+        }
+    }
+
+    /// Render the window scaled between its own min and max
+    pub fn render(&self) -> String {
+        self.render_with(&BARS)
+    }
+
+    /// Render with ASCII characters only
+    pub fn render_ascii(&self) -> String {
+        self.render_with(&ASCII_BARS)
+    }
+
+    fn render_with(&self, bars: &[char; 8]) -> String {
+        let (min, max) = self
+            .values
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        let range = max - min;
+
+        self.values
+            .iter()
+            .map(|v| {
+                if range <= f64::EPSILON {
+                    bars[bars.len() / 2]
+                } else {
+                    let idx = ((v - min) / range * (bars.len() - 1) as f64).round() as usize;
+                    bars[idx.min(bars.len() - 1)]
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sparkline(capacity: usize, values: &[f64]) -> Sparkline {
+        let mut sparkline = Sparkline::new(capacity);
+        for value in values {
+            sparkline.push(*value);
+        }
+        sparkline
+    }
+
+    #[test]
+    fn scales_between_the_window_min_and_max() {
+        let line = sparkline(8, &[100.0, 101.0, 102.0, 103.0, 104.0, 105.0, 106.0, 107.0]);
+        assert_eq!(line.render(), "▁▂▃▄▅▆▇█");
+        assert_eq!(line.render_ascii(), "_.-~=+*#");
+        assert!((line.change_pct().unwrap() - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn evicts_the_oldest_value_and_skips_non_finite_ones() {
+        let line = sparkline(3, &[1.0, 2.0, f64::NAN, 3.0, f64::INFINITY, 4.0]);
+        assert_eq!(line.render_ascii(), "_=#");
+        assert_eq!(line.last(), Some(4.0));
+        assert_eq!(line.change_pct(), Some(100.0));
+    }
+
+    #[test]
+    fn flat_and_single_sample_windows_sit_mid_height() {
+        let flat = sparkline(4, &[50.0, 50.0, 50.0]);
+        assert_eq!(flat.render_ascii(), "===");
+        assert_eq!(flat.change_pct(), Some(0.0));
+
+        let single = sparkline(4, &[50.0]);
+        assert_eq!(single.render(), "▅");
+        assert_eq!(single.change_pct(), Some(0.0));
+    }
+
+    #[test]
+    fn empty_windows_render_nothing() {
+        let empty = sparkline(4, &[]);
+        assert_eq!(empty.render(), "");
+        assert_eq!(empty.last(), None);
+        assert_eq!(empty.change_pct(), None);
+    }
+
+    #[test]
+    fn zero_width_still_keeps_two_samples() {
+        let line = sparkline(0, &[1.0, 2.0, 3.0]);
+        assert_eq!(line.render_ascii(), "_#");
+        assert_eq!(line.change_pct(), Some(50.0));
+    }
+
+    #[test]
+    fn change_from_zero_is_undefined() {
+        assert_eq!(sparkline(4, &[0.0, 1.0]).change_pct(), None);
+    }
+}
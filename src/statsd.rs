@@ -0,0 +1,247 @@
+/// file: src/statsd.rs
+/// description: StatsD/DogStatsD metrics backend that flushes aggregated values over UDP
+/// reference: https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/
+use crate::{error::HyperliquidError, monitoring::MetricsRecorder};
+use anyhow::Result;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::{debug, info};
+
+// Short enough that little is lost when the process exits between flushes
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+// Stay under a typical MTU so datagrams are never fragmented
+const MAX_DATAGRAM: usize = 1432;
+
+/// Resolve the agent address and start flushing to it every second
+///
+/// Nothing is sent on the hot path: counters and gauges are aggregated in memory and written as
+/// a few datagrams per flush. Labels become DogStatsD tags.
+pub async fn setup_statsd(addr: &str) -> Result<MetricsRecorder> {
+    let error =
+        |reason: String| HyperliquidError::MetricsError(format!("statsd {}: {}", addr, reason));
+    let target = tokio::net::lookup_host(addr)
+        .await
+        .map_err(|e| error(e.to_string()))?
+        .next()
+        .ok_or_else(|| error("address did not resolve".to_string()))?;
+    let local: SocketAddr = if target.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(local)
+        .await
+        .map_err(|e| error(e.to_string()))?;
+    socket
+        .connect(target)
+        .await
+        .map_err(|e| error(e.to_string()))?;
+
+    let registry = Arc::new(Mutex::new(Registry::default()));
+    let flushed = registry.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let lines = flushed.lock().unwrap_or_else(|e| e.into_inner()).drain();
+            for datagram in pack(&lines) {
+                // No agent listening is not worth more than a debug line every second
+                if let Err(e) = socket.send(datagram.as_bytes()).await {
+                    debug!("statsd send to {} failed: {}", target, e);
+                }
+            }
+        }
+    });
+
+    info!("Sending StatsD metrics to {}", target);
+    Ok(Box::new(StatsdRecorder { registry }))
+}
+
+/// Join lines into datagrams no larger than `MAX_DATAGRAM`
+fn pack(lines: &[String]) -> Vec<String> {
+    let mut datagrams: Vec<String> = Vec::new();
+    for line in lines {
+        match datagrams.last_mut() {
+            Some(datagram) if datagram.len() + 1 + line.len() <= MAX_DATAGRAM => {
+                datagram.push('\n');
+                datagram.push_str(line);
+            }
+            _ => datagrams.push(line.clone()),
+        }
+    }
+    datagrams
+}
+
+/// Every metric registered so far, keyed so a key registered twice shares one aggregate
+#[derive(Default)]
+struct Registry {
+    counters: HashMap<Key, Arc<StatsdCounter>>,
+    gauges: HashMap<Key, Arc<StatsdGauge>>,
+    histograms: HashMap<Key, Arc<StatsdHistogram>>,
+}
+
+impl Registry {
+    /// Lines for one flush: counter deltas, every gauge, and the samples recorded since the last
+    fn drain(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for counter in self.counters.values() {
+            let delta = counter.pending.swap(0, Ordering::Relaxed);
+            if delta > 0 {
+                lines.push(format!("{}:{}|c{}", counter.name, delta, counter.tags));
+            }
+        }
+        // Gauges are re-sent each flush so the series has no gaps between changes
+        for gauge in self.gauges.values() {
+            let value = *gauge.value.lock().unwrap_or_else(|e| e.into_inner());
+            if !value.is_finite() {
+                continue;
+            }
+            // Plain StatsD reads a leading sign as a relative change, so a negative reading
+            // goes through zero first
+            if value < 0.0 {
+                lines.push(format!("{}:0|g{}", gauge.name, gauge.tags));
+            }
+            lines.push(format!("{}:{}|g{}", gauge.name, value, gauge.tags));
+        }
+        for histogram in self.histograms.values() {
+            let samples =
+                std::mem::take(&mut *histogram.samples.lock().unwrap_or_else(|e| e.into_inner()));
+            lines.extend(
+                samples
+                    .iter()
+                    .filter(|value| value.is_finite())
+                    .map(|value| format!("{}:{}|h{}", histogram.name, value, histogram.tags)),
+            );
+        }
+        lines
+    }
+}
+
+struct StatsdRecorder {
+    registry: Arc<Mutex<Registry>>,
+}
+
+/// `|#key:value,...` for labelled keys, empty otherwise
+fn tags(key: &Key) -> String {
+    let tags: Vec<String> = key
+        .labels()
+        .map(|label| format!("{}:{}", label.key(), label.value()))
+        .collect();
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!("|#{}", tags.join(","))
+    }
+}
+
+impl Recorder for StatsdRecorder {
+    // StatsD has nowhere to put descriptions or units
+    fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+    fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+    fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let mut registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        let counter = registry.counters.entry(key.clone()).or_insert_with(|| {
+            Arc::new(StatsdCounter {
+                name: key.name().to_string(),
+                tags: tags(key),
+                pending: AtomicU64::new(0),
+                total: AtomicU64::new(0),
+            })
+        });
+        Counter::from_arc(counter.clone())
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let mut registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        let gauge = registry.gauges.entry(key.clone()).or_insert_with(|| {
+            Arc::new(StatsdGauge {
+                name: key.name().to_string(),
+                tags: tags(key),
+                value: Mutex::new(0.0),
+            })
+        });
+        Gauge::from_arc(gauge.clone())
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let mut registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        let histogram = registry.histograms.entry(key.clone()).or_insert_with(|| {
+            Arc::new(StatsdHistogram {
+                name: key.name().to_string(),
+                tags: tags(key),
+                samples: Mutex::default(),
+            })
+        });
+        Histogram::from_arc(histogram.clone())
+    }
+}
+
+struct StatsdCounter {
+    name: String,
+    tags: String,
+    /// Sent as a delta on the next flush
+    pending: AtomicU64,
+    /// StatsD counters only take increments, so `absolute` adds the difference
+    total: AtomicU64,
+}
+
+impl CounterFn for StatsdCounter {
+    fn increment(&self, value: u64) {
+        self.total.fetch_add(value, Ordering::Relaxed);
+        self.pending.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.total.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.pending.fetch_add(value - previous, Ordering::Relaxed);
+        }
+    }
+}
+
+struct StatsdGauge {
+    name: String,
+    tags: String,
+    value: Mutex<f64>,
+}
+
+impl GaugeFn for StatsdGauge {
+    fn increment(&self, value: f64) {
+        *self.value.lock().unwrap_or_else(|e| e.into_inner()) += value;
+    }
+
+    fn decrement(&self, value: f64) {
+        *self.value.lock().unwrap_or_else(|e| e.into_inner()) -= value;
+    }
+
+    fn set(&self, value: f64) {
+        *self.value.lock().unwrap_or_else(|e| e.into_inner()) = value;
+    }
+}
+
+struct StatsdHistogram {
+    name: String,
+    tags: String,
+    samples: Mutex<Vec<f64>>,
+}
+
+impl HistogramFn for StatsdHistogram {
+    fn record(&self, value: f64) {
+        self.samples
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(value);
+    }
+}
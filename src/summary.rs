@@ -0,0 +1,68 @@
+/// file: src/summary.rs
+/// description: Per-session trade totals collected for the exit summary
+use crate::{symbol::Symbol, types::Trade};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Totals for one coin over the session
+#[derive(Debug, Clone, Default)]
+pub struct CoinSummary {
+    pub trades: u64,
+    pub volume: f64,
+    pub notional: f64,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+}
+
+/// Everything printed in the summary block on shutdown
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    started: Instant,
+    pub coins: BTreeMap<Symbol, CoinSummary>,
+    /// Handshakes completed, so a session that flapped shows more than one
+    pub connections: u32,
+    /// Time connected summed over every connection
+    pub connected: Duration,
+    pub reconnects: u32,
+    pub gaps: u32,
+    pub downtime: Duration,
+    pub duplicate_trades: u64,
+}
+
+impl SessionSummary {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            coins: BTreeMap::new(),
+            connections: 0,
+            connected: Duration::ZERO,
+            reconnects: 0,
+            gaps: 0,
+            downtime: Duration::ZERO,
+            duplicate_trades: 0,
+        }
+    }
+
+    pub fn record(&mut self, trade: &Trade) {
+        let coin = self.coins.entry(trade.coin.clone()).or_default();
+        coin.trades += 1;
+        coin.volume += trade.sz;
+        coin.notional += trade.value();
+        coin.high = Some(coin.high.map_or(trade.px, |high| high.max(trade.px)));
+        coin.low = Some(coin.low.map_or(trade.px, |low| low.min(trade.px)));
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn total_trades(&self) -> u64 {
+        self.coins.values().map(|coin| coin.trades).sum()
+    }
+}
+
+impl Default for SessionSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
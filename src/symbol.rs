@@ -0,0 +1,156 @@
+/// file: src/symbol.rs
+/// description: Interned coin symbols shared by every message, event, and formatter that names a coin
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Distinct symbols kept before new ones are allocated without interning
+///
+/// Both markets list a few hundred coins; the cap only stops a misbehaving feed from growing the
+/// table without bound.
+const MAX_INTERNED: usize = 4096;
+
+fn table() -> &'static RwLock<HashSet<Arc<str>>> {
+    static TABLE: OnceLock<RwLock<HashSet<Arc<str>>>> = OnceLock::new();
+    TABLE.get_or_init(|| RwLock::new(HashSet::new()))
+}
+
+/// A coin name such as `BTC` or `@107`, one shared allocation per distinct name
+///
+/// Cloning copies a pointer, so trades, books, and the maps keyed by coin share the string the
+/// first message allocated. Compares, hashes, and serializes as the plain string.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    /// The shared copy of `name`, adding it on first sight
+    pub fn intern(name: &str) -> Self {
+        if let Some(symbol) = table().read().unwrap_or_else(|e| e.into_inner()).get(name) {
+            return Symbol(Arc::clone(symbol));
+        }
+        let mut table = table().write().unwrap_or_else(|e| e.into_inner());
+        if let Some(symbol) = table.get(name) {
+            return Symbol(Arc::clone(symbol));
+        }
+        let symbol: Arc<str> = Arc::from(name);
+        if table.len() < MAX_INTERNED {
+            table.insert(Arc::clone(&symbol));
+        }
+        Symbol(symbol)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Symbol::intern("")
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::intern(&name)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<Symbol> for String {
+    fn eq(&self, other: &Symbol) -> bool {
+        **self == *other.0
+    }
+}
+
+impl PartialEq<Symbol> for &str {
+    fn eq(&self, other: &Symbol) -> bool {
+        **self == *other.0
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(SymbolVisitor)
+    }
+}
+
+/// Looks the name up where the deserializer has it, so a known symbol costs no allocation
+struct SymbolVisitor;
+
+impl Visitor<'_> for SymbolVisitor {
+    type Value = Symbol;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a coin symbol")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Symbol, E> {
+        Ok(Symbol::intern(name))
+    }
+}
@@ -0,0 +1,178 @@
+/// file: src/system_log.rs
+/// description: tracing layer that writes events to the systemd journal or the local syslog socket
+/// reference: https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
+use std::fmt::{self, Write as _};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+// Linux, then macOS and the BSDs
+const SYSLOG_SOCKETS: [&str; 2] = ["/dev/log", "/var/run/syslog"];
+const IDENTIFIER: &str = "rs-hyperliquid";
+// LOG_DAEMON
+const SYSLOG_FACILITY: u8 = 3;
+
+#[derive(Debug, Clone, Copy)]
+enum Protocol {
+    Journald,
+    Syslog,
+}
+
+/// Sends each event as one datagram, with the level mapped to a syslog priority
+///
+/// The journal gets every event field as a field of its own; syslog gets them appended to the
+/// message as `key=value`.
+#[derive(Debug)]
+pub struct SystemLogLayer {
+    socket: UnixDatagram,
+    protocol: Protocol,
+    /// Only the first failed send is reported, so a stopped journal does not flood stderr
+    send_failed: AtomicBool,
+}
+
+impl SystemLogLayer {
+    pub fn journald() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(JOURNALD_SOCKET)?;
+        Ok(Self::new(socket, Protocol::Journald))
+    }
+
+    pub fn syslog() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        let mut error = io::Error::from(io::ErrorKind::NotFound);
+        for path in SYSLOG_SOCKETS {
+            match socket.connect(path) {
+                Ok(()) => return Ok(Self::new(socket, Protocol::Syslog)),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+
+    fn new(socket: UnixDatagram, protocol: Protocol) -> Self {
+        Self {
+            socket,
+            protocol,
+            send_failed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SystemLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let metadata = event.metadata();
+        let priority = priority(*metadata.level());
+
+        let datagram = match self.protocol {
+            Protocol::Journald => {
+                let mut datagram = Vec::new();
+                journal_field(&mut datagram, "PRIORITY", priority.to_string().as_bytes());
+                journal_field(&mut datagram, "SYSLOG_IDENTIFIER", IDENTIFIER.as_bytes());
+                journal_field(&mut datagram, "MESSAGE", fields.message.as_bytes());
+                journal_field(&mut datagram, "TARGET", metadata.target().as_bytes());
+                if let Some(file) = metadata.file() {
+                    journal_field(&mut datagram, "CODE_FILE", file.as_bytes());
+                }
+                if let Some(line) = metadata.line() {
+                    journal_field(&mut datagram, "CODE_LINE", line.to_string().as_bytes());
+                }
+                for (name, value) in &fields.extra {
+                    if let Some(name) = journal_name(name) {
+                        journal_field(&mut datagram, &name, value.as_bytes());
+                    }
+                }
+                datagram
+            }
+            Protocol::Syslog => {
+                // The local daemon stamps the time and host itself
+                let mut line = format!(
+                    "<{}>{}[{}]: {}",
+                    SYSLOG_FACILITY * 8 + priority,
+                    IDENTIFIER,
+                    std::process::id(),
+                    fields.message
+                );
+                for (name, value) in &fields.extra {
+                    let _ = write!(line, " {}={}", name, value);
+                }
+                line.into_bytes()
+            }
+        };
+
+        if let Err(e) = self.socket.send(&datagram)
+            && !self.send_failed.swap(true, Ordering::Relaxed)
+        {
+            eprintln!("Cannot write to the system log, dropping log lines: {}", e);
+        }
+    }
+}
+
+/// Syslog severity: err, warning, info, and debug for both debug and trace
+fn priority(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+/// `NAME=value`, or the length-prefixed form when the value spans lines
+fn journal_field(datagram: &mut Vec<u8>, name: &str, value: &[u8]) {
+    datagram.extend_from_slice(name.as_bytes());
+    if value.contains(&b'\n') {
+        datagram.push(b'\n');
+        datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        datagram.push(b'=');
+    }
+    datagram.extend_from_slice(value);
+    datagram.push(b'\n');
+}
+
+/// Journal field names are upper case letters, digits, and underscores, and a leading
+/// underscore is reserved for fields the journal adds itself
+fn journal_name(name: &str) -> Option<String> {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    let name = name.trim_start_matches('_');
+    (!name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit())).then(|| name.to_string())
+}
+
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    extra: Vec<(&'static str, String)>,
+}
+
+impl EventFields {
+    fn push(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            name => self.extra.push((name, value)),
+        }
+    }
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format!("{:?}", value));
+    }
+}
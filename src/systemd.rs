@@ -0,0 +1,122 @@
+/// file: src/systemd.rs
+/// description: sd_notify readiness and watchdog messages when running as a systemd service
+/// reference: https://www.freedesktop.org/software/systemd/man/latest/sd_notify.html
+use crate::client_state::SharedClientState;
+use std::io;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Sends state changes to the socket systemd passes in `NOTIFY_SOCKET`
+#[derive(Debug, Clone)]
+pub struct SystemdNotifier {
+    socket_path: String,
+}
+
+impl SystemdNotifier {
+    /// `None` unless the unit uses `Type=notify` (or `NotifyAccess=`), which sets `NOTIFY_SOCKET`
+    pub fn from_env() -> Option<Self> {
+        std::env::var("NOTIFY_SOCKET")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(|socket_path| Self { socket_path })
+    }
+
+    /// Send newline-separated `KEY=value` assignments in one datagram
+    pub fn notify(&self, state: &str) -> io::Result<()> {
+        send(&self.socket_path, state.as_bytes())
+    }
+
+    /// The service is up; systemd finishes starting the unit and its dependents
+    pub fn ready(&self, status: &str) {
+        match self.notify(&format!("READY=1\nSTATUS={}", status)) {
+            Ok(()) => info!("Notified systemd: ready"),
+            Err(e) => warn!("sd_notify READY failed: {}", e),
+        }
+    }
+
+    pub fn stopping(&self) {
+        if let Err(e) = self.notify("STOPPING=1") {
+            debug!("sd_notify STOPPING failed: {}", e);
+        }
+    }
+
+    /// `WatchdogSec=` of the unit, when the watchdog is meant for this process
+    pub fn watchdog_interval() -> Option<Duration> {
+        let usec = std::env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+        // WATCHDOG_PID names the watched process when it differs from the one systemd started
+        let for_us = std::env::var("WATCHDOG_PID")
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok())
+            .is_none_or(|pid| pid == std::process::id());
+        (usec > 0 && for_us).then(|| Duration::from_micros(usec))
+    }
+}
+
+#[cfg(unix)]
+fn send(socket_path: &str, payload: &[u8]) -> io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let socket = UnixDatagram::unbound()?;
+    // A leading '@' names a Linux abstract socket
+    match socket_path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(payload, &addr)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract notify sockets are Linux-only",
+            ));
+        }
+        None => {
+            socket.send_to(payload, socket_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send(_socket_path: &str, _payload: &[u8]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "sd_notify needs Unix domain sockets",
+    ))
+}
+
+/// Pet the watchdog at half its interval while messages keep arriving
+///
+/// A feed that has gone quiet for a whole interval stops the pings, so systemd restarts the unit.
+/// Reconnect attempts count as activity, so a client that is still retrying is left alone.
+pub fn spawn_watchdog(
+    notifier: SystemdNotifier,
+    state: SharedClientState,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval / 2);
+        let mut withheld = false;
+        loop {
+            ticker.tick().await;
+            let last_message_age = state.lock().await.last_message_time.map(|at| at.elapsed());
+            let alive = last_message_age.is_none_or(|age| age < interval);
+            if !alive {
+                if !withheld {
+                    warn!(
+                        "No messages for {:.0}s, withholding the systemd watchdog ping",
+                        last_message_age.unwrap_or_default().as_secs_f64()
+                    );
+                    withheld = true;
+                }
+                continue;
+            }
+            withheld = false;
+            if let Err(e) = notifier.notify("WATCHDOG=1") {
+                warn!("sd_notify WATCHDOG failed: {}", e);
+            }
+        }
+    })
+}
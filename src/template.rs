@@ -0,0 +1,260 @@
+/// file: src/template.rs
+/// description: Placeholder templates for shaping minimal and price-only trade output
+use crate::{
+    error::HyperliquidError,
+    number_format::Precision,
+    time_display::{SHORT_TIME_FORMAT, TimeDisplay},
+    types::Trade,
+};
+use std::fmt::Write;
+
+/// Trade fields that can be referenced from a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+    Time,
+    Date,
+    Timestamp,
+    Coin,
+    Side,
+    Px,
+    Sz,
+    Value,
+    Tid,
+    Hash,
+    Buyer,
+    Seller,
+}
+
+impl TemplateField {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "time" => TemplateField::Time,
+            "date" => TemplateField::Date,
+            "timestamp" | "ts" => TemplateField::Timestamp,
+            "coin" => TemplateField::Coin,
+            "side" => TemplateField::Side,
+            "px" | "price" => TemplateField::Px,
+            "sz" | "size" => TemplateField::Sz,
+            "value" | "notional" => TemplateField::Value,
+            "tid" => TemplateField::Tid,
+            "hash" => TemplateField::Hash,
+            "buyer" => TemplateField::Buyer,
+            "seller" => TemplateField::Seller,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field {
+        field: TemplateField,
+        precision: Option<usize>,
+    },
+}
+
+/// Parsed `--template` string such as `{time} {coin} {side} {px:.1} {sz}`
+///
+/// Placeholders are `{name}` or `{name:.N}` for numeric precision; `{{` and `}}`
+/// produce literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputTemplate {
+    segments: Vec<Segment>,
+}
+
+impl OutputTemplate {
+    pub fn parse(template: &str) -> Result<Self, HyperliquidError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => spec.push(ch),
+                            None => {
+                                return Err(HyperliquidError::TemplateError(format!(
+                                    "unclosed placeholder '{{{}'",
+                                    spec
+                                )));
+                            }
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Self::parse_placeholder(&spec)?);
+                }
+                '}' => {
+                    return Err(HyperliquidError::TemplateError(
+                        "unmatched '}' (use '}}' for a literal brace)".to_string(),
+                    ));
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    fn parse_placeholder(spec: &str) -> Result<Segment, HyperliquidError> {
+        let (name, precision) = match spec.split_once(':') {
+            Some((name, format)) => {
+                let precision = format
+                    .strip_prefix('.')
+                    .and_then(|digits| digits.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        HyperliquidError::TemplateError(format!(
+                            "invalid format '{}' in '{{{}}}', expected ':.N'",
+                            format, spec
+                        ))
+                    })?;
+                (name.trim(), Some(precision))
+            }
+            None => (spec.trim(), None),
+        };
+
+        let field = TemplateField::parse(name).ok_or_else(|| {
+            HyperliquidError::TemplateError(format!(
+                "unknown placeholder '{{{}}}', expected one of time, date, timestamp, coin, side, px, sz, value, tid, hash, buyer, seller",
+                name
+            ))
+        })?;
+
+        Ok(Segment::Field { field, precision })
+    }
+
+    /// A `:.N` in the template wins over `--price-decimals` and `--size-decimals`
+    pub fn render(&self, trade: &Trade, time: &TimeDisplay, decimals: &Precision) -> String {
+        let mut out = String::with_capacity(64);
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field { field, precision } => {
+                    Self::write_field(&mut out, trade, time, decimals, *field, *precision)
+                }
+            }
+        }
+        out
+    }
+
+    fn write_field(
+        out: &mut String,
+        trade: &Trade,
+        time: &TimeDisplay,
+        decimals: &Precision,
+        field: TemplateField,
+        precision: Option<usize>,
+    ) {
+        let _ = match field {
+            TemplateField::Time => {
+                write!(out, "{}", time.format_millis(trade.time, SHORT_TIME_FORMAT))
+            }
+            TemplateField::Date => write!(out, "{}", time.format_in_zone(trade.time, "%Y-%m-%d")),
+            TemplateField::Timestamp => write!(out, "{}", trade.time),
+            TemplateField::Coin => write!(out, "{}", trade.coin),
+            TemplateField::Side => write!(out, "{}", trade.side_formatted()),
+            TemplateField::Px => {
+                let precision = precision.unwrap_or_else(|| decimals.price(&trade.coin, 2));
+                write!(out, "{:.*}", precision, trade.px)
+            }
+            TemplateField::Sz => {
+                let precision = precision.unwrap_or_else(|| decimals.size(&trade.coin, 6));
+                write!(out, "{:.*}", precision, trade.sz)
+            }
+            TemplateField::Value => write!(out, "{:.*}", precision.unwrap_or(2), trade.value()),
+            TemplateField::Tid => write!(out, "{}", trade.tid),
+            TemplateField::Hash => write!(out, "{}", trade.hash),
+            TemplateField::Buyer => write!(out, "{}", trade.buyer_seller().0.map_or("", |s| s)),
+            TemplateField::Seller => write!(out, "{}", trade.buyer_seller().1.map_or("", |s| s)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade() -> Trade {
+        Trade {
+            coin: "BTC".into(),
+            side: "B".to_string(),
+            px: 112450.25,
+            sz: 0.4,
+            time: 1760000000123,
+            hash: "0xabc".to_string(),
+            tid: 42,
+            users: vec!["0xbuyer".to_string(), "0xseller".to_string()],
+        }
+    }
+
+    fn render(template: &str) -> String {
+        render_with(template, &Precision::default())
+    }
+
+    fn render_with(template: &str, decimals: &Precision) -> String {
+        let time = TimeDisplay::new("UTC", None).unwrap();
+        OutputTemplate::parse(template)
+            .unwrap()
+            .render(&trade(), &time, decimals)
+    }
+
+    #[test]
+    fn renders_fields_and_literals() {
+        assert_eq!(
+            render("{time} {coin} {side} {px} {sz} {tid}"),
+            "08:53:20 BTC BUY 112450.25 0.400000 42"
+        );
+        assert_eq!(
+            render("{date}|{ts}|{value}"),
+            "2025-10-09|1760000000123|44980.10"
+        );
+        assert_eq!(
+            render("{buyer}->{seller} {hash}"),
+            "0xbuyer->0xseller 0xabc"
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{{coin}}} }}"), "{BTC} }");
+    }
+
+    #[test]
+    fn field_precision_wins_over_decimal_settings() {
+        let decimals = Precision::parse(&["4".to_string()], &["1".to_string()]).unwrap();
+        assert_eq!(render_with("{px} {sz}", &decimals), "112450.2500 0.4");
+        assert_eq!(
+            render_with("{px:.0} {sz:.3} {value:.1}", &decimals),
+            "112450 0.400 44980.1"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for template in ["{nope}", "{px", "px}", "{px:3}", "{px:.x}", "{}"] {
+            assert!(
+                OutputTemplate::parse(template).is_err(),
+                "{} parsed",
+                template
+            );
+        }
+    }
+}
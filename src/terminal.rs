@@ -0,0 +1,168 @@
+/// file: src/terminal.rs
+/// description: Terminal capability detection and alternate screen handling for full-screen views
+/// reference: https://no-color.org and https://bixense.com/clicolors/
+use std::io::{IsTerminal, Write};
+use std::sync::Once;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalCapabilities {
+    /// Emit ANSI color and style sequences
+    pub color: bool,
+    /// Use Unicode box drawing and symbols
+    pub unicode: bool,
+    /// Stdout is a terminal, so cursor movement and in-place redraws are safe
+    pub is_tty: bool,
+}
+
+impl TerminalCapabilities {
+    /// Detect capabilities of stdout, honoring `--no-color`, `--ascii`, NO_COLOR, CLICOLOR,
+    /// and CLICOLOR_FORCE
+    pub fn detect(no_color_flag: bool, ascii_flag: bool) -> Self {
+        let is_tty = std::io::stdout().is_terminal();
+        let term = std::env::var("TERM").unwrap_or_default();
+        let dumb = term == "dumb";
+
+        Self {
+            color: Self::color_enabled(no_color_flag, is_tty && !dumb, |name| {
+                std::env::var_os(name).map(|value| value.to_string_lossy().into_owned())
+            }),
+            unicode: !ascii_flag
+                && is_tty
+                && !dumb
+                && !Self::limited_glyphs(&term)
+                && Self::utf8_locale(),
+            is_tty,
+        }
+    }
+
+    /// Terminals whose fonts lack box drawing and block elements
+    fn limited_glyphs(term: &str) -> bool {
+        matches!(term, "vt100" | "vt102" | "vt220" | "ansi" | "cons25")
+    }
+
+    /// The first of LC_ALL, LC_CTYPE, LANG that is set decides the encoding
+    fn utf8_locale() -> bool {
+        ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+            .map(|locale| {
+                let locale = locale.to_ascii_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            })
+            // No locale variables at all (common on Windows) is treated as capable
+            .unwrap_or(true)
+    }
+
+    /// `env` looks up a variable by name, so the decision can be checked without the process env
+    fn color_enabled(
+        no_color_flag: bool,
+        tty_default: bool,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> bool {
+        if no_color_flag {
+            return false;
+        }
+        // NO_COLOR wins over everything when set to a non-empty value
+        if env("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return false;
+        }
+        if env("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0") {
+            return true;
+        }
+        if env("CLICOLOR").is_some_and(|value| value == "0") {
+            return false;
+        }
+        tty_default
+    }
+}
+
+// Leave the alternate screen, drop any scroll region, and show the cursor
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[r\x1b[?25h\x1b[?1049l";
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Switches to the alternate screen and restores the user's scrollback when dropped
+///
+/// A panic hook performs the same restore, since release builds abort on panic
+/// without running destructors.
+pub struct AlternateScreen {
+    _private: (),
+}
+
+impl AlternateScreen {
+    pub fn enter() -> std::io::Result<Self> {
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let _ = std::io::stdout().write_all(LEAVE_ALTERNATE_SCREEN.as_bytes());
+                let _ = std::io::stdout().flush();
+                previous(info);
+            }));
+        });
+
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(b"\x1b[?1049h\x1b[H\x1b[2J")?;
+        stdout.flush()?;
+        Ok(Self { _private: () })
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(LEAVE_ALTERNATE_SCREEN.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Env = &'static [(&'static str, &'static str)];
+
+    #[test]
+    fn color_follows_flags_and_environment() {
+        // (--no-color, stdout is a color terminal, environment, expected)
+        let cases: &[(bool, bool, Env, bool)] = &[
+            (false, true, &[], true),
+            (false, false, &[], false),
+            (true, true, &[], false),
+            (true, true, &[("CLICOLOR_FORCE", "1")], false),
+            (false, true, &[("NO_COLOR", "1")], false),
+            (false, true, &[("NO_COLOR", "")], true),
+            (
+                false,
+                false,
+                &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")],
+                false,
+            ),
+            (false, false, &[("CLICOLOR_FORCE", "1")], true),
+            (false, false, &[("CLICOLOR_FORCE", "0")], false),
+            (false, false, &[("CLICOLOR_FORCE", "")], false),
+            (false, true, &[("CLICOLOR", "0")], false),
+            (false, false, &[("CLICOLOR", "1")], false),
+            (
+                false,
+                true,
+                &[("CLICOLOR", "0"), ("CLICOLOR_FORCE", "1")],
+                true,
+            ),
+        ];
+        for (no_color_flag, tty_default, env, expected) in cases {
+            let lookup = |name: &str| {
+                env.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            assert_eq!(
+                TerminalCapabilities::color_enabled(*no_color_flag, *tty_default, lookup),
+                *expected,
+                "--no-color {} tty {} env {:?}",
+                no_color_flag,
+                tty_default,
+                env
+            );
+        }
+    }
+}
@@ -0,0 +1,216 @@
+/// file: src/theme.rs
+/// description: Color themes mapping semantic roles (buy, sell, muted, ...) to ANSI sequences
+/// reference: https://en.wikipedia.org/wiki/ANSI_escape_code#SGR_(Select_Graphic_Rendition)_parameters
+use crate::{error::HyperliquidError, formatter::Colors};
+use serde::Deserialize;
+use std::path::Path;
+
+/// ANSI sequences for each semantic role used by the formatters
+///
+/// Attributes (bold, dim, reset) stay fixed; only the colors are themed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Buys, bids, rising prices, healthy connections
+    pub buy: &'static str,
+    /// Sells, asks, falling prices, failures
+    pub sell: &'static str,
+    /// Borders, timestamps, secondary details
+    pub muted: &'static str,
+    /// Neutral status text
+    pub text: &'static str,
+    /// Banner frame and headings
+    pub accent: &'static str,
+    /// Connecting and reconnect notices
+    pub warning: &'static str,
+    /// Informational notices
+    pub info: &'static str,
+    /// Subscription and shutdown notices
+    pub notice: &'static str,
+    /// Error details
+    pub error: &'static str,
+}
+
+impl Theme {
+    /// Bright palette for dark backgrounds (the original look)
+    pub const DARK: Theme = Theme {
+        buy: Colors::BRIGHT_GREEN,
+        sell: Colors::BRIGHT_RED,
+        muted: Colors::GRAY,
+        text: Colors::WHITE,
+        accent: Colors::BRIGHT_CYAN,
+        warning: Colors::BRIGHT_YELLOW,
+        info: Colors::BRIGHT_BLUE,
+        notice: Colors::BRIGHT_MAGENTA,
+        error: Colors::RED,
+    };
+
+    /// Darker tones that stay readable on white or pastel backgrounds
+    pub const LIGHT: Theme = Theme {
+        buy: "\x1b[38;5;28m",
+        sell: "\x1b[38;5;160m",
+        muted: "\x1b[38;5;243m",
+        text: "\x1b[30m",
+        accent: "\x1b[38;5;25m",
+        warning: "\x1b[38;5;130m",
+        info: "\x1b[38;5;25m",
+        notice: "\x1b[38;5;90m",
+        error: "\x1b[38;5;124m",
+    };
+
+    /// Bold bright colors with no dim grays
+    pub const HIGH_CONTRAST: Theme = Theme {
+        buy: "\x1b[1;92m",
+        sell: "\x1b[1;91m",
+        muted: "\x1b[97m",
+        text: "\x1b[1;97m",
+        accent: "\x1b[1;96m",
+        warning: "\x1b[1;93m",
+        info: "\x1b[1;94m",
+        notice: "\x1b[1;95m",
+        error: "\x1b[1;91m",
+    };
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(Theme::DARK),
+            "light" => Some(Theme::LIGHT),
+            "high-contrast" | "high_contrast" | "contrast" => Some(Theme::HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    /// Resolve `--theme`: a built-in name, or a path to a TOML theme file
+    pub fn resolve(spec: &str) -> Result<Theme, HyperliquidError> {
+        if let Some(theme) = Theme::builtin(spec) {
+            return Ok(theme);
+        }
+        let path = Path::new(spec);
+        if path.is_file() {
+            return Theme::load(path);
+        }
+        Err(HyperliquidError::ThemeError(format!(
+            "'{}' is neither a built-in theme (dark, light, high-contrast) nor a theme file",
+            spec
+        )))
+    }
+
+    /// Load a user-defined theme file
+    ///
+    /// ```toml
+    /// base = "light"        # optional, defaults to dark
+    /// buy = "blue"          # named color
+    /// sell = "208"          # 256-color index
+    /// accent = "bold #005f87" # truecolor, optional bold prefix
+    /// ```
+    pub fn load(path: &Path) -> Result<Theme, HyperliquidError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            HyperliquidError::ThemeError(format!("cannot read {}: {}", path.display(), e))
+        })?;
+        let file: ThemeFile = toml::from_str(&contents).map_err(|e| {
+            HyperliquidError::ThemeError(format!("cannot parse {}: {}", path.display(), e))
+        })?;
+        file.into_theme()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DARK
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    buy: Option<String>,
+    sell: Option<String>,
+    muted: Option<String>,
+    text: Option<String>,
+    accent: Option<String>,
+    warning: Option<String>,
+    info: Option<String>,
+    notice: Option<String>,
+    error: Option<String>,
+}
+
+impl ThemeFile {
+    fn into_theme(self) -> Result<Theme, HyperliquidError> {
+        let mut theme = match &self.base {
+            Some(base) => Theme::builtin(base).ok_or_else(|| {
+                HyperliquidError::ThemeError(format!("unknown base theme '{}'", base))
+            })?,
+            None => Theme::DARK,
+        };
+
+        let overrides = [
+            (self.buy, &mut theme.buy),
+            (self.sell, &mut theme.sell),
+            (self.muted, &mut theme.muted),
+            (self.text, &mut theme.text),
+            (self.accent, &mut theme.accent),
+            (self.warning, &mut theme.warning),
+            (self.info, &mut theme.info),
+            (self.notice, &mut theme.notice),
+            (self.error, &mut theme.error),
+        ];
+        for (spec, slot) in overrides {
+            if let Some(spec) = spec {
+                // Themes are loaded once at startup, so the sequences live for the whole run
+                *slot = Box::leak(parse_color(&spec)?.into_boxed_str());
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Convert a color spec (name, 0-255 index, or `#rrggbb`, optionally prefixed
+/// with `bold`) into an SGR sequence
+fn parse_color(spec: &str) -> Result<String, HyperliquidError> {
+    let spec = spec.trim();
+    let (bold, color) = match spec.strip_prefix("bold ") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, spec),
+    };
+
+    let code = match color.to_ascii_lowercase().as_str() {
+        "black" => "30".to_string(),
+        "red" => "31".to_string(),
+        "green" => "32".to_string(),
+        "yellow" => "33".to_string(),
+        "blue" => "34".to_string(),
+        "magenta" => "35".to_string(),
+        "cyan" => "36".to_string(),
+        "white" => "37".to_string(),
+        "gray" | "grey" => "90".to_string(),
+        "bright-red" => "91".to_string(),
+        "bright-green" => "92".to_string(),
+        "bright-yellow" => "93".to_string(),
+        "bright-blue" => "94".to_string(),
+        "bright-magenta" => "95".to_string(),
+        "bright-cyan" => "96".to_string(),
+        "bright-white" => "97".to_string(),
+        other => {
+            if let Ok(index) = other.parse::<u8>() {
+                format!("38;5;{}", index)
+            } else if let Some(hex) = other.strip_prefix('#')
+                && hex.len() == 6
+                && let Ok(rgb) = u32::from_str_radix(hex, 16)
+            {
+                format!("38;2;{};{};{}", rgb >> 16, (rgb >> 8) & 0xff, rgb & 0xff)
+            } else {
+                return Err(HyperliquidError::ThemeError(format!(
+                    "invalid color '{}', expected a name, 0-255, or #rrggbb",
+                    spec
+                )));
+            }
+        }
+    };
+
+    Ok(if bold {
+        format!("\x1b[1;{}m", code)
+    } else {
+        format!("\x1b[{}m", code)
+    })
+}
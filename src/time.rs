@@ -0,0 +1,51 @@
+/// file: src/time.rs
+/// description: Exchange timestamp conversion with bounds, and receive delays corrected for clock skew
+use chrono::{DateTime, Utc};
+
+/// Before Hyperliquid traded; an earlier timestamp is corrupt, not old
+const EARLIEST_MS: i64 = 1_672_531_200_000; // 2023-01-01T00:00:00Z
+/// How far ahead of the local clock an exchange timestamp may be before it is treated as corrupt
+///
+/// A slow local clock makes live timestamps look like the future, so this is far wider than any
+/// skew `--max-clock-skew` would let pass without a warning.
+const MAX_AHEAD_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// An exchange timestamp in milliseconds as a time, or `None` when it cannot be one
+///
+/// Timestamps slightly ahead of the local clock are kept as sent. Values before the exchange
+/// existed or a day past the local clock are rejected instead of replaced with the current time,
+/// so callers can count or mark them rather than show a time nobody sent.
+pub fn exchange_time(millis: i64) -> Option<DateTime<Utc>> {
+    let latest = Utc::now().timestamp_millis().saturating_add(MAX_AHEAD_MS);
+    if !(EARLIEST_MS..=latest).contains(&millis) {
+        return None;
+    }
+    DateTime::from_timestamp_millis(millis)
+}
+
+/// Time from an exchange timestamp to local receipt, with the skew estimate that corrects it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delay {
+    /// Local receive time minus exchange time, as measured
+    pub raw_ms: i64,
+    /// Local clock minus exchange clock from `ClockSkewMonitor`, once it has an estimate
+    pub skew_ms: Option<i64>,
+}
+
+impl Delay {
+    pub fn new(exchange_ms: i64, local_ms: i64, skew_ms: Option<i64>) -> Self {
+        Self {
+            raw_ms: local_ms.saturating_sub(exchange_ms),
+            skew_ms,
+        }
+    }
+
+    /// The delay with the skew estimate taken out, never negative
+    ///
+    /// The estimate is the fastest delivery of its window, so this is the delay beyond that best
+    /// case. Without an estimate the raw figure is used, and a timestamp slightly ahead of the
+    /// local clock counts as no delay.
+    pub fn corrected_ms(&self) -> i64 {
+        self.raw_ms.saturating_sub(self.skew_ms.unwrap_or(0)).max(0)
+    }
+}
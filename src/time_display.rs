@@ -0,0 +1,99 @@
+/// file: src/time_display.rs
+/// description: Timezone and format selection for timestamps shown in every output format
+/// reference: https://docs.rs/chrono/latest/chrono/format/strftime/
+use crate::error::HyperliquidError;
+use crate::time::exchange_time;
+use chrono::{Local, format::Item, format::StrftimeItems};
+use chrono_tz::Tz;
+
+/// Default format for compact outputs (table, minimal, ladder)
+pub const SHORT_TIME_FORMAT: &str = "%H:%M:%S";
+/// Default format for record outputs (CSV, JSON)
+pub const FULL_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Shown in place of a timestamp outside the range `time::exchange_time` accepts
+pub const INVALID_TIME: &str = "invalid";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayZone {
+    Utc,
+    Local,
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// Parse `UTC`, `local`, or an IANA name such as `America/New_York`
+    pub fn parse(value: &str) -> Result<Self, HyperliquidError> {
+        match value.to_ascii_lowercase().as_str() {
+            "utc" | "z" => Ok(DisplayZone::Utc),
+            "local" => Ok(DisplayZone::Local),
+            _ => value.parse::<Tz>().map(DisplayZone::Named).map_err(|_| {
+                HyperliquidError::InvalidTimeSetting(format!(
+                    "unknown timezone '{}', expected UTC, local, or an IANA name",
+                    value
+                ))
+            }),
+        }
+    }
+}
+
+/// How exchange timestamps are rendered for humans
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeDisplay {
+    zone: DisplayZone,
+    format: Option<String>,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        Self {
+            zone: DisplayZone::Local,
+            format: None,
+        }
+    }
+}
+
+impl TimeDisplay {
+    pub fn new(zone: &str, format: Option<String>) -> Result<Self, HyperliquidError> {
+        if let Some(format) = &format
+            && StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+        {
+            return Err(HyperliquidError::InvalidTimeSetting(format!(
+                "invalid time format '{}'",
+                format
+            )));
+        }
+
+        Ok(Self {
+            zone: DisplayZone::parse(zone)?,
+            format,
+        })
+    }
+
+    /// Format a millisecond timestamp, using `default_format` unless `--time-format` was given
+    pub fn format_millis(&self, millis: i64, default_format: &str) -> String {
+        self.format_in_zone(millis, self.format.as_deref().unwrap_or(default_format))
+    }
+
+    /// Format a millisecond timestamp with an explicit format in the configured timezone
+    pub fn format_in_zone(&self, millis: i64, format: &str) -> String {
+        let Some(utc) = exchange_time(millis) else {
+            return INVALID_TIME.to_string();
+        };
+
+        match self.zone {
+            DisplayZone::Utc => utc.format(format).to_string(),
+            DisplayZone::Local => utc.with_timezone(&Local).format(format).to_string(),
+            DisplayZone::Named(tz) => utc.with_timezone(&tz).format(format).to_string(),
+        }
+    }
+
+    /// Short form used by compact outputs
+    pub fn short(&self, millis: i64) -> String {
+        self.format_millis(millis, SHORT_TIME_FORMAT)
+    }
+
+    /// Full form used by record outputs
+    pub fn full(&self, millis: i64) -> String {
+        self.format_millis(millis, FULL_TIME_FORMAT)
+    }
+}
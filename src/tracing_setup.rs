@@ -1,20 +1,155 @@
 /// file: src/tracing_setup.rs
 /// description: structured logging configuration and tracing initialization
 /// reference: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/
+use crate::error::HyperliquidError;
+use crate::log_file::{LogFileSettings, RollingLog};
+#[cfg(unix)]
+use crate::system_log::SystemLogLayer;
 use anyhow::Result;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::{Subscriber, level_filters::LevelFilter};
 use tracing_subscriber::{
-    EnvFilter,
-    fmt::{self, format::FmtSpan},
+    EnvFilter, Registry,
+    fmt::{self, format::FmtSpan, writer::BoxMakeWriter},
+    layer::{Layer, Layered},
     prelude::*,
+    reload,
 };
 
-pub fn setup_tracing(log_level: &str, json_logs: bool) -> Result<()> {
+/// Where log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTarget {
+    Stdout,
+    /// `--log-file`, with rotation and retention
+    File,
+    /// The systemd journal, with event fields kept as journal fields
+    Journald,
+    /// The local syslog socket, facility daemon
+    Syslog,
+}
+
+impl LogTarget {
+    /// `file` when a log file is configured, `stdout` otherwise
+    pub fn resolve(target: Option<LogTarget>, has_log_file: bool) -> Self {
+        target.unwrap_or(if has_log_file {
+            LogTarget::File
+        } else {
+            LogTarget::Stdout
+        })
+    }
+}
+
+impl FromStr for LogTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "stdout" => Ok(LogTarget::Stdout),
+            "file" => Ok(LogTarget::File),
+            "journald" | "journal" => Ok(LogTarget::Journald),
+            "syslog" => Ok(LogTarget::Syslog),
+            _ => Err(format!(
+                "unknown log target '{}', expected stdout, file, journald, or syslog",
+                value
+            )),
+        }
+    }
+}
+
+/// Share of messages that get a detailed span, from 0 (none) to 1 (all)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SampleRate(f64);
+
+impl FromStr for SampleRate {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.parse::<f64>() {
+            Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(SampleRate(rate)),
+            _ => Err(format!(
+                "invalid sample rate '{}', expected a number from 0 to 1",
+                value
+            )),
+        }
+    }
+}
+
+/// Picks every Nth call for a span, N being the inverse of the rate
+///
+/// A counter rather than a random draw, so an unsampled message costs one increment and the
+/// sampled ones are spread evenly through bursts.
+#[derive(Debug, Default)]
+pub struct SpanSampler {
+    every: u64,
+    seen: u64,
+}
+
+impl SpanSampler {
+    pub fn new(rate: SampleRate) -> Self {
+        let every = if rate.0 > 0.0 {
+            (1.0 / rate.0).round() as u64
+        } else {
+            0
+        };
+        Self { every, seen: 0 }
+    }
+
+    pub fn sample(&mut self) -> bool {
+        if self.every == 0 {
+            return false;
+        }
+        self.seen += 1;
+        self.seen.is_multiple_of(self.every)
+    }
+}
+
+/// Extra subscriber layer beside the log output, such as the OTLP span exporter
+pub type ExportLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Swaps the active log filter at runtime, e.g. on config reload
+pub type LogLevelHandle = reload::Handle<EnvFilter, Layered<Option<ExportLayer>, Registry>>;
+
+/// Logs go to `target`; `log_file` is only opened for [`LogTarget::File`]
+///
+/// The log filter applies to `export` as well, so it sees the same spans and events.
+pub fn setup_tracing(
+    log_level: &str,
+    json_logs: bool,
+    target: LogTarget,
+    log_file: Option<LogFileSettings>,
+    export: Option<ExportLayer>,
+) -> Result<LogLevelHandle> {
+    let (writer, ansi) = match (target, log_file) {
+        (LogTarget::File, Some(settings)) => {
+            let path = settings.path.clone();
+            let log = RollingLog::open(settings).map_err(|e| {
+                HyperliquidError::SinkError(format!("cannot log to {}: {}", path.display(), e))
+            })?;
+            (BoxMakeWriter::new(Mutex::new(log)), false)
+        }
+        (LogTarget::File, None) => {
+            return Err(HyperliquidError::ConfigError(
+                "--log-target file needs --log-file".to_string(),
+            )
+            .into());
+        }
+        _ => (BoxMakeWriter::new(std::io::stdout), true),
+    };
+    let system_log = system_log_layer(target)?;
+
     let filter = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new(format!("hyperliquid_ws_client={}", log_level)))
+        .or_else(|_| level_filter(log_level))
         .unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
 
-    let fmt_layer = if json_logs {
+    let fmt_layer = if let Some(system_log) = system_log {
+        system_log
+    } else if json_logs {
         fmt::layer()
+            .with_writer(writer)
             .json()
             .with_current_span(false)
             .with_span_list(true)
@@ -26,6 +161,8 @@ pub fn setup_tracing(log_level: &str, json_logs: bool) -> Result<()> {
             .boxed()
     } else {
         fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi)
             .with_target(true)
             .with_thread_ids(true)
             .with_thread_names(true)
@@ -36,9 +173,89 @@ pub fn setup_tracing(log_level: &str, json_logs: bool) -> Result<()> {
     };
 
     tracing_subscriber::registry()
+        .with(export)
         .with(filter)
         .with(fmt_layer)
         .init();
 
+    Ok(handle)
+}
+
+/// The journald or syslog layer that takes the place of the formatted output
+#[cfg(unix)]
+fn system_log_layer<S: Subscriber>(
+    target: LogTarget,
+) -> Result<Option<Box<dyn Layer<S> + Send + Sync>>> {
+    let (name, layer) = match target {
+        LogTarget::Journald => ("the journal", SystemLogLayer::journald()),
+        LogTarget::Syslog => ("syslog", SystemLogLayer::syslog()),
+        LogTarget::Stdout | LogTarget::File => return Ok(None),
+    };
+    let layer =
+        layer.map_err(|e| HyperliquidError::SinkError(format!("cannot log to {}: {}", name, e)))?;
+    Ok(Some(layer.boxed()))
+}
+
+#[cfg(not(unix))]
+fn system_log_layer<S: Subscriber>(
+    target: LogTarget,
+) -> Result<Option<Box<dyn Layer<S> + Send + Sync>>> {
+    match target {
+        LogTarget::Journald | LogTarget::Syslog => Err(HyperliquidError::ConfigError(
+            "--log-target journald and syslog need a Unix system".to_string(),
+        )
+        .into()),
+        LogTarget::Stdout | LogTarget::File => Ok(None),
+    }
+}
+
+/// Replace the log level set at startup
+pub fn set_log_level(handle: &LogLevelHandle, log_level: &str) -> Result<()> {
+    handle.reload(level_filter(log_level)?)?;
     Ok(())
 }
+
+/// Make logging one step more verbose on every SIGUSR2, e.g. `kill -USR2 <pid>` during an
+/// incident; after `trace` it returns to the level in effect when the listener started
+#[cfg(unix)]
+pub fn spawn_log_level_cycler(handle: LogLevelHandle) -> std::io::Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut user2 = signal(SignalKind::user_defined2())?;
+    let base = current_level(&handle).unwrap_or(LevelFilter::INFO);
+    tokio::spawn(async move {
+        while user2.recv().await.is_some() {
+            let next = match current_level(&handle).unwrap_or(base) {
+                LevelFilter::OFF => LevelFilter::ERROR,
+                LevelFilter::ERROR => LevelFilter::WARN,
+                LevelFilter::WARN => LevelFilter::INFO,
+                LevelFilter::INFO => LevelFilter::DEBUG,
+                LevelFilter::DEBUG => LevelFilter::TRACE,
+                _ => base,
+            };
+            let level = next.to_string().to_ascii_lowercase();
+            match set_log_level(&handle, &level) {
+                // Logged at warn so the change shows at every level but error
+                Ok(()) => tracing::warn!("Log level set to {} by SIGUSR2", level),
+                Err(e) => eprintln!("Cannot change log level to {}: {}", level, e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// The most verbose level the active filter lets through
+fn current_level(handle: &LogLevelHandle) -> Option<LevelFilter> {
+    handle
+        .with_current(|filter| filter.max_level_hint())
+        .ok()
+        .flatten()
+}
+
+fn level_filter(log_level: &str) -> Result<EnvFilter> {
+    Ok(EnvFilter::try_new(format!(
+        "{}={}",
+        env!("CARGO_CRATE_NAME"),
+        log_level
+    ))?)
+}
@@ -0,0 +1,115 @@
+/// file: src/transport.rs
+/// description: TCP and TLS connection setup shared by the WebSocket and REST clients
+/// reference: https://docs.rs/tokio-rustls/latest/tokio_rustls/
+use crate::error::HyperliquidError;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tracing::error;
+
+/// A plain TCP stream, or one wrapped in TLS for `wss://` and `https://` URLs
+pub enum MaybeTlsStream {
+    Tls(Box<tokio_rustls::client::TlsStream<TcpStream>>),
+    Plain(TcpStream),
+}
+
+impl tokio::io::AsyncRead for MaybeTlsStream {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        match &mut *self {
+            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_read(cx, buf),
+            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl tokio::io::AsyncWrite for MaybeTlsStream {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        match &mut *self {
+            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_write(cx, buf),
+            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        match &mut *self {
+            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_flush(cx),
+            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        match &mut *self {
+            MaybeTlsStream::Tls(s) => std::pin::Pin::new(s.as_mut()).poll_shutdown(cx),
+            MaybeTlsStream::Plain(s) => std::pin::Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Open a TCP connection to the URL's host, adding TLS for `wss` and `https` schemes
+pub async fn connect(
+    url: &url::Url,
+    connect_timeout: Duration,
+) -> Result<MaybeTlsStream, HyperliquidError> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| HyperliquidError::WebSocketError("Invalid host".to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    // Establish TCP connection
+    let stream = timeout(
+        connect_timeout,
+        TcpStream::connect(format!("{}:{}", host, port)),
+    )
+    .await
+    .map_err(|_| HyperliquidError::Timeout)?
+    .map_err(|e| {
+        error!("Failed to connect to TCP stream: {}", e);
+        HyperliquidError::IoError(e)
+    })?;
+
+    if !matches!(url.scheme(), "wss" | "https") {
+        return Ok(MaybeTlsStream::Plain(stream));
+    }
+
+    // Perform TLS handshake
+    let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(
+        rustls::ClientConfig::builder_with_provider(
+            rustls::crypto::ring::default_provider().into(),
+        )
+        .with_safe_default_protocol_versions()
+        .map_err(|e| HyperliquidError::WebSocketError(format!("TLS config error: {}", e)))?
+        .with_root_certificates(rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        })
+        .with_no_client_auth(),
+    ));
+    let domain = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| HyperliquidError::WebSocketError(format!("Invalid DNS name: {}", e)))?;
+    let tls_stream = connector
+        .connect(domain, stream)
+        .await
+        .map_err(|e| HyperliquidError::WebSocketError(format!("TLS error: {}", e)))?;
+    Ok(MaybeTlsStream::Tls(Box::new(tls_stream)))
+}
+
+/// `Name: value` request lines for extra headers, each ending in CRLF
+pub fn header_lines(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect()
+}
@@ -1,21 +1,56 @@
 /// file: src/types.rs
 /// description: type definitions and data structures for Hyperliquid WebSocket api messages
 /// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/websocket/ws-general
+use crate::{symbol::Symbol, time::exchange_time};
 use chrono::{DateTime, Local, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de::IgnoredAny};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
 
-// Helper for deserializing strings to f64
+// Helper for deserializing strings to f64, parsed in place from the frame
 mod string_to_float {
     use super::*;
     pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
+        #[derive(Deserialize)]
+        struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+        let Borrowed(s) = Borrowed::deserialize(deserializer)?;
         s.parse::<f64>().map_err(serde::de::Error::custom)
     }
 }
 
+/// A list of strings borrowed from the frame; a plain `Vec<Cow<str>>` would copy each one
+fn borrowed_strs<'de: 'a, 'a, D>(deserializer: D) -> Result<Vec<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+    Ok(Vec::<Borrowed>::deserialize(deserializer)?
+        .into_iter()
+        .map(|Borrowed(s)| s)
+        .collect())
+}
+
+/// Candle pushes carry one candle; snapshots carry several
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<Candle>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Candle),
+        Many(Vec<Candle>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(candle) => vec![candle],
+        OneOrMany::Many(candles) => candles,
+    })
+}
+
 // Subscription request types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscriptionRequest {
@@ -23,32 +58,78 @@ pub struct SubscriptionRequest {
     pub subscription: Subscription,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Subscription {
     #[serde(rename = "type")]
     pub subscription_type: String,
+    /// Empty for user-scoped channels, which are keyed by `user` instead, and for `allMids`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub coin: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// `l2Book` only: round levels to this many significant figures
+    #[serde(rename = "nSigFigs", default, skip_serializing_if = "Option::is_none")]
+    pub n_sig_figs: Option<u32>,
+    /// `l2Book` only: aggregation step mantissa, allowed with 5 significant figures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mantissa: Option<u32>,
+    /// `candle` only: bar length such as `1m` or `1h`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
 }
 
 // Response types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/// A frame from the server, parsed with `WebSocketMessage::parse`
+///
+/// Serialization writes the message back in its wire shape, which the strict schema check
+/// compares with the payload.
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
-pub enum WebSocketMessage {
+pub enum WebSocketMessage<'a> {
     SubscriptionResponse(SubscriptionResponse),
-    TradeData(TradeDataMessage),
+    TradeData(TradeDataMessage<'a>),
     BookData(BookDataMessage),
     BboData(BboDataMessage),
     AllMidsData(AllMidsDataMessage),
     CandleData(CandleDataMessage),
     UserEvent(UserEventMessage),
+    OrderUpdates(OrderUpdatesMessage),
+    UserFundings(UserFundingsMessage),
     Notification(NotificationMessage),
-    DirectTrades(Vec<Trade>),
+    /// A request the server rejected, such as a subscription to an unknown coin
+    Error(ErrorMessage),
+    DirectTrades(Vec<TradeRef<'a>>),
     DirectCandles(Vec<Candle>),
-    Ping(Channel),
+    /// The explorer's `explorerBlock` channel, pushed as a bare array
+    DirectBlocks(Vec<ExplorerBlock>),
+    /// The explorer's `explorerTxs` channel, pushed as a bare array
+    DirectTxs(Vec<ExplorerTx>),
+    /// A channel without a payload type: pings, pongs, and channels this client does not handle
+    Ping(ChannelOnly),
+}
+
+/// The fields every pushed message shares, read before the payload type is known
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow)]
+    channel: Cow<'a, str>,
+    #[serde(borrow, default)]
+    data: Option<&'a RawValue>,
 }
 
+/// Enough of a bare array element to tell trades, explorer blocks and transactions, and candles
+/// apart
+#[derive(Deserialize)]
+struct ElementKeys {
+    tid: Option<IgnoredAny>,
+    height: Option<IgnoredAny>,
+    action: Option<IgnoredAny>,
+}
+
+/// A message reduced to its channel name; `config::Channel` is the set of channels to subscribe to
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Channel {
+pub struct ChannelOnly {
     pub channel: String,
 }
 
@@ -65,9 +146,10 @@ pub struct SubscriptionResponseData {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TradeDataMessage {
+pub struct TradeDataMessage<'a> {
     pub channel: String,
-    pub data: Vec<Trade>,
+    #[serde(borrow)]
+    pub data: Vec<TradeRef<'a>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +173,7 @@ pub struct AllMidsDataMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CandleDataMessage {
     pub channel: String,
+    #[serde(deserialize_with = "one_or_many")]
     pub data: Vec<Candle>,
 }
 
@@ -100,17 +183,36 @@ pub struct UserEventMessage {
     pub data: UserEvent,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderUpdatesMessage {
+    pub channel: String,
+    pub data: Vec<OrderUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserFundingsMessage {
+    pub channel: String,
+    pub data: UserFundings,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationMessage {
     pub channel: String,
     pub data: Notification,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorMessage {
+    pub channel: String,
+    /// The server's description, which quotes the rejected request
+    pub data: String,
+}
+
 // Core data structures based on Hyperliquid API
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Trade {
-    pub coin: String,
+    pub coin: Symbol,
     pub side: String,
     #[serde(deserialize_with = "string_to_float::deserialize")]
     pub px: f64, // price
@@ -122,16 +224,41 @@ pub struct Trade {
     pub users: Vec<String>, // [buyer, seller] user addresses
 }
 
+/// A trade as parsed from a frame, with its strings borrowed from the payload
+///
+/// Strings only need copying when they contain escapes, which coins, sides, hashes, and
+/// addresses never do, so a burst of trades parses without a per-field allocation. Trades that
+/// pass validation and filtering become an owned `Trade` with `into_owned` when they are sent
+/// on as events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeRef<'a> {
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    #[serde(borrow)]
+    pub side: Cow<'a, str>,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub px: f64,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub sz: f64,
+    pub time: i64,
+    #[serde(borrow)]
+    pub hash: Cow<'a, str>,
+    pub tid: i64,
+    #[serde(borrow, deserialize_with = "borrowed_strs")]
+    pub users: Vec<Cow<'a, str>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Book {
-    pub coin: String,
+    pub coin: Symbol,
     pub levels: (Vec<Level>, Vec<Level>), // [bids, asks]
     pub time: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bbo {
-    pub coin: String,
+    pub coin: Symbol,
     pub time: i64,
     pub bbo: (Option<Level>, Option<Level>), // [best_bid, best_ask]
 }
@@ -147,7 +274,7 @@ pub struct Level {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllMids {
-    pub mids: std::collections::HashMap<String, String>,
+    pub mids: std::collections::HashMap<Symbol, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,12 +282,17 @@ pub struct Candle {
     pub t: i64, // open millis
     #[serde(rename = "T")]
     pub close_time: i64, // close millis
-    pub s: String, // coin
+    pub s: Symbol, // coin
     pub i: String, // interval
+    #[serde(deserialize_with = "string_to_float::deserialize")]
     pub o: f64, // open price
+    #[serde(deserialize_with = "string_to_float::deserialize")]
     pub c: f64, // close price
+    #[serde(deserialize_with = "string_to_float::deserialize")]
     pub h: f64, // high price
+    #[serde(deserialize_with = "string_to_float::deserialize")]
     pub l: f64, // low price
+    #[serde(deserialize_with = "string_to_float::deserialize")]
     pub v: f64, // volume (base unit)
     pub n: i32, // number of trades
 }
@@ -168,28 +300,60 @@ pub struct Candle {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UserEvent {
-    Fills { fills: Vec<Fill> },
-    Funding { funding: UserFunding },
-    Liquidation { liquidation: Liquidation },
-    NonUserCancel { non_user_cancel: Vec<NonUserCancel> },
+    /// From `userFills`, which also names the user and marks the history sent on subscribing as
+    /// a snapshot, or from `userEvents`, which carries only the fills
+    Fills {
+        fills: Vec<Fill>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+        #[serde(
+            rename = "isSnapshot",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        is_snapshot: Option<bool>,
+    },
+    Funding {
+        funding: UserFunding,
+    },
+    Liquidation {
+        liquidation: Liquidation,
+    },
+    NonUserCancel {
+        #[serde(rename = "nonUserCancel")]
+        non_user_cancel: Vec<NonUserCancel>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fill {
-    pub coin: String,
-    pub px: String,
-    pub sz: String,
+    pub coin: Symbol,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub px: f64,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub sz: f64,
     pub side: String,
     pub time: i64,
-    #[serde(rename = "startPosition")]
-    pub start_position: String,
+    /// Signed position size before the fill
+    #[serde(
+        rename = "startPosition",
+        deserialize_with = "string_to_float::deserialize"
+    )]
+    pub start_position: f64,
+    /// What the fill did to the position, such as `Open Long` or `Close Short`
     pub dir: String,
-    #[serde(rename = "closedPnl")]
-    pub closed_pnl: String,
+    #[serde(
+        rename = "closedPnl",
+        deserialize_with = "string_to_float::deserialize"
+    )]
+    pub closed_pnl: f64,
     pub hash: String,
     pub oid: i64,
+    /// Whether the order took liquidity
     pub crossed: bool,
-    pub fee: String,
+    /// Negative for a maker rebate
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub fee: f64,
     pub tid: i64,
     #[serde(rename = "feeToken")]
     pub fee_token: String,
@@ -200,11 +364,31 @@ pub struct Fill {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserFunding {
     pub time: i64,
-    pub coin: String,
-    pub usdc: String,
-    pub szi: String,
-    #[serde(rename = "fundingRate")]
-    pub funding_rate: String,
+    pub coin: Symbol,
+    /// Funding received, negative when paid
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub usdc: f64,
+    /// Signed position size the funding applied to
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub szi: f64,
+    #[serde(
+        rename = "fundingRate",
+        deserialize_with = "string_to_float::deserialize"
+    )]
+    pub funding_rate: f64,
+}
+
+/// Funding payments from the `userFundings` channel, which opens with a snapshot of recent ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserFundings {
+    pub user: String,
+    #[serde(
+        rename = "isSnapshot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub is_snapshot: Option<bool>,
+    pub fundings: Vec<UserFunding>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -212,40 +396,216 @@ pub struct Liquidation {
     pub lid: i64,
     pub liquidator: String,
     pub liquidated_user: String,
-    pub liquidated_ntl_pos: String,
-    pub liquidated_account_value: String,
+    /// Notional of the liquidated position
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub liquidated_ntl_pos: f64,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub liquidated_account_value: f64,
 }
 
+/// An order canceled by the exchange rather than the user, such as for insufficient margin
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NonUserCancel {
-    pub coin: String,
+    pub coin: Symbol,
     pub oid: i64,
 }
 
+/// A change to one of the account's orders from the `orderUpdates` channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderUpdate {
+    pub order: BasicOrder,
+    /// The exchange's status, such as `open`, `filled`, `canceled`, or `marginCanceled`
+    pub status: String,
+    pub status_timestamp: i64, // timestamp in milliseconds
+}
+
+/// An order as `orderUpdates` describes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicOrder {
+    pub coin: Symbol,
+    pub side: String,
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub limit_px: f64,
+    /// Size still resting
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub sz: f64,
+    pub oid: i64,
+    pub timestamp: i64, // placed, in milliseconds
+    #[serde(deserialize_with = "string_to_float::deserialize")]
+    pub orig_sz: f64,
+    /// Client order id, when the order was placed with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloid: Option<String>,
+}
+
+/// Where an order stands, grouping the exchange's many cancel and reject reasons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    Open,
+    Filled,
+    Canceled,
+    /// A trigger order whose trigger price was reached, placing it on the book
+    Triggered,
+    Rejected,
+    /// A status this client does not know
+    Other,
+}
+
+impl OrderState {
+    /// No further updates follow for the order
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            OrderState::Filled | OrderState::Canceled | OrderState::Rejected
+        )
+    }
+}
+
+impl OrderUpdate {
+    pub fn state(&self) -> OrderState {
+        match self.status.as_str() {
+            "open" => OrderState::Open,
+            "filled" => OrderState::Filled,
+            "triggered" => OrderState::Triggered,
+            "canceled" | "scheduledCancel" => OrderState::Canceled,
+            "rejected" => OrderState::Rejected,
+            // Reasons such as `marginCanceled` or `tickRejected`
+            status if status.ends_with("Canceled") => OrderState::Canceled,
+            status if status.ends_with("Rejected") => OrderState::Rejected,
+            _ => OrderState::Other,
+        }
+    }
+
+    /// Size filled so far
+    pub fn filled_sz(&self) -> f64 {
+        self.order.filled_sz()
+    }
+
+    pub fn is_buy(&self) -> bool {
+        self.order.is_buy()
+    }
+
+    pub fn side_formatted(&self) -> &'static str {
+        self.order.side_formatted()
+    }
+}
+
+impl BasicOrder {
+    /// Size filled so far
+    pub fn filled_sz(&self) -> f64 {
+        (self.orig_sz - self.sz).max(0.0)
+    }
+
+    pub fn is_buy(&self) -> bool {
+        is_buy_side(&self.side)
+    }
+
+    pub fn side_formatted(&self) -> &'static str {
+        if self.is_buy() { "BUY" } else { "SELL" }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
     pub notification: String,
 }
 
+/// A block from the explorer's `explorerBlock` channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplorerBlock {
+    pub height: u64,
+    pub block_time: i64, // timestamp in milliseconds
+    pub hash: String,
+    pub proposer: String, // validator address
+    pub num_txs: u64,
+}
+
+/// A transaction from the explorer's `explorerTxs` channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorerTx {
+    pub hash: String,
+    pub block: u64, // height of the block that included it
+    pub time: i64,  // timestamp in milliseconds
+    pub user: String,
+    /// The signed action, kept whole; its `type` names it, e.g. `order` or `cancel`
+    pub action: serde_json::Value,
+    /// Why the exchange rejected it; `None` when it succeeded
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl ExplorerTx {
+    pub fn action_type(&self) -> &str {
+        self.action
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown")
+    }
+}
+
+/// Perpetuals metadata returned by the `meta` info request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meta {
+    pub universe: Vec<AssetMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetMeta {
+    pub name: String,
+    #[serde(rename = "szDecimals")]
+    pub sz_decimals: u32,
+    #[serde(rename = "maxLeverage", default)]
+    pub max_leverage: u32,
+    #[serde(rename = "isDelisted", default)]
+    pub is_delisted: bool,
+}
+
+/// Spot tokens and pairs returned by the `spotMeta` info request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotMeta {
+    pub tokens: Vec<SpotToken>,
+    pub universe: Vec<SpotPair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotToken {
+    pub name: String,
+    #[serde(rename = "szDecimals")]
+    pub sz_decimals: u32,
+    pub index: u32,
+}
+
+/// A tradable pair; `name` is the coin used on the WebSocket (`PURR/USDC` or `@107`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotPair {
+    pub name: String,
+    /// Base and quote token indices into `SpotMeta::tokens`
+    pub tokens: [u32; 2],
+    pub index: u32,
+}
+
 impl Trade {
     /// Calculate the trade value (price * size)
     pub fn value(&self) -> f64 {
         self.px * self.sz
     }
 
-    /// Get timestamp as UTC DateTime
-    pub fn datetime_utc(&self) -> DateTime<Utc> {
-        DateTime::from_timestamp_millis(self.time).unwrap_or_else(Utc::now)
+    /// Get timestamp as UTC DateTime, `None` when the exchange sent an unusable one
+    pub fn datetime_utc(&self) -> Option<DateTime<Utc>> {
+        exchange_time(self.time)
     }
 
-    /// Get timestamp as Local DateTime
-    pub fn datetime_local(&self) -> DateTime<Local> {
-        self.datetime_utc().with_timezone(&Local)
+    /// Get timestamp as Local DateTime, `None` when the exchange sent an unusable one
+    pub fn datetime_local(&self) -> Option<DateTime<Local>> {
+        self.datetime_utc().map(|at| at.with_timezone(&Local))
     }
 
     /// Check if this is a buy trade
     pub fn is_buy(&self) -> bool {
-        self.side.eq_ignore_ascii_case("B") || self.side.eq_ignore_ascii_case("BUY")
+        is_buy_side(&self.side)
     }
 
     /// Check if this is a sell trade
@@ -268,28 +628,322 @@ impl Trade {
     }
 }
 
+impl TradeRef<'_> {
+    /// Calculate the trade value (price * size)
+    pub fn value(&self) -> f64 {
+        self.px * self.sz
+    }
+
+    /// Check if this is a buy trade
+    pub fn is_buy(&self) -> bool {
+        is_buy_side(&self.side)
+    }
+
+    /// Copy the borrowed strings out but keep the borrowing type
+    fn into_static(self) -> TradeRef<'static> {
+        TradeRef {
+            coin: Cow::Owned(self.coin.into_owned()),
+            side: Cow::Owned(self.side.into_owned()),
+            px: self.px,
+            sz: self.sz,
+            time: self.time,
+            hash: Cow::Owned(self.hash.into_owned()),
+            tid: self.tid,
+            users: self
+                .users
+                .into_iter()
+                .map(|user| Cow::Owned(user.into_owned()))
+                .collect(),
+        }
+    }
+
+    /// Copy the borrowed strings out, for a trade that outlives its frame
+    pub fn into_owned(self) -> Trade {
+        Trade {
+            coin: Symbol::intern(&self.coin),
+            side: self.side.into_owned(),
+            px: self.px,
+            sz: self.sz,
+            time: self.time,
+            hash: self.hash.into_owned(),
+            tid: self.tid,
+            users: self.users.into_iter().map(Cow::into_owned).collect(),
+        }
+    }
+}
+
+fn is_buy_side(side: &str) -> bool {
+    side.eq_ignore_ascii_case("B") || side.eq_ignore_ascii_case("BUY")
+}
+
 impl Candle {
     /// Get open time as UTC DateTime
-    pub fn open_time_utc(&self) -> DateTime<Utc> {
-        DateTime::from_timestamp_millis(self.t).unwrap_or_else(Utc::now)
+    pub fn open_time_utc(&self) -> Option<DateTime<Utc>> {
+        exchange_time(self.t)
     }
 
     /// Get close time as UTC DateTime
-    pub fn close_time_utc(&self) -> DateTime<Utc> {
-        DateTime::from_timestamp_millis(self.close_time).unwrap_or_else(Utc::now)
+    pub fn close_time_utc(&self) -> Option<DateTime<Utc>> {
+        exchange_time(self.close_time)
     }
 
     /// Get open time as Local DateTime
-    pub fn open_time_local(&self) -> DateTime<Local> {
-        self.open_time_utc().with_timezone(&Local)
+    pub fn open_time_local(&self) -> Option<DateTime<Local>> {
+        self.open_time_utc().map(|at| at.with_timezone(&Local))
     }
 
     /// Get close time as Local DateTime
-    pub fn close_time_local(&self) -> DateTime<Local> {
-        self.close_time_utc().with_timezone(&Local)
+    pub fn close_time_local(&self) -> Option<DateTime<Local>> {
+        self.close_time_utc().map(|at| at.with_timezone(&Local))
+    }
+}
+
+impl Book {
+    /// Get the best (highest) bid level
+    pub fn best_bid(&self) -> Option<&Level> {
+        self.levels.0.first()
+    }
+
+    /// Get the best (lowest) ask level
+    pub fn best_ask(&self) -> Option<&Level> {
+        self.levels.1.first()
+    }
+
+    /// Calculate the spread between best ask and best bid
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask.px - bid.px),
+            _ => None,
+        }
+    }
+
+    /// Calculate the mid price between best bid and best ask
+    pub fn mid(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((ask.px + bid.px) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Get timestamp as Local DateTime, `None` when the exchange sent an unusable one
+    pub fn datetime_local(&self) -> Option<DateTime<Local>> {
+        exchange_time(self.time).map(|at| at.with_timezone(&Local))
+    }
+}
+
+impl Fill {
+    pub fn is_buy(&self) -> bool {
+        is_buy_side(&self.side)
+    }
+
+    pub fn side_formatted(&self) -> &'static str {
+        if self.is_buy() { "BUY" } else { "SELL" }
+    }
+}
+
+impl Bbo {
+    /// Calculate the spread between best ask and best bid
+    pub fn spread(&self) -> Option<f64> {
+        match &self.bbo {
+            (Some(bid), Some(ask)) => Some(ask.px - bid.px),
+            _ => None,
+        }
+    }
+
+    /// Calculate the mid price between best bid and best ask
+    pub fn mid(&self) -> Option<f64> {
+        match &self.bbo {
+            (Some(bid), Some(ask)) => Some((ask.px + bid.px) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Spread in basis points of the mid price
+    pub fn spread_bps(&self) -> Option<f64> {
+        match (self.spread(), self.mid()) {
+            (Some(spread), Some(mid)) if mid > 0.0 => Some(spread / mid * 10_000.0),
+            _ => None,
+        }
     }
 }
 
+impl<'a> WebSocketMessage<'a> {
+    /// Parse a frame by its `channel` field
+    ///
+    /// The payload is deserialized once, as the type its channel carries, so a malformed message
+    /// fails with that type's error instead of after every variant has been tried. Bare arrays,
+    /// which carry no channel, are trades when their elements have a `tid`, explorer blocks with
+    /// a `height`, explorer transactions with an `action`, and candles otherwise.
+    pub fn parse(text: &'a str) -> serde_json::Result<Self> {
+        if text.trim_start().starts_with('[') {
+            let elements: Vec<ElementKeys> = serde_json::from_str(text)?;
+            let Some(first) = elements.first() else {
+                return Ok(WebSocketMessage::DirectTrades(Vec::new()));
+            };
+            return Ok(if first.tid.is_some() {
+                WebSocketMessage::DirectTrades(payload("trades", text)?)
+            } else if first.height.is_some() {
+                WebSocketMessage::DirectBlocks(payload("explorerBlock", text)?)
+            } else if first.action.is_some() {
+                WebSocketMessage::DirectTxs(payload("explorerTxs", text)?)
+            } else {
+                WebSocketMessage::DirectCandles(payload("candle", text)?)
+            });
+        }
+
+        let Envelope { channel, data } = serde_json::from_str(text)?;
+        let data = || {
+            data.map(RawValue::get).ok_or_else(|| {
+                serde::de::Error::custom(format_args!("{} message without data", channel))
+            })
+        };
+        Ok(match channel.as_ref() {
+            "subscriptionResponse" => {
+                WebSocketMessage::SubscriptionResponse(SubscriptionResponse {
+                    data: payload(&channel, data()?)?,
+                    channel: channel.into_owned(),
+                })
+            }
+            "trades" => WebSocketMessage::TradeData(TradeDataMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "l2Book" => WebSocketMessage::BookData(BookDataMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "bbo" => WebSocketMessage::BboData(BboDataMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "allMids" => WebSocketMessage::AllMidsData(AllMidsDataMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            // Pushes carry one candle, snapshots a list
+            "candle" => {
+                let data = data()?;
+                let data = if data.starts_with('[') {
+                    payload(&channel, data)?
+                } else {
+                    vec![payload(&channel, data)?]
+                };
+                WebSocketMessage::CandleData(CandleDataMessage {
+                    data,
+                    channel: channel.into_owned(),
+                })
+            }
+            "user" | "userEvents" | "userFills" => WebSocketMessage::UserEvent(UserEventMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "orderUpdates" => WebSocketMessage::OrderUpdates(OrderUpdatesMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "userFundings" => WebSocketMessage::UserFundings(UserFundingsMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "notification" => WebSocketMessage::Notification(NotificationMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            "error" => WebSocketMessage::Error(ErrorMessage {
+                data: payload(&channel, data()?)?,
+                channel: channel.into_owned(),
+            }),
+            _ => WebSocketMessage::Ping(ChannelOnly {
+                channel: channel.into_owned(),
+            }),
+        })
+    }
+
+    /// Channel and coin of a market data message, for routing it to a subscription block
+    pub fn route_key(&self) -> Option<(&'static str, &str)> {
+        match self {
+            WebSocketMessage::TradeData(message) => {
+                Some(("trades", message.data.first()?.coin.as_ref()))
+            }
+            WebSocketMessage::BookData(message) => Some(("l2Book", message.data.coin.as_str())),
+            WebSocketMessage::BboData(message) => Some(("bbo", message.data.coin.as_str())),
+            WebSocketMessage::CandleData(message) => {
+                Some(("candle", message.data.first()?.s.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Channel the message arrived on, as sent by the server
+    pub fn channel(&self) -> &str {
+        match self {
+            WebSocketMessage::SubscriptionResponse(message) => &message.channel,
+            WebSocketMessage::TradeData(message) => &message.channel,
+            WebSocketMessage::BookData(message) => &message.channel,
+            WebSocketMessage::BboData(message) => &message.channel,
+            WebSocketMessage::AllMidsData(message) => &message.channel,
+            WebSocketMessage::CandleData(message) => &message.channel,
+            WebSocketMessage::UserEvent(message) => &message.channel,
+            WebSocketMessage::OrderUpdates(message) => &message.channel,
+            WebSocketMessage::UserFundings(message) => &message.channel,
+            WebSocketMessage::Notification(message) => &message.channel,
+            WebSocketMessage::Error(message) => &message.channel,
+            WebSocketMessage::DirectTrades(_) => "trades",
+            WebSocketMessage::DirectCandles(_) => "candle",
+            WebSocketMessage::DirectBlocks(_) => "explorerBlock",
+            WebSocketMessage::DirectTxs(_) => "explorerTxs",
+            WebSocketMessage::Ping(message) => &message.channel,
+        }
+    }
+
+    /// Subscription acknowledgements, server errors, and heartbeats, as opposed to market or
+    /// account data
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            WebSocketMessage::SubscriptionResponse(_)
+                | WebSocketMessage::Error(_)
+                | WebSocketMessage::Ping(_)
+        )
+    }
+
+    /// Copy borrowed trade strings out, for a message that outlives its frame
+    pub fn into_owned(self) -> WebSocketMessage<'static> {
+        let owned = |trades: Vec<TradeRef<'_>>| -> Vec<TradeRef<'static>> {
+            trades.into_iter().map(TradeRef::into_static).collect()
+        };
+        match self {
+            WebSocketMessage::SubscriptionResponse(message) => {
+                WebSocketMessage::SubscriptionResponse(message)
+            }
+            WebSocketMessage::TradeData(message) => WebSocketMessage::TradeData(TradeDataMessage {
+                channel: message.channel,
+                data: owned(message.data),
+            }),
+            WebSocketMessage::BookData(message) => WebSocketMessage::BookData(message),
+            WebSocketMessage::BboData(message) => WebSocketMessage::BboData(message),
+            WebSocketMessage::AllMidsData(message) => WebSocketMessage::AllMidsData(message),
+            WebSocketMessage::CandleData(message) => WebSocketMessage::CandleData(message),
+            WebSocketMessage::UserEvent(message) => WebSocketMessage::UserEvent(message),
+            WebSocketMessage::OrderUpdates(message) => WebSocketMessage::OrderUpdates(message),
+            WebSocketMessage::UserFundings(message) => WebSocketMessage::UserFundings(message),
+            WebSocketMessage::Notification(message) => WebSocketMessage::Notification(message),
+            WebSocketMessage::Error(message) => WebSocketMessage::Error(message),
+            WebSocketMessage::DirectTrades(trades) => WebSocketMessage::DirectTrades(owned(trades)),
+            WebSocketMessage::DirectCandles(candles) => WebSocketMessage::DirectCandles(candles),
+            WebSocketMessage::DirectBlocks(blocks) => WebSocketMessage::DirectBlocks(blocks),
+            WebSocketMessage::DirectTxs(txs) => WebSocketMessage::DirectTxs(txs),
+            WebSocketMessage::Ping(message) => WebSocketMessage::Ping(message),
+        }
+    }
+}
+
+/// Deserialize the payload of a `channel` message, naming the channel in the error
+fn payload<'a, T: Deserialize<'a>>(channel: &str, data: &'a str) -> serde_json::Result<T> {
+    serde_json::from_str(data)
+        .map_err(|e| serde::de::Error::custom(format_args!("{} message: {}", channel, e)))
+}
+
 impl SubscriptionRequest {
     pub fn new_trades_subscription(coin: &str) -> Self {
         Self {
@@ -297,6 +951,7 @@ impl SubscriptionRequest {
             subscription: Subscription {
                 subscription_type: "trades".to_string(),
                 coin: coin.to_string(),
+                ..Subscription::default()
             },
         }
     }
@@ -307,16 +962,25 @@ impl SubscriptionRequest {
             subscription: Subscription {
                 subscription_type: "l2Book".to_string(),
                 coin: coin.to_string(),
+                ..Subscription::default()
             },
         }
     }
 
+    /// Request server-side aggregation on an `l2Book` subscription; `None` keeps full precision
+    pub fn with_book_aggregation(mut self, n_sig_figs: Option<u32>, mantissa: Option<u32>) -> Self {
+        self.subscription.n_sig_figs = n_sig_figs;
+        self.subscription.mantissa = mantissa;
+        self
+    }
+
     pub fn new_bbo_subscription(coin: &str) -> Self {
         Self {
             method: "subscribe".to_string(),
             subscription: Subscription {
                 subscription_type: "bbo".to_string(),
                 coin: coin.to_string(),
+                ..Subscription::default()
             },
         }
     }
@@ -326,7 +990,7 @@ impl SubscriptionRequest {
             method: "subscribe".to_string(),
             subscription: Subscription {
                 subscription_type: "allMids".to_string(),
-                coin: "*".to_string(),
+                ..Subscription::default()
             },
         }
     }
@@ -335,38 +999,69 @@ impl SubscriptionRequest {
         Self {
             method: "subscribe".to_string(),
             subscription: Subscription {
-                subscription_type: format!("candle.{}", interval),
+                subscription_type: "candle".to_string(),
                 coin: coin.to_string(),
+                interval: Some(interval.to_string()),
+                ..Subscription::default()
             },
         }
     }
 
-    pub fn new_user_events_subscription(user: &str) -> Self {
+    /// Subscription to an explorer channel, `explorerBlock` or `explorerTxs`, which takes no coin
+    pub fn new_explorer_subscription(channel: &str) -> Self {
         Self {
             method: "subscribe".to_string(),
             subscription: Subscription {
-                subscription_type: "userEvents".to_string(),
-                coin: user.to_string(),
+                subscription_type: channel.to_string(),
+                ..Subscription::default()
             },
         }
     }
 
-    pub fn new_user_fills_subscription(user: &str) -> Self {
+    pub fn new_user_events_subscription(user: &str) -> Self {
+        Self::new_user_subscription("userEvents", user)
+    }
+
+    pub fn new_order_updates_subscription(user: &str) -> Self {
+        Self::new_user_subscription("orderUpdates", user)
+    }
+
+    /// Subscription to a user-scoped channel such as `userFills` or `orderUpdates`
+    pub fn new_user_subscription(channel: &str, user: &str) -> Self {
         Self {
             method: "subscribe".to_string(),
             subscription: Subscription {
-                subscription_type: "userFills".to_string(),
-                coin: user.to_string(),
+                subscription_type: channel.to_string(),
+                coin: String::new(),
+                user: Some(user.to_string()),
+                ..Subscription::default()
             },
         }
     }
 
+    pub fn new_user_fills_subscription(user: &str) -> Self {
+        Self::new_user_subscription("userFills", user)
+    }
+
+    pub fn new_user_fundings_subscription(user: &str) -> Self {
+        Self::new_user_subscription("userFundings", user)
+    }
+
+    /// Turn a subscribe request into the matching unsubscribe request
+    pub fn into_unsubscribe(self) -> Self {
+        Self {
+            method: "unsubscribe".to_string(),
+            ..self
+        }
+    }
+
     pub fn new_notification_subscription() -> Self {
         Self {
             method: "subscribe".to_string(),
             subscription: Subscription {
                 subscription_type: "notification".to_string(),
                 coin: "*".to_string(),
+                ..Subscription::default()
             },
         }
     }
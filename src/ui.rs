@@ -1,30 +1,134 @@
 /// file: src/ui.rs
 /// description: ui presentation layer that handles events from the client
 use crate::{
-    events::{ClientEvent, EventReceiver},
-    formatter::{Colors, OutputFormat, TradeFormatter},
+    budget::{Buffer, MemoryBudget, event_bytes},
+    client_state::SharedClientState,
+    config::Channel,
+    error::HyperliquidError,
+    events::{BroadcastSender, ClientCommand, ClientEvent, CommandSender, EventReceiver},
+    filter::TradeFilter,
+    formatter::TableColumn,
+    formatter::{
+        AllMidsFormatter, BboFormatter, BookLadderFormatter, Colors, OutputFormat, QuietLevel,
+        StatusBarFormatter, TickerFormatter, TradeFormatter,
+    },
+    heatmap::DepthHeatmap,
+    history::{HistoryEntry, TradeHistory, sort_entries},
+    input::{InputCommand, InputReceiver},
+    layout::SplitLayout,
+    number_format::NumberFormat,
+    orders::OrderTracker,
+    output::TradeOutput,
+    recent::{self, SharedRecent},
+    summary::SessionSummary,
+    template::OutputTemplate,
+    theme::Theme,
+    time_display::TimeDisplay,
+    tracing_setup::{SampleRate, SpanSampler},
+    types::{Book, Trade},
 };
-use tracing::{debug, info};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use tracing::{debug, field, info, info_span, warn};
+
+// Trades shown per history page or search result listing
+const HISTORY_PAGE_SIZE: usize = 20;
+
+/// A history page or search result listing on screen, kept so a header click can sort it
+struct HistoryPage {
+    status: &'static str,
+    message: String,
+    entries: Vec<HistoryEntry>,
+    /// Column and whether it is sorted descending
+    sort: Option<(TableColumn, bool)>,
+    /// Screen row of the column labels, when the page is drawn in place for clicks
+    header_row: Option<u16>,
+}
 
 pub struct UIController {
     event_receiver: EventReceiver,
     trade_formatter: TradeFormatter,
+    book_formatter: BookLadderFormatter,
+    ticker: Option<TickerFormatter>,
+    heatmap: Option<DepthHeatmap>,
+    /// Latest book that arrived while paused, drawn on resume
+    paused_book: Option<Arc<Book>>,
+    bbo_formatter: BboFormatter,
+    mids_formatter: AllMidsFormatter,
+    orders: OrderTracker,
+    /// Mids moved while paused, so the grid is redrawn on resume
+    paused_mids: bool,
+    status_bar: Option<StatusBarFormatter>,
+    split_layout: Option<SplitLayout>,
+    bbo_row: u16,
+    book_view: bool,
     quiet_mode: bool,
+    silent: bool,
     header_printed: bool,
     max_trades: Option<u64>,
+    input_receiver: Option<InputReceiver>,
+    command_sender: Option<CommandSender>,
+    colored: bool,
+    unicode: bool,
+    theme: Theme,
+    paused: bool,
+    filter: TradeFilter,
+    history: Option<TradeHistory>,
+    history_size: usize,
+    history_page: Option<HistoryPage>,
+    in_place: bool,
+    /// Clicks arrive as input, so pages and grids are drawn where a click can find them
+    mouse: bool,
+    trades_received: u64,
+    trades_matched: u64,
+    summary: SessionSummary,
+    client_state: Option<SharedClientState>,
+    broadcast: Option<BroadcastSender>,
+    recent: Option<SharedRecent>,
+    budget: Option<Arc<MemoryBudget>>,
+    span_sampler: SpanSampler,
 }
 
 pub struct UIOptions {
     pub colored: bool,
+    pub unicode: bool,
+    pub in_place: bool,
     pub verbose: bool,
-    pub quiet: bool,
+    pub quiet: QuietLevel,
     pub price_only: bool,
     pub csv_export: bool,
     pub max_trades: u64,
+    pub book_view: bool,
+    pub book_depth: usize,
+    pub ticker: bool,
+    /// Coin subscribed at startup
+    pub coin: String,
+    pub heatmap: bool,
+    pub heatmap_width: usize,
+    pub heatmap_rows: usize,
+    pub split_layout: bool,
+    pub status_bar: bool,
+    pub sparkline: bool,
+    pub sparkline_len: usize,
+    pub history_size: usize,
+    pub template: Option<OutputTemplate>,
+    pub time_display: TimeDisplay,
+    pub number_format: NumberFormat,
+    pub theme: Theme,
+    pub jq_friendly: bool,
+    pub stats_every: usize,
+    pub highlight_notional: Option<f64>,
+    pub highlight_marker: Option<String>,
+    pub trace_sample_rate: SampleRate,
 }
 
 impl UIController {
     pub fn new(event_receiver: EventReceiver, format: OutputFormat, options: UIOptions) -> Self {
+        // Pinned rows: status bar first, then the BBO line
+        let pinned_rows = options.status_bar as u16 + options.split_layout as u16;
+        // Event lines own stdout, so the human-readable status lines stay off it
+        let events_mode = matches!(format, OutputFormat::Events);
         Self {
             event_receiver,
             trade_formatter: TradeFormatter::new(
@@ -34,27 +138,441 @@ impl UIController {
                 options.quiet,
                 options.price_only,
                 options.csv_export,
-            ),
-            quiet_mode: options.quiet,
+                options.sparkline.then_some(options.sparkline_len),
+            )
+            .with_template(options.template)
+            .with_unicode(options.unicode)
+            .with_in_place(options.in_place)
+            .with_time_display(options.time_display.clone())
+            .with_number_format(options.number_format.clone())
+            .with_theme(options.theme)
+            .with_jq_friendly(options.jq_friendly)
+            .with_stats_footer(options.stats_every)
+            .with_highlight(options.highlight_notional, options.highlight_marker),
+            book_formatter: BookLadderFormatter::new(
+                options.colored,
+                options.book_depth,
+                options.sparkline_len,
+            )
+            .with_in_place(options.in_place)
+            .with_unicode(options.unicode)
+            .with_time_display(options.time_display.clone())
+            .with_number_format(options.number_format.clone())
+            .with_theme(options.theme),
+            ticker: options.ticker.then(|| {
+                TickerFormatter::new(options.colored, options.in_place, &options.coin)
+                    .with_theme(options.theme)
+                    .with_number_format(options.number_format.clone())
+            }),
+            heatmap: options.heatmap.then(|| {
+                DepthHeatmap::new(options.heatmap_width, options.heatmap_rows, options.colored)
+                    .with_unicode(options.unicode)
+                    .with_in_place(options.in_place)
+                    .with_time_display(options.time_display.clone())
+                    .with_number_format(options.number_format.clone())
+            }),
+            bbo_formatter: BboFormatter::new(options.colored, options.time_display.clone())
+                .with_theme(options.theme)
+                .with_number_format(options.number_format.clone()),
+            mids_formatter: AllMidsFormatter::new(options.colored)
+                .with_in_place(options.in_place)
+                .with_theme(options.theme)
+                .with_number_format(options.number_format.clone()),
+            paused_mids: false,
+            orders: OrderTracker::new(),
+            status_bar: options.status_bar.then(|| {
+                StatusBarFormatter::new(options.colored, options.unicode).with_theme(options.theme)
+            }),
+            split_layout: if pinned_rows > 0 {
+                SplitLayout::enable(pinned_rows)
+                    .inspect_err(|e| warn!("Split layout unavailable: {}", e))
+                    .ok()
+            } else {
+                None
+            },
+            paused_book: None,
+            bbo_row: options.status_bar as u16,
+            book_view: options.book_view || options.heatmap,
+            quiet_mode: options.quiet >= QuietLevel::NoBanners || events_mode,
+            silent: options.quiet == QuietLevel::Silent,
             header_printed: false,
             max_trades: if options.max_trades == 0 {
                 None
             } else {
                 Some(options.max_trades)
             },
+            input_receiver: None,
+            command_sender: None,
+            colored: options.colored,
+            unicode: options.unicode,
+            theme: options.theme,
+            paused: false,
+            filter: TradeFilter::default(),
+            history: None,
+            history_size: options.history_size,
+            history_page: None,
+            in_place: options.in_place,
+            mouse: false,
+            trades_received: 0,
+            trades_matched: 0,
+            summary: SessionSummary::new(),
+            client_state: None,
+            broadcast: None,
+            recent: None,
+            budget: None,
+            span_sampler: SpanSampler::new(options.trace_sample_rate),
+        }
+    }
+
+    /// Enable keyboard controls, forwarding connection-level commands to the client
+    pub fn with_controls(
+        mut self,
+        input_receiver: InputReceiver,
+        command_sender: CommandSender,
+    ) -> Self {
+        self.input_receiver = Some(input_receiver);
+        self.command_sender = Some(command_sender);
+        self.history = Some(TradeHistory::new(self.history_size).with_budget(self.budget.clone()));
+        self
+    }
+
+    /// Resolve mouse clicks: history page headers sort the page, and mids grid cells focus a coin
+    pub fn with_mouse(mut self) -> Self {
+        self.mouse = true;
+        self
+    }
+
+    /// Start with trades outside `filter` hidden
+    pub fn with_filter(mut self, filter: TradeFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Accept pause toggles from non-keyboard sources such as SIGUSR1
+    pub fn with_input(mut self, input_receiver: InputReceiver) -> Self {
+        self.input_receiver = Some(input_receiver);
+        self
+    }
+
+    /// Send the formatted trade stream to a file instead of stdout
+    pub fn with_output(mut self, output: TradeOutput) -> Self {
+        self.trade_formatter = self.trade_formatter.with_output(output);
+        self
+    }
+
+    /// Read reconnect and gap counters from the client for the exit summary
+    pub fn with_client_state(mut self, client_state: SharedClientState) -> Self {
+        self.client_state = Some(client_state);
+        self
+    }
+
+    /// Republish every received event on the broadcast bus
+    pub fn with_broadcast(mut self, broadcast: BroadcastSender) -> Self {
+        self.broadcast = Some(broadcast);
+        self
+    }
+
+    /// Release queued events from `budget` as they arrive and count the scrollback against it;
+    /// call before `with_controls`
+    pub fn with_memory_budget(mut self, budget: Arc<MemoryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Keep recent trades and books for consumers that attach mid-session
+    pub fn with_recent(mut self, recent: SharedRecent) -> Self {
+        self.recent = Some(recent);
+        self
+    }
+
+    /// Print session totals; called once on shutdown regardless of why the session ended
+    pub async fn print_exit_summary(&mut self) {
+        if let Some(client_state) = &self.client_state {
+            let state = client_state.lock().await;
+            self.summary.connections = state.connections;
+            self.summary.connected = state.session_uptime();
+            self.summary.reconnects = state.total_reconnects.load(Ordering::Relaxed);
+            self.summary.gaps = state.gap_count;
+            self.summary.downtime = state.total_downtime;
+            self.summary.duplicate_trades = state.duplicate_trades.load(Ordering::Relaxed);
         }
+        self.trade_formatter.print_summary(&self.summary);
+    }
+
+    /// Flush `--output` after the last event; reports write failures from the session
+    pub fn finish_output(&self) -> Result<(), HyperliquidError> {
+        self.trade_formatter.finish_output()
     }
 
     pub async fn run(&mut self) {
         self.print_startup_banner();
-        while let Some(event) = self.event_receiver.recv().await {
-            if !self.handle_event(event).await {
+        loop {
+            let keep_running = tokio::select! {
+                event = self.event_receiver.recv() => match event {
+                    Some(event) => self.handle_event(event).await,
+                    None => false,
+                },
+                Some(command) = next_input(&mut self.input_receiver) => {
+                    self.handle_input(command)
+                }
+            };
+
+            if !keep_running {
                 break;
             }
         }
     }
 
+    fn handle_input(&mut self, command: InputCommand) -> bool {
+        match command {
+            InputCommand::TogglePause => {
+                self.paused = !self.paused;
+                if let Some(history) = self.history.as_mut() {
+                    history.reset_scroll();
+                }
+                self.history_page = None;
+                // Redrawn before the status line, which the ladder would otherwise clear
+                if let Some(book) = self.paused_book.take().filter(|_| !self.paused) {
+                    self.draw_book(&book);
+                }
+                if !self.paused && std::mem::take(&mut self.paused_mids) {
+                    self.mids_formatter.redraw();
+                }
+                let status = if self.paused { "PAUSED" } else { "RESUMED" };
+                self.print_connection_status(
+                    status,
+                    "Output toggled; recording and metrics continue",
+                );
+            }
+            InputCommand::ToggleColumn(column) => {
+                let visible = self.trade_formatter.toggle_column(column);
+                self.print_connection_status(
+                    "COLUMNS",
+                    &format!(
+                        "{} column {}",
+                        column.label(),
+                        if visible { "shown" } else { "hidden" }
+                    ),
+                );
+                if self.header_printed {
+                    self.print_trade_header();
+                }
+            }
+            InputCommand::SetMinSize(min_size) => {
+                self.filter.min_size = min_size;
+                self.filter_changed();
+            }
+            InputCommand::SetMinNotional(min_notional) => {
+                self.filter.min_notional = min_notional;
+                self.filter_changed();
+            }
+            InputCommand::CycleSide => {
+                self.filter.side = self.filter.side.next();
+                self.filter_changed();
+            }
+            InputCommand::SetFilter(filter) => {
+                if filter != self.filter {
+                    self.filter = filter;
+                    self.filter_changed();
+                }
+            }
+            InputCommand::SwitchCoin(coin) => {
+                let Some(sender) = &self.command_sender else {
+                    return true;
+                };
+                match sender.try_send(ClientCommand::SwitchCoin(coin.clone())) {
+                    Ok(()) => {
+                        self.print_connection_status("SWITCHING", &format!("Active coin: {}", coin))
+                    }
+                    Err(e) => warn!("Failed to request coin switch: {}", e),
+                }
+            }
+            InputCommand::ScrollHistory(pages) => self.show_history_page(pages),
+            InputCommand::Search(query) => self.show_search_results(&query),
+            InputCommand::SortHistory(column) => {
+                let Some(mut page) = self.history_page.take() else {
+                    return true;
+                };
+                let descending = match page.sort {
+                    Some((sorted, descending)) if sorted == column => !descending,
+                    _ => false,
+                };
+                sort_entries(&mut page.entries, column, descending);
+                page.sort = Some((column, descending));
+                self.draw_history_page(page);
+            }
+            InputCommand::Click { column, row } => return self.click(column, row),
+            InputCommand::Quit => {
+                self.print_connection_status("STOPPING", "Quit requested from keyboard");
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn show_trade(&mut self, trade: &Trade) {
+        if let Some(ticker) = self.ticker.as_mut() {
+            if !self.silent {
+                ticker.on_trade(trade);
+            }
+            return;
+        }
+
+        // Ensure header is printed before any trades (fallback safety)
+        if !self.header_printed {
+            self.print_trade_header();
+            self.header_printed = true;
+        }
+        if !self.span_sampler.sample() {
+            self.trade_formatter.print_trade(trade);
+            return;
+        }
+        let span = info_span!("format_trade", coin = %trade.coin, format_us = field::Empty);
+        let _entered = span.enter();
+        let started = Instant::now();
+        self.trade_formatter.print_trade(trade);
+        span.record("format_us", started.elapsed().as_micros() as u64);
+    }
+
+    fn filter_changed(&mut self) {
+        self.print_connection_status("FILTER", &self.filter.to_string());
+        if self.header_printed {
+            self.trade_formatter.print_header();
+        }
+    }
+
+    /// Table header preceded by the active filter, so hidden trades are never a surprise
+    fn print_trade_header(&mut self) {
+        if self.filter.is_active() {
+            self.print_connection_status("FILTER", &self.filter.to_string());
+        }
+        self.trade_formatter.print_header();
+    }
+
+    fn show_history_page(&mut self, pages: i32) {
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+
+        // Scrolling freezes the live tape so the page stays on screen
+        self.paused = true;
+        let total = history.len();
+        let page: Vec<_> = history
+            .scroll(pages, HISTORY_PAGE_SIZE)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let range = match (page.first(), page.last()) {
+            (Some(first), Some(last)) => format!("#{}-#{}", first.index, last.index),
+            _ => "empty".to_string(),
+        };
+        self.draw_history_page(HistoryPage {
+            status: "HISTORY",
+            message: format!(
+                "{} of {} buffered trades ([ older, ] newer, p resumes)",
+                range, total
+            ),
+            entries: page,
+            sort: None,
+            header_row: None,
+        });
+    }
+
+    fn show_search_results(&mut self, query: &str) {
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+
+        self.paused = true;
+        history.reset_scroll();
+        let matches: Vec<_> = history
+            .search(query, HISTORY_PAGE_SIZE)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        self.draw_history_page(HistoryPage {
+            status: "SEARCH",
+            message: format!(
+                "{} most recent matches for '{}' (p resumes)",
+                matches.len(),
+                query
+            ),
+            entries: matches,
+            sort: None,
+            header_row: None,
+        });
+    }
+
+    /// Print a page under its status line and column labels; with the mouse on a terminal it
+    /// replaces the output area, so the labels sit on a known row
+    fn draw_history_page(&mut self, mut page: HistoryPage) {
+        page.header_row = None;
+        if self.mouse && self.in_place {
+            let top = self
+                .split_layout
+                .as_ref()
+                .map_or(0, SplitLayout::header_rows);
+            print!("\x1b[{};1H\x1b[J", top + 1);
+            page.header_row = Some(top + u16::from(!self.quiet_mode));
+        }
+        self.print_connection_status(page.status, &page.message);
+        self.trade_formatter.print_history_header(page.sort);
+        for entry in &page.entries {
+            self.trade_formatter
+                .print_history_row(entry.index, &entry.trade);
+        }
+        self.history_page = Some(page);
+    }
+
+    /// Sort the history page by a clicked column label, or focus a coin clicked in the mids grid
+    fn click(&mut self, column: u16, row: u16) -> bool {
+        if let Some(page) = &self.history_page {
+            if page.header_row == Some(row)
+                && let Some(sorted) = self.trade_formatter.history_column_at(column.into())
+            {
+                return self.handle_input(InputCommand::SortHistory(sorted));
+            }
+            return true;
+        }
+        if self.paused || self.silent || self.trade_formatter.prints_records() {
+            return true;
+        }
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        let Some(coin) =
+            self.mids_formatter
+                .coin_at(column.into(), row.into(), width.into(), height.into())
+        else {
+            return true;
+        };
+        self.mids_formatter.focus(coin.clone());
+        self.mids_formatter.redraw();
+        self.handle_input(InputCommand::SwitchCoin(coin.to_string()))
+    }
+
     async fn handle_event(&mut self, event: ClientEvent) -> bool {
+        if let Some(budget) = &self.budget {
+            budget.release(Buffer::Events, event_bytes(&event));
+        }
+        // Held across the send, so a dashboard attaching meanwhile sees the event exactly once
+        let mut recent = self.recent.as_ref().map(recent::lock);
+        if let Some(recent) = recent.as_mut() {
+            recent.record(&event);
+        }
+        // Sending only fails when nobody is subscribed, which is fine
+        if let Some(broadcast) = &self.broadcast {
+            let _ = broadcast.send(event.clone());
+        }
+        drop(recent);
+
+        // Trades still go through the normal path for filtering, history and max-trades
+        if self.trade_formatter.prints_events() && !matches!(event, ClientEvent::TradeReceived(_)) {
+            self.trade_formatter.print_event(&event);
+            return true;
+        }
+
         match event {
             ClientEvent::Starting => {
                 info!("Client starting...");
@@ -68,25 +586,47 @@ impl UIController {
             ClientEvent::SubscriptionSent { message } => {
                 self.print_subscription_info(&message);
             }
-            ClientEvent::SubscriptionConfirmed { sub_type, coin } => {
-                self.print_subscription_confirmed(&sub_type, &coin);
-                // Print the table header here, after connection is fully established
-                if !self.header_printed {
-                    self.trade_formatter.print_header();
+            ClientEvent::SubscriptionConfirmed {
+                sub_type,
+                coin,
+                interval,
+            } => {
+                self.print_subscription_confirmed(&sub_type, &coin, interval.as_deref());
+                // Print the table header here, after connection is fully established; book,
+                // BBO, mids, candle, account, and explorer lines carry their own labels
+                if !self.header_printed
+                    && !self.book_view
+                    && self.ticker.is_none()
+                    && sub_type.parse::<Channel>() == Ok(Channel::Trades)
+                {
+                    self.print_trade_header();
                     self.header_printed = true;
                 }
             }
             ClientEvent::TradeReceived(trade) => {
-                // Ensure header is printed before any trades (fallback safety)
-                if !self.header_printed {
-                    self.trade_formatter.print_header();
-                    self.header_printed = true;
+                // History records every trade, even while output is paused or filtered
+                self.trades_received += 1;
+                self.summary.record(&trade);
+                if let Some(history) = self.history.as_mut() {
+                    history.push(self.trades_received, trade.clone());
+                }
+
+                if !self.filter.matches(&trade) {
+                    return true;
+                }
+                // Pausing hides trades but still counts them toward --max-trades
+                self.trades_matched += 1;
+
+                if !self.paused {
+                    self.show_trade(&trade);
                 }
-                self.trade_formatter.print_trade(&trade);
 
                 if let Some(max_trades) = self.max_trades
-                    && self.trade_formatter.trade_count() >= max_trades
+                    && self.trades_matched >= max_trades
                 {
+                    if self.trade_formatter.prints_events() {
+                        self.trade_formatter.print_event(&ClientEvent::Stopping);
+                    }
                     self.print_connection_status(
                         "STOPPING",
                         &format!("Reached configured max trades ({max_trades})"),
@@ -94,21 +634,131 @@ impl UIController {
                     return false;
                 }
             }
+            ClientEvent::BookReceived(book) if self.trade_formatter.prints_records() => {
+                if !self.paused {
+                    self.trade_formatter.print_book(&book);
+                }
+            }
+            ClientEvent::BookReceived(_) if self.silent => {}
+            // The ladder keeps the last snapshot on screen while paused
+            ClientEvent::BookReceived(book) if self.paused => self.paused_book = Some(book),
+            ClientEvent::BookReceived(book) => self.draw_book(&book),
+            ClientEvent::BboReceived(bbo) if self.trade_formatter.prints_records() => {
+                if !self.paused {
+                    self.trade_formatter.print_bbo(&bbo);
+                }
+            }
+            ClientEvent::BboReceived(bbo) => {
+                if self.paused || self.silent {
+                    return true;
+                }
+                if let Some(ticker) = self.ticker.as_mut() {
+                    ticker.on_bbo(&bbo);
+                } else if let Some(layout) = &self.split_layout
+                    && self.bbo_row < layout.header_rows()
+                {
+                    layout.update_header(self.bbo_row, &self.bbo_formatter.render_line(&bbo));
+                } else {
+                    println!("{}", self.bbo_formatter.render_line(&bbo));
+                }
+            }
+            // Tracked even while paused, so the moves shown after it are against the latest mids
+            ClientEvent::AllMidsReceived(all_mids) => {
+                let moved = self.mids_formatter.update(&all_mids);
+                if self.paused {
+                    self.paused_mids = !self.trade_formatter.prints_records() && !self.silent;
+                } else if self.trade_formatter.prints_records() {
+                    self.trade_formatter.print_mids(&moved);
+                } else if !self.silent {
+                    self.mids_formatter.print(&moved);
+                }
+            }
+            ClientEvent::FillReceived { fill, snapshot } => {
+                if !self.paused {
+                    self.trade_formatter.print_fill(&fill, snapshot);
+                }
+            }
+            ClientEvent::FundingReceived(funding) => {
+                if !self.paused {
+                    self.trade_formatter.print_funding(&funding);
+                }
+            }
+            ClientEvent::LiquidationReceived(liquidation) => {
+                if !self.paused {
+                    self.trade_formatter.print_liquidation(&liquidation);
+                }
+            }
+            ClientEvent::NonUserCancelReceived(cancel) => {
+                if !self.paused {
+                    self.trade_formatter.print_non_user_cancel(&cancel);
+                }
+            }
+            // Tracked even while paused, so the transitions shown after it stay right
+            ClientEvent::OrderUpdated(update) => {
+                let previous = self.orders.observe(&update);
+                if !self.paused {
+                    self.trade_formatter
+                        .print_order(&update, previous.as_deref());
+                }
+            }
+            ClientEvent::CandleReceived { candle, closed } => {
+                if !self.paused {
+                    self.trade_formatter.print_candle(&candle, closed);
+                }
+            }
+            ClientEvent::BlockReceived(block) => {
+                if !self.paused {
+                    self.trade_formatter.print_block(&block);
+                }
+            }
+            ClientEvent::TxReceived(tx) => {
+                if !self.paused {
+                    self.trade_formatter.print_tx(&tx);
+                }
+            }
             ClientEvent::MessageReceived { raw_message } => {
                 debug!("Received message: {}", raw_message);
             }
             ClientEvent::ConnectionFailed(error) => {
                 self.print_error("CONNECTION FAILED", &error);
             }
+            ClientEvent::ServerError(error) => {
+                self.print_error("SERVER ERROR", &error);
+            }
             ClientEvent::Reconnecting {
                 attempt,
                 delay_secs,
             } => {
                 self.print_reconnect_info(delay_secs, attempt);
             }
+            ClientEvent::ClockSkew {
+                skew_ms,
+                threshold_ms,
+            } => {
+                let threshold_secs = threshold_ms as f64 / 1000.0;
+                let message = if skew_ms.abs() > threshold_ms {
+                    format!(
+                        "Local clock is {:.1}s {} exchange time (limit {:.1}s); latency and candle times will be off",
+                        skew_ms.unsigned_abs() as f64 / 1000.0,
+                        if skew_ms > 0 { "ahead of" } else { "behind" },
+                        threshold_secs
+                    )
+                } else {
+                    format!(
+                        "Local clock back within {:.1}s of exchange time",
+                        threshold_secs
+                    )
+                };
+                self.print_connection_status("CLOCK", &message);
+            }
             ClientEvent::Disconnected => {
                 self.print_connection_status("DISCONNECTED", "Connection closed");
             }
+            ClientEvent::Stats(stats) => {
+                if let (Some(layout), Some(status_bar)) = (&self.split_layout, &self.status_bar) {
+                    layout.update_header(0, &status_bar.render_line(&stats));
+                }
+            }
             ClientEvent::Stopping => {
                 self.print_connection_status("STOPPING", "Client shutting down");
             }
@@ -122,74 +772,86 @@ impl UIController {
             return;
         }
 
-        println!();
-        println!(
-            "{}{}╔══════════════════════════════════════════════════════════════════════════════╗{}",
-            Colors::BOLD,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET
-        );
-        println!(
-            "{}{}║                         HYPERLIQUID WEBSOCKET CLIENT                        ║{}",
-            Colors::BOLD,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET
+        let (h, v, top_l, top_r, mid_l, mid_r, bot_l, bot_r) = if self.unicode {
+            ('═', '║', '╔', '╗', '╠', '╣', '╚', '╝')
+        } else {
+            ('=', '|', '+', '+', '+', '+', '+', '+')
+        };
+        let rule = h.to_string().repeat(78);
+        let frame = format!(
+            "{}{}",
+            self.color(Colors::BOLD),
+            self.color(self.theme.accent)
         );
+        let reset = self.color(Colors::RESET);
+
+        println!();
+        println!("{}{}{}{}{}", frame, top_l, rule, top_r, reset);
         println!(
-            "{}{}╠══════════════════════════════════════════════════════════════════════════════╣{}",
-            Colors::BOLD,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET
+            "{}{}                         HYPERLIQUID WEBSOCKET CLIENT                        {}{}",
+            frame, v, v, reset
         );
+        println!("{}{}{}{}{}", frame, mid_l, rule, mid_r, reset);
         println!(
-            "{}{}║{} Version: {}{:<8}{} │ Type: {}{:<10}{} │ Status: {}INITIALIZING{}{}║{}",
-            Colors::BOLD,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET,
-            Colors::BRIGHT_GREEN,
+            "{}{}{} Version: {}{:<8}{} {} Type: {}{:<10}{} {} Status: {}INITIALIZING{}{}{}{}",
+            frame,
+            v,
+            reset,
+            self.color(self.theme.buy),
             env!("CARGO_PKG_VERSION"),
-            Colors::RESET,
-            Colors::BRIGHT_YELLOW,
-            "TRADES",
-            Colors::RESET,
-            Colors::BRIGHT_MAGENTA,
-            Colors::RESET,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET
-        );
-        println!(
-            "{}{}╚══════════════════════════════════════════════════════════════════════════════╝{}",
-            Colors::BOLD,
-            Colors::BRIGHT_CYAN,
-            Colors::RESET
+            reset,
+            if self.unicode { '│' } else { '|' },
+            self.color(self.theme.warning),
+            if self.book_view { "BOOK" } else { "TRADES" },
+            reset,
+            if self.unicode { '│' } else { '|' },
+            self.color(self.theme.notice),
+            reset,
+            frame,
+            v,
+            reset
         );
+        println!("{}{}{}{}{}", frame, bot_l, rule, bot_r, reset);
         println!();
     }
 
+    fn draw_book(&mut self, book: &Book) {
+        match self.heatmap.as_mut() {
+            Some(heatmap) => heatmap.print(book),
+            None => self.book_formatter.print_ladder(book),
+        }
+    }
+
+    /// Return the ANSI sequence only when colored output is enabled
+    fn color(&self, code: &'static str) -> &'static str {
+        if self.colored { code } else { "" }
+    }
+
     fn print_connection_status(&self, status: &str, message: &str) {
         if self.quiet_mode && status != "ERROR" {
             return;
         }
 
         let (color, symbol) = match status {
-            "CONNECTING" => (Colors::BRIGHT_YELLOW, "*"),
-            "CONNECTED" => (Colors::BRIGHT_GREEN, "+"),
-            "LISTENING" => (Colors::BRIGHT_BLUE, "~"),
-            "DISCONNECTED" => (Colors::BRIGHT_RED, "X"),
-            "STOPPING" => (Colors::BRIGHT_MAGENTA, "!"),
-            _ => (Colors::WHITE, "-"),
+            "CONNECTING" => (self.color(self.theme.warning), "*"),
+            "CONNECTED" => (self.color(self.theme.buy), "+"),
+            "LISTENING" => (self.color(self.theme.info), "~"),
+            "DISCONNECTED" => (self.color(self.theme.sell), "X"),
+            "STOPPING" => (self.color(self.theme.notice), "!"),
+            "CLOCK" => (self.color(self.theme.warning), "!"),
+            _ => (self.color(self.theme.text), "-"),
         };
 
         println!(
             "{}{}[{}]{} {} {}{}{}",
-            Colors::BOLD,
+            self.color(Colors::BOLD),
             color,
             status,
-            Colors::RESET,
+            self.color(Colors::RESET),
             symbol,
-            Colors::WHITE,
+            self.color(self.theme.text),
             message,
-            Colors::RESET
+            self.color(Colors::RESET)
         );
     }
 
@@ -200,29 +862,41 @@ impl UIController {
 
         println!(
             "{}{}[SUBSCRIBING]{} > {}{}{}",
-            Colors::BOLD,
-            Colors::BRIGHT_MAGENTA,
-            Colors::RESET,
-            Colors::DIM,
+            self.color(Colors::BOLD),
+            self.color(self.theme.notice),
+            self.color(Colors::RESET),
+            self.color(Colors::DIM),
             message,
-            Colors::RESET
+            self.color(Colors::RESET)
         );
     }
 
-    fn print_subscription_confirmed(&self, sub_type: &str, coin: &str) {
+    fn print_subscription_confirmed(&self, sub_type: &str, coin: &str, interval: Option<&str>) {
         if self.quiet_mode {
             return;
         }
 
+        // User and explorer channels are not keyed by a coin
+        let target = if coin.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " for {}{}{}",
+                self.color(self.theme.warning),
+                coin,
+                self.color(Colors::RESET)
+            )
+        };
+        // Each candle interval is its own subscription
+        let interval = interval.map(|interval| format!(" {}", interval));
         println!(
-            "{}{}[SUBSCRIPTION OK]{} + {} subscription active for {}{}{}",
-            Colors::BOLD,
-            Colors::BRIGHT_GREEN,
-            Colors::RESET,
+            "{}{}[SUBSCRIPTION OK]{} + {}{} subscription active{}",
+            self.color(Colors::BOLD),
+            self.color(self.theme.buy),
+            self.color(Colors::RESET),
             sub_type,
-            Colors::BRIGHT_YELLOW,
-            coin,
-            Colors::RESET
+            interval.unwrap_or_default(),
+            target
         );
         println!();
     }
@@ -230,24 +904,35 @@ impl UIController {
     fn print_error(&self, error_type: &str, message: &str) {
         println!(
             "{}{}[{}]{} ! {}{}{}",
-            Colors::BOLD,
-            Colors::BRIGHT_RED,
+            self.color(Colors::BOLD),
+            self.color(self.theme.sell),
             error_type,
-            Colors::RESET,
-            Colors::RED,
+            self.color(Colors::RESET),
+            self.color(self.theme.error),
             message,
-            Colors::RESET
+            self.color(Colors::RESET)
         );
     }
 
     fn print_reconnect_info(&self, delay_secs: u64, attempt: u32) {
+        if self.silent {
+            return;
+        }
+
         println!(
             "{}{}[RECONNECTING]{} > Attempt {} in {}s...",
-            Colors::BOLD,
-            Colors::BRIGHT_YELLOW,
-            Colors::RESET,
+            self.color(Colors::BOLD),
+            self.color(self.theme.warning),
+            self.color(Colors::RESET),
             attempt,
             delay_secs
         );
     }
 }
+
+async fn next_input(receiver: &mut Option<InputReceiver>) -> Option<InputCommand> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
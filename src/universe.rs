@@ -0,0 +1,160 @@
+/// file: src/universe.rs
+/// description: Check requested coins against the exchange universe and suggest close matches
+/// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/info-endpoint/perpetuals
+/// reference: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/info-endpoint/spot
+use crate::{
+    error::HyperliquidError,
+    types::{Meta, SpotMeta, SpotPair},
+};
+
+// Suggestions further than this many edits away are noise rather than typos
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 3;
+
+// `--spot HYPE` means HYPE/USDC
+const DEFAULT_SPOT_QUOTE: &str = "USDC";
+
+/// A spot pair resolved from its human name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpotMarket {
+    /// Identifier the WebSocket API expects (`@107`, or `PURR/USDC` for the first pair)
+    pub coin: String,
+    /// `BASE/QUOTE` as shown to the user
+    pub name: String,
+}
+
+/// Spot pairs (`PURR/USDC`, `@107`) and builder-deployed markets (`dex:COIN`) are not in `meta`
+pub fn is_perp_name(coin: &str) -> bool {
+    !coin.starts_with('@') && !coin.contains('/') && !coin.contains(':')
+}
+
+/// Fail with "did you mean ..." when `coin` is not a listed perpetual
+pub fn validate_coin(meta: &Meta, coin: &str) -> Result<(), HyperliquidError> {
+    if let Some(asset) = meta.universe.iter().find(|asset| asset.name == coin) {
+        if asset.is_delisted {
+            return Err(HyperliquidError::ConfigError(format!(
+                "coin '{}' is delisted and no longer trades",
+                coin
+            )));
+        }
+        return Ok(());
+    }
+
+    let listed = meta
+        .universe
+        .iter()
+        .filter(|asset| !asset.is_delisted)
+        .map(|asset| asset.name.as_str());
+    Err(HyperliquidError::ConfigError(format!(
+        "unknown coin '{}'{}; pass --no-validate to connect anyway",
+        coin,
+        did_you_mean(&suggest(listed, coin))
+    )))
+}
+
+/// Map `PURR/USDC`, `HYPE` (quoted in USDC), or `@107` to the pair's WebSocket coin
+pub fn resolve_spot(meta: &SpotMeta, name: &str) -> Result<SpotMarket, HyperliquidError> {
+    let name = name.trim();
+    let market = |pair: &SpotPair| {
+        spot_pair_name(meta, pair).map(|display| SpotMarket {
+            coin: pair.name.clone(),
+            name: display,
+        })
+    };
+
+    let found = if name.starts_with('@') {
+        meta.universe
+            .iter()
+            .find(|pair| pair.name == name)
+            .and_then(market)
+    } else {
+        let (base, quote) = name.split_once('/').unwrap_or((name, DEFAULT_SPOT_QUOTE));
+        let wanted = format!("{}/{}", base.trim(), quote.trim());
+        meta.universe.iter().find_map(|pair| {
+            market(pair).filter(|market| market.name.eq_ignore_ascii_case(&wanted))
+        })
+    };
+    if let Some(market) = found {
+        return Ok(market);
+    }
+
+    let names: Vec<String> = meta
+        .universe
+        .iter()
+        .filter_map(|pair| spot_pair_name(meta, pair))
+        .collect();
+    let wanted = if name.contains('/') || name.starts_with('@') {
+        name.to_string()
+    } else {
+        format!("{}/{}", name, DEFAULT_SPOT_QUOTE)
+    };
+    Err(HyperliquidError::ConfigError(format!(
+        "unknown spot pair '{}'{}",
+        name,
+        did_you_mean(&suggest(names.iter().map(String::as_str), &wanted))
+    )))
+}
+
+/// `BASE/QUOTE` from the pair's token indices
+/// `BASE/QUOTE` for a pair, `None` when `meta` lacks one of its tokens
+pub fn spot_pair_name(meta: &SpotMeta, pair: &SpotPair) -> Option<String> {
+    let token = |index: u32| meta.tokens.iter().find(|token| token.index == index);
+    let [base, quote] = pair.tokens;
+    Some(format!("{}/{}", token(base)?.name, token(quote)?.name))
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(" (did you mean {}?)", suggestions.join(", "))
+    }
+}
+
+/// Names closest to `wanted`, ignoring case
+fn suggest<'a>(names: impl Iterator<Item = &'a str>, wanted: &str) -> Vec<String> {
+    let wanted = wanted.to_ascii_uppercase();
+    let mut candidates: Vec<(usize, &str)> = names
+        .map(|name| (edit_distance(&wanted, &name.to_ascii_uppercase()), name))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect();
+    candidates.sort();
+    // A case-only mismatch has exactly one answer
+    if candidates
+        .first()
+        .is_some_and(|(distance, _)| *distance == 0)
+    {
+        candidates.truncate(1);
+    }
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Levenshtein distance, counting a swap of adjacent letters as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
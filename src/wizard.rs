@@ -0,0 +1,307 @@
+/// file: src/wizard.rs
+/// description: Interactive `init` command that writes a ready-to-use TOML config file
+use crate::{
+    cli::InitArgs,
+    config::{ConfigFile, validate_address},
+    error::HyperliquidError,
+};
+use anyhow::Result;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const FORMATS: [&str; 6] = ["table", "csv", "json", "json-pretty", "minimal", "events"];
+const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+/// Answers collected by the wizard, rendered into the config file
+#[derive(Debug, Clone)]
+struct Answers {
+    coin: String,
+    channels: Vec<String>,
+    user: Option<String>,
+    format: String,
+    output: Option<String>,
+    record: Option<String>,
+    record_rotate_secs: u64,
+    metrics_port: Option<u16>,
+    log_level: String,
+}
+
+/// Ask for the common settings on stdin and write them to `args.path` or the user config file
+pub fn run_init(args: InitArgs) -> Result<()> {
+    let path = args.path.or_else(ConfigFile::user_path).ok_or_else(|| {
+        HyperliquidError::ConfigError(
+            "no config directory (HOME is not set), pass a path to init".to_string(),
+        )
+    })?;
+
+    let stdin = std::io::stdin();
+    let mut prompt = Prompt {
+        input: stdin.lock(),
+    };
+    println!("rs-hyperliquid setup: press Enter to keep the default in brackets.\n");
+
+    if path.exists()
+        && !args.force
+        && !prompt.yes_no(&format!("Overwrite {}?", path.display()), false)?
+    {
+        println!("Left {} unchanged.", path.display());
+        return Ok(());
+    }
+
+    let answers = ask(&mut prompt)?;
+    let contents = render(&answers);
+    // The file must load exactly like one written by hand
+    toml::from_str::<ConfigFile>(&contents).map_err(|e| {
+        HyperliquidError::ConfigError(format!("generated config does not parse: {}", e))
+    })?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, contents).map_err(|e| {
+        HyperliquidError::ConfigError(format!("cannot write {}: {}", path.display(), e))
+    })?;
+
+    println!("\nWrote {}.", path.display());
+    if is_default_location(&path) {
+        println!("Start streaming with: rs-hyperliquid");
+    } else {
+        println!(
+            "Start streaming with: rs-hyperliquid --config {}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn ask<R: BufRead>(prompt: &mut Prompt<R>) -> Result<Answers> {
+    let coin = prompt.text("Coin to stream", "BTC", |coin| {
+        if coin.chars().any(char::is_whitespace) {
+            Err("a coin symbol has no spaces".to_string())
+        } else {
+            Ok(coin.to_string())
+        }
+    })?;
+
+    let mut channels = prompt.text(
+        "Market channels (comma-separated: trades, bbo, l2Book)",
+        "trades",
+        parse_market_channels,
+    )?;
+
+    let user = prompt.text(
+        "Wallet address for fills, orders, and funding (blank to skip)",
+        "",
+        |address| {
+            if address.is_empty() {
+                return Ok(None);
+            }
+            validate_address(address)
+                .map(Some)
+                .map_err(|e| e.to_string())
+        },
+    )?;
+    if user.is_some() {
+        channels.extend(["userFills", "orderUpdates", "userFundings"].map(str::to_string));
+    }
+
+    let format = prompt.choice("Output format", &FORMATS, "table")?;
+    let output =
+        prompt.optional("Write the formatted stream to a file (blank for the terminal)")?;
+    let record = prompt.optional("Record raw messages to a JSONL file (blank to skip)")?;
+    let record_rotate_secs = match record {
+        Some(_) => prompt.text(
+            "Start a new recording file every N seconds (0 = never)",
+            "0",
+            |secs| {
+                secs.parse::<u64>()
+                    .map_err(|_| "enter a whole number of seconds".to_string())
+            },
+        )?,
+        None => 0,
+    };
+
+    let metrics_port = if prompt.yes_no("Serve Prometheus metrics?", false)? {
+        Some(prompt.text("Metrics port", "9090", |port| {
+            port.parse::<u16>()
+                .ok()
+                .filter(|port| *port > 0)
+                .ok_or_else(|| "enter a port between 1 and 65535".to_string())
+        })?)
+    } else {
+        None
+    };
+
+    let log_level = prompt.choice("Log level", &LOG_LEVELS, "info")?;
+
+    Ok(Answers {
+        coin,
+        channels,
+        user,
+        format,
+        output,
+        record,
+        record_rotate_secs,
+        metrics_port,
+        log_level,
+    })
+}
+
+fn parse_market_channels(list: &str) -> Result<Vec<String>, String> {
+    let mut channels = Vec::new();
+    for name in list
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let channel = match name.to_ascii_lowercase().as_str() {
+            "trades" => "trades",
+            "bbo" => "bbo",
+            "l2book" | "book" => "l2Book",
+            _ => {
+                return Err(format!(
+                    "unknown channel '{}', expected trades, bbo, or l2Book",
+                    name
+                ));
+            }
+        };
+        if !channels.iter().any(|existing| existing == channel) {
+            channels.push(channel.to_string());
+        }
+    }
+    if channels.is_empty() {
+        return Err("choose at least one channel".to_string());
+    }
+    Ok(channels)
+}
+
+/// TOML with a comment per section so the file is easy to edit later
+fn render(answers: &Answers) -> String {
+    let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+    let list = answers
+        .channels
+        .iter()
+        .map(|channel| quote(channel))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut toml = String::from(
+        "# Generated by `rs-hyperliquid init`; command-line flags override these values\n\n",
+    );
+    toml.push_str("[subscription]\n");
+    toml.push_str(&format!("coin = {}\n", quote(&answers.coin)));
+    toml.push_str(&format!("channels = [{}]\n", list));
+    if let Some(user) = &answers.user {
+        toml.push_str(&format!("user = {}\n", quote(user)));
+    }
+
+    toml.push_str("\n# Formatted output and raw recording\n[output]\n");
+    toml.push_str(&format!("format = {}\n", quote(&answers.format)));
+    if let Some(output) = &answers.output {
+        toml.push_str(&format!("file = {}\n", quote(output)));
+    }
+    if let Some(record) = &answers.record {
+        toml.push_str(&format!("record = {}\n", quote(record)));
+        if answers.record_rotate_secs > 0 {
+            toml.push_str(&format!(
+                "record_rotate_secs = {}\n",
+                answers.record_rotate_secs
+            ));
+        }
+    }
+
+    toml.push_str("\n[metrics]\n");
+    match answers.metrics_port {
+        Some(port) => toml.push_str(&format!("enabled = true\nport = {}\n", port)),
+        None => toml.push_str("enabled = false\n"),
+    }
+
+    toml.push_str("\n# Reloaded on SIGHUP\n[log]\n");
+    toml.push_str(&format!("level = {}\n", quote(&answers.log_level)));
+    toml
+}
+
+fn is_default_location(path: &Path) -> bool {
+    // `locate` only looks for the local file in the working directory
+    let local = PathBuf::from("hyperliquid.toml");
+    path == local || ConfigFile::user_path().is_some_and(|user| user == path)
+}
+
+/// Line-based prompts that re-ask until the answer validates
+struct Prompt<R> {
+    input: R,
+}
+
+impl<R: BufRead> Prompt<R> {
+    fn read(&mut self, question: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            print!("{}: ", question);
+        } else {
+            print!("{} [{}]: ", question, default);
+        }
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(HyperliquidError::ConfigError(
+                "setup cancelled: input ended before every question was answered".to_string(),
+            )
+            .into());
+        }
+        let answer = line.trim();
+        Ok(if answer.is_empty() { default } else { answer }.to_string())
+    }
+
+    fn text<T>(
+        &mut self,
+        question: &str,
+        default: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<T> {
+        loop {
+            let answer = self.read(question, default)?;
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => println!("  {}", e),
+            }
+        }
+    }
+
+    fn optional(&mut self, question: &str) -> Result<Option<String>> {
+        let answer = self.read(question, "")?;
+        Ok((!answer.is_empty()).then_some(answer))
+    }
+
+    fn choice(&mut self, question: &str, options: &[&str], default: &str) -> Result<String> {
+        let question = format!("{} ({})", question, options.join(", "));
+        self.text(&question, default, |answer| {
+            options
+                .iter()
+                .find(|option| option.eq_ignore_ascii_case(answer))
+                .map(|option| option.to_string())
+                .ok_or_else(|| format!("choose one of: {}", options.join(", ")))
+        })
+    }
+
+    fn yes_no(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        let question = format!("{} [{}]", question, hint);
+        loop {
+            print!("{} ", question);
+            std::io::stdout().flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Err(HyperliquidError::ConfigError(
+                    "setup cancelled: input ended before every question was answered".to_string(),
+                )
+                .into());
+            }
+            match line.trim().to_ascii_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => println!("  answer y or n"),
+            }
+        }
+    }
+}
@@ -0,0 +1,392 @@
+/// file: tests/fixtures.rs
+/// description: Golden messages for every channel, checked for lossless parsing and stable output
+///
+/// Each file in `tests/fixtures/` is one frame as the exchange sends it. A new channel or a
+/// schema change adds or edits a fixture here; `cargo insta review` accepts changed output.
+use clap::Parser;
+use fastwebsockets::{Frame, Payload, Role, WebSocket};
+use rs_hyperliquid::{
+    cli::Args,
+    client::HyperliquidWebSocketClient,
+    client_state::ClientState,
+    config::Config,
+    events::{ClientEvent, create_event_channel, trade_json},
+    formatter::{
+        AllMidsFormatter, BboFormatter, BookLadderFormatter, OutputFormat, QuietLevel,
+        TradeFormatter,
+    },
+    orders::OrderTracker,
+    output::TradeOutput,
+    schema::unknown_fields,
+    time_display::TimeDisplay,
+    types::{Trade, UserEvent, WebSocketMessage},
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+struct Fixture {
+    file: &'static str,
+    channel: &'static str,
+    /// Payload fields the types do not keep, so dropping them is deliberate
+    dropped: &'static [&'static str],
+}
+
+const fn fixture(file: &'static str, channel: &'static str) -> Fixture {
+    Fixture {
+        file,
+        channel,
+        dropped: &[],
+    }
+}
+
+const FIXTURES: &[Fixture] = &[
+    fixture("trades.json", "trades"),
+    fixture("trades_spot.json", "trades"),
+    fixture("trades_direct.json", "trades"),
+    fixture("trades_direct_bad_time.json", "trades"),
+    fixture("l2book.json", "l2Book"),
+    fixture("bbo.json", "bbo"),
+    fixture("bbo_one_sided.json", "bbo"),
+    fixture("all_mids.json", "allMids"),
+    fixture("candle.json", "candle"),
+    fixture("candle_snapshot.json", "candle"),
+    fixture("candle_direct.json", "candle"),
+    fixture("subscription_response.json", "subscriptionResponse"),
+    fixture("subscription_response_l2book.json", "subscriptionResponse"),
+    fixture("subscription_response_candle.json", "subscriptionResponse"),
+    fixture("subscription_response_user.json", "subscriptionResponse"),
+    fixture("user_fills.json", "userFills"),
+    fixture("user_event_fills.json", "user"),
+    fixture("user_event_funding.json", "user"),
+    fixture("user_event_liquidation.json", "user"),
+    fixture("user_event_non_user_cancel.json", "user"),
+    fixture("order_updates.json", "orderUpdates"),
+    fixture("user_fundings.json", "userFundings"),
+    fixture("notification.json", "notification"),
+    fixture("explorer_block.json", "explorerBlock"),
+    fixture("explorer_txs.json", "explorerTxs"),
+    fixture("pong.json", "pong"),
+    fixture("error.json", "error"),
+];
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn read(file: &str) -> String {
+    std::fs::read_to_string(fixture_dir().join(file))
+        .unwrap_or_else(|e| panic!("cannot read fixture {}: {}", file, e))
+}
+
+fn trades(text: &str) -> Vec<Trade> {
+    let trades = match WebSocketMessage::parse(text).unwrap() {
+        WebSocketMessage::TradeData(message) => message.data,
+        WebSocketMessage::DirectTrades(trades) => trades,
+        other => panic!("expected trades, got {}", other.channel()),
+    };
+    trades.into_iter().map(|trade| trade.into_owned()).collect()
+}
+
+fn utc() -> TimeDisplay {
+    TimeDisplay::new("UTC", None).unwrap()
+}
+
+/// What `print` writes through a plain `format` formatter with UTC times
+fn render(format: OutputFormat, print: impl FnOnce(&mut TradeFormatter)) -> String {
+    render_with(
+        TradeFormatter::new(format, false, false, QuietLevel::Normal, false, false, None)
+            .with_time_display(utc()),
+        print,
+    )
+}
+
+/// What `print` writes through `formatter`, captured in a file
+fn render_with(formatter: TradeFormatter, print: impl FnOnce(&mut TradeFormatter)) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("output");
+    let mut formatter = formatter.with_output(TradeOutput::create(&path).unwrap());
+    print(&mut formatter);
+    formatter.finish_output().unwrap();
+    std::fs::read_to_string(&path).unwrap()
+}
+
+#[test]
+fn every_fixture_parses_on_its_channel_without_losing_fields() {
+    for fixture in FIXTURES {
+        let text = read(fixture.file);
+        let message = WebSocketMessage::parse(&text)
+            .unwrap_or_else(|e| panic!("{} does not parse: {}", fixture.file, e));
+        assert_eq!(message.channel(), fixture.channel, "{}", fixture.file);
+        assert_eq!(
+            unknown_fields(&text, &message),
+            fixture.dropped,
+            "{} lost fields in parsing",
+            fixture.file
+        );
+    }
+}
+
+#[test]
+fn every_fixture_file_is_listed() {
+    let mut files: Vec<String> = std::fs::read_dir(fixture_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+    files.sort();
+    let mut listed: Vec<&str> = FIXTURES.iter().map(|fixture| fixture.file).collect();
+    listed.sort();
+    assert_eq!(files, listed);
+}
+
+#[test]
+fn trade_formats() {
+    let trades = trades(&read("trades.json"));
+    for (name, format) in [
+        ("table", OutputFormat::Table),
+        ("csv", OutputFormat::Csv),
+        ("json", OutputFormat::Json),
+        ("json_pretty", OutputFormat::JsonPretty),
+        ("minimal", OutputFormat::Minimal),
+        ("events", OutputFormat::Events),
+    ] {
+        let formatter = TradeFormatter::new(
+            format,
+            false,
+            true,
+            QuietLevel::DataOnly,
+            false,
+            false,
+            None,
+        )
+        .with_time_display(utc());
+        let output = render_with(formatter, |formatter| {
+            trades.iter().for_each(|trade| formatter.print_trade(trade))
+        });
+        insta::assert_snapshot!(format!("trades_{}", name), output);
+    }
+}
+
+/// A bare trade array goes through the same checks as a `trades` message, so a trade with an
+/// impossible time is counted and never shown
+#[tokio::test]
+async fn bare_trades_with_a_bad_timestamp_are_dropped() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+    let frame = read("trades_direct_bad_time.json");
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        stream
+            .write_all(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n")
+            .await
+            .unwrap();
+        let mut ws = WebSocket::after_handshake(stream, Role::Server);
+        // Wait for the subscription, so the trades cannot arrive with the handshake response
+        ws.read_frame().await.unwrap();
+        ws.write_frame(Frame::text(Payload::Owned(frame.into_bytes())))
+            .await
+            .unwrap();
+        std::future::pending::<()>().await;
+    });
+
+    let args = Args::try_parse_from(["rs-hyperliquid", "--url", &url, "--coin", "BTC"]).unwrap();
+    let config = Config::from_args(&args.stream).unwrap();
+    let (sender, mut receiver) = create_event_channel();
+    let state = Arc::new(tokio::sync::Mutex::new(ClientState::new()));
+    let mut client = HyperliquidWebSocketClient::new(Arc::new(config), sender, Arc::clone(&state));
+    let task = tokio::spawn(async move { client.run().await });
+
+    let trade = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match receiver.recv().await.expect("client stopped") {
+                ClientEvent::TradeReceived(trade) => break trade,
+                _ => continue,
+            }
+        }
+    })
+    .await
+    .expect("no trade received");
+    task.abort();
+
+    assert_eq!(trade.tid, 901122334455667);
+    let state = state.lock().await;
+    assert_eq!(state.invalid_timestamps.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn spot_trade_json() {
+    let trades = trades(&read("trades_spot.json"));
+    insta::assert_snapshot!(serde_json::to_string_pretty(&trade_json(&trades[0])).unwrap());
+}
+
+#[test]
+fn book_ladder_and_event() {
+    let book = match WebSocketMessage::parse(&read("l2book.json")).unwrap() {
+        WebSocketMessage::BookData(message) => message.data,
+        other => panic!("expected l2Book, got {}", other.channel()),
+    };
+    let ladder = BookLadderFormatter::new(false, 5, 0).with_time_display(utc());
+    insta::assert_snapshot!("book_ladder", ladder.render(&book));
+    let event = ClientEvent::BookReceived(Arc::new(book)).to_json();
+    insta::assert_snapshot!("book_event", serde_json::to_string_pretty(&event).unwrap());
+}
+
+#[test]
+fn bbo_lines() {
+    let line = BboFormatter::new(false, utc());
+    let rendered: Vec<String> = ["bbo.json", "bbo_one_sided.json"]
+        .into_iter()
+        .map(|file| match WebSocketMessage::parse(&read(file)).unwrap() {
+            WebSocketMessage::BboData(message) => line.render_line(&message.data),
+            other => panic!("expected bbo, got {}", other.channel()),
+        })
+        .collect();
+    insta::assert_snapshot!(rendered.join("\n"));
+}
+
+#[test]
+fn market_record_formats() {
+    let book = match WebSocketMessage::parse(&read("l2book.json")).unwrap() {
+        WebSocketMessage::BookData(message) => message.data,
+        other => panic!("expected l2Book, got {}", other.channel()),
+    };
+    let bbos: Vec<_> = ["bbo.json", "bbo_one_sided.json"]
+        .into_iter()
+        .map(|file| match WebSocketMessage::parse(&read(file)).unwrap() {
+            WebSocketMessage::BboData(message) => message.data,
+            other => panic!("expected bbo, got {}", other.channel()),
+        })
+        .collect();
+    let all_mids = match WebSocketMessage::parse(&read("all_mids.json")).unwrap() {
+        WebSocketMessage::AllMidsData(message) => message.data,
+        other => panic!("expected allMids, got {}", other.channel()),
+    };
+    for (name, format) in [("csv", OutputFormat::Csv), ("json", OutputFormat::Json)] {
+        let output = render(format, |formatter| {
+            formatter.print_book(&book);
+            bbos.iter().for_each(|bbo| formatter.print_bbo(bbo));
+            formatter.print_mids(&AllMidsFormatter::new(false).update(&all_mids));
+        });
+        insta::assert_snapshot!(format!("market_records_{}", name), output);
+    }
+}
+
+#[test]
+fn fill_formats() {
+    let (fills, snapshot) = match WebSocketMessage::parse(&read("user_fills.json")).unwrap() {
+        WebSocketMessage::UserEvent(message) => match message.data {
+            UserEvent::Fills {
+                fills, is_snapshot, ..
+            } => (fills, is_snapshot.unwrap_or(false)),
+            other => panic!("expected fills, got {:?}", other),
+        },
+        other => panic!("expected userFills, got {}", other.channel()),
+    };
+    for (name, format) in [
+        ("table", OutputFormat::Table),
+        ("csv", OutputFormat::Csv),
+        ("json", OutputFormat::Json),
+    ] {
+        let output = render(format, |formatter| {
+            fills
+                .iter()
+                .for_each(|fill| formatter.print_fill(fill, snapshot))
+        });
+        insta::assert_snapshot!(format!("fills_{}", name), output);
+    }
+}
+
+#[test]
+fn user_event_formats() {
+    let files = [
+        "user_event_fills.json",
+        "user_event_funding.json",
+        "user_event_liquidation.json",
+        "user_event_non_user_cancel.json",
+        "user_fundings.json",
+    ];
+    for (name, format) in [("table", OutputFormat::Table), ("csv", OutputFormat::Csv)] {
+        let output = render(format, |formatter| {
+            for file in files {
+                match WebSocketMessage::parse(&read(file)).unwrap() {
+                    WebSocketMessage::UserEvent(message) => match message.data {
+                        UserEvent::Fills { fills, .. } => fills
+                            .iter()
+                            .for_each(|fill| formatter.print_fill(fill, false)),
+                        UserEvent::Funding { funding } => formatter.print_funding(&funding),
+                        UserEvent::Liquidation { liquidation } => {
+                            formatter.print_liquidation(&liquidation)
+                        }
+                        UserEvent::NonUserCancel { non_user_cancel } => non_user_cancel
+                            .iter()
+                            .for_each(|cancel| formatter.print_non_user_cancel(cancel)),
+                    },
+                    WebSocketMessage::UserFundings(message) => message
+                        .data
+                        .fundings
+                        .iter()
+                        .for_each(|funding| formatter.print_funding(funding)),
+                    other => panic!("expected a user event, got {}", other.channel()),
+                }
+            }
+        });
+        insta::assert_snapshot!(format!("user_events_{}", name), output);
+    }
+}
+
+#[test]
+fn explorer_formats() {
+    let blocks = match WebSocketMessage::parse(&read("explorer_block.json")).unwrap() {
+        WebSocketMessage::DirectBlocks(blocks) => blocks,
+        other => panic!("expected explorer blocks, got {}", other.channel()),
+    };
+    let txs = match WebSocketMessage::parse(&read("explorer_txs.json")).unwrap() {
+        WebSocketMessage::DirectTxs(txs) => txs,
+        other => panic!("expected explorer transactions, got {}", other.channel()),
+    };
+    for (name, format) in [
+        ("table", OutputFormat::Table),
+        ("csv", OutputFormat::Csv),
+        ("json", OutputFormat::Json),
+    ] {
+        let output = render(format, |formatter| {
+            blocks.iter().for_each(|block| formatter.print_block(block));
+            txs.iter().for_each(|tx| formatter.print_tx(tx));
+        });
+        insta::assert_snapshot!(format!("explorer_{}", name), output);
+    }
+}
+
+#[test]
+fn order_update_formats() {
+    let updates = match WebSocketMessage::parse(&read("order_updates.json")).unwrap() {
+        WebSocketMessage::OrderUpdates(message) => message.data,
+        other => panic!("expected orderUpdates, got {}", other.channel()),
+    };
+    for (name, format) in [
+        ("table", OutputFormat::Table),
+        ("csv", OutputFormat::Csv),
+        ("json", OutputFormat::Json),
+    ] {
+        let formatter =
+            TradeFormatter::new(format, false, false, QuietLevel::Normal, false, false, None)
+                .with_unicode(false)
+                .with_time_display(utc());
+        let output = render_with(formatter, |formatter| {
+            let mut orders = OrderTracker::new();
+            for update in &updates {
+                let previous = orders.observe(update);
+                formatter.print_order(update, previous.as_deref());
+            }
+        });
+        insta::assert_snapshot!(format!("orders_{}", name), output);
+    }
+}